@@ -12,8 +12,9 @@ use uuid::Uuid;
 
 use crate::config::ApiConfig;
 use crate::credentials::{Credential, CredentialVault, VaultError};
+use crate::execution::smb::CopyProgress;
 
-use super::types::{ApiErrorResponse, DeploymentJob, JobResult};
+use super::types::{ApiErrorResponse, DeploymentJob, JobResult, MsiLogChunk};
 
 /// Errors from API operations
 #[derive(Debug, Error)]
@@ -48,6 +49,8 @@ pub struct ApiClient {
     api_token: String,
     /// Worker ID for job claiming
     worker_id: String,
+    /// Tags the backend matches jobs against when this worker polls
+    worker_tags: Vec<String>,
 }
 
 impl ApiClient {
@@ -73,9 +76,19 @@ impl ApiClient {
             config,
             api_token,
             worker_id,
+            worker_tags: Vec::new(),
         })
     }
 
+    /// Only claim jobs matching these tags (e.g. `segment:dmz`).
+    ///
+    /// Sent as a comma-separated `X-Worker-Tags` header on every poll; an
+    /// empty list claims any job.
+    pub fn with_worker_tags(mut self, worker_tags: Vec<String>) -> Self {
+        self.worker_tags = worker_tags;
+        self
+    }
+
     /// Load API token from Windows Credential Manager.
     fn load_api_token(credential_target: &str) -> Result<String, ApiError> {
         #[cfg(all(windows, feature = "mock-mode"))]
@@ -94,7 +107,10 @@ impl ApiClient {
     /// Refresh the API token from Windows Credential Manager.
     ///
     /// Call this periodically for long-running services to pick up token rotations.
+    /// Also clears the credential cache, since target credentials may have been
+    /// rotated together with the token.
     pub fn refresh_token(&mut self) -> Result<(), ApiError> {
+        CredentialVault::clear_cache();
         self.api_token = Self::load_api_token(&self.config.credential_target)?;
         info!("API token refreshed");
         Ok(())
@@ -110,15 +126,17 @@ impl ApiClient {
     pub async fn poll_next_job(&self) -> Result<Option<DeploymentJob>, ApiError> {
         let url = format!("{}{}", self.config.base_url, self.config.poll_endpoint);
 
-        debug!(url = %url, "Polling for next job");
+        debug!(url = %url, tags = ?self.worker_tags, "Polling for next job");
 
-        let response = self
+        let mut request = self
             .client
             .get(&url)
             .header(header::AUTHORIZATION, format!("Bearer {}", self.api_token))
-            .header("X-Worker-ID", &self.worker_id)
-            .send()
-            .await?;
+            .header("X-Worker-ID", &self.worker_id);
+        if !self.worker_tags.is_empty() {
+            request = request.header("X-Worker-Tags", self.worker_tags.join(","));
+        }
+        let response = request.send().await?;
 
         match response.status() {
             StatusCode::OK => {
@@ -165,6 +183,103 @@ impl ApiClient {
         }
     }
 
+    /// Fetch a previously submitted job by id.
+    ///
+    /// Used by retry jobs to recover the original payload (installer, credentials
+    /// and target list).
+    ///
+    /// # Returns
+    /// * `Ok(job)` - The job as stored by the backend
+    /// * `Err(ApiError::ServerError { status_code: 404, .. })` - Unknown job id
+    #[instrument(skip(self), fields(worker_id = %self.worker_id))]
+    pub async fn get_job(&self, job_id: Uuid) -> Result<DeploymentJob, ApiError> {
+        let url = format!("{}/internal/deployment-jobs/{}", self.config.base_url, job_id);
+
+        debug!(url = %url, "Fetching job");
+
+        let response = self
+            .client
+            .get(&url)
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_token))
+            .header("X-Worker-ID", &self.worker_id)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => response.json().await.map_err(|e| {
+                ApiError::InvalidResponse(format!("Failed to parse job: {}", e))
+            }),
+            StatusCode::UNAUTHORIZED => {
+                Err(ApiError::AuthenticationFailed("Invalid or expired token".to_string()))
+            }
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                let message = serde_json::from_str::<ApiErrorResponse>(&error_body)
+                    .map(|e| e.detail)
+                    .unwrap_or(error_body);
+                error!(status = %status, message = %message, "Failed to fetch job");
+                Err(ApiError::ServerError {
+                    status_code: status.as_u16(),
+                    message,
+                })
+            }
+        }
+    }
+
+    /// Return a claimed job to the queue without executing it (NAK).
+    ///
+    /// Used when pre-flight validation shows this worker cannot run the job,
+    /// so the backend releases the claim and another worker can pick it up.
+    ///
+    /// # Arguments
+    /// * `job_id` - The claimed job
+    /// * `reason` - Why this worker cannot run it
+    #[instrument(skip(self), fields(worker_id = %self.worker_id))]
+    pub async fn nak_job(&self, job_id: Uuid, reason: &str) -> Result<(), ApiError> {
+        let url = format!("{}/internal/deployment-jobs/{}/nak", self.config.base_url, job_id);
+
+        debug!(url = %url, "Returning job to queue");
+
+        let body = serde_json::json!({
+            "workerId": self.worker_id,
+            "reason": reason,
+        });
+
+        let response = self
+            .client
+            .post(&url)
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_token))
+            .header("X-Worker-ID", &self.worker_id)
+            .json(&body)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK | StatusCode::ACCEPTED | StatusCode::NO_CONTENT => {
+                info!(job_id = %job_id, reason = %reason, "Job returned to queue");
+                Ok(())
+            }
+            StatusCode::UNAUTHORIZED => {
+                Err(ApiError::AuthenticationFailed("Invalid or expired token".to_string()))
+            }
+            StatusCode::NOT_FOUND => {
+                warn!(job_id = %job_id, "Job not found, may have been cancelled");
+                Ok(())
+            }
+            status => {
+                let error_body = response.text().await.unwrap_or_default();
+                let message = serde_json::from_str::<ApiErrorResponse>(&error_body)
+                    .map(|e| e.detail)
+                    .unwrap_or(error_body);
+                error!(status = %status, message = %message, "Failed to return job to queue");
+                Err(ApiError::ServerError {
+                    status_code: status.as_u16(),
+                    message,
+                })
+            }
+        }
+    }
+
     /// Report job execution result to the backend.
     ///
     /// # Arguments
@@ -218,16 +333,57 @@ impl ApiClient {
         }
     }
 
+    /// Send newly appended MSI log lines of a running install (if supported).
+    ///
+    /// Best effort: the full log stays on the target and is referenced in
+    /// the result, so failures are only logged by the caller.
+    #[instrument(skip(self, chunk), fields(job_id = %chunk.job_id, host = %chunk.hostname, offset = chunk.offset))]
+    pub async fn send_msi_log(&self, chunk: &MsiLogChunk) -> Result<(), ApiError> {
+        let url = format!("{}/internal/deployment-jobs/{}/msi-log", self.config.base_url, chunk.job_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_token))
+            .header("X-Worker-ID", &self.worker_id)
+            .json(chunk)
+            .send()
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            StatusCode::UNAUTHORIZED => {
+                Err(ApiError::AuthenticationFailed("Invalid or expired token".to_string()))
+            }
+            status => Err(ApiError::ServerError {
+                status_code: status.as_u16(),
+                message: "MSI log chunk rejected".to_string(),
+            }),
+        }
+    }
+
     /// Send a heartbeat to the backend (if supported).
     ///
     /// This can be used to signal that the worker is still alive and processing.
+    /// If an SMB copy is in progress, its byte-level progress is included,
+    /// along with the number of poll loop restarts since the worker started.
+    ///
+    /// Transport errors and 5xx responses are returned so the caller can back
+    /// off; other non-success responses (e.g. endpoint not supported) are not.
     #[instrument(skip(self))]
-    pub async fn send_heartbeat(&self, job_id: Option<Uuid>) -> Result<(), ApiError> {
+    pub async fn send_heartbeat(
+        &self,
+        job_id: Option<Uuid>,
+        copy_progress: Option<CopyProgress>,
+        poller_restarts: u32,
+    ) -> Result<(), ApiError> {
         let url = format!("{}/internal/workers/{}/heartbeat", self.config.base_url, self.worker_id);
 
         let body = serde_json::json!({
             "workerId": self.worker_id,
             "currentJobId": job_id,
+            "copyProgress": copy_progress,
+            "pollerRestarts": poller_restarts,
             "timestamp": chrono::Utc::now(),
         });
 
@@ -244,15 +400,22 @@ impl ApiClient {
                 debug!("Heartbeat sent successfully");
                 Ok(())
             }
+            Ok(resp) if resp.status().is_server_error() => {
+                let status = resp.status();
+                debug!(status = %status, "Heartbeat rejected by server");
+                Err(ApiError::ServerError {
+                    status_code: status.as_u16(),
+                    message: "Heartbeat failed".to_string(),
+                })
+            }
             Ok(resp) => {
                 // Heartbeat failures are not critical
                 debug!(status = %resp.status(), "Heartbeat response");
                 Ok(())
             }
             Err(e) => {
-                // Log but don't fail on heartbeat errors
                 warn!(error = %e, "Failed to send heartbeat");
-                Ok(())
+                Err(ApiError::RequestFailed(e))
             }
         }
     }
@@ -274,6 +437,7 @@ pub mod mock {
                 config,
                 api_token: "mock-token".to_string(),
                 worker_id,
+                worker_tags: Vec::new(),
             }
         }
 
@@ -299,10 +463,25 @@ pub mod mock {
                         targets: vec![DeploymentTarget {
                             hostname: "mock-target-01".to_string(),
                             vault_ref: None,
+                            install_args: None,
                             machine_id: Some("mock-machine-id".to_string()),
+                            mac_address: None,
                         }],
                         product_code: None,
                         force_restart: false,
+                        disable_av_during_install: false,
+                        enable_firewall_rules_for_deploy: false,
+                        msi_log_level: Default::default(),
+                        cleanup_on_failure: true,
+                        working_directory: None,
+                        environment: Vec::new(),
+                        retry_of: None,
+                        retry_hostnames: Vec::new(),
+                        file_path: None,
+                        tail_lines: None,
+                        logs_of: None,
+                        logs_since: None,
+                        inline_credentials: None,
                     },
                     claimed_by: Some(self.worker_id.clone()),
                     claimed_at: Some(Utc::now()),
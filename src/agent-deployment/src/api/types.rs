@@ -3,6 +3,8 @@
 //! All types use camelCase for JSON serialization to match the backend's
 //! HTTPSchemaModel which automatically converts to camelCase.
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -44,12 +46,30 @@ pub enum JobType {
     MsiUninstall,
     /// Generic executable
     Execute,
+    /// Re-run the failed targets of a previous job
+    Retry,
+    /// Read the end of a file on each target (post-install verification)
+    ReadFile,
+    /// Collect the MSI logs and installer event log entries of a failed job
+    CollectLogs,
+    /// Check targets before a deployment (reachability, credentials, ADMIN$
+    /// access, security products, virtualization) without changing them
+    Validate,
 }
 
 impl JobType {
     /// Check if this job type is supported by this worker
     pub fn is_supported(&self) -> bool {
-        matches!(self, JobType::MsiInstall | JobType::MsiUninstall | JobType::Execute)
+        matches!(
+            self,
+            JobType::MsiInstall
+                | JobType::MsiUninstall
+                | JobType::Execute
+                | JobType::Retry
+                | JobType::ReadFile
+                | JobType::CollectLogs
+                | JobType::Validate
+        )
     }
 
     /// Get human-readable name
@@ -58,6 +78,34 @@ impl JobType {
             JobType::MsiInstall => "MSI Install",
             JobType::MsiUninstall => "MSI Uninstall",
             JobType::Execute => "Execute",
+            JobType::Retry => "Retry",
+            JobType::ReadFile => "Read File",
+            JobType::CollectLogs => "Collect Logs",
+            JobType::Validate => "Validate",
+        }
+    }
+}
+
+/// MSI log verbosity (maps to msiexec `/l` flags)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MsiLogLevel {
+    /// Errors only (`/le`)
+    Errors,
+    /// Warnings and errors (`/lwe`)
+    #[default]
+    Warnings,
+    /// Everything, verbose (`/l*v`)
+    Verbose,
+}
+
+impl MsiLogLevel {
+    /// Get the msiexec logging switch for this level
+    pub fn msiexec_flag(&self) -> &'static str {
+        match self {
+            MsiLogLevel::Errors => "/le",
+            MsiLogLevel::Warnings => "/lwe",
+            MsiLogLevel::Verbose => "/l*v",
         }
     }
 }
@@ -79,13 +127,20 @@ fn default_credential_type() -> String {
     "domain_admin".to_string()
 }
 
+fn default_cleanup_on_failure() -> bool {
+    true
+}
+
 /// Job payload containing execution details
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JobPayload {
     /// Source path to the installer (SMB path like `\\server\share\installer.msi`)
+    /// Empty for retry jobs, which reuse the original job's payload.
+    #[serde(default)]
     pub installer_path: String,
     /// Credential Manager target name for SMB access (use "__inline__" for inline credentials)
+    #[serde(default)]
     pub vault_ref: String,
     /// Inline credentials (used when vault_ref is "__inline__")
     #[serde(default)]
@@ -97,6 +152,7 @@ pub struct JobPayload {
     #[serde(default)]
     pub enroll_token: Option<String>,
     /// List of target machines to deploy to
+    #[serde(default)]
     pub targets: Vec<DeploymentTarget>,
     /// Product code for uninstall operations
     #[serde(default)]
@@ -104,6 +160,73 @@ pub struct JobPayload {
     /// Whether to force restart after installation
     #[serde(default)]
     pub force_restart: bool,
+    /// Turn Defender real-time protection off on each target while the MSI
+    /// installs (MsiInstall jobs only; requires `allow_av_disable` on the worker)
+    #[serde(default)]
+    pub disable_av_during_install: bool,
+    /// Turn on the inbound "File and Printer Sharing" and "Remote Service
+    /// Management" firewall rules on each target for the deployment and turn
+    /// them back off afterwards (requires `allow_firewall_changes` on the worker)
+    #[serde(default)]
+    pub enable_firewall_rules_for_deploy: bool,
+    /// MSI log verbosity (logging is always enabled on the target)
+    #[serde(default)]
+    pub msi_log_level: MsiLogLevel,
+    /// Delete the staged MSI from a target whose install failed (default: true).
+    /// When false it is left next to the MSI log for manual investigation
+    #[serde(default = "default_cleanup_on_failure")]
+    pub cleanup_on_failure: bool,
+    /// Working directory on the target (Execute jobs only)
+    #[serde(default)]
+    pub working_directory: Option<String>,
+    /// Environment variables as (name, value) pairs (Execute jobs only)
+    #[serde(default)]
+    pub environment: Vec<(String, String)>,
+    /// Original job to re-run (retry jobs only)
+    #[serde(default)]
+    pub retry_of: Option<Uuid>,
+    /// Hostnames of the original job's targets to re-run (retry jobs only)
+    #[serde(default)]
+    pub retry_hostnames: Vec<String>,
+    /// Local path of the file to read on each target (ReadFile jobs only)
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Number of trailing lines to return (ReadFile jobs only; default: all read bytes)
+    #[serde(default)]
+    pub tail_lines: Option<usize>,
+    /// Job whose logs to collect (CollectLogs jobs only)
+    #[serde(default)]
+    pub logs_of: Option<Uuid>,
+    /// Earliest event log entries to collect (CollectLogs jobs only; default: last 24 hours)
+    #[serde(default)]
+    pub logs_since: Option<DateTime<Utc>>,
+}
+
+impl JobPayload {
+    /// Select the targets whose hostname is in `hostnames` (case-insensitive).
+    ///
+    /// Returns the matching targets and the hostnames that matched none.
+    pub fn filter_targets(&self, hostnames: &[String]) -> (Vec<DeploymentTarget>, Vec<String>) {
+        let targets = self
+            .targets
+            .iter()
+            .filter(|t| hostnames.iter().any(|h| h.eq_ignore_ascii_case(&t.hostname)))
+            .cloned()
+            .collect();
+
+        let missing = hostnames
+            .iter()
+            .filter(|h| !self.targets.iter().any(|t| t.hostname.eq_ignore_ascii_case(h)))
+            .cloned()
+            .collect();
+
+        (targets, missing)
+    }
+
+    /// Installer arguments for a target: its own override, else the job-level args
+    pub fn install_args_for<'a>(&'a self, target: &'a DeploymentTarget) -> Option<&'a str> {
+        target.install_args.as_deref().or(self.install_args.as_deref())
+    }
 }
 
 /// Target machine for deployment
@@ -115,9 +238,15 @@ pub struct DeploymentTarget {
     /// Optional per-target credential override
     #[serde(default)]
     pub vault_ref: Option<String>,
+    /// Optional per-target installer arguments (replace the job-level `install_args`)
+    #[serde(default)]
+    pub install_args: Option<String>,
     /// Optional machine identifier (for tracking)
     #[serde(default)]
     pub machine_id: Option<String>,
+    /// Optional MAC address; an unreachable target is woken with Wake-on-LAN
+    #[serde(default)]
+    pub mac_address: Option<String>,
 }
 
 /// Job result to report via `POST /internal/deployment-jobs/{id}/result`
@@ -141,6 +270,9 @@ pub struct JobResult {
     /// Overall error message (if any)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    /// Original job this result retries (retry jobs only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_of: Option<Uuid>,
 }
 
 impl JobResult {
@@ -155,6 +287,7 @@ impl JobResult {
             duration_seconds: 0,
             target_results: Vec::new(),
             error_message: None,
+            retry_of: None,
         }
     }
 
@@ -218,6 +351,114 @@ pub struct TargetResult {
     /// Execution phase where failure occurred (if any)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub failed_phase: Option<ExecutionPhase>,
+    /// UNC path of the MSI log left on the target (if msiexec was run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub msi_log_path: Option<String>,
+    /// Bytes copied to the target over SMB (MSI installs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copied_bytes: Option<u64>,
+    /// Average SMB copy throughput to the target (MSI installs), to tell
+    /// slow links and copy-bound deployments from install-bound ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_throughput_bytes_per_sec: Option<u64>,
+    /// UNC path of the installer kept on a failed target (`cleanup_on_failure` off)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preserved_installer_path: Option<String>,
+    /// File read from the target (ReadFile jobs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_content: Option<FileContent>,
+    /// Antivirus/EDR products registered on the target (MSI and Validate jobs, best effort)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security_products: Vec<SecurityProduct>,
+    /// Whether the target is a virtual machine (MSI and Validate jobs, best effort)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtualization: Option<VirtualizationInfo>,
+    /// Log files collected from the target; content is the end of each file (CollectLogs jobs)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub collected_files: Vec<FileContent>,
+    /// Worker-local directory holding the complete collected files (CollectLogs jobs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_bundle_path: Option<String>,
+    /// Installer and service event log entries on the target (CollectLogs jobs)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub event_log_entries: Vec<EventLogEntry>,
+    /// Time spent in each completed phase (and the failed one), in milliseconds
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub phase_timings: HashMap<ExecutionPhase, u64>,
+}
+
+/// Antivirus/EDR product registered with Windows Security Center on a target
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityProduct {
+    pub name: String,
+    /// Real-time protection is on
+    pub enabled: bool,
+    /// Signature definitions are current
+    pub up_to_date: bool,
+    /// Raw `productState` bit field
+    pub product_state: u32,
+    /// Executable of the product's UI/agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
+}
+
+/// Lines appended to a target's MSI log while msiexec runs, sent to
+/// `POST /internal/deployment-jobs/{id}/msi-log` for a live install log
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MsiLogChunk {
+    pub job_id: Uuid,
+    pub hostname: String,
+    /// Byte offset of `content` in the log, so the backend can order chunks and spot gaps
+    pub offset: u64,
+    /// New complete lines (lossy UTF-8)
+    pub content: String,
+}
+
+/// Virtual machine detection result for a target (`Win32_ComputerSystem`)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualizationInfo {
+    pub is_virtual_machine: bool,
+    /// Detected hypervisor ("VMware", "Hyper-V", ...), None on physical hardware
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hypervisor: Option<String>,
+    pub manufacturer: String,
+    pub model: String,
+    /// CPUID hypervisor bit; also set on physical hosts running Hyper-V/VBS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hypervisor_present: Option<bool>,
+}
+
+/// End of a file read from a target
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContent {
+    /// Local path on the target
+    pub path: String,
+    /// Content (lossy UTF-8)
+    pub content: String,
+    /// Total size of the file in bytes
+    pub file_size: u64,
+    /// Whether only the end of the file is included
+    pub truncated: bool,
+}
+
+/// Windows event log entry read from a target
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventLogEntry {
+    /// Log name ("Application", "System")
+    pub log_file: String,
+    /// Event source (e.g. "MsiInstaller")
+    pub source: String,
+    pub event_code: u32,
+    /// "Error", "Warning", "Information", ...
+    pub entry_type: String,
+    /// When the event was generated (WMI datetime, target local time with offset)
+    pub time_generated: String,
+    pub message: String,
 }
 
 impl TargetResult {
@@ -231,6 +472,17 @@ impl TargetResult {
             error_message: None,
             duration_seconds,
             failed_phase: None,
+            msi_log_path: None,
+            copied_bytes: None,
+            copy_throughput_bytes_per_sec: None,
+            preserved_installer_path: None,
+            file_content: None,
+            security_products: Vec::new(),
+            virtualization: None,
+            collected_files: Vec::new(),
+            log_bundle_path: None,
+            event_log_entries: Vec::new(),
+            phase_timings: HashMap::new(),
         }
     }
 
@@ -250,24 +502,41 @@ impl TargetResult {
             error_message: Some(error),
             duration_seconds,
             failed_phase: Some(phase),
+            msi_log_path: None,
+            copied_bytes: None,
+            copy_throughput_bytes_per_sec: None,
+            preserved_installer_path: None,
+            file_content: None,
+            security_products: Vec::new(),
+            virtualization: None,
+            collected_files: Vec::new(),
+            log_bundle_path: None,
+            event_log_entries: Vec::new(),
+            phase_timings: HashMap::new(),
         }
     }
 }
 
-/// Execution phases for tracking where failures occur
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// Execution phases for tracking where failures occur and how long each took
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecutionPhase {
     /// Checking if target is reachable
     ReachabilityCheck,
     /// Resolving credentials from vault
     CredentialResolution,
+    /// Checking free disk space on the target before copying
+    DiskSpaceCheck,
     /// Copying installer via SMB
     SmbCopy,
     /// Creating Windows service
     ServiceCreation,
     /// Starting and monitoring service
     ServiceExecution,
+    /// Reading a file from the target
+    FileRead,
+    /// Collecting logs and event log entries from the target
+    LogCollection,
     /// Cleanup operations
     Cleanup,
 }
@@ -277,9 +546,12 @@ impl std::fmt::Display for ExecutionPhase {
         match self {
             ExecutionPhase::ReachabilityCheck => write!(f, "reachability check"),
             ExecutionPhase::CredentialResolution => write!(f, "credential resolution"),
+            ExecutionPhase::DiskSpaceCheck => write!(f, "disk space check"),
             ExecutionPhase::SmbCopy => write!(f, "SMB copy"),
             ExecutionPhase::ServiceCreation => write!(f, "service creation"),
             ExecutionPhase::ServiceExecution => write!(f, "service execution"),
+            ExecutionPhase::FileRead => write!(f, "file read"),
+            ExecutionPhase::LogCollection => write!(f, "log collection"),
             ExecutionPhase::Cleanup => write!(f, "cleanup"),
         }
     }
@@ -303,6 +575,10 @@ mod tests {
         assert!(JobType::MsiInstall.is_supported());
         assert!(JobType::MsiUninstall.is_supported());
         assert!(JobType::Execute.is_supported());
+        assert!(JobType::Retry.is_supported());
+        assert!(JobType::ReadFile.is_supported());
+        assert!(JobType::CollectLogs.is_supported());
+        assert!(JobType::Validate.is_supported());
     }
 
     #[test]
@@ -386,7 +662,97 @@ mod tests {
         let job: DeploymentJob = serde_json::from_str(json).unwrap();
         assert_eq!(job.job_type, JobType::MsiInstall);
         assert_eq!(job.priority, 1);
+        assert_eq!(job.payload.msi_log_level, MsiLogLevel::Warnings);
         assert_eq!(job.payload.targets.len(), 1);
         assert_eq!(job.payload.targets[0].hostname, "target-01");
     }
+
+    #[test]
+    fn test_retry_job_deserialization() {
+        let json = r#"{
+            "id": "550e8400-e29b-41d4-a716-446655440001",
+            "jobType": "retry",
+            "createdAt": "2025-01-01T13:00:00Z",
+            "payload": {
+                "retryOf": "550e8400-e29b-41d4-a716-446655440000",
+                "retryHostnames": ["target-02", "target-05"]
+            }
+        }"#;
+
+        let job: DeploymentJob = serde_json::from_str(json).unwrap();
+        assert_eq!(job.job_type, JobType::Retry);
+        assert!(job.payload.targets.is_empty());
+        assert_eq!(job.payload.retry_hostnames.len(), 2);
+        assert_eq!(
+            job.payload.retry_of,
+            Some(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_filter_targets() {
+        let target = |hostname: &str| DeploymentTarget {
+            hostname: hostname.to_string(),
+            vault_ref: None,
+            install_args: None,
+            machine_id: None,
+            mac_address: None,
+        };
+        let payload = JobPayload {
+            installer_path: String::new(),
+            vault_ref: String::new(),
+            inline_credentials: None,
+            install_args: None,
+            enroll_token: None,
+            targets: vec![target("host1"), target("host2"), target("host3")],
+            product_code: None,
+            force_restart: false,
+            disable_av_during_install: false,
+            enable_firewall_rules_for_deploy: false,
+            msi_log_level: MsiLogLevel::default(),
+            cleanup_on_failure: true,
+            working_directory: None,
+            environment: Vec::new(),
+            retry_of: None,
+            retry_hostnames: Vec::new(),
+            file_path: None,
+            tail_lines: None,
+            logs_of: None,
+            logs_since: None,
+        };
+
+        let (targets, missing) =
+            payload.filter_targets(&["HOST2".to_string(), "host9".to_string()]);
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].hostname, "host2");
+        assert_eq!(missing, vec!["host9".to_string()]);
+    }
+
+    #[test]
+    fn test_install_args_override() {
+        let json = r#"{
+            "installerPath": "\\\\server\\share\\app.msi",
+            "vaultRef": "DeploymentWorker:Default",
+            "installArgs": "/qn",
+            "targets": [
+                { "hostname": "target-01" },
+                { "hostname": "target-02", "installArgs": "/qn ROLE=kiosk" }
+            ]
+        }"#;
+
+        let payload: JobPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(payload.install_args_for(&payload.targets[0]), Some("/qn"));
+        assert_eq!(payload.install_args_for(&payload.targets[1]), Some("/qn ROLE=kiosk"));
+        assert!(payload.cleanup_on_failure);
+    }
+
+    #[test]
+    fn test_msi_log_level_flags() {
+        assert_eq!(MsiLogLevel::Errors.msiexec_flag(), "/le");
+        assert_eq!(MsiLogLevel::Warnings.msiexec_flag(), "/lwe");
+        assert_eq!(MsiLogLevel::Verbose.msiexec_flag(), "/l*v");
+
+        let level: MsiLogLevel = serde_json::from_str("\"verbose\"").unwrap();
+        assert_eq!(level, MsiLogLevel::Verbose);
+    }
 }
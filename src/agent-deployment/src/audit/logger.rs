@@ -125,6 +125,8 @@ pub enum AuditEventType {
     JobReceived,
     /// Job execution started
     JobStarted,
+    /// Job returned to the queue (worker cannot run it)
+    JobRejected,
     /// Successfully connected to target
     TargetConnected,
     /// File copied via SMB
@@ -150,6 +152,7 @@ impl std::fmt::Display for AuditEventType {
             AuditEventType::WorkerStopped => write!(f, "WORKER_STOPPED"),
             AuditEventType::JobReceived => write!(f, "JOB_RECEIVED"),
             AuditEventType::JobStarted => write!(f, "JOB_STARTED"),
+            AuditEventType::JobRejected => write!(f, "JOB_REJECTED"),
             AuditEventType::TargetConnected => write!(f, "TARGET_CONNECTED"),
             AuditEventType::FileCopied => write!(f, "FILE_COPIED"),
             AuditEventType::InstallStarted => write!(f, "INSTALL_STARTED"),
@@ -307,6 +310,7 @@ mod tests {
     #[test]
     fn test_audit_event_type_display() {
         assert_eq!(AuditEventType::JobReceived.to_string(), "JOB_RECEIVED");
+        assert_eq!(AuditEventType::JobRejected.to_string(), "JOB_REJECTED");
         assert_eq!(AuditEventType::Error.to_string(), "ERROR");
     }
 
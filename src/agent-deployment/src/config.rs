@@ -7,7 +7,8 @@
 
 use config::{Config as ConfigLoader, ConfigError, Environment, File};
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
 /// Main configuration structure
 #[derive(Debug, Clone, Deserialize)]
@@ -64,6 +65,73 @@ pub struct WorkerConfig {
     /// Reachability check timeout in seconds (default: 5)
     #[serde(default = "default_reachability_timeout")]
     pub reachability_timeout_seconds: u64,
+    /// SMB keepalive interval in seconds while the remote service runs (default: 0 = disabled)
+    #[serde(default = "default_smb_keepalive")]
+    pub smb_keepalive_seconds: u64,
+    /// Heartbeat interval in seconds; backs off on failures up to max_backoff_seconds (default: 60)
+    #[serde(default = "default_heartbeat_interval")]
+    pub heartbeat_interval_seconds: u64,
+    /// Free space required on the target as a multiple of the installer size,
+    /// checked before copying (default: 3.0, 0 = disabled)
+    #[serde(default = "default_disk_space_multiplier")]
+    pub disk_space_multiplier: f64,
+    /// Maximum job results kept in memory for re-sending after a failed report (default: 50)
+    #[serde(default = "default_pending_results_capacity")]
+    pub pending_results_capacity: usize,
+    /// Query the antivirus/EDR products on each target before installing and
+    /// include them in the result, to correlate failures with security tooling (default: true)
+    #[serde(default = "default_detect_security_products")]
+    pub detect_security_products: bool,
+    /// Check whether each target is a virtual machine before installing and
+    /// include the hypervisor in the result (default: true)
+    #[serde(default = "default_detect_virtualization")]
+    pub detect_virtualization: bool,
+    /// Jobs kept claimed at once, topped up before every job and run in
+    /// priority order (default: 1)
+    #[serde(default = "default_poll_batch_size")]
+    pub poll_batch_size: usize,
+    /// Allow jobs to turn off Defender real-time protection on a target for the
+    /// duration of an install (`disableAvDuringInstall`). SECURITY: off by default
+    #[serde(default = "default_allow_av_disable")]
+    pub allow_av_disable: bool,
+    /// Allow jobs to turn on the firewall rules needed for deployment on a
+    /// target for the duration of the job (`enableFirewallRulesForDeploy`).
+    /// SECURITY: off by default
+    #[serde(default = "default_allow_firewall_changes")]
+    pub allow_firewall_changes: bool,
+    /// How long a credential read from the vault is reused for other targets
+    /// of a job, in seconds (default: 60, 0 = always read the vault)
+    #[serde(default = "default_credential_cache_ttl")]
+    pub credential_cache_ttl_seconds: u64,
+    /// Tags sent with every poll so the backend only hands this worker jobs it
+    /// can serve, e.g. workers in an isolated segment: ["segment:dmz"] (default: none)
+    #[serde(default)]
+    pub worker_tags: Vec<String>,
+    /// Fail the SMB phase unless the connection to the target is signed
+    /// (or encrypted), checked before copying (default: false)
+    #[serde(default = "default_require_smb_signing")]
+    pub require_smb_signing: bool,
+    /// Fail the SMB phase unless the connection to the target is encrypted
+    /// (SMB 3.x), checked before copying (default: false)
+    #[serde(default = "default_require_smb_encryption")]
+    pub require_smb_encryption: bool,
+    /// How long to wait for a target woken by Wake-on-LAN (targets with a MAC
+    /// address that fail the reachability check) to come online, in seconds
+    /// (default: 180, 0 = don't wake targets)
+    #[serde(default = "default_wake_on_lan_wait")]
+    pub wake_on_lan_wait_seconds: u64,
+    /// Address Wake-on-LAN packets are broadcast to (default: "255.255.255.255",
+    /// the worker's subnet only; use a subnet-directed broadcast for other subnets)
+    #[serde(default = "default_wake_on_lan_broadcast")]
+    pub wake_on_lan_broadcast: String,
+    /// How often the target's MSI log is read while msiexec runs, to send the
+    /// new lines to the backend as a live install log, in seconds (default: 15, 0 = disabled)
+    #[serde(default = "default_msi_log_tail")]
+    pub msi_log_tail_seconds: u64,
+    /// Local directory CollectLogs jobs copy the full target logs to, one
+    /// `<job id>\<hostname>` bundle per target
+    #[serde(default = "default_collect_logs_dir")]
+    pub collect_logs_dir: String,
 }
 
 /// Logging configuration
@@ -131,6 +199,77 @@ fn default_reachability_timeout() -> u64 {
     5
 }
 
+fn default_smb_keepalive() -> u64 {
+    0
+}
+
+fn default_heartbeat_interval() -> u64 {
+    60
+}
+
+fn default_disk_space_multiplier() -> f64 {
+    3.0
+}
+
+fn default_pending_results_capacity() -> usize {
+    50
+}
+
+fn default_detect_security_products() -> bool {
+    true
+}
+
+fn default_detect_virtualization() -> bool {
+    true
+}
+
+fn default_poll_batch_size() -> usize {
+    1
+}
+
+fn default_allow_av_disable() -> bool {
+    false
+}
+
+fn default_allow_firewall_changes() -> bool {
+    false
+}
+
+fn default_require_smb_signing() -> bool {
+    false
+}
+
+fn default_require_smb_encryption() -> bool {
+    false
+}
+
+fn default_wake_on_lan_wait() -> u64 {
+    180
+}
+
+fn default_wake_on_lan_broadcast() -> String {
+    "255.255.255.255".to_string()
+}
+
+fn default_msi_log_tail() -> u64 {
+    15
+}
+
+fn default_collect_logs_dir() -> String {
+    #[cfg(windows)]
+    {
+        "C:\\ProgramData\\DeploymentWorker\\collected-logs".to_string()
+    }
+    #[cfg(not(windows))]
+    {
+        "/var/lib/deployment-worker/collected-logs".to_string()
+    }
+}
+
+fn default_credential_cache_ttl() -> u64 {
+    60
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -182,6 +321,22 @@ impl Config {
             .set_default("worker.smb_copy_timeout_seconds", default_smb_timeout())?
             .set_default("worker.service_execution_timeout_seconds", default_execution_timeout())?
             .set_default("worker.reachability_timeout_seconds", default_reachability_timeout())?
+            .set_default("worker.smb_keepalive_seconds", default_smb_keepalive())?
+            .set_default("worker.heartbeat_interval_seconds", default_heartbeat_interval())?
+            .set_default("worker.disk_space_multiplier", default_disk_space_multiplier())?
+            .set_default("worker.pending_results_capacity", default_pending_results_capacity() as u64)?
+            .set_default("worker.detect_security_products", default_detect_security_products())?
+            .set_default("worker.detect_virtualization", default_detect_virtualization())?
+            .set_default("worker.poll_batch_size", default_poll_batch_size() as u64)?
+            .set_default("worker.allow_av_disable", default_allow_av_disable())?
+            .set_default("worker.allow_firewall_changes", default_allow_firewall_changes())?
+            .set_default("worker.credential_cache_ttl_seconds", default_credential_cache_ttl())?
+            .set_default("worker.require_smb_signing", default_require_smb_signing())?
+            .set_default("worker.require_smb_encryption", default_require_smb_encryption())?
+            .set_default("worker.wake_on_lan_wait_seconds", default_wake_on_lan_wait())?
+            .set_default("worker.wake_on_lan_broadcast", default_wake_on_lan_broadcast())?
+            .set_default("worker.msi_log_tail_seconds", default_msi_log_tail())?
+            .set_default("worker.collect_logs_dir", default_collect_logs_dir())?
             .set_default("logging.level", default_log_level())?
             .set_default("logging.max_size_mb", default_log_size())?
             .set_default("logging.max_files", default_log_files())?
@@ -230,6 +385,23 @@ impl Config {
                 smb_copy_timeout_seconds: 10,
                 service_execution_timeout_seconds: 30,
                 reachability_timeout_seconds: 2,
+                smb_keepalive_seconds: 5,
+                heartbeat_interval_seconds: 10,
+                disk_space_multiplier: 3.0,
+                pending_results_capacity: 10,
+                detect_security_products: true,
+                detect_virtualization: true,
+                poll_batch_size: 1,
+                allow_av_disable: false,
+                allow_firewall_changes: false,
+                credential_cache_ttl_seconds: 10,
+                worker_tags: Vec::new(),
+                require_smb_signing: default_require_smb_signing(),
+                require_smb_encryption: default_require_smb_encryption(),
+                wake_on_lan_wait_seconds: default_wake_on_lan_wait(),
+                wake_on_lan_broadcast: default_wake_on_lan_broadcast(),
+                msi_log_tail_seconds: default_msi_log_tail(),
+                collect_logs_dir: default_collect_logs_dir(),
             },
             logging: LoggingConfig {
                 level: "debug".to_string(),
@@ -274,19 +446,98 @@ impl Config {
             ));
         }
 
+        // Tags travel comma-separated in an HTTP header
+        if let Some(tag) = self
+            .worker
+            .worker_tags
+            .iter()
+            .find(|tag| tag.is_empty() || !tag.chars().all(|c| c.is_ascii_graphic() && c != ','))
+        {
+            return Err(ConfigError::Message(format!(
+                "worker.worker_tags: invalid tag {:?} (printable ASCII without spaces or commas)",
+                tag
+            )));
+        }
+
+        if self.worker.wake_on_lan_broadcast.parse::<std::net::Ipv4Addr>().is_err() {
+            return Err(ConfigError::Message(format!(
+                "worker.wake_on_lan_broadcast: invalid IPv4 address {:?}",
+                self.worker.wake_on_lan_broadcast
+            )));
+        }
+
         Ok(())
     }
 
+    /// Get the file holding this installation's persisted UUID
+    fn get_installation_id_path() -> PathBuf {
+        #[cfg(test)]
+        {
+            std::env::temp_dir().join("deployment-worker-test").join("installation-id")
+        }
+        #[cfg(all(windows, not(test)))]
+        {
+            PathBuf::from("C:\\ProgramData\\DeploymentWorker\\installation-id")
+        }
+        #[cfg(all(not(windows), not(test)))]
+        {
+            PathBuf::from("/var/lib/deployment-worker/installation-id")
+        }
+    }
+
     /// Generate or get worker ID
+    ///
+    /// Without `worker.worker_id`, the ID is `<hostname>-<installation id>`,
+    /// stable across restarts so the backend can track the worker's job history.
     pub fn get_worker_id(&self) -> String {
         self.worker.worker_id.clone().unwrap_or_else(|| {
-            // Generate worker ID from hostname and process ID
-            let hostname = hostname::get()
-                .map(|h| h.to_string_lossy().to_string())
-                .unwrap_or_else(|_| "unknown".to_string());
-            format!("{}-{}", hostname, std::process::id())
+            let installation_id = load_or_create_installation_id(&Self::get_installation_id_path())
+                .unwrap_or_else(|e| {
+                    // Still unique, but changes on every restart
+                    tracing::warn!(error = %e, "Failed to persist installation ID, worker ID will not be stable");
+                    Uuid::new_v4()
+                });
+            derive_worker_id(&installation_id)
         })
     }
+
+    /// Replace the persisted installation ID, giving this machine a new
+    /// derived worker ID (e.g. after cloning a VM image with the worker installed)
+    pub fn rotate_installation_id() -> std::io::Result<String> {
+        let path = Self::get_installation_id_path();
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        load_or_create_installation_id(&path).map(|id| derive_worker_id(&id))
+    }
+}
+
+/// Worker ID from the hostname and the installation ID
+fn derive_worker_id(installation_id: &Uuid) -> String {
+    let hostname = hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    // 12 hex digits are plenty to tell installations on one host apart
+    format!("{}-{}", hostname, &installation_id.simple().to_string()[..12])
+}
+
+/// Read the installation UUID, generating and persisting one on first use
+/// (or if the file is unreadable)
+fn load_or_create_installation_id(path: &Path) -> std::io::Result<Uuid> {
+    if let Ok(contents) = std::fs::read_to_string(path) {
+        if let Ok(id) = Uuid::parse_str(contents.trim()) {
+            return Ok(id);
+        }
+    }
+
+    let id = Uuid::new_v4();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, id.to_string())?;
+    Ok(id)
 }
 
 impl Default for Config {
@@ -308,6 +559,23 @@ impl Default for Config {
                 smb_copy_timeout_seconds: default_smb_timeout(),
                 service_execution_timeout_seconds: default_execution_timeout(),
                 reachability_timeout_seconds: default_reachability_timeout(),
+                smb_keepalive_seconds: default_smb_keepalive(),
+                heartbeat_interval_seconds: default_heartbeat_interval(),
+                disk_space_multiplier: default_disk_space_multiplier(),
+                pending_results_capacity: default_pending_results_capacity(),
+                detect_security_products: default_detect_security_products(),
+                detect_virtualization: default_detect_virtualization(),
+                poll_batch_size: default_poll_batch_size(),
+                allow_av_disable: default_allow_av_disable(),
+                allow_firewall_changes: default_allow_firewall_changes(),
+                credential_cache_ttl_seconds: default_credential_cache_ttl(),
+                worker_tags: Vec::new(),
+                require_smb_signing: default_require_smb_signing(),
+                require_smb_encryption: default_require_smb_encryption(),
+                wake_on_lan_wait_seconds: default_wake_on_lan_wait(),
+                wake_on_lan_broadcast: default_wake_on_lan_broadcast(),
+                msi_log_tail_seconds: default_msi_log_tail(),
+                collect_logs_dir: default_collect_logs_dir(),
             },
             logging: LoggingConfig {
                 level: default_log_level(),
@@ -339,4 +607,34 @@ mod tests {
         let worker_id = config.get_worker_id();
         assert!(!worker_id.is_empty());
     }
+
+    #[test]
+    fn test_worker_id_is_stable() {
+        let config = Config::default();
+        assert_eq!(config.get_worker_id(), config.get_worker_id());
+
+        let path = std::env::temp_dir().join(format!("installation-id-{}", Uuid::new_v4()));
+        let first = load_or_create_installation_id(&path).unwrap();
+        assert_eq!(load_or_create_installation_id(&path).unwrap(), first);
+
+        std::fs::write(&path, "not-a-uuid").unwrap();
+        assert_ne!(load_or_create_installation_id(&path).unwrap(), first);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_worker_tags_validation() {
+        let mut config = Config::default();
+        config.api.base_url = "https://api.example.com".to_string();
+        assert!(config.worker.worker_tags.is_empty());
+
+        config.worker.worker_tags = vec!["segment:dmz".to_string(), "site:cairo".to_string()];
+        assert!(config.validate().is_ok());
+
+        config.worker.worker_tags = vec!["segment:dmz,lan".to_string()];
+        assert!(config.validate().is_err());
+
+        config.worker.worker_tags = vec!["segment dmz".to_string()];
+        assert!(config.validate().is_err());
+    }
 }
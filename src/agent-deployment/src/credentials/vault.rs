@@ -3,6 +3,14 @@
 //! Uses the Windows Credential Manager (CredRead/CredWrite APIs) to securely
 //! store and retrieve credentials. Credentials are automatically zeroed from
 //! memory when dropped using the zeroize crate.
+//!
+//! Lookups can go through a short-TTL in-memory cache so a batch deployment
+//! reads a shared `vault_ref` once instead of once per target. Cached entries
+//! are ordinary `Credential`s, so they are zeroed when evicted or cleared.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use thiserror::Error;
 use zeroize::{Zeroize, ZeroizeOnDrop};
@@ -48,6 +56,70 @@ pub enum VaultError {
 /// Vault manager for Windows Credential Manager operations
 pub struct CredentialVault;
 
+/// A cached vault lookup and when it was read
+struct CachedCredential {
+    credential: Credential,
+    fetched_at: Instant,
+}
+
+lazy_static::lazy_static! {
+    // Cached lookups keyed by target name
+    static ref CREDENTIAL_CACHE: Mutex<HashMap<String, CachedCredential>> = Mutex::new(HashMap::new());
+}
+
+impl CredentialVault {
+    /// Retrieve a credential, reusing a cached lookup younger than `ttl`.
+    ///
+    /// A zero `ttl` bypasses the cache. Expired entries are evicted (and
+    /// zeroed) whenever the vault is read, so rotated credentials are picked
+    /// up after at most `ttl`.
+    ///
+    /// # Arguments
+    /// * `target_name` - The target name to look up
+    /// * `ttl` - Maximum age of a cached credential
+    pub fn get_cached_credential(target_name: &str, ttl: Duration) -> Result<Credential, VaultError> {
+        if ttl.is_zero() {
+            return Self::read_vault(target_name);
+        }
+
+        let mut cache = CREDENTIAL_CACHE.lock().unwrap();
+        if let Some(cached) = cache.get(target_name) {
+            if cached.fetched_at.elapsed() < ttl {
+                return Ok(cached.credential.clone());
+            }
+        }
+
+        cache.retain(|_, cached| cached.fetched_at.elapsed() < ttl);
+        let credential = Self::read_vault(target_name)?;
+        cache.insert(
+            target_name.to_string(),
+            CachedCredential {
+                credential: credential.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(credential)
+    }
+
+    /// Drop all cached credentials (zeroing them), e.g. after a token refresh.
+    pub fn clear_cache() {
+        CREDENTIAL_CACHE.lock().unwrap().clear();
+    }
+
+    /// Read the vault, using the mock credentials in mock mode on Windows.
+    fn read_vault(target_name: &str) -> Result<Credential, VaultError> {
+        #[cfg(all(windows, feature = "mock-mode"))]
+        {
+            return Self::get_mock_credential(target_name);
+        }
+
+        #[cfg(not(all(windows, feature = "mock-mode")))]
+        {
+            Self::get_credential(target_name)
+        }
+    }
+}
+
 #[cfg(windows)]
 mod windows_impl {
     use super::*;
@@ -337,4 +409,24 @@ mod tests {
         assert!(CredentialVault::credential_exists("DeploymentWorker:API"));
         assert!(!CredentialVault::credential_exists("NonExistent:Credential"));
     }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_cached_credential() {
+        let target = "DeploymentWorker:CacheTest";
+        let ttl = Duration::from_secs(60);
+        CredentialVault::add_mock_credential(target, "user", "old-password");
+        assert_eq!(CredentialVault::get_cached_credential(target, ttl).unwrap().password, "old-password");
+
+        // Rotated in the vault: cached value is reused until the TTL or a clear
+        CredentialVault::add_mock_credential(target, "user", "new-password");
+        assert_eq!(CredentialVault::get_cached_credential(target, ttl).unwrap().password, "old-password");
+        assert_eq!(
+            CredentialVault::get_cached_credential(target, Duration::ZERO).unwrap().password,
+            "new-password"
+        );
+
+        CredentialVault::clear_cache();
+        assert_eq!(CredentialVault::get_cached_credential(target, ttl).unwrap().password, "new-password");
+    }
 }
@@ -0,0 +1,179 @@
+//! Event log collection on remote machines.
+//!
+//! Reads the event log entries relevant to a deployment from a target over
+//! WMI (`root\cimv2`, class `Win32_NTLogEvent`):
+//! - `MsiInstaller` entries in the Application log (install result, errors)
+//! - `Service Control Manager` entries in the System log (the temporary
+//!   service failing to start or being blocked)
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use tracing::{instrument, warn};
+
+use crate::api::types::EventLogEntry;
+use crate::credentials::Credential;
+
+/// Errors from event log queries
+#[derive(Debug, Error)]
+pub enum EventLogError {
+    #[error("Event log query failed: {0}")]
+    QueryFailed(String),
+
+    #[error("Timeout during {operation}")]
+    Timeout { operation: String },
+}
+
+/// Format a timestamp as a WMI datetime (`yyyymmddHHMMSS.ffffff+000`, UTC).
+pub fn wmi_datetime(time: DateTime<Utc>) -> String {
+    format!("{}.000000+000", time.format("%Y%m%d%H%M%S"))
+}
+
+/// WQL query for deployment-related events generated since `since`.
+fn deployment_events_query(since: DateTime<Utc>) -> String {
+    format!(
+        "SELECT Logfile, SourceName, EventCode, Type, TimeGenerated, Message FROM Win32_NTLogEvent \
+         WHERE TimeGenerated >= '{}' AND ((Logfile = 'Application' AND SourceName = 'MsiInstaller') \
+         OR (Logfile = 'System' AND SourceName = 'Service Control Manager'))",
+        wmi_datetime(since)
+    )
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::*;
+    use crate::execution::security::{exec_remote_query, wmi_i32, wmi_string};
+    use tracing::debug;
+    use windows::core::w;
+
+    /// Query deployment-related events on a remote machine with explicit credentials.
+    #[instrument(skip(credentials))]
+    pub fn query_deployment_events_internal(
+        hostname: &str,
+        credentials: &Credential,
+        since: DateTime<Utc>,
+        max_entries: usize,
+        timeout: Duration,
+    ) -> Result<Vec<EventLogEntry>, EventLogError> {
+        let mut entries = Vec::new();
+        exec_remote_query(
+            hostname,
+            "root\\cimv2",
+            &deployment_events_query(since),
+            credentials,
+            timeout,
+            |object| unsafe {
+                if entries.len() >= max_entries {
+                    return;
+                }
+                entries.push(EventLogEntry {
+                    log_file: wmi_string(object, w!("Logfile")).unwrap_or_default(),
+                    source: wmi_string(object, w!("SourceName")).unwrap_or_default(),
+                    event_code: wmi_i32(object, w!("EventCode")).unwrap_or(0) as u32,
+                    entry_type: wmi_string(object, w!("Type")).unwrap_or_default(),
+                    time_generated: wmi_string(object, w!("TimeGenerated")).unwrap_or_default(),
+                    message: wmi_string(object, w!("Message")).unwrap_or_default(),
+                });
+            },
+        )
+        .map_err(|e| EventLogError::QueryFailed(e.to_string()))?;
+
+        debug!(host = %hostname, count = entries.len(), "Queried deployment events");
+        Ok(entries)
+    }
+}
+
+#[cfg(not(windows))]
+mod mock_impl {
+    use super::*;
+    use tracing::info;
+
+    /// Mock query (reports one successful MsiInstaller entry).
+    pub fn query_deployment_events_internal(
+        hostname: &str,
+        _credentials: &Credential,
+        since: DateTime<Utc>,
+        max_entries: usize,
+        _timeout: Duration,
+    ) -> Result<Vec<EventLogEntry>, EventLogError> {
+        info!("[MOCK] Would query: {}", deployment_events_query(since));
+        info!("[MOCK] on {}", hostname);
+        let entry = EventLogEntry {
+            log_file: "Application".to_string(),
+            source: "MsiInstaller".to_string(),
+            event_code: 11707,
+            entry_type: "Information".to_string(),
+            time_generated: wmi_datetime(Utc::now()),
+            message: "Product: Mock Product -- Installation completed successfully.".to_string(),
+        };
+        Ok(std::iter::once(entry).take(max_entries).collect())
+    }
+}
+
+#[cfg(windows)]
+use windows_impl::query_deployment_events_internal;
+
+#[cfg(not(windows))]
+use mock_impl::query_deployment_events_internal;
+
+/// Query the installer and service events generated on a target since `since`.
+///
+/// Runs the WMI query on a blocking thread bounded by `timeout`. DCOM calls
+/// cannot be cancelled, so on timeout the thread is abandoned.
+///
+/// # Arguments
+/// * `hostname` - Target machine
+/// * `credentials` - Credentials for the WMI connection
+/// * `since` - Earliest event to return
+/// * `max_entries` - Maximum number of entries to return
+/// * `timeout` - Maximum time for connect + query
+#[instrument(skip(credentials))]
+pub async fn query_deployment_events(
+    hostname: &str,
+    credentials: &Credential,
+    since: DateTime<Utc>,
+    max_entries: usize,
+    timeout: Duration,
+) -> Result<Vec<EventLogEntry>, EventLogError> {
+    let host = hostname.to_string();
+    let creds = credentials.clone();
+
+    let task = tokio::task::spawn_blocking(move || {
+        query_deployment_events_internal(&host, &creds, since, max_entries, timeout)
+    });
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| EventLogError::QueryFailed(format!("Task failed: {}", e)))?,
+        Err(_) => {
+            warn!(host = %hostname, timeout_secs = timeout.as_secs(), "Event log query timed out");
+            Err(EventLogError::Timeout {
+                operation: format!("event log query on {}", hostname),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_wmi_datetime() {
+        let time = Utc.with_ymd_and_hms(2025, 3, 7, 14, 5, 9).unwrap();
+        assert_eq!(wmi_datetime(time), "20250307140509.000000+000");
+        assert!(deployment_events_query(time).contains("TimeGenerated >= '20250307140509.000000+000'"));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_query_deployment_events_mock() {
+        let creds = Credential::new("DOMAIN\\user".to_string(), "secret".to_string());
+        let entries = query_deployment_events("target-01", &creds, Utc::now(), 10, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, "MsiInstaller");
+    }
+}
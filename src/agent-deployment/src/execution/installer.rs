@@ -6,7 +6,9 @@
 use thiserror::Error;
 use tracing::{debug, info};
 
-use crate::api::types::JobType;
+use uuid::Uuid;
+
+use crate::api::types::{JobType, MsiLogLevel};
 
 /// Errors from installer operations
 #[derive(Debug, Error)]
@@ -22,6 +24,9 @@ pub enum InstallerError {
 
     #[error("Invalid product code format: {0}")]
     InvalidProductCode(String),
+
+    #[error("Invalid environment variable: {0}")]
+    InvalidEnvironment(String),
 }
 
 /// MSI exit codes and their meanings
@@ -108,6 +113,8 @@ pub struct MsiCommandBuilder {
     no_restart: bool,
     /// Logging options
     log_file: Option<String>,
+    /// Log verbosity (used when logging is enabled)
+    log_level: MsiLogLevel,
 }
 
 impl MsiCommandBuilder {
@@ -125,6 +132,7 @@ impl MsiCommandBuilder {
             quiet: true,
             no_restart: true,
             log_file: None,
+            log_level: MsiLogLevel::Verbose,
         }
     }
 
@@ -157,12 +165,18 @@ impl MsiCommandBuilder {
         self
     }
 
-    /// Enable logging to a file.
+    /// Enable logging to a file (verbose unless `with_log_level` is used).
     pub fn with_logging(mut self, log_file: &str) -> Self {
         self.log_file = Some(log_file.to_string());
         self
     }
 
+    /// Set the log verbosity (default: verbose).
+    pub fn with_log_level(mut self, level: MsiLogLevel) -> Self {
+        self.log_level = level;
+        self
+    }
+
     /// Build the msiexec command line.
     ///
     /// # Returns
@@ -178,7 +192,7 @@ impl MsiCommandBuilder {
             JobType::MsiUninstall => {
                 cmd.push_str(" /x");
             }
-            JobType::Execute => {
+            JobType::Execute | JobType::Retry | JobType::ReadFile | JobType::CollectLogs | JobType::Validate => {
                 return Err(InstallerError::UnsupportedJobType(self.job_type));
             }
         }
@@ -198,7 +212,7 @@ impl MsiCommandBuilder {
 
         // Add logging if enabled
         if let Some(log_file) = &self.log_file {
-            cmd.push_str(&format!(" /l*v \"{}\"", log_file));
+            cmd.push_str(&format!(" {} \"{}\"", self.log_level.msiexec_flag(), log_file));
         }
 
         // Add custom arguments
@@ -227,6 +241,7 @@ impl MsiCommandBuilder {
 /// * `msi_path` - Path to the MSI file (local or UNC)
 /// * `install_args` - Optional additional arguments
 /// * `enroll_token` - Optional enrollment token
+/// * `logging` - Optional target-side log file and verbosity
 ///
 /// # Returns
 /// The complete msiexec command line.
@@ -234,9 +249,14 @@ pub fn build_msi_install_command(
     msi_path: &str,
     install_args: Option<&str>,
     enroll_token: Option<&str>,
+    logging: Option<(&str, MsiLogLevel)>,
 ) -> Result<String, InstallerError> {
     let mut builder = MsiCommandBuilder::new(msi_path, JobType::MsiInstall);
 
+    if let Some((log_file, level)) = logging {
+        builder = builder.with_logging(log_file).with_log_level(level);
+    }
+
     if let Some(args) = install_args {
         builder = builder.with_args(args);
     }
@@ -253,12 +273,14 @@ pub fn build_msi_install_command(
 /// # Arguments
 /// * `product_code` - MSI product code (GUID) or path to MSI
 /// * `install_args` - Optional additional arguments
+/// * `logging` - Optional target-side log file and verbosity
 ///
 /// # Returns
 /// The complete msiexec command line.
 pub fn build_msi_uninstall_command(
     product_code: &str,
     install_args: Option<&str>,
+    logging: Option<(&str, MsiLogLevel)>,
 ) -> Result<String, InstallerError> {
     // Validate product code format if it looks like a GUID
     if product_code.starts_with('{') && !is_valid_product_code(product_code) {
@@ -267,6 +289,10 @@ pub fn build_msi_uninstall_command(
 
     let mut builder = MsiCommandBuilder::new(product_code, JobType::MsiUninstall);
 
+    if let Some((log_file, level)) = logging {
+        builder = builder.with_logging(log_file).with_log_level(level);
+    }
+
     if let Some(args) = install_args {
         builder = builder.with_args(args);
     }
@@ -299,6 +325,133 @@ fn is_valid_product_code(code: &str) -> bool {
     true
 }
 
+/// Characters that cannot be safely escaped inside a quoted cmd.exe argument.
+///
+/// `"` ends the quoting and `%` triggers variable expansion even inside quotes;
+/// line breaks would start a new command.
+const CMD_UNSAFE_CHARS: &[char] = &['"', '%', '\r', '\n'];
+
+/// Check that an environment variable name is a plain identifier.
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Build a command line for an Execute job.
+///
+/// Prefixes the command with a change of working directory and environment
+/// variable assignments, e.g.
+/// `cd /d "C:\Scripts" && set "LOG_LEVEL=debug" && fix.cmd`.
+/// Values are quoted (`set "NAME=value"`) so `&`, `|`, `<`, `>` and `^` are
+/// literal; characters that cannot be escaped are rejected.
+///
+/// # Arguments
+/// * `command` - The command to run
+/// * `working_directory` - Optional absolute working directory on the target
+/// * `environment` - Environment variables as (name, value) pairs
+///
+/// # Returns
+/// The command line (wrap with `wrap_for_service_execution` to run it).
+pub fn build_execute_command(
+    command: &str,
+    working_directory: Option<&str>,
+    environment: &[(String, String)],
+) -> Result<String, InstallerError> {
+    if command.trim().is_empty() {
+        return Err(InstallerError::MissingParameter("command".to_string()));
+    }
+
+    let mut parts = Vec::with_capacity(environment.len() + 2);
+
+    if let Some(dir) = working_directory {
+        let is_absolute = dir.len() >= 3
+            && dir.as_bytes()[0].is_ascii_alphabetic()
+            && dir[1..].starts_with(":\\");
+        if !is_absolute || dir.contains(CMD_UNSAFE_CHARS) {
+            return Err(InstallerError::InvalidPath(format!(
+                "Working directory must be an absolute path without quotes or %: {}",
+                dir
+            )));
+        }
+        parts.push(format!("cd /d \"{}\"", dir));
+    }
+
+    for (name, value) in environment {
+        if !is_valid_env_name(name) {
+            return Err(InstallerError::InvalidEnvironment(format!("invalid name '{}'", name)));
+        }
+        if value.contains(CMD_UNSAFE_CHARS) {
+            return Err(InstallerError::InvalidEnvironment(format!(
+                "value of '{}' contains quotes, % or line breaks",
+                name
+            )));
+        }
+        parts.push(format!("set \"{}={}\"", name, value));
+    }
+
+    parts.push(command.to_string());
+
+    let cmd = parts.join(" && ");
+    debug!(command = %cmd, "Built execute command");
+    Ok(cmd)
+}
+
+/// Build a command turning Defender real-time protection off or on.
+///
+/// The setting is read back afterwards because Tamper Protection silently
+/// ignores the change; the command exits with 1 if it did not take effect.
+///
+/// # Returns
+/// The command line (wrap with `wrap_for_service_execution` to run it).
+pub fn build_defender_realtime_command(disable: bool) -> String {
+    let value = if disable { "$true" } else { "$false" };
+    format!(
+        "powershell.exe -NoProfile -NonInteractive -Command \"Set-MpPreference -DisableRealtimeMonitoring {0}; \
+         if ((Get-MpPreference).DisableRealtimeMonitoring -ne {0}) {{ exit 1 }}\"",
+        value
+    )
+}
+
+/// Firewall rule groups needed for deployment, by their indirect resource
+/// names so they match on localized Windows: "File and Printer Sharing"
+/// (SMB to ADMIN$) and "Remote Service Management" (SCM over RPC).
+pub const DEPLOY_FIREWALL_GROUPS: &[&str] = &["@FirewallAPI.dll,-28502", "@FirewallAPI.dll,-29502"];
+
+/// Build a command turning the deployment firewall rules on, or back off.
+///
+/// Enabling only touches inbound rules of `DEPLOY_FIREWALL_GROUPS` that are
+/// off, and saves their names to a state file on the target; restoring turns
+/// exactly those rules back off and removes the file. So rules the user had
+/// on stay on, which `netsh advfirewall firewall set rule group=...` (group
+/// granularity only) cannot guarantee.
+///
+/// # Returns
+/// The command line (wrap with `wrap_for_service_execution` to run it).
+pub fn build_firewall_rules_command(job_id: Uuid, enable: bool) -> String {
+    let state_file = format!("C:\\Windows\\Temp\\deploy-fw-{}.txt", job_id);
+    let script = if enable {
+        let groups = DEPLOY_FIREWALL_GROUPS
+            .iter()
+            .map(|group| format!("'{}'", group))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "$r = @(Get-NetFirewallRule -Group {} | Where-Object {{ $_.Direction -eq 'Inbound' -and $_.Enabled -eq 'False' }}); \
+             [IO.File]::WriteAllLines('{}', [string[]]@($r | ForEach-Object Name)); \
+             if ($r.Count -gt 0) {{ $r | Enable-NetFirewallRule }}",
+            groups, state_file
+        )
+    } else {
+        format!(
+            "if (Test-Path '{0}') {{ $n = @(Get-Content '{0}'); \
+             if ($n.Count -gt 0) {{ Disable-NetFirewallRule -Name $n }}; Remove-Item '{0}' }}",
+            state_file
+        )
+    };
+    format!("powershell.exe -NoProfile -NonInteractive -Command \"{}\"", script)
+}
+
 /// Build a command for executing via remote service.
 ///
 /// This wraps the MSI command in cmd.exe for execution via Windows service.
@@ -312,6 +465,19 @@ pub fn wrap_for_service_execution(msi_command: &str) -> String {
     format!("cmd.exe /c {}", msi_command)
 }
 
+/// Build a service command that also captures the command's console output.
+///
+/// Like `wrap_for_service_execution`, with stdout and stderr redirected to
+/// files on the target so log collection can pick them up.
+///
+/// # Arguments
+/// * `command` - The command line to run
+/// * `stdout_path` - Target-local path for stdout
+/// * `stderr_path` - Target-local path for stderr
+pub fn wrap_for_service_execution_with_output(command: &str, stdout_path: &str, stderr_path: &str) -> String {
+    format!("cmd.exe /c {} > \"{}\" 2> \"{}\"", command, stdout_path, stderr_path)
+}
+
 /// Get the remote log file path for an installation.
 ///
 /// # Arguments
@@ -325,6 +491,36 @@ pub fn get_remote_log_path(target_hostname: &str, msi_filename: &str) -> String
     format!("\\\\{}\\ADMIN$\\Temp\\{}", target_hostname, log_name)
 }
 
+/// Get the MSI log file name for a job.
+///
+/// Named after the job so repeated runs do not overwrite each other's logs.
+pub fn get_msi_log_name(job_id: Uuid, job_type: JobType) -> String {
+    let operation = match job_type {
+        JobType::MsiUninstall => "uninstall",
+        _ => "install",
+    };
+    format!("msi-{}-{}.log", operation, job_id)
+}
+
+/// Get the names of the files a job's service command writes its stdout and stderr to.
+///
+/// Kept in ADMIN$\Temp next to the MSI log of the same job.
+pub fn get_service_output_names(job_id: Uuid, job_type: JobType) -> [String; 2] {
+    let operation = match job_type {
+        JobType::MsiUninstall => "uninstall",
+        _ => "install",
+    };
+    ["stdout", "stderr"].map(|stream| format!("service-{}-{}.{}.log", operation, job_id, stream))
+}
+
+/// Get the target-local path of a log file in ADMIN$\Temp.
+///
+/// # Returns
+/// Path as seen by msiexec on the target (`C:\Windows\Temp\...`).
+pub fn get_local_log_path(log_name: &str) -> String {
+    format!("C:\\Windows\\Temp\\{}", log_name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,6 +531,7 @@ mod tests {
             "C:\\Windows\\Temp\\installer.msi",
             Some("/norestart"),
             Some("token123"),
+            None,
         )
         .unwrap();
 
@@ -350,6 +547,7 @@ mod tests {
         let cmd = build_msi_uninstall_command(
             "{12345678-1234-1234-1234-123456789012}",
             None,
+            None,
         )
         .unwrap();
 
@@ -360,7 +558,7 @@ mod tests {
 
     #[test]
     fn test_invalid_product_code() {
-        let result = build_msi_uninstall_command("{invalid-guid}", None);
+        let result = build_msi_uninstall_command("{invalid-guid}", None, None);
         assert!(matches!(result, Err(InstallerError::InvalidProductCode(_))));
     }
 
@@ -378,6 +576,33 @@ mod tests {
         assert_eq!(cmd, "cmd.exe /c msiexec /i test.msi /qn");
     }
 
+    #[test]
+    fn test_build_defender_realtime_command() {
+        let disable = build_defender_realtime_command(true);
+        assert!(disable.starts_with("powershell.exe -NoProfile -NonInteractive -Command \""));
+        assert!(disable.contains("Set-MpPreference -DisableRealtimeMonitoring $true; if"));
+        assert!(disable.contains("-ne $true) { exit 1 }\""));
+
+        let enable = build_defender_realtime_command(false);
+        assert!(enable.contains("-DisableRealtimeMonitoring $false"));
+        assert!(!enable.contains("$true"));
+    }
+
+    #[test]
+    fn test_build_firewall_rules_command() {
+        let job_id = Uuid::nil();
+        let enable = build_firewall_rules_command(job_id, true);
+        assert!(enable.starts_with("powershell.exe -NoProfile -NonInteractive -Command \""));
+        assert!(enable.contains("Get-NetFirewallRule -Group '@FirewallAPI.dll,-28502','@FirewallAPI.dll,-29502'"));
+        assert!(enable.contains("'C:\\Windows\\Temp\\deploy-fw-00000000-0000-0000-0000-000000000000.txt'"));
+        assert!(enable.contains("Enable-NetFirewallRule"));
+        assert!(!enable.contains("Disable-NetFirewallRule"));
+
+        let restore = build_firewall_rules_command(job_id, false);
+        assert!(restore.contains("Disable-NetFirewallRule -Name $n"));
+        assert!(restore.contains("Remove-Item 'C:\\Windows\\Temp\\deploy-fw-"));
+    }
+
     #[test]
     fn test_valid_product_code() {
         assert!(is_valid_product_code("{12345678-1234-1234-1234-123456789012}"));
@@ -403,4 +628,83 @@ mod tests {
         assert!(cmd.contains("KEY2=\"value with space\""));
         assert!(cmd.contains("/l*v"));
     }
+
+    #[test]
+    fn test_build_execute_command() {
+        let env = vec![
+            ("LOG_LEVEL".to_string(), "debug".to_string()),
+            ("TARGETS".to_string(), "a&b|c".to_string()),
+        ];
+        let cmd = build_execute_command("fix.cmd", Some("C:\\Scripts"), &env).unwrap();
+        assert_eq!(
+            cmd,
+            "cd /d \"C:\\Scripts\" && set \"LOG_LEVEL=debug\" && set \"TARGETS=a&b|c\" && fix.cmd"
+        );
+
+        // Plain command without working directory or environment
+        assert_eq!(build_execute_command("fix.cmd", None, &[]).unwrap(), "fix.cmd");
+    }
+
+    #[test]
+    fn test_build_execute_command_rejects_injection() {
+        let quote = vec![("X".to_string(), "a\" & calc & \"".to_string())];
+        assert!(matches!(
+            build_execute_command("fix.cmd", None, &quote),
+            Err(InstallerError::InvalidEnvironment(_))
+        ));
+
+        let bad_name = vec![("X=1 & calc".to_string(), "a".to_string())];
+        assert!(matches!(
+            build_execute_command("fix.cmd", None, &bad_name),
+            Err(InstallerError::InvalidEnvironment(_))
+        ));
+
+        let percent = vec![("X".to_string(), "%PATH%".to_string())];
+        assert!(build_execute_command("fix.cmd", None, &percent).is_err());
+
+        assert!(matches!(
+            build_execute_command("fix.cmd", Some("C:\\a\" & calc"), &[]),
+            Err(InstallerError::InvalidPath(_))
+        ));
+        assert!(build_execute_command("fix.cmd", Some("relative\\dir"), &[]).is_err());
+    }
+
+    #[test]
+    fn test_msi_log_level() {
+        let cmd = build_msi_install_command(
+            "C:\\installer.msi",
+            None,
+            None,
+            Some(("C:\\Windows\\Temp\\install.log", MsiLogLevel::Errors)),
+        )
+        .unwrap();
+        assert!(cmd.contains("/le \"C:\\Windows\\Temp\\install.log\""));
+        assert!(!cmd.contains("/l*v"));
+    }
+
+    #[test]
+    fn test_msi_log_paths() {
+        let job_id = Uuid::nil();
+        let name = get_msi_log_name(job_id, JobType::MsiUninstall);
+        assert_eq!(name, format!("msi-uninstall-{}.log", job_id));
+        assert_eq!(
+            get_local_log_path(&name),
+            format!("C:\\Windows\\Temp\\{}", name)
+        );
+        assert_eq!(
+            get_remote_log_path("target-01", &name),
+            format!("\\\\target-01\\ADMIN$\\Temp\\{}", name)
+        );
+    }
+
+    #[test]
+    fn test_service_output_capture() {
+        let job_id = Uuid::nil();
+        let [stdout, stderr] = get_service_output_names(job_id, JobType::MsiInstall);
+        assert_eq!(stdout, format!("service-install-{}.stdout.log", job_id));
+        assert_eq!(stderr, format!("service-install-{}.stderr.log", job_id));
+
+        let cmd = wrap_for_service_execution_with_output("msiexec /i a.msi", "C:\\out.log", "C:\\err.log");
+        assert_eq!(cmd, "cmd.exe /c msiexec /i a.msi > \"C:\\out.log\" 2> \"C:\\err.log\"");
+    }
 }
@@ -1,7 +1,15 @@
+pub mod events;
 pub mod installer;
+pub mod platform;
+pub mod security;
 pub mod service;
 pub mod smb;
+pub mod wol;
 
+pub use events::*;
 pub use installer::*;
+pub use platform::*;
+pub use security::*;
 pub use service::*;
 pub use smb::*;
+pub use wol::*;
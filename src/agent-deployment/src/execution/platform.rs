@@ -0,0 +1,195 @@
+//! Virtual machine detection on remote machines.
+//!
+//! Reads the system information of a target over WMI (`root\cimv2`, class
+//! `Win32_ComputerSystem`) to tell virtual machines from physical hardware,
+//! so the backend can apply VM-specific logic (activation, hardware-bound
+//! installers) or keep VMs out of a rollout.
+//!
+//! The hypervisor is identified from the reported manufacturer/model.
+//! `HypervisorPresent` (the CPUID hypervisor bit) is reported as well but is
+//! not proof of a VM on its own: physical hosts running Hyper-V or
+//! virtualization-based security set it too.
+
+use std::time::Duration;
+
+use thiserror::Error;
+use tracing::{instrument, warn};
+
+use crate::api::types::VirtualizationInfo;
+use crate::credentials::Credential;
+
+/// Errors from virtualization queries
+#[derive(Debug, Error)]
+pub enum PlatformError {
+    #[error("System information query failed: {0}")]
+    QueryFailed(String),
+
+    #[error("Timeout during {operation}")]
+    Timeout { operation: String },
+}
+
+/// Known hypervisors: (substring of manufacturer or model, lowercase; name)
+const HYPERVISOR_SIGNATURES: &[(&str, &str)] = &[
+    ("vmware", "VMware"),
+    ("virtualbox", "VirtualBox"),
+    ("innotek", "VirtualBox"),
+    ("qemu", "QEMU/KVM"),
+    ("kvm", "QEMU/KVM"),
+    ("xen", "Xen"),
+    ("parallels", "Parallels"),
+    ("amazon ec2", "Amazon EC2"),
+    ("google compute engine", "Google Compute Engine"),
+    ("nutanix", "Nutanix AHV"),
+    // Hyper-V (and Azure) guests report "Microsoft Corporation" / "Virtual Machine"
+    ("virtual machine", "Hyper-V"),
+    ("virtual", "Unknown"),
+];
+
+/// Identify the hypervisor from `Win32_ComputerSystem` manufacturer and model.
+///
+/// # Returns
+/// The hypervisor name, or None for physical hardware
+pub fn detect_hypervisor(manufacturer: &str, model: &str) -> Option<&'static str> {
+    let haystack = format!("{} {}", manufacturer, model).to_lowercase();
+    HYPERVISOR_SIGNATURES
+        .iter()
+        .find(|(signature, _)| haystack.contains(signature))
+        .map(|&(_, name)| name)
+}
+
+/// Build the report from the raw `Win32_ComputerSystem` values.
+fn virtualization_info(manufacturer: String, model: String, hypervisor_present: Option<bool>) -> VirtualizationInfo {
+    let hypervisor = detect_hypervisor(&manufacturer, &model).map(str::to_string);
+    VirtualizationInfo {
+        is_virtual_machine: hypervisor.is_some(),
+        hypervisor,
+        manufacturer,
+        model,
+        hypervisor_present,
+    }
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::*;
+    use crate::execution::security::{exec_remote_query, wmi_bool, wmi_string};
+    use tracing::debug;
+    use windows::core::w;
+
+    /// Query `Win32_ComputerSystem` on a remote machine with explicit credentials.
+    #[instrument(skip(credentials))]
+    pub fn query_virtualization_internal(
+        hostname: &str,
+        credentials: &Credential,
+        timeout: Duration,
+    ) -> Result<VirtualizationInfo, PlatformError> {
+        let mut info = None;
+        exec_remote_query(
+            hostname,
+            "root\\cimv2",
+            "SELECT Manufacturer, Model, HypervisorPresent FROM Win32_ComputerSystem",
+            credentials,
+            timeout,
+            |object| unsafe {
+                if info.is_none() {
+                    info = Some(virtualization_info(
+                        wmi_string(object, w!("Manufacturer")).unwrap_or_default(),
+                        wmi_string(object, w!("Model")).unwrap_or_default(),
+                        wmi_bool(object, w!("HypervisorPresent")),
+                    ));
+                }
+            },
+        )
+        .map_err(|e| PlatformError::QueryFailed(e.to_string()))?;
+
+        let info = info.ok_or_else(|| PlatformError::QueryFailed("Win32_ComputerSystem returned no instance".to_string()))?;
+        debug!(host = %hostname, vm = info.is_virtual_machine, hypervisor = ?info.hypervisor, "Queried virtualization");
+        Ok(info)
+    }
+}
+
+#[cfg(not(windows))]
+mod mock_impl {
+    use super::*;
+    use tracing::info;
+
+    /// Mock query (reports a VMware virtual machine).
+    pub fn query_virtualization_internal(
+        hostname: &str,
+        _credentials: &Credential,
+        _timeout: Duration,
+    ) -> Result<VirtualizationInfo, PlatformError> {
+        info!("[MOCK] Would query Win32_ComputerSystem on {}", hostname);
+        Ok(virtualization_info(
+            "VMware, Inc.".to_string(),
+            "VMware7,1".to_string(),
+            Some(true),
+        ))
+    }
+}
+
+#[cfg(windows)]
+use windows_impl::query_virtualization_internal;
+
+#[cfg(not(windows))]
+use mock_impl::query_virtualization_internal;
+
+/// Determine whether a target is a virtual machine.
+///
+/// Runs the WMI query on a blocking thread bounded by `timeout`. DCOM calls
+/// cannot be cancelled, so on timeout the thread is abandoned.
+///
+/// # Arguments
+/// * `hostname` - Target machine
+/// * `credentials` - Credentials for the WMI connection
+/// * `timeout` - Maximum time for connect + query
+#[instrument(skip(credentials))]
+pub async fn query_virtualization(
+    hostname: &str,
+    credentials: &Credential,
+    timeout: Duration,
+) -> Result<VirtualizationInfo, PlatformError> {
+    let host = hostname.to_string();
+    let creds = credentials.clone();
+
+    let task = tokio::task::spawn_blocking(move || query_virtualization_internal(&host, &creds, timeout));
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| PlatformError::QueryFailed(format!("Task failed: {}", e)))?,
+        Err(_) => {
+            warn!(host = %hostname, timeout_secs = timeout.as_secs(), "Virtualization query timed out");
+            Err(PlatformError::Timeout {
+                operation: format!("virtualization query on {}", hostname),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_hypervisor() {
+        assert_eq!(detect_hypervisor("VMware, Inc.", "VMware7,1"), Some("VMware"));
+        assert_eq!(detect_hypervisor("innotek GmbH", "VirtualBox"), Some("VirtualBox"));
+        assert_eq!(detect_hypervisor("Microsoft Corporation", "Virtual Machine"), Some("Hyper-V"));
+        assert_eq!(detect_hypervisor("QEMU", "Standard PC (Q35 + ICH9, 2009)"), Some("QEMU/KVM"));
+        assert_eq!(detect_hypervisor("Xen", "HVM domU"), Some("Xen"));
+        assert_eq!(detect_hypervisor("Dell Inc.", "OptiPlex 7090"), None);
+        assert_eq!(detect_hypervisor("LENOVO", "20XW0026US"), None);
+        // A physical Surface is "Microsoft Corporation" too
+        assert_eq!(detect_hypervisor("Microsoft Corporation", "Surface Laptop 4"), None);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_query_virtualization_mock() {
+        let creds = Credential::new("DOMAIN\\user".to_string(), "secret".to_string());
+        let info = query_virtualization("target-01", &creds, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(info.is_virtual_machine);
+        assert_eq!(info.hypervisor.as_deref(), Some("VMware"));
+    }
+}
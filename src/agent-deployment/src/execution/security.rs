@@ -0,0 +1,391 @@
+//! Security product detection on remote machines.
+//!
+//! Queries the antivirus/EDR products registered with Windows Security
+//! Center on a target (WMI `root\SecurityCenter2`, class `AntiVirusProduct`)
+//! so failed deployments can be correlated with the security tooling that
+//! may have blocked the temporary service or msiexec.
+//!
+//! Security Center only exists on client editions of Windows; servers report
+//! `SecurityError::QueryFailed` (invalid namespace).
+
+use std::time::Duration;
+
+use thiserror::Error;
+use tracing::{instrument, warn};
+
+use crate::api::types::SecurityProduct;
+use crate::credentials::Credential;
+
+/// Errors from security product queries
+#[derive(Debug, Error)]
+pub enum SecurityError {
+    #[error("Failed to connect to WMI on {host}: {message}")]
+    ConnectionFailed { host: String, message: String },
+
+    #[error("Security Center query failed: {0}")]
+    QueryFailed(String),
+
+    #[error("Timeout during {operation}")]
+    Timeout { operation: String },
+}
+
+/// Decode Security Center's `productState` bit field.
+///
+/// The second byte holds the scanner state (0x10 = on) and the low byte the
+/// signature state (0x00 = up to date, 0x10 = out of date).
+///
+/// # Returns
+/// `(enabled, up_to_date)`
+pub fn decode_product_state(state: u32) -> (bool, bool) {
+    (state & 0x1000 != 0, state & 0x10 == 0)
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::*;
+    use tracing::debug;
+    use windows::core::{w, IUnknown, Param, BSTR, PCWSTR, VARIANT};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoSetProxyBlanket, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COAUTHIDENTITY, COINIT_MULTITHREADED, EOAC_NONE, RPC_C_AUTHN_LEVEL_PKT_PRIVACY,
+        RPC_C_IMP_LEVEL_IMPERSONATE,
+    };
+    use windows::Win32::System::Rpc::{RPC_C_AUTHN_WINNT, RPC_C_AUTHZ_NONE};
+    use windows::Win32::System::Wmi::{
+        IWbemClassObject, IWbemLocator, WbemLocator, WBEM_FLAG_FORWARD_ONLY,
+        WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_TIMEOUT_TYPE,
+    };
+
+    /// `SEC_WINNT_AUTH_IDENTITY_UNICODE`
+    const AUTH_IDENTITY_UNICODE: u32 = 2;
+
+    fn to_utf16(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    /// Make calls through a WMI proxy authenticate as `identity`.
+    ///
+    /// Proxies to a remote machine need the identity set explicitly,
+    /// otherwise calls go out as the worker's service account.
+    unsafe fn set_proxy_identity<P: Param<IUnknown>>(
+        proxy: P,
+        identity: &COAUTHIDENTITY,
+    ) -> Result<(), SecurityError> {
+        CoSetProxyBlanket(
+            proxy,
+            RPC_C_AUTHN_WINNT,
+            RPC_C_AUTHZ_NONE,
+            PCWSTR::null(),
+            RPC_C_AUTHN_LEVEL_PKT_PRIVACY,
+            RPC_C_IMP_LEVEL_IMPERSONATE,
+            Some(identity as *const COAUTHIDENTITY as *const _),
+            EOAC_NONE,
+        )
+        .map_err(|e| SecurityError::QueryFailed(format!("Failed to set WMI proxy security: {}", e)))
+    }
+
+    /// Read a string property of a WMI object (None if missing or null).
+    pub unsafe fn wmi_string(object: &IWbemClassObject, name: PCWSTR) -> Option<String> {
+        let mut value = VARIANT::default();
+        object.Get(name, 0, &mut value, None, None).ok()?;
+        BSTR::try_from(&value).ok().map(|s| s.to_string()).filter(|s| !s.is_empty())
+    }
+
+    /// Read an integer property of a WMI object (None if missing or null).
+    pub unsafe fn wmi_i32(object: &IWbemClassObject, name: PCWSTR) -> Option<i32> {
+        let mut value = VARIANT::default();
+        object.Get(name, 0, &mut value, None, None).ok()?;
+        i32::try_from(&value).ok()
+    }
+
+    /// Read a boolean property of a WMI object (None if missing or null).
+    pub unsafe fn wmi_bool(object: &IWbemClassObject, name: PCWSTR) -> Option<bool> {
+        let mut value = VARIANT::default();
+        object.Get(name, 0, &mut value, None, None).ok()?;
+        bool::try_from(&value).ok()
+    }
+
+    /// Run a WQL query in `namespace` on this machine as the worker's own
+    /// account, calling `visit` for each returned object.
+    ///
+    /// `timeout` bounds each wait for the next result object.
+    pub fn exec_local_query(
+        namespace: &str,
+        wql: &str,
+        timeout: Duration,
+        mut visit: impl FnMut(&IWbemClassObject),
+    ) -> Result<(), SecurityError> {
+        unsafe {
+            // S_FALSE = already initialized, still needs uninit
+            let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+            let result = (|| {
+                let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| SecurityError::QueryFailed(format!("Failed to create WMI locator: {}", e)))?;
+
+                let services = locator
+                    .ConnectServer(
+                        &BSTR::from(namespace),
+                        &BSTR::new(),
+                        &BSTR::new(),
+                        &BSTR::new(),
+                        0,
+                        &BSTR::new(),
+                        None,
+                    )
+                    .map_err(|e| SecurityError::ConnectionFailed {
+                        host: "localhost".to_string(),
+                        message: e.to_string(),
+                    })?;
+
+                let results = services
+                    .ExecQuery(
+                        &BSTR::from("WQL"),
+                        &BSTR::from(wql),
+                        WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+                        None,
+                    )
+                    .map_err(|e| SecurityError::QueryFailed(e.to_string()))?;
+
+                let next_timeout = WBEM_TIMEOUT_TYPE(timeout.as_millis().min(i32::MAX as u128) as i32);
+                loop {
+                    let mut objects: [Option<IWbemClassObject>; 1] = [None];
+                    let mut returned = 0u32;
+                    let _ = results.Next(next_timeout, &mut objects, &mut returned);
+                    let Some(object) = objects[0].take().filter(|_| returned > 0) else {
+                        break;
+                    };
+                    visit(&object);
+                }
+
+                Ok(())
+            })();
+
+            if com_initialized {
+                CoUninitialize();
+            }
+
+            result
+        }
+    }
+
+    /// Run a WQL query in `namespace` on a remote machine with explicit
+    /// credentials, calling `visit` for each returned object.
+    ///
+    /// `timeout` bounds each wait for the next result object.
+    pub fn exec_remote_query(
+        hostname: &str,
+        namespace: &str,
+        wql: &str,
+        credentials: &Credential,
+        timeout: Duration,
+        mut visit: impl FnMut(&IWbemClassObject),
+    ) -> Result<(), SecurityError> {
+        // DOMAIN\user -> (DOMAIN, user)
+        let (domain, user) = match credentials.username.split_once('\\') {
+            Some((domain, user)) => (domain.to_string(), user.to_string()),
+            None => (String::new(), credentials.username.clone()),
+        };
+        let mut user_wide = to_utf16(&user);
+        let mut domain_wide = to_utf16(&domain);
+        let mut password_wide = to_utf16(&credentials.password);
+
+        let result = unsafe {
+            // S_FALSE = already initialized, still needs uninit
+            let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+            let result = (|| {
+                let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)
+                    .map_err(|e| SecurityError::QueryFailed(format!("Failed to create WMI locator: {}", e)))?;
+
+                let services = locator
+                    .ConnectServer(
+                        &BSTR::from(format!("\\\\{}\\{}", hostname, namespace)),
+                        &BSTR::from(credentials.username.as_str()),
+                        &BSTR::from(credentials.password.as_str()),
+                        &BSTR::new(),
+                        0,
+                        &BSTR::new(),
+                        None,
+                    )
+                    .map_err(|e| SecurityError::ConnectionFailed {
+                        host: hostname.to_string(),
+                        message: e.to_string(),
+                    })?;
+
+                let identity = COAUTHIDENTITY {
+                    User: user_wide.as_mut_ptr(),
+                    UserLength: user_wide.len() as u32,
+                    Domain: domain_wide.as_mut_ptr(),
+                    DomainLength: domain_wide.len() as u32,
+                    Password: password_wide.as_mut_ptr(),
+                    PasswordLength: password_wide.len() as u32,
+                    Flags: AUTH_IDENTITY_UNICODE,
+                };
+                set_proxy_identity(&services, &identity)?;
+
+                let results = services
+                    .ExecQuery(
+                        &BSTR::from("WQL"),
+                        &BSTR::from(wql),
+                        WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+                        None,
+                    )
+                    .map_err(|e| SecurityError::QueryFailed(e.to_string()))?;
+                set_proxy_identity(&results, &identity)?;
+
+                let next_timeout = WBEM_TIMEOUT_TYPE(timeout.as_millis().min(i32::MAX as u128) as i32);
+                loop {
+                    let mut objects: [Option<IWbemClassObject>; 1] = [None];
+                    let mut returned = 0u32;
+                    let _ = results.Next(next_timeout, &mut objects, &mut returned);
+                    let Some(object) = objects[0].take().filter(|_| returned > 0) else {
+                        break;
+                    };
+                    visit(&object);
+                }
+
+                Ok(())
+            })();
+
+            if com_initialized {
+                CoUninitialize();
+            }
+
+            result
+        };
+
+        // Don't leave the password in the UTF-16 copy
+        password_wide.fill(0);
+
+        result
+    }
+
+    /// Query `AntiVirusProduct` on a remote machine with explicit credentials.
+    #[instrument(skip(credentials))]
+    pub fn query_security_products_internal(
+        hostname: &str,
+        credentials: &Credential,
+        timeout: Duration,
+    ) -> Result<Vec<SecurityProduct>, SecurityError> {
+        let mut products = Vec::new();
+        let result = exec_remote_query(
+            hostname,
+            "root\\SecurityCenter2",
+            "SELECT displayName, productState, pathToSignedProductExe FROM AntiVirusProduct",
+            credentials,
+            timeout,
+            |object| unsafe {
+                let Some(name) = wmi_string(object, w!("displayName")) else {
+                    return;
+                };
+                let product_state = wmi_i32(object, w!("productState")).unwrap_or(0) as u32;
+                let (enabled, up_to_date) = decode_product_state(product_state);
+
+                products.push(SecurityProduct {
+                    name,
+                    enabled,
+                    up_to_date,
+                    product_state,
+                    executable: wmi_string(object, w!("pathToSignedProductExe")),
+                });
+            },
+        )
+        .map(|_| products);
+
+        debug!(host = %hostname, count = result.as_ref().map(|p| p.len()).unwrap_or(0), "Queried security products");
+        result
+    }
+}
+
+#[cfg(not(windows))]
+mod mock_impl {
+    use super::*;
+    use tracing::info;
+
+    /// Mock query (reports Windows Defender, enabled and up to date).
+    pub fn query_security_products_internal(
+        hostname: &str,
+        _credentials: &Credential,
+        _timeout: Duration,
+    ) -> Result<Vec<SecurityProduct>, SecurityError> {
+        info!("[MOCK] Would query security products on {}", hostname);
+        let product_state = 0x61100;
+        let (enabled, up_to_date) = decode_product_state(product_state);
+        Ok(vec![SecurityProduct {
+            name: "Windows Defender".to_string(),
+            enabled,
+            up_to_date,
+            product_state,
+            executable: Some("windowsdefender://".to_string()),
+        }])
+    }
+}
+
+#[cfg(windows)]
+use windows_impl::query_security_products_internal;
+
+#[cfg(windows)]
+pub(crate) use windows_impl::{exec_local_query, exec_remote_query, wmi_bool, wmi_i32, wmi_string};
+
+#[cfg(not(windows))]
+use mock_impl::query_security_products_internal;
+
+/// Query the security products registered on a target.
+///
+/// Runs the WMI query on a blocking thread bounded by `timeout`. DCOM calls
+/// cannot be cancelled, so on timeout the thread is abandoned.
+///
+/// # Arguments
+/// * `hostname` - Target machine
+/// * `credentials` - Credentials for the WMI connection
+/// * `timeout` - Maximum time for connect + query
+#[instrument(skip(credentials))]
+pub async fn query_security_products(
+    hostname: &str,
+    credentials: &Credential,
+    timeout: Duration,
+) -> Result<Vec<SecurityProduct>, SecurityError> {
+    let host = hostname.to_string();
+    let creds = credentials.clone();
+
+    let task = tokio::task::spawn_blocking(move || query_security_products_internal(&host, &creds, timeout));
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| SecurityError::QueryFailed(format!("Task failed: {}", e)))?,
+        Err(_) => {
+            warn!(host = %hostname, timeout_secs = timeout.as_secs(), "Security product query timed out");
+            Err(SecurityError::Timeout {
+                operation: format!("security product query on {}", hostname),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_product_state() {
+        // Windows Defender: on, up to date
+        assert_eq!(decode_product_state(0x61100), (true, true));
+        // Off, up to date
+        assert_eq!(decode_product_state(0x60100), (false, true));
+        // On, out of date
+        assert_eq!(decode_product_state(0x61010), (true, false));
+        // Off, out of date
+        assert_eq!(decode_product_state(0x60110), (false, false));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_query_security_products_mock() {
+        let creds = Credential::new("DOMAIN\\user".to_string(), "secret".to_string());
+        let products = query_security_products("target-01", &creds, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(products.len(), 1);
+        assert!(products[0].enabled);
+    }
+}
@@ -7,6 +7,7 @@ use std::time::Duration;
 
 use thiserror::Error;
 use tracing::{debug, error, info, instrument, warn};
+#[cfg(any(windows, test))]
 use uuid::Uuid;
 
 use crate::credentials::Credential;
@@ -46,16 +47,83 @@ pub struct ServiceExecutionResult {
     pub exit_code: i32,
     /// Duration of execution
     pub duration: Duration,
-    /// Whether cleanup was successful
+    /// Whether the temporary service was deleted (verified after deletion)
     pub cleanup_success: bool,
+    /// Orphaned temporary services from earlier runs removed from the target
+    pub orphans_removed: Vec<String>,
 }
 
+/// Name prefix of the temporary services created by this worker
+#[cfg(any(windows, test))]
+pub const TEMP_SERVICE_PREFIX: &str = "DeployWorker_";
+
+/// Minimum age of a temporary service before it is swept as an orphan.
+///
+/// Far longer than any execution timeout, so a service created by another
+/// worker or a concurrent job targeting the same host is never removed
+/// between its creation and start.
+#[cfg(any(windows, test))]
+pub const ORPHAN_MIN_AGE: Duration = Duration::from_secs(24 * 3600);
+
 /// Generate a unique temporary service name.
 ///
-/// Format: DeployWorker_XXXXXXXX where X is a UUID short form.
+/// Format: DeployWorker_<creation time, Unix seconds in hex>_XXXXXXXX where
+/// X is a UUID short form.
+#[cfg(any(windows, test))]
 pub fn generate_temp_service_name() -> String {
     let uuid = Uuid::new_v4();
-    format!("DeployWorker_{}", &uuid.simple().to_string()[..8])
+    format!(
+        "{}{:x}_{}",
+        TEMP_SERVICE_PREFIX,
+        unix_time_secs(),
+        &uuid.simple().to_string()[..8]
+    )
+}
+
+/// Current time in Unix seconds
+#[cfg(any(windows, test))]
+fn unix_time_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Creation time (Unix seconds) encoded in one of our temporary service names.
+///
+/// None for other names and for names from older workers, which carry no
+/// creation time (`DeployWorker_XXXXXXXX`).
+#[cfg(any(windows, test))]
+pub fn temp_service_created_at(name: &str) -> Option<u64> {
+    let (created, id) = name.strip_prefix(TEMP_SERVICE_PREFIX)?.split_once('_')?;
+    let is_hex = |part: &str| part.chars().all(|c| c.is_ascii_hexdigit());
+    if created.is_empty() || created.len() > 16 || !is_hex(created) || id.len() != 8 || !is_hex(id) {
+        return None;
+    }
+    u64::from_str_radix(created, 16).ok()
+}
+
+/// Check whether a service name is one of our temporary services.
+///
+/// Matches the exact `generate_temp_service_name` format (and the older
+/// format without a creation time) so unrelated services that happen to
+/// share the prefix are never touched.
+#[cfg(any(windows, test))]
+pub fn is_temp_service_name(name: &str) -> bool {
+    let legacy = name
+        .strip_prefix(TEMP_SERVICE_PREFIX)
+        .is_some_and(|id| id.len() == 8 && id.chars().all(|c| c.is_ascii_hexdigit()));
+    legacy || temp_service_created_at(name).is_some()
+}
+
+/// Check whether a temporary service is old enough to be swept as an orphan.
+///
+/// Services without a creation time in their name are never swept.
+#[cfg(any(windows, test))]
+pub fn is_sweepable_orphan(name: &str, now_secs: u64) -> bool {
+    is_temp_service_name(name)
+        && temp_service_created_at(name)
+            .is_some_and(|created| now_secs.saturating_sub(created) >= ORPHAN_MIN_AGE.as_secs())
 }
 
 #[cfg(windows)]
@@ -68,16 +136,17 @@ mod windows_impl {
 
     use windows::core::PCWSTR;
     use windows::Win32::Foundation::{
-        CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_SERVICE_EXISTS,
+        CloseHandle, GetLastError, ERROR_ACCESS_DENIED, ERROR_MORE_DATA,
+        ERROR_SERVICE_DOES_NOT_EXIST, ERROR_SERVICE_EXISTS, ERROR_SERVICE_MARKED_FOR_DELETE,
         HANDLE, WIN32_ERROR,
     };
     use windows::Win32::System::Services::{
         CloseServiceHandle, ControlService, CreateServiceW, DeleteService,
-        OpenSCManagerW, OpenServiceW, QueryServiceStatus, StartServiceW,
-        SC_HANDLE, SC_MANAGER_ALL_ACCESS, SC_MANAGER_CONNECT,
-        SERVICE_ALL_ACCESS, SERVICE_CONTROL_STOP, SERVICE_DEMAND_START,
-        SERVICE_ERROR_NORMAL, SERVICE_RUNNING, SERVICE_START_PENDING,
-        SERVICE_STATUS, SERVICE_STOPPED, SERVICE_WIN32_OWN_PROCESS,
+        EnumServicesStatusExW, OpenSCManagerW, OpenServiceW, QueryServiceStatus, StartServiceW,
+        ENUM_SERVICE_STATUS_PROCESSW, SC_ENUM_PROCESS_INFO, SC_HANDLE, SC_MANAGER_ALL_ACCESS,
+        SC_MANAGER_CONNECT, SERVICE_ALL_ACCESS, SERVICE_CONTROL_STOP, SERVICE_DEMAND_START,
+        SERVICE_ERROR_NORMAL, SERVICE_RUNNING, SERVICE_START_PENDING, SERVICE_STATE_ALL,
+        SERVICE_STATUS, SERVICE_STOPPED, SERVICE_WIN32, SERVICE_WIN32_OWN_PROCESS,
     };
 
     /// Convert a Rust string to a null-terminated wide string
@@ -182,6 +251,147 @@ mod windows_impl {
                 })
             }
         }
+
+        /// Open an existing service with full access.
+        fn open_service(&self, service_name: &str) -> Result<RemoteService, WIN32_ERROR> {
+            let name_wide = to_wide_string(service_name);
+
+            unsafe {
+                match OpenServiceW(self.handle, PCWSTR::from_raw(name_wide.as_ptr()), SERVICE_ALL_ACCESS) {
+                    Ok(handle) if !handle.is_invalid() => Ok(RemoteService {
+                        handle,
+                        name: service_name.to_string(),
+                    }),
+                    _ => Err(GetLastError()),
+                }
+            }
+        }
+
+        /// Confirm a deleted service is gone.
+        ///
+        /// Must be called after the service's handles are closed: the SCM only
+        /// removes a service marked for deletion once no handle is open. A
+        /// service still present is deleted again.
+        ///
+        /// # Returns
+        /// `true` if the service is gone or marked for deletion.
+        fn verify_deleted(&self, service_name: &str) -> bool {
+            let service = match self.open_service(service_name) {
+                Err(error) if error == ERROR_SERVICE_DOES_NOT_EXIST => {
+                    debug!("Verified service {} is deleted", service_name);
+                    return true;
+                }
+                Err(error) => {
+                    warn!("Could not verify deletion of service {}: {:?}", service_name, error);
+                    return false;
+                }
+                Ok(service) => service,
+            };
+
+            match service.delete() {
+                Ok(()) => {
+                    info!("Service {} was still registered, deleted it again", service_name);
+                    true
+                }
+                Err(_) => unsafe { GetLastError() == ERROR_SERVICE_MARKED_FOR_DELETE },
+            }
+        }
+
+        /// List the names of our temporary services that are not running and
+        /// older than `ORPHAN_MIN_AGE`.
+        fn find_orphans(&self) -> Result<Vec<String>, ServiceError> {
+            let now = unix_time_secs();
+            // u64 buffer keeps ENUM_SERVICE_STATUS_PROCESSW properly aligned
+            let mut buffer: Vec<u64> = vec![0; 8 * 1024];
+            let mut orphans = Vec::new();
+            let mut resume = 0u32;
+
+            loop {
+                let mut needed = 0u32;
+                let mut returned = 0u32;
+                let bytes = unsafe {
+                    std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8)
+                };
+
+                let result = unsafe {
+                    EnumServicesStatusExW(
+                        self.handle,
+                        SC_ENUM_PROCESS_INFO,
+                        SERVICE_WIN32,
+                        SERVICE_STATE_ALL,
+                        Some(bytes),
+                        &mut needed,
+                        &mut returned,
+                        Some(&mut resume),
+                        PCWSTR::null(),
+                    )
+                };
+                let more_data = match result {
+                    Ok(()) => false,
+                    Err(_) if unsafe { GetLastError() } == ERROR_MORE_DATA => true,
+                    Err(e) => {
+                        return Err(ServiceError::NetworkError(format!(
+                            "Failed to enumerate services on {}: {}",
+                            self.hostname, e
+                        )))
+                    }
+                };
+
+                let entries = unsafe {
+                    std::slice::from_raw_parts(
+                        buffer.as_ptr() as *const ENUM_SERVICE_STATUS_PROCESSW,
+                        returned as usize,
+                    )
+                };
+                for entry in entries {
+                    let Ok(name) = (unsafe { entry.lpServiceName.to_string() }) else {
+                        continue;
+                    };
+                    let state = entry.ServiceStatusProcess.dwCurrentState;
+                    // A young or running service may belong to an install in progress
+                    if is_sweepable_orphan(&name, now) && state != SERVICE_RUNNING && state != SERVICE_START_PENDING {
+                        orphans.push(name);
+                    }
+                }
+
+                if !more_data {
+                    break;
+                }
+                if returned == 0 {
+                    // A single entry didn't fit; grow to the size requested
+                    buffer.resize((needed as usize).div_ceil(8), 0);
+                }
+            }
+
+            Ok(orphans)
+        }
+
+        /// Delete orphaned temporary services left by earlier runs.
+        ///
+        /// # Returns
+        /// The names of the services that were removed.
+        fn sweep_orphans(&self) -> Vec<String> {
+            let orphans = match self.find_orphans() {
+                Ok(orphans) => orphans,
+                Err(e) => {
+                    warn!(error = %e, "Orphaned service sweep failed");
+                    return Vec::new();
+                }
+            };
+
+            let mut removed = Vec::new();
+            for name in orphans {
+                let deleted = match self.open_service(&name) {
+                    Ok(service) => service.delete().is_ok(),
+                    Err(_) => false,
+                };
+                if deleted && self.verify_deleted(&name) {
+                    info!(service = %name, host = %self.hostname, "Removed orphaned temporary service");
+                    removed.push(name);
+                }
+            }
+            removed
+        }
     }
 
     impl Drop for RemoteScm {
@@ -304,10 +514,11 @@ mod windows_impl {
     ///
     /// This function:
     /// 1. Connects to the remote SCM
-    /// 2. Creates a temporary service with the command
-    /// 3. Starts the service
-    /// 4. Waits for completion
-    /// 5. Deletes the service (cleanup)
+    /// 2. Removes orphaned temporary services (older than `ORPHAN_MIN_AGE`) from earlier runs
+    /// 3. Creates a temporary service with the command
+    /// 4. Starts the service
+    /// 5. Waits for completion
+    /// 6. Deletes the service and verifies it is gone (also if 4 or 5 failed)
     ///
     /// # Arguments
     /// * `target_hostname` - Remote machine hostname
@@ -326,7 +537,6 @@ mod windows_impl {
     ) -> Result<ServiceExecutionResult, ServiceError> {
         let service_name = generate_temp_service_name();
         let start_time = std::time::Instant::now();
-        let mut cleanup_success = true;
 
         info!(
             service_name = %service_name,
@@ -337,27 +547,35 @@ mod windows_impl {
         // Connect to remote SCM
         let scm = RemoteScm::connect(target_hostname, credentials)?;
 
+        // Remove services a previous run failed to delete
+        let orphans_removed = scm.sweep_orphans();
+
         // Create the temporary service
         let service = scm.create_service(&service_name, command)?;
 
-        // Start the service
-        service.start()?;
-
-        // Wait for completion
-        let exit_code = service.wait_for_stop(timeout)?;
-
-        // Delete the service (cleanup)
-        if let Err(e) = service.delete() {
-            warn!(error = %e, "Failed to delete temporary service");
-            cleanup_success = false;
-        }
+        // Start the service and wait for completion
+        let outcome = service.start().and_then(|_| service.wait_for_stop(timeout));
+
+        // Delete the service (cleanup), even if it failed to start or timed out
+        let deleted = service.delete();
+        // Close our handle so the SCM can remove the service before verifying
+        drop(service);
+        let cleanup_success = match deleted {
+            Ok(()) => scm.verify_deleted(&service_name),
+            Err(e) => {
+                warn!(error = %e, "Failed to delete temporary service");
+                false
+            }
+        };
 
+        let exit_code = outcome?;
         let duration = start_time.elapsed();
 
         Ok(ServiceExecutionResult {
             exit_code,
             duration,
             cleanup_success,
+            orphans_removed,
         })
     }
 }
@@ -386,6 +604,7 @@ mod mock_impl {
             exit_code: 0,
             duration: Duration::from_secs(2),
             cleanup_success: true,
+            orphans_removed: Vec::new(),
         })
     }
 }
@@ -487,6 +706,34 @@ mod tests {
         assert!(name.len() > 12);
     }
 
+    #[test]
+    fn test_is_temp_service_name() {
+        assert!(is_temp_service_name(&generate_temp_service_name()));
+        assert!(is_temp_service_name("DeployWorker_0a1b2c3d"));
+        assert!(!is_temp_service_name("DeployWorker_"));
+        assert!(!is_temp_service_name("DeployWorker_Agent"));
+        assert!(!is_temp_service_name("DeployWorker_0a1b2c3d4"));
+        assert!(!is_temp_service_name("deployworker_0a1b2c3d"));
+    }
+
+    #[test]
+    fn test_orphan_sweep_age() {
+        let now = unix_time_secs();
+        let name = generate_temp_service_name();
+        let created = temp_service_created_at(&name).unwrap();
+        assert!(now.abs_diff(created) <= 1);
+
+        // A service just created by another job is never an orphan
+        assert!(!is_sweepable_orphan(&name, now));
+        assert!(is_sweepable_orphan(&name, created + ORPHAN_MIN_AGE.as_secs()));
+
+        // Old-format names carry no creation time and are left alone
+        assert!(temp_service_created_at("DeployWorker_0a1b2c3d").is_none());
+        assert!(!is_sweepable_orphan("DeployWorker_0a1b2c3d", u64::MAX));
+        assert!(!is_sweepable_orphan("DeployWorker_zz_0a1b2c3d", u64::MAX));
+        assert!(!is_sweepable_orphan("Spooler", u64::MAX));
+    }
+
     #[test]
     fn test_service_error_display() {
         let err = ServiceError::Timeout { seconds: 300 };
@@ -1,11 +1,14 @@
 //! SMB file operations for copying installers to remote machines.
 //!
-//! Uses Windows native APIs (WNetAddConnection2W, CopyFileW) for
+//! Uses Windows native APIs (WNetAddConnection2W, CopyFileExW) for
 //! authenticated SMB access to ADMIN$ shares.
 
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+use serde::Serialize;
 use thiserror::Error;
+use tokio::sync::watch;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::credentials::Credential;
@@ -32,6 +35,16 @@ pub enum SmbError {
     #[error("Access denied to {0}")]
     AccessDenied(String),
 
+    #[error("Target disk is full: {0}")]
+    DiskFull(String),
+
+    #[error("Insufficient disk space on {path}: {required} bytes required, {available} bytes free")]
+    InsufficientSpace {
+        path: String,
+        required: u64,
+        available: u64,
+    },
+
     #[error("Network error: {0}")]
     NetworkError(String),
 
@@ -40,6 +53,103 @@ pub enum SmbError {
 
     #[error("Invalid path format: {0}")]
     InvalidPath(String),
+
+    #[error("SMB connection to {path} does not meet policy: {message}")]
+    SecurityRequirementNotMet { path: String, message: String },
+}
+
+/// Progress of an in-flight file copy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyProgress {
+    /// Bytes copied so far
+    pub transferred: u64,
+    /// Total file size in bytes
+    pub total: u64,
+}
+
+impl CopyProgress {
+    /// Percentage complete (0-100). An empty file counts as complete.
+    pub fn percent(&self) -> u8 {
+        if self.total == 0 {
+            return 100;
+        }
+        ((self.transferred.min(self.total) * 100) / self.total) as u8
+    }
+
+    /// Average bytes per second over `elapsed`, None if no time elapsed
+    pub fn throughput(&self, elapsed: Duration) -> Option<u64> {
+        let secs = elapsed.as_secs_f64();
+        (secs > 0.0).then(|| (self.transferred as f64 / secs) as u64)
+    }
+}
+
+/// Negotiated security of an SMB connection
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmbSessionSecurity {
+    /// Negotiated dialect (e.g. "3.1.1")
+    pub dialect: Option<String>,
+    /// Packets are signed
+    pub signed: bool,
+    /// Traffic is encrypted (SMB 3.x)
+    pub encrypted: bool,
+}
+
+impl SmbSessionSecurity {
+    /// Describe the requirements this connection misses, None if it meets them.
+    ///
+    /// An encrypted session counts as signed: SMB 3 encryption already
+    /// protects integrity and Windows reports such sessions as unsigned.
+    pub fn unmet_requirements(&self, require_signing: bool, require_encryption: bool) -> Option<String> {
+        let dialect = self.dialect.as_deref().unwrap_or("unknown");
+        if require_encryption && !self.encrypted {
+            Some(format!("encryption required but not negotiated (dialect {})", dialect))
+        } else if require_signing && !self.signed && !self.encrypted {
+            Some(format!("signing required but not negotiated (dialect {})", dialect))
+        } else {
+            None
+        }
+    }
+}
+
+/// Sender for copy progress updates
+pub type CopyProgressSender = watch::Sender<Option<CopyProgress>>;
+
+/// Win32 error codes with a dedicated copy error mapping (used by `windows_impl`)
+#[cfg(any(windows, test))]
+const ERROR_PATH_NOT_FOUND: u32 = 3;
+#[cfg(any(windows, test))]
+const ERROR_ACCESS_DENIED: u32 = 5;
+#[cfg(any(windows, test))]
+const ERROR_HANDLE_DISK_FULL: u32 = 39;
+#[cfg(any(windows, test))]
+const ERROR_NETWORK_BUSY: u32 = 54;
+#[cfg(any(windows, test))]
+const ERROR_DISK_FULL: u32 = 112;
+
+/// Map a Win32 error code from a failed copy to an `SmbError`.
+///
+/// Distinguishes the failures operators act on differently: target disk
+/// full, permissions, a missing share/directory and a busy network.
+/// Anything else stays a generic `CopyFailed` with the raw code.
+#[cfg(any(windows, test))]
+fn copy_error(source: &str, dest: &str, code: u32) -> SmbError {
+    match code {
+        ERROR_DISK_FULL | ERROR_HANDLE_DISK_FULL => SmbError::DiskFull(dest.to_string()),
+        ERROR_ACCESS_DENIED => SmbError::AccessDenied(dest.to_string()),
+        ERROR_PATH_NOT_FOUND => {
+            SmbError::PathNotFound(format!("{} (share or directory missing)", dest))
+        }
+        ERROR_NETWORK_BUSY => {
+            SmbError::NetworkError(format!("Network busy while copying to {}", dest))
+        }
+        _ => SmbError::CopyFailed {
+            src_path: source.to_string(),
+            dest_path: dest.to_string(),
+            message: format!("Error code: {}", code),
+        },
+    }
 }
 
 /// SMB connection manager
@@ -66,8 +176,10 @@ mod windows_impl {
     use windows::Win32::NetworkManagement::WNet::{
         WNetAddConnection2W, WNetCancelConnection2W, NETRESOURCEW, RESOURCETYPE_DISK,
     };
+    use windows::Win32::Foundation::HANDLE;
     use windows::Win32::Storage::FileSystem::{
-        CopyFileW, DeleteFileW, GetFileAttributesW, INVALID_FILE_ATTRIBUTES,
+        CopyFileExW, DeleteFileW, GetDiskFreeSpaceExW, GetFileAttributesW, INVALID_FILE_ATTRIBUTES,
+        LPPROGRESS_ROUTINE_CALLBACK_REASON, PROGRESS_CONTINUE,
     };
 
     /// Convert a Rust string to a null-terminated wide string
@@ -188,41 +300,67 @@ mod windows_impl {
         }
     }
 
+    /// Progress routine for CopyFileExW.
+    ///
+    /// `lpdata` points to the optional progress sender passed by `copy_file_internal`.
+    unsafe extern "system" fn copy_progress_routine(
+        total_file_size: i64,
+        total_bytes_transferred: i64,
+        _stream_size: i64,
+        _stream_bytes_transferred: i64,
+        _stream_number: u32,
+        _callback_reason: LPPROGRESS_ROUTINE_CALLBACK_REASON,
+        _source_file: HANDLE,
+        _destination_file: HANDLE,
+        lpdata: *const core::ffi::c_void,
+    ) -> u32 {
+        if !lpdata.is_null() {
+            let sender = &*(lpdata as *const CopyProgressSender);
+            sender.send_replace(Some(CopyProgress {
+                transferred: total_bytes_transferred.max(0) as u64,
+                total: total_file_size.max(0) as u64,
+            }));
+        }
+        PROGRESS_CONTINUE.0
+    }
+
     /// Copy a file from source to destination.
     ///
     /// # Arguments
     /// * `source` - Source file path (can be local or UNC)
     /// * `dest` - Destination file path (can be local or UNC)
+    /// * `progress` - Optional sender for byte-level progress updates
     ///
     /// # Returns
     /// Ok(()) if the file was copied successfully.
-    #[instrument]
-    pub fn copy_file_internal(source: &str, dest: &str) -> Result<(), SmbError> {
+    #[instrument(skip(progress))]
+    pub fn copy_file_internal(
+        source: &str,
+        dest: &str,
+        progress: Option<&CopyProgressSender>,
+    ) -> Result<(), SmbError> {
         let source_wide = to_wide_string(source);
         let dest_wide = to_wide_string(dest);
 
+        let lpdata = progress.map(|sender| sender as *const CopyProgressSender as *const core::ffi::c_void);
+
         unsafe {
-            let result = CopyFileW(
+            let result = CopyFileExW(
                 PCWSTR::from_raw(source_wide.as_ptr()),
                 PCWSTR::from_raw(dest_wide.as_ptr()),
-                false, // Overwrite if exists
+                Some(copy_progress_routine),
+                lpdata,
+                None,
+                0, // Overwrite if exists
             );
 
-            if result.as_bool() {
+            if result.is_ok() {
                 debug!("File copied from {} to {}", source, dest);
                 Ok(())
             } else {
-                let error = GetLastError();
-                let message = match error {
-                    ERROR_ACCESS_DENIED => "Access denied".to_string(),
-                    _ => format!("Error code: {:?}", error),
-                };
-                error!(error = %message, "File copy failed");
-                Err(SmbError::CopyFailed {
-                    src_path: source.to_string(),
-                    dest_path: dest.to_string(),
-                    message,
-                })
+                let error = copy_error(source, dest, GetLastError().0);
+                error!(error = %error, "File copy failed");
+                Err(error)
             }
         }
     }
@@ -271,6 +409,140 @@ mod windows_impl {
             attrs != INVALID_FILE_ATTRIBUTES
         }
     }
+
+    /// Get the free space available to the caller on the volume holding a path.
+    ///
+    /// # Arguments
+    /// * `path` - Directory path (can be local or UNC, e.g. `\\target\ADMIN$\Temp`)
+    ///
+    /// # Returns
+    /// Free bytes available to the connected user (honors disk quotas).
+    pub fn free_space_internal(path: &str) -> Result<u64, SmbError> {
+        // GetDiskFreeSpaceExW requires a trailing backslash for UNC share roots
+        let dir = if path.ends_with('\\') {
+            path.to_string()
+        } else {
+            format!("{}\\", path)
+        };
+        let path_wide = to_wide_string(&dir);
+        let mut free_bytes: u64 = 0;
+
+        unsafe {
+            GetDiskFreeSpaceExW(
+                PCWSTR::from_raw(path_wide.as_ptr()),
+                Some(&mut free_bytes as *mut u64),
+                None,
+                None,
+            )
+            .map_err(|e| SmbError::NetworkError(format!(
+                "Failed to query free space on {}: {}",
+                path, e
+            )))?;
+        }
+
+        debug!(path = %path, free_bytes, "Queried free disk space");
+        Ok(free_bytes)
+    }
+
+    /// Get the size of a file in bytes.
+    pub fn file_size_internal(path: &str) -> Result<u64, SmbError> {
+        std::fs::metadata(path)
+            .map(|m| m.len())
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => SmbError::PathNotFound(path.to_string()),
+                std::io::ErrorKind::PermissionDenied => SmbError::AccessDenied(path.to_string()),
+                _ => SmbError::NetworkError(format!("Failed to read size of {}: {}", path, e)),
+            })
+    }
+
+    /// Read up to the last `max_bytes` of a file.
+    ///
+    /// # Returns
+    /// The bytes read and the total file size.
+    pub fn read_tail_internal(path: &str, max_bytes: u64) -> Result<(Vec<u8>, u64), SmbError> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let map_err = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::NotFound => SmbError::PathNotFound(path.to_string()),
+            std::io::ErrorKind::PermissionDenied => SmbError::AccessDenied(path.to_string()),
+            _ => SmbError::NetworkError(format!("Failed to read {}: {}", path, e)),
+        };
+
+        let mut file = std::fs::File::open(path).map_err(map_err)?;
+        let size = file.metadata().map_err(map_err)?.len();
+        let offset = size.saturating_sub(max_bytes);
+        file.seek(SeekFrom::Start(offset)).map_err(map_err)?;
+
+        let mut buffer = Vec::with_capacity((size - offset) as usize);
+        file.take(max_bytes).read_to_end(&mut buffer).map_err(map_err)?;
+
+        debug!(path = %path, size, read = buffer.len(), "Read file tail");
+        Ok((buffer, size))
+    }
+
+    /// Read up to `max_bytes` of a file starting at `offset`.
+    ///
+    /// # Returns
+    /// The bytes read (empty if the file is shorter than `offset`) and the
+    /// total file size.
+    pub fn read_from_internal(path: &str, offset: u64, max_bytes: u64) -> Result<(Vec<u8>, u64), SmbError> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let map_err = |e: std::io::Error| match e.kind() {
+            std::io::ErrorKind::NotFound => SmbError::PathNotFound(path.to_string()),
+            std::io::ErrorKind::PermissionDenied => SmbError::AccessDenied(path.to_string()),
+            _ => SmbError::NetworkError(format!("Failed to read {}: {}", path, e)),
+        };
+
+        // msiexec keeps its log open for writing; share access lets us read alongside
+        let mut file = std::fs::File::open(path).map_err(map_err)?;
+        let size = file.metadata().map_err(map_err)?.len();
+        if offset >= size {
+            return Ok((Vec::new(), size));
+        }
+        file.seek(SeekFrom::Start(offset)).map_err(map_err)?;
+
+        let mut buffer = Vec::with_capacity((size - offset).min(max_bytes) as usize);
+        file.take(max_bytes).read_to_end(&mut buffer).map_err(map_err)?;
+        Ok((buffer, size))
+    }
+
+    /// Query the negotiated security of this machine's open connection to
+    /// `\\server\share` (WMI `MSFT_SmbConnection`, as `Get-SmbConnection`).
+    pub fn session_security_internal(
+        server: &str,
+        share: &str,
+        timeout: Duration,
+    ) -> Result<SmbSessionSecurity, SmbError> {
+        use crate::execution::security::{exec_local_query, wmi_bool, wmi_string};
+        use windows::core::w;
+
+        let mut found = None;
+        exec_local_query(
+            "root\\Microsoft\\Windows\\SMB",
+            "SELECT ServerName, ShareName, Dialect, Signed, Encrypted FROM MSFT_SmbConnection",
+            timeout,
+            |object| unsafe {
+                if found.is_some() {
+                    return;
+                }
+                let server_name = wmi_string(object, w!("ServerName")).unwrap_or_default();
+                let share_name = wmi_string(object, w!("ShareName")).unwrap_or_default();
+                if server_name.eq_ignore_ascii_case(server) && share_name.eq_ignore_ascii_case(share) {
+                    found = Some(SmbSessionSecurity {
+                        dialect: wmi_string(object, w!("Dialect")),
+                        signed: wmi_bool(object, w!("Signed")).unwrap_or(false),
+                        encrypted: wmi_bool(object, w!("Encrypted")).unwrap_or(false),
+                    });
+                }
+            },
+        )
+        .map_err(|e| SmbError::NetworkError(format!("Failed to query SMB connection security: {}", e)))?;
+
+        found.ok_or_else(|| {
+            SmbError::NetworkError(format!("No SMB connection to \\\\{}\\{} found", server, share))
+        })
+    }
 }
 
 #[cfg(not(windows))]
@@ -308,11 +580,22 @@ mod mock_impl {
     }
 
     /// Mock file copy.
-    #[instrument]
-    pub fn copy_file_internal(source: &str, dest: &str) -> Result<(), SmbError> {
+    #[instrument(skip(progress))]
+    pub fn copy_file_internal(
+        source: &str,
+        dest: &str,
+        progress: Option<&CopyProgressSender>,
+    ) -> Result<(), SmbError> {
         info!("[MOCK] Would copy file from {} to {}", source, dest);
-        // Simulate some delay
+        // Simulate some delay with start/end progress for a 1 MB file
+        let total = 1024 * 1024;
+        if let Some(sender) = progress {
+            sender.send_replace(Some(CopyProgress { transferred: 0, total }));
+        }
         std::thread::sleep(std::time::Duration::from_millis(100));
+        if let Some(sender) = progress {
+            sender.send_replace(Some(CopyProgress { transferred: total, total }));
+        }
         Ok(())
     }
 
@@ -327,14 +610,62 @@ mod mock_impl {
     pub fn path_exists_internal(_path: &str) -> bool {
         true
     }
+
+    /// Mock free space query (reports 100 GB free).
+    pub fn free_space_internal(path: &str) -> Result<u64, SmbError> {
+        info!("[MOCK] Would query free space on {}", path);
+        Ok(100 * 1024 * 1024 * 1024)
+    }
+
+    /// Mock file size (matches the 1 MB mock copy).
+    pub fn file_size_internal(_path: &str) -> Result<u64, SmbError> {
+        Ok(1024 * 1024)
+    }
+
+    /// Mock file read (returns a short sample log).
+    pub fn read_tail_internal(path: &str, max_bytes: u64) -> Result<(Vec<u8>, u64), SmbError> {
+        info!("[MOCK] Would read last {} bytes of {}", max_bytes, path);
+        let content = b"[MOCK] line 1\r\n[MOCK] line 2\r\n[MOCK] line 3\r\n".to_vec();
+        let size = content.len() as u64;
+        Ok((content, size))
+    }
+
+    /// Mock read from an offset (the same short sample log).
+    pub fn read_from_internal(path: &str, offset: u64, max_bytes: u64) -> Result<(Vec<u8>, u64), SmbError> {
+        debug!("[MOCK] Would read {} bytes of {} from offset {}", max_bytes, path, offset);
+        let content: &[u8] = b"[MOCK] line 1\r\n[MOCK] line 2\r\n[MOCK] line 3\r\n";
+        let start = (offset as usize).min(content.len());
+        let end = start.saturating_add(max_bytes as usize).min(content.len());
+        Ok((content[start..end].to_vec(), content.len() as u64))
+    }
+
+    /// Mock session security (SMB 3.1.1, signed, not encrypted).
+    pub fn session_security_internal(
+        server: &str,
+        share: &str,
+        _timeout: Duration,
+    ) -> Result<SmbSessionSecurity, SmbError> {
+        info!("[MOCK] Would query SMB session security for \\\\{}\\{}", server, share);
+        Ok(SmbSessionSecurity {
+            dialect: Some("3.1.1".to_string()),
+            signed: true,
+            encrypted: false,
+        })
+    }
 }
 
 // Re-export internal functions based on platform
 #[cfg(windows)]
-use windows_impl::{copy_file_internal, delete_file_internal, path_exists_internal};
+use windows_impl::{
+    copy_file_internal, delete_file_internal, file_size_internal, free_space_internal,
+    path_exists_internal, read_from_internal, read_tail_internal, session_security_internal,
+};
 
 #[cfg(not(windows))]
-use mock_impl::{copy_file_internal, delete_file_internal, path_exists_internal};
+use mock_impl::{
+    copy_file_internal, delete_file_internal, file_size_internal, free_space_internal,
+    path_exists_internal, read_from_internal, read_tail_internal, session_security_internal,
+};
 
 /// Copy a file from source to a remote SMB share.
 ///
@@ -354,6 +685,28 @@ pub async fn copy_file(
     dest_share: &str,
     credentials: &Credential,
     timeout: Duration,
+) -> Result<String, SmbError> {
+    copy_file_with_progress(source_path, dest_share, credentials, timeout, None).await
+}
+
+/// Copy a file to a remote SMB share, reporting byte-level progress.
+///
+/// # Arguments
+/// * `source_path` - Source file path (UNC path)
+/// * `dest_share` - Destination share path (like `\\target\ADMIN$\Temp`)
+/// * `credentials` - Credentials for SMB authentication
+/// * `timeout` - Operation timeout
+/// * `progress` - Optional sender that receives `CopyProgress` updates
+///
+/// # Returns
+/// The full path to the copied file on the destination.
+#[instrument(skip(credentials, progress))]
+pub async fn copy_file_with_progress(
+    source_path: &str,
+    dest_share: &str,
+    credentials: &Credential,
+    timeout: Duration,
+    progress: Option<CopyProgressSender>,
 ) -> Result<String, SmbError> {
     // Validate paths
     if !source_path.starts_with("\\\\") {
@@ -389,7 +742,7 @@ pub async fn copy_file(
         let _conn = SmbConnection::connect(&share, &creds, timeout)?;
 
         // Copy the file
-        copy_file_internal(&source, &dest)?;
+        copy_file_internal(&source, &dest, progress.as_ref())?;
 
         Ok::<_, SmbError>(())
     })
@@ -446,16 +799,84 @@ pub async fn delete_file(file_path: &str, credentials: &Credential) -> Result<()
     .map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))?
 }
 
+/// Copy a file from a remote SMB share to a local directory.
+///
+/// # Arguments
+/// * `source_path` - UNC path of the file (like `\\target\ADMIN$\Temp\msi.log`)
+/// * `dest_dir` - Local directory, created if missing
+/// * `credentials` - Credentials for SMB authentication
+/// * `timeout` - Maximum time for connect + copy
+///
+/// # Returns
+/// The local path of the copied file.
+#[instrument(skip(credentials))]
+pub async fn download_file(
+    source_path: &str,
+    dest_dir: &Path,
+    credentials: &Credential,
+    timeout: Duration,
+) -> Result<PathBuf, SmbError> {
+    let parts: Vec<&str> = source_path.trim_start_matches("\\\\").splitn(3, '\\').collect();
+    if !source_path.starts_with("\\\\") || parts.len() < 3 {
+        return Err(SmbError::InvalidPath(format!(
+            "Source must be a UNC path to a file: {}",
+            source_path
+        )));
+    }
+    let share_path = format!("\\\\{}\\{}", parts[0], parts[1]);
+    let filename = extract_filename(source_path)
+        .ok_or_else(|| SmbError::InvalidPath("Cannot extract filename".to_string()))?;
+    let dest_path = dest_dir.join(filename);
+
+    let source = source_path.to_string();
+    let dir = dest_dir.to_path_buf();
+    let dest = dest_path.to_string_lossy().into_owned();
+    let creds = credentials.clone();
+
+    let task = tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&dir).map_err(|e| SmbError::CopyFailed {
+            src_path: source.clone(),
+            dest_path: dir.display().to_string(),
+            message: format!("Cannot create directory: {}", e),
+        })?;
+        let _conn = SmbConnection::connect(&share_path, &creds, timeout)?;
+        copy_file_internal(&source, &dest, None)
+    });
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))??,
+        Err(_) => {
+            return Err(SmbError::Timeout {
+                operation: format!("download of {}", source_path),
+            })
+        }
+    }
+
+    info!(dest = %dest_path.display(), "File downloaded successfully");
+    Ok(dest_path)
+}
+
 /// Check if a path exists on an SMB share.
 ///
+/// The connect (`WNetAddConnection2W`) and check run on a blocking thread
+/// bounded by `timeout`. `WNetAddConnection2W` cannot be cancelled, so on
+/// timeout the thread is abandoned and the connection is dropped when the
+/// call eventually returns.
+///
 /// # Arguments
 /// * `path` - UNC path to check
 /// * `credentials` - Credentials for SMB authentication
+/// * `timeout` - Maximum time for connect + check
 ///
 /// # Returns
-/// `true` if the path exists.
+/// `true` if the path exists, `SmbError::Timeout` if the share did not
+/// respond in time.
 #[instrument(skip(credentials))]
-pub async fn check_path_exists(path: &str, credentials: &Credential) -> Result<bool, SmbError> {
+pub async fn check_path_exists(
+    path: &str,
+    credentials: &Credential,
+    timeout: Duration,
+) -> Result<bool, SmbError> {
     if !path.starts_with("\\\\") {
         return Err(SmbError::InvalidPath(format!(
             "Path must be a UNC path: {}",
@@ -472,15 +893,525 @@ pub async fn check_path_exists(path: &str, credentials: &Credential) -> Result<b
 
     let check_path = path.to_string();
     let creds = credentials.clone();
+    let share = share_path.clone();
 
-    tokio::task::spawn_blocking(move || {
+    let task = tokio::task::spawn_blocking(move || {
         // Connect to the share
-        let _conn = SmbConnection::connect(&share_path, &creds, Duration::from_secs(30))?;
+        let _conn = SmbConnection::connect(&share, &creds, timeout)?;
 
         Ok::<_, SmbError>(path_exists_internal(&check_path))
+    });
+
+    match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))?,
+        Err(_) => {
+            warn!(share = %share_path, timeout_secs = timeout.as_secs(), "SMB path check timed out");
+            Err(SmbError::Timeout {
+                operation: format!("connect to {}", share_path),
+            })
+        }
+    }
+}
+
+/// Check that the SMB connection to a destination share meets the signing
+/// and encryption policy before copying to it.
+///
+/// `WNetAddConnection2W` can succeed on a connection the policy forbids
+/// (e.g. an unencrypted SMB 2 session), leaving the copy to fail obscurely,
+/// so the negotiated state is read back from the open connection.
+///
+/// # Arguments
+/// * `dest_share` - Destination share path (like `\\target\ADMIN$\Temp`)
+/// * `credentials` - Credentials for SMB authentication
+/// * `require_signing` - Fail unless the session is signed (or encrypted)
+/// * `require_encryption` - Fail unless the session is encrypted
+/// * `timeout` - Maximum time for connect + query
+///
+/// # Returns
+/// The negotiated security, or `SmbError::SecurityRequirementNotMet`.
+#[instrument(skip(credentials))]
+pub async fn check_session_security(
+    dest_share: &str,
+    credentials: &Credential,
+    require_signing: bool,
+    require_encryption: bool,
+    timeout: Duration,
+) -> Result<SmbSessionSecurity, SmbError> {
+    let parts: Vec<&str> = dest_share.trim_start_matches("\\\\").splitn(3, '\\').collect();
+    if !dest_share.starts_with("\\\\") || parts.len() < 2 {
+        return Err(SmbError::InvalidPath(format!(
+            "Destination must be a UNC path: {}",
+            dest_share
+        )));
+    }
+    let (server, share) = (parts[0].to_string(), parts[1].to_string());
+    let share_path = format!("\\\\{}\\{}", server, share);
+    let creds = credentials.clone();
+
+    let task = tokio::task::spawn_blocking(move || {
+        let _conn = SmbConnection::connect(&share_path, &creds, timeout)?;
+        session_security_internal(&server, &share, timeout)
+    });
+
+    let security = match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))??,
+        Err(_) => {
+            return Err(SmbError::Timeout {
+                operation: format!("SMB security query on {}", dest_share),
+            })
+        }
+    };
+
+    if let Some(message) = security.unmet_requirements(require_signing, require_encryption) {
+        warn!(share = %dest_share, ?security, "SMB connection does not meet policy");
+        return Err(SmbError::SecurityRequirementNotMet {
+            path: dest_share.to_string(),
+            message,
+        });
+    }
+
+    debug!(share = %dest_share, ?security, "SMB security check passed");
+    Ok(security)
+}
+
+/// Bytes of free space needed to stage and run an installer.
+///
+/// The installer is copied once and MSI extraction/caching needs additional
+/// room, so the installer size is scaled by `multiplier` (at least 1x).
+pub fn required_free_space(installer_size: u64, multiplier: f64) -> u64 {
+    (installer_size as f64 * multiplier.max(1.0)).ceil() as u64
+}
+
+/// Check that a destination share has room for an installer before copying it.
+///
+/// Reads the installer size locally and queries the free space of the
+/// destination volume (`GetDiskFreeSpaceExW`) over an authenticated
+/// connection, bounded by `timeout`.
+///
+/// # Arguments
+/// * `source_path` - Installer path (UNC path)
+/// * `dest_share` - Destination share path (like `\\target\ADMIN$\Temp`)
+/// * `credentials` - Credentials for SMB authentication
+/// * `multiplier` - Installer size multiplier for extraction overhead
+/// * `timeout` - Maximum time for connect + query
+///
+/// # Returns
+/// The free bytes on the destination, or `SmbError::InsufficientSpace` if
+/// it holds less than `required_free_space(installer_size, multiplier)`.
+#[instrument(skip(credentials))]
+pub async fn check_free_space(
+    source_path: &str,
+    dest_share: &str,
+    credentials: &Credential,
+    multiplier: f64,
+    timeout: Duration,
+) -> Result<u64, SmbError> {
+    if !dest_share.starts_with("\\\\") {
+        return Err(SmbError::InvalidPath(format!(
+            "Destination must be a UNC path: {}",
+            dest_share
+        )));
+    }
+
+    let source = source_path.to_string();
+    let share = dest_share.to_string();
+    let creds = credentials.clone();
+
+    let task = tokio::task::spawn_blocking(move || {
+        let installer_size = file_size_internal(&source)?;
+
+        let _conn = SmbConnection::connect(&share, &creds, timeout)?;
+        let available = free_space_internal(&share)?;
+
+        Ok::<_, SmbError>((installer_size, available))
+    });
+
+    let (installer_size, available) = match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))??,
+        Err(_) => {
+            return Err(SmbError::Timeout {
+                operation: format!("free space query on {}", dest_share),
+            })
+        }
+    };
+
+    let required = required_free_space(installer_size, multiplier);
+    if available < required {
+        warn!(share = %dest_share, required, available, "Insufficient disk space on target");
+        return Err(SmbError::InsufficientSpace {
+            path: dest_share.to_string(),
+            required,
+            available,
+        });
+    }
+
+    debug!(share = %dest_share, required, available, "Disk space check passed");
+    Ok(available)
+}
+
+/// Tail of a file read from a target.
+#[derive(Debug, Clone)]
+pub struct FileTail {
+    /// Content (lossy UTF-8), limited to the requested lines/bytes
+    pub content: String,
+    /// Total size of the file in bytes
+    pub file_size: u64,
+    /// Whether content was cut off (file larger than the byte cap or line count)
+    pub truncated: bool,
+}
+
+/// Map a local path on a target to its administrative share.
+///
+/// `C:\ProgramData\Vendor\app.log` on `target` becomes
+/// `\\target\C$\ProgramData\Vendor\app.log`. The path must be absolute,
+/// must not contain `..` components or stream names, and must lie under one of
+/// `allowed_roots` (case-insensitive), so jobs cannot read arbitrary files.
+pub fn admin_share_path(hostname: &str, local_path: &str, allowed_roots: &[&str]) -> Result<String, SmbError> {
+    let path = local_path.trim().replace('/', "\\");
+    let bytes = path.as_bytes();
+    if bytes.len() < 3 || !bytes[0].is_ascii_alphabetic() || bytes[1] != b':' || bytes[2] != b'\\' {
+        return Err(SmbError::InvalidPath(format!("Path must be absolute (like C:\\...): {}", local_path)));
+    }
+    if path.split('\\').any(|part| part == "..") || path[2..].contains(':') {
+        return Err(SmbError::InvalidPath(format!("Path must not contain '..' or streams: {}", local_path)));
+    }
+
+    let lower = path.to_ascii_lowercase();
+    let allowed = allowed_roots.iter().any(|root| {
+        let root = root.trim_end_matches('\\').to_ascii_lowercase();
+        lower.len() > root.len() && lower.starts_with(&root) && lower.as_bytes()[root.len()] == b'\\'
+    });
+    if !allowed {
+        return Err(SmbError::AccessDenied(format!("Path is outside the allowed roots: {}", local_path)));
+    }
+
+    Ok(format!("\\\\{}\\{}${}", hostname, &path[..1].to_ascii_uppercase(), &path[2..]))
+}
+
+/// Keep the last `lines` lines of `text`.
+///
+/// # Returns
+/// The kept text and whether anything was dropped.
+pub fn tail_lines(text: &str, lines: usize) -> (&str, bool) {
+    if lines == 0 {
+        return ("", !text.is_empty());
+    }
+
+    // Ignore the final line break so "a\nb\n" counts as two lines
+    let trimmed = text.trim_end_matches(['\r', '\n']);
+    match trimmed.rmatch_indices('\n').nth(lines - 1) {
+        Some((index, _)) => (&text[index + 1..], true),
+        None => (text, false),
+    }
+}
+
+/// Read the end of a file on a target over an authenticated SMB connection.
+///
+/// At most `max_bytes` are read from the end of the file; when `lines` is set
+/// only the last `lines` lines of that are kept.
+///
+/// # Arguments
+/// * `path` - UNC path of the file (like `\\target\C$\ProgramData\app.log`)
+/// * `credentials` - Credentials for SMB authentication
+/// * `max_bytes` - Maximum bytes to read from the end of the file
+/// * `lines` - Optional number of trailing lines to keep
+/// * `timeout` - Maximum time for connect + read
+#[instrument(skip(credentials))]
+pub async fn read_file_tail(
+    path: &str,
+    credentials: &Credential,
+    max_bytes: u64,
+    lines: Option<usize>,
+    timeout: Duration,
+) -> Result<FileTail, SmbError> {
+    if !path.starts_with("\\\\") {
+        return Err(SmbError::InvalidPath(format!(
+            "Path must be a UNC path: {}",
+            path
+        )));
+    }
+
+    let parts: Vec<&str> = path.trim_start_matches("\\\\").splitn(3, '\\').collect();
+    if parts.len() < 3 {
+        return Err(SmbError::InvalidPath("Invalid UNC path format".to_string()));
+    }
+    let share_path = format!("\\\\{}\\{}", parts[0], parts[1]);
+
+    let file_path = path.to_string();
+    let creds = credentials.clone();
+    let share = share_path.clone();
+
+    let task = tokio::task::spawn_blocking(move || {
+        let _conn = SmbConnection::connect(&share, &creds, timeout)?;
+        read_tail_internal(&file_path, max_bytes)
+    });
+
+    let (bytes, file_size) = match tokio::time::timeout(timeout, task).await {
+        Ok(result) => result.map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))??,
+        Err(_) => {
+            return Err(SmbError::Timeout {
+                operation: format!("read of {}", path),
+            })
+        }
+    };
+
+    let text = String::from_utf8_lossy(&bytes);
+    let mut truncated = (bytes.len() as u64) < file_size;
+    let content = match lines {
+        Some(lines) => {
+            let (kept, dropped) = tail_lines(&text, lines);
+            truncated |= dropped;
+            kept.to_string()
+        }
+        None => text.into_owned(),
+    };
+
+    Ok(FileTail {
+        content,
+        file_size,
+        truncated,
+    })
+}
+
+/// Handle to a running SMB keepalive task.
+///
+/// The task holds an SMB connection to the share and periodically touches
+/// the share root so the session isn't dropped during long operations.
+/// The connection is released when the keepalive is stopped or dropped.
+pub struct SmbKeepalive {
+    share: String,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl SmbKeepalive {
+    /// Stop the keepalive task and release its connection.
+    pub fn stop(self) {
+        debug!(share = %self.share, "Stopping SMB keepalive");
+        // Drop aborts the task
+    }
+}
+
+impl Drop for SmbKeepalive {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Start an SMB keepalive for a share.
+///
+/// # Arguments
+/// * `share` - UNC share path (like `\\target\ADMIN$\Temp`)
+/// * `credentials` - Credentials for SMB authentication
+/// * `interval` - How often to touch the share (zero disables the keepalive)
+///
+/// # Returns
+/// A keepalive handle, or `None` if disabled.
+#[instrument(skip(credentials))]
+pub fn start_keepalive(
+    share: &str,
+    credentials: &Credential,
+    interval: Duration,
+) -> Option<SmbKeepalive> {
+    if interval.is_zero() {
+        return None;
+    }
+
+    let share_path = share.to_string();
+    let creds = credentials.clone();
+
+    let handle = tokio::spawn(async move {
+        // Hold a connection for the lifetime of the keepalive
+        let connect_share = share_path.clone();
+        let _conn = match tokio::task::spawn_blocking(move || {
+            SmbConnection::connect(&connect_share, &creds, Duration::from_secs(30))
+        })
+        .await
+        {
+            Ok(Ok(conn)) => conn,
+            Ok(Err(e)) => {
+                warn!(error = %e, "SMB keepalive could not connect");
+                return;
+            }
+            Err(e) => {
+                warn!(error = %e, "SMB keepalive task failed");
+                return;
+            }
+        };
+
+        loop {
+            tokio::time::sleep(interval).await;
+
+            // Cheap attribute query on the share root keeps the session active
+            let touch_path = share_path.clone();
+            match tokio::task::spawn_blocking(move || path_exists_internal(&touch_path)).await {
+                Ok(true) => debug!(share = %share_path, "SMB keepalive"),
+                Ok(false) => warn!(share = %share_path, "SMB keepalive: share not accessible"),
+                Err(e) => warn!(error = %e, "SMB keepalive task failed"),
+            }
+        }
+    });
+
+    debug!(share = %share, interval_secs = interval.as_secs(), "Started SMB keepalive");
+
+    Some(SmbKeepalive {
+        share: share.to_string(),
+        handle,
+    })
+}
+
+/// Most bytes of a tailed log read per poll
+const LOG_TAIL_MAX_READ: u64 = 256 * 1024;
+
+/// Time allowed for the final read when a log tail is finished
+const LOG_TAIL_FINISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Handle to a running log tail task.
+///
+/// The task holds an SMB connection to the file's share and periodically
+/// reads what was appended since the last read, handing complete lines to
+/// its callback with the offset they start at.
+pub struct SmbLogTail {
+    path: String,
+    stop: Option<tokio::sync::oneshot::Sender<()>>,
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl SmbLogTail {
+    /// Stop tailing after a last read of anything appended since the previous
+    /// poll (including an unterminated final line).
+    pub async fn finish(mut self) {
+        debug!(path = %self.path, "Finishing log tail");
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if tokio::time::timeout(LOG_TAIL_FINISH_TIMEOUT, &mut self.handle).await.is_err() {
+            warn!(path = %self.path, "Log tail did not finish in time");
+        }
+        // Drop aborts the task if it is still running
+    }
+}
+
+impl Drop for SmbLogTail {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Length of the complete lines at the start of `bytes`.
+///
+/// A read that filled the whole buffer without a newline is taken as is, so
+/// one very long line can't stall the tail.
+fn complete_lines_len(bytes: &[u8], max_read: u64) -> usize {
+    match bytes.iter().rposition(|&b| b == b'\n') {
+        Some(last) => last + 1,
+        None if bytes.len() as u64 >= max_read => bytes.len(),
+        None => 0,
+    }
+}
+
+/// Start tailing a growing file on a target.
+///
+/// The file may not exist yet (msiexec creates its log once it starts). If
+/// it shrinks, it was replaced and is read again from the start.
+///
+/// # Arguments
+/// * `path` - UNC path of the file (like `\\target\ADMIN$\Temp\install.log`)
+/// * `credentials` - Credentials for SMB authentication
+/// * `interval` - How often to read new content (zero disables the tail)
+/// * `on_content` - Called with `(offset, text)` for each batch of new lines
+///
+/// # Returns
+/// A tail handle, or `None` if disabled or the path is not a UNC path.
+#[instrument(skip(credentials, on_content))]
+pub fn start_log_tail(
+    path: &str,
+    credentials: &Credential,
+    interval: Duration,
+    mut on_content: impl FnMut(u64, String) + Send + 'static,
+) -> Option<SmbLogTail> {
+    if interval.is_zero() {
+        return None;
+    }
+
+    let parts: Vec<&str> = path.trim_start_matches("\\\\").splitn(3, '\\').collect();
+    if !path.starts_with("\\\\") || parts.len() < 3 {
+        warn!(path = %path, "Cannot tail a non-UNC path");
+        return None;
+    }
+    let share_path = format!("\\\\{}\\{}", parts[0], parts[1]);
+
+    let file_path = path.to_string();
+    let creds = credentials.clone();
+    let (stop_tx, mut stop_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let handle = tokio::spawn(async move {
+        // Hold a connection for the lifetime of the tail
+        let _conn = match tokio::task::spawn_blocking(move || {
+            SmbConnection::connect(&share_path, &creds, Duration::from_secs(30))
+        })
+        .await
+        {
+            Ok(Ok(conn)) => conn,
+            Ok(Err(e)) => {
+                warn!(error = %e, "Log tail could not connect");
+                return;
+            }
+            Err(e) => {
+                warn!(error = %e, "Log tail task failed");
+                return;
+            }
+        };
+
+        let mut offset = 0u64;
+        let mut stopping = false;
+        while !stopping {
+            tokio::select! {
+                _ = tokio::time::sleep(interval) => {}
+                _ = &mut stop_rx => stopping = true,
+            }
+
+            let read_path = file_path.clone();
+            let from = offset;
+            let (bytes, size) =
+                match tokio::task::spawn_blocking(move || read_from_internal(&read_path, from, LOG_TAIL_MAX_READ)).await {
+                    Ok(Ok(read)) => read,
+                    // Not created yet
+                    Ok(Err(SmbError::PathNotFound(_))) => continue,
+                    Ok(Err(e)) => {
+                        debug!(error = %e, "Log tail read failed");
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "Log tail task failed");
+                        continue;
+                    }
+                };
+
+            if size < offset {
+                debug!(path = %file_path, "Tailed file shrank, reading from the start");
+                offset = 0;
+                continue;
+            }
+
+            let len = if stopping {
+                bytes.len()
+            } else {
+                complete_lines_len(&bytes, LOG_TAIL_MAX_READ)
+            };
+            if len > 0 {
+                on_content(offset, String::from_utf8_lossy(&bytes[..len]).into_owned());
+                offset += len as u64;
+            }
+        }
+    });
+
+    debug!(path = %path, interval_secs = interval.as_secs(), "Started log tail");
+
+    Some(SmbLogTail {
+        path: path.to_string(),
+        stop: Some(stop_tx),
+        handle,
     })
-    .await
-    .map_err(|e| SmbError::NetworkError(format!("Task failed: {}", e)))?
 }
 
 /// Extract the filename from a UNC path.
@@ -511,6 +1442,173 @@ mod tests {
         assert_eq!(extract_filename("file.msi"), Some("file.msi"));
     }
 
+    #[test]
+    fn test_copy_error_mapping() {
+        let dest = "\\\\server\\ADMIN$\\Temp\\installer.msi";
+
+        assert!(matches!(copy_error("src", dest, 112), SmbError::DiskFull(_)));
+        assert!(matches!(copy_error("src", dest, 39), SmbError::DiskFull(_)));
+        assert!(matches!(copy_error("src", dest, 5), SmbError::AccessDenied(_)));
+        assert!(matches!(copy_error("src", dest, 3), SmbError::PathNotFound(_)));
+        assert!(matches!(copy_error("src", dest, 54), SmbError::NetworkError(_)));
+
+        assert_eq!(
+            copy_error("src", dest, 112).to_string(),
+            format!("Target disk is full: {}", dest)
+        );
+
+        match copy_error("src", dest, 1234) {
+            SmbError::CopyFailed { message, .. } => assert_eq!(message, "Error code: 1234"),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_admin_share_path() {
+        let roots = ["C:\\ProgramData", "C:\\Program Files\\"];
+
+        assert_eq!(
+            admin_share_path("target-01", "c:\\programdata\\Vendor\\app.log", &roots).unwrap(),
+            "\\\\target-01\\C$\\programdata\\Vendor\\app.log"
+        );
+        assert_eq!(
+            admin_share_path("target-01", "C:/Program Files/App/app.ini", &roots).unwrap(),
+            "\\\\target-01\\C$\\Program Files\\App\\app.ini"
+        );
+
+        // Outside the allowed roots (including prefix look-alikes)
+        assert!(admin_share_path("target-01", "C:\\Windows\\System32\\config\\SAM", &roots).is_err());
+        assert!(admin_share_path("target-01", "C:\\ProgramDataX\\secret.txt", &roots).is_err());
+        assert!(admin_share_path("target-01", "C:\\ProgramData", &roots).is_err());
+        // Traversal and relative paths
+        assert!(admin_share_path("target-01", "C:\\ProgramData\\..\\Users\\a.txt", &roots).is_err());
+        assert!(admin_share_path("target-01", "ProgramData\\app.log", &roots).is_err());
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), ("b\nc\n", true));
+        assert_eq!(tail_lines("a\r\nb\r\nc", 1), ("c", true));
+        assert_eq!(tail_lines("a\nb\n", 5), ("a\nb\n", false));
+        assert_eq!(tail_lines("a\nb\n", 0), ("", true));
+    }
+
+    #[test]
+    fn test_required_free_space() {
+        assert_eq!(required_free_space(500, 3.0), 1500);
+        assert_eq!(required_free_space(500, 1.5), 750);
+        // Never less than the installer itself
+        assert_eq!(required_free_space(500, 0.5), 500);
+        assert_eq!(required_free_space(0, 3.0), 0);
+    }
+
+    #[test]
+    fn test_smb_security_requirements() {
+        let signed = SmbSessionSecurity {
+            dialect: Some("3.0.2".to_string()),
+            signed: true,
+            encrypted: false,
+        };
+        assert_eq!(signed.unmet_requirements(true, false), None);
+        assert!(signed.unmet_requirements(false, true).unwrap().contains("encryption"));
+
+        // Encrypted sessions report unsigned but satisfy a signing requirement
+        let encrypted = SmbSessionSecurity {
+            dialect: Some("3.1.1".to_string()),
+            signed: false,
+            encrypted: true,
+        };
+        assert_eq!(encrypted.unmet_requirements(true, true), None);
+
+        let plain = SmbSessionSecurity::default();
+        assert!(plain.unmet_requirements(true, false).unwrap().contains("signing"));
+        assert_eq!(plain.unmet_requirements(false, false), None);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_check_session_security_mock() {
+        let creds = Credential::new("user".to_string(), "pass".to_string());
+        let share = "\\\\target\\ADMIN$\\Temp";
+        let security = check_session_security(share, &creds, true, false, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(security.signed);
+
+        let err = check_session_security(share, &creds, false, true, Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SmbError::SecurityRequirementNotMet { .. }));
+    }
+
+    #[test]
+    fn test_copy_progress_percent() {
+        let progress = CopyProgress { transferred: 512, total: 1024 };
+        assert_eq!(progress.percent(), 50);
+
+        let empty = CopyProgress { transferred: 0, total: 0 };
+        assert_eq!(empty.percent(), 100);
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_copy_progress_kept_without_receivers() {
+        // The executor keeps no receiver between heartbeats; progress must still be stored
+        let (sender, _) = watch::channel(None);
+        let creds = Credential::new("user".to_string(), "pass".to_string());
+        copy_file_with_progress(
+            "\\\\server\\share\\installer.msi",
+            "\\\\target\\ADMIN$\\Temp",
+            &creds,
+            Duration::from_secs(5),
+            Some(sender.clone()),
+        )
+        .await
+        .unwrap();
+
+        let progress = (*sender.borrow()).unwrap();
+        assert_eq!(progress.transferred, progress.total);
+        assert!(progress.total > 0);
+    }
+
+    #[test]
+    fn test_complete_lines_len() {
+        assert_eq!(complete_lines_len(b"line 1\r\nline 2\r\npartial", 1024), 16);
+        assert_eq!(complete_lines_len(b"partial", 1024), 0);
+        assert_eq!(complete_lines_len(b"", 1024), 0);
+        // A full read without a newline is taken whole
+        assert_eq!(complete_lines_len(b"0123456789", 10), 10);
+    }
+
+    #[tokio::test]
+    async fn test_log_tail_disabled_with_zero_interval() {
+        let creds = Credential::new("user".to_string(), "pass".to_string());
+        let tail = start_log_tail("\\\\server\\ADMIN$\\Temp\\install.log", &creds, Duration::ZERO, |_, _| {});
+        assert!(tail.is_none());
+    }
+
+    #[test]
+    fn test_copy_progress_throughput() {
+        let progress = CopyProgress { transferred: 10 * 1024 * 1024, total: 10 * 1024 * 1024 };
+        assert_eq!(progress.throughput(Duration::from_secs(4)), Some(2_621_440));
+        assert_eq!(progress.throughput(Duration::from_millis(500)), Some(20_971_520));
+        assert_eq!(progress.throughput(Duration::ZERO), None);
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_disabled_with_zero_interval() {
+        let creds = Credential::new("user".to_string(), "pass".to_string());
+        let keepalive = start_keepalive("\\\\server\\ADMIN$\\Temp", &creds, Duration::ZERO);
+        assert!(keepalive.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_path_exists_rejects_non_unc() {
+        let creds = Credential::new("user".to_string(), "pass".to_string());
+        let result = check_path_exists("C:\\Temp\\file.msi", &creds, Duration::from_secs(1)).await;
+        assert!(matches!(result, Err(SmbError::InvalidPath(_))));
+    }
+
     #[test]
     fn test_smb_error_display() {
         let err = SmbError::ConnectionFailed {
@@ -0,0 +1,112 @@
+//! Wake-on-LAN for powered-off or sleeping targets.
+//!
+//! Sends the standard magic packet (6 x 0xFF followed by the target's MAC
+//! address 16 times) as a UDP broadcast on port 9. The limited broadcast
+//! (255.255.255.255) only reaches the worker's own subnet; targets elsewhere
+//! need a subnet-directed broadcast address that the routers forward.
+
+use std::net::{Ipv4Addr, SocketAddrV4, UdpSocket};
+
+use thiserror::Error;
+use tracing::{debug, instrument};
+
+/// UDP port magic packets are sent to ("discard")
+const WOL_PORT: u16 = 9;
+
+/// Packets sent per wake; UDP broadcast gives no delivery guarantee
+const WOL_REPEAT: usize = 3;
+
+/// Errors from Wake-on-LAN
+#[derive(Debug, Error)]
+pub enum WolError {
+    #[error("Invalid MAC address: {0}")]
+    InvalidMac(String),
+
+    #[error("Failed to send magic packet: {0}")]
+    SendFailed(String),
+}
+
+/// Parse a MAC address.
+///
+/// Accepts `AA:BB:CC:DD:EE:FF`, `AA-BB-CC-DD-EE-FF`, `aabb.ccdd.eeff` and
+/// `AABBCCDDEEFF`.
+pub fn parse_mac(mac: &str) -> Result<[u8; 6], WolError> {
+    let hex: String = mac
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect();
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(WolError::InvalidMac(mac.to_string()));
+    }
+
+    let mut bytes = [0u8; 6];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| WolError::InvalidMac(mac.to_string()))?;
+    }
+    Ok(bytes)
+}
+
+/// Build the magic packet for a MAC address.
+pub fn magic_packet(mac: [u8; 6]) -> [u8; 102] {
+    let mut packet = [0xFFu8; 102];
+    for chunk in packet[6..].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac);
+    }
+    packet
+}
+
+/// Send a Wake-on-LAN magic packet to a MAC address.
+///
+/// # Arguments
+/// * `mac_address` - Target's MAC address (see `parse_mac` for formats)
+/// * `broadcast` - Broadcast address to send to (limited or subnet-directed)
+#[instrument]
+pub async fn wake_on_lan(mac_address: &str, broadcast: Ipv4Addr) -> Result<(), WolError> {
+    let packet = magic_packet(parse_mac(mac_address)?);
+
+    tokio::task::spawn_blocking(move || {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).map_err(|e| WolError::SendFailed(e.to_string()))?;
+        socket
+            .set_broadcast(true)
+            .map_err(|e| WolError::SendFailed(e.to_string()))?;
+
+        let destination = SocketAddrV4::new(broadcast, WOL_PORT);
+        for _ in 0..WOL_REPEAT {
+            socket
+                .send_to(&packet, destination)
+                .map_err(|e| WolError::SendFailed(e.to_string()))?;
+        }
+
+        debug!(%destination, "Sent Wake-on-LAN magic packets");
+        Ok(())
+    })
+    .await
+    .map_err(|e| WolError::SendFailed(format!("Task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mac() {
+        let expected = [0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E];
+        assert_eq!(parse_mac("00:1A:2B:3C:4D:5E").unwrap(), expected);
+        assert_eq!(parse_mac("00-1a-2b-3c-4d-5e").unwrap(), expected);
+        assert_eq!(parse_mac("001a.2b3c.4d5e").unwrap(), expected);
+        assert_eq!(parse_mac(" 001A2B3C4D5E ").unwrap(), expected);
+
+        assert!(parse_mac("00:1A:2B:3C:4D").is_err());
+        assert!(parse_mac("00:1A:2B:3C:4D:5G").is_err());
+        assert!(parse_mac("").is_err());
+    }
+
+    #[test]
+    fn test_magic_packet() {
+        let mac = [0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E];
+        let packet = magic_packet(mac);
+        assert_eq!(&packet[..6], &[0xFF; 6]);
+        assert!(packet[6..].chunks_exact(6).all(|chunk| chunk == mac));
+    }
+}
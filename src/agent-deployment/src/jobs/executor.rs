@@ -3,26 +3,99 @@
 //! Handles the execution of deployment jobs across multiple targets,
 //! including credential resolution, SMB copy, and MSI installation.
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use thiserror::Error;
+use tokio::sync::{mpsc, watch};
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
 use crate::api::types::{
-    DeploymentJob, DeploymentTarget, ExecutionPhase, InlineCredentials, JobPayload, JobResult,
-    JobStatus, JobType, TargetResult,
+    DeploymentJob, DeploymentTarget, ExecutionPhase, FileContent, InlineCredentials, JobPayload,
+    JobResult, JobStatus, JobType, MsiLogChunk, TargetResult,
 };
 use crate::audit::{audit_event, AuditEvent, AuditEventType};
 use crate::config::WorkerConfig;
 use crate::credentials::{Credential, CredentialVault, VaultError};
 use crate::execution::{
-    installer::{build_msi_install_command, build_msi_uninstall_command, wrap_for_service_execution, MsiExitCode},
-    service::{check_reachability, execute_msi_via_service},
-    smb::{copy_file, delete_file},
+    installer::{
+        build_defender_realtime_command, build_execute_command, build_firewall_rules_command, build_msi_install_command, build_msi_uninstall_command, get_local_log_path,
+        get_msi_log_name, get_remote_log_path, get_service_output_names, wrap_for_service_execution,
+        wrap_for_service_execution_with_output, MsiExitCode,
+    },
+    events::query_deployment_events,
+    platform::query_virtualization,
+    security::query_security_products,
+    service::{check_reachability, execute_msi_via_service, ServiceExecutionResult},
+    smb::{
+        admin_share_path, check_free_space, check_session_security, copy_file_with_progress, delete_file, download_file,
+        read_file_tail, start_keepalive, start_log_tail, CopyProgress, CopyProgressSender, SmbError, SmbLogTail,
+    },
+    wol::wake_on_lan,
 };
 
+/// Interval between reachability checks while a woken target boots
+const WAKE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Timeout for the pre-copy free space query on a target
+const DISK_SPACE_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for the pre-copy SMB signing/encryption check on a target
+const SMB_SECURITY_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for the security product (WMI) query on a target
+const SECURITY_PRODUCTS_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Timeout for the virtual machine (WMI) query on a target
+const VIRTUALIZATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Timeout for reading a file from a target (ReadFile jobs)
+const READ_FILE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum bytes returned per target by ReadFile jobs (read from the end of the file)
+const READ_FILE_MAX_BYTES: u64 = 64 * 1024;
+
+/// Directories ReadFile jobs may read from: where installers put their
+/// configs and logs, not user profiles or system hives
+const READ_FILE_ALLOWED_ROOTS: &[&str] = &[
+    "C:\\ProgramData",
+    "C:\\Program Files",
+    "C:\\Program Files (x86)",
+    "C:\\Windows\\Temp",
+    "C:\\Windows\\Logs",
+];
+
+/// Maximum bytes of each log returned inline by CollectLogs jobs (read from the
+/// end; the complete file goes to the worker's log bundle)
+const COLLECT_LOGS_MAX_BYTES: u64 = 256 * 1024;
+
+/// Timeout for reading or deleting one log file (CollectLogs jobs)
+const COLLECT_LOGS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Timeout for the event log (WMI) query on a target (CollectLogs jobs)
+const EVENT_LOG_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum event log entries returned per target (CollectLogs jobs)
+const EVENT_LOG_MAX_ENTRIES: usize = 200;
+
+/// How far back CollectLogs jobs read the event log when `logsSince` is not set
+const EVENT_LOG_DEFAULT_HOURS: i64 = 24;
+
+/// Timeout for turning Defender real-time protection off or on (disableAvDuringInstall)
+const DEFENDER_TOGGLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Attempts to turn Defender real-time protection back on after an install
+const DEFENDER_RESTORE_ATTEMPTS: u32 = 3;
+
+/// Timeout for turning the deployment firewall rules on or off (enableFirewallRulesForDeploy)
+const FIREWALL_TOGGLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Attempts to turn the deployment firewall rules back off after a target
+const FIREWALL_RESTORE_ATTEMPTS: u32 = 3;
+
 /// Errors from job execution
 #[derive(Debug, Error)]
 pub enum ExecutionError {
@@ -55,6 +128,10 @@ pub enum ExecutionError {
 pub struct JobExecutor {
     config: WorkerConfig,
     worker_id: String,
+    /// Progress of the current SMB copy (None when no copy is running)
+    copy_progress: CopyProgressSender,
+    /// Receives new MSI log lines while msiexec runs (None = not tailed)
+    msi_log_sender: Option<mpsc::UnboundedSender<MsiLogChunk>>,
 }
 
 impl JobExecutor {
@@ -64,13 +141,33 @@ impl JobExecutor {
     /// * `config` - Worker configuration
     /// * `worker_id` - Unique worker identifier
     pub fn new(config: WorkerConfig, worker_id: String) -> Self {
-        Self { config, worker_id }
+        let (copy_progress, _) = watch::channel(None);
+        Self {
+            config,
+            worker_id,
+            copy_progress,
+            msi_log_sender: None,
+        }
+    }
+
+    /// Send new lines of the target's MSI log to `sender` while installs and
+    /// uninstalls run (every `msi_log_tail_seconds`).
+    pub fn with_msi_log_tail(mut self, sender: mpsc::UnboundedSender<MsiLogChunk>) -> Self {
+        self.msi_log_sender = Some(sender);
+        self
+    }
+
+    /// Subscribe to progress updates for the current SMB copy.
+    ///
+    /// The value is `None` while no copy is in progress.
+    pub fn copy_progress(&self) -> watch::Receiver<Option<CopyProgress>> {
+        self.copy_progress.subscribe()
     }
 
     /// Execute a deployment job.
     ///
     /// This is the main entry point for job execution. It:
-    /// 1. Validates the job type
+    /// 1. Validates the job type and that it has targets
     /// 2. Executes on each target sequentially
     /// 3. Collects results
     /// 4. Returns a complete JobResult
@@ -80,13 +177,149 @@ impl JobExecutor {
     ///
     /// # Returns
     /// A JobResult with outcomes for each target.
-    #[instrument(skip(self, job), fields(job_id = %job.id, job_type = ?job.job_type))]
     pub async fn execute(&self, job: DeploymentJob) -> JobResult {
+        if job.payload.targets.is_empty() {
+            warn!(job_id = %job.id, "Job has no targets, skipping execution");
+            return self.failed_result(job.id, "Job has no targets".to_string());
+        }
+
+        let targets = job.payload.targets.clone();
+        self.execute_targets(&job, &targets).await
+    }
+
+    /// Execute a retry job against the payload of its original job.
+    ///
+    /// Only the original targets listed in the retry payload's `retry_hostnames`
+    /// are executed. The result is tagged with the original job id.
+    ///
+    /// # Arguments
+    /// * `retry` - The retry job (provides id, priority and hostnames)
+    /// * `original` - The original job, as fetched from the backend
+    #[instrument(skip(self, retry, original), fields(job_id = %retry.id, retry_of = %original.id))]
+    pub async fn execute_retry(&self, retry: DeploymentJob, original: DeploymentJob) -> JobResult {
+        let original_id = original.id;
+        let (targets, missing) = original
+            .payload
+            .filter_targets(&retry.payload.retry_hostnames);
+
+        if !missing.is_empty() {
+            warn!(
+                missing = ?missing,
+                "Retry hostnames not found in original job, skipping"
+            );
+        }
+
+        let mut result = if original.job_type == JobType::Retry {
+            self.failed_result(retry.id, "Cannot retry a retry job".to_string())
+        } else if targets.is_empty() {
+            self.failed_result(
+                retry.id,
+                format!("No targets of job {} match the retry hostnames", original_id),
+            )
+        } else {
+            info!(
+                targets = targets.len(),
+                original_targets = original.payload.targets.len(),
+                "Retrying failed targets of original job"
+            );
+
+            let job = DeploymentJob {
+                id: retry.id,
+                job_type: original.job_type,
+                created_at: retry.created_at,
+                priority: retry.priority,
+                payload: original.payload,
+                claimed_by: retry.claimed_by,
+                claimed_at: retry.claimed_at,
+            };
+            self.execute_targets(&job, &targets).await
+        };
+
+        result.retry_of = Some(original_id);
+        result
+    }
+
+    /// Check whether this worker can run the job at all.
+    ///
+    /// Runs before execution so that jobs this worker cannot handle (unsupported
+    /// job type, credentials missing from this worker's vault) can be returned
+    /// to the queue for another worker instead of failing.
+    ///
+    /// # Returns
+    /// `Err(reason)` if the job should be NAK'd.
+    pub fn preflight(&self, job: &DeploymentJob) -> Result<(), String> {
+        match job.job_type {
+            JobType::MsiInstall | JobType::MsiUninstall | JobType::ReadFile | JobType::CollectLogs | JobType::Validate => {}
+            // Resolved against the original job after fetching it
+            JobType::Retry => return Ok(()),
+            JobType::Execute => {
+                // SECURITY: refuse a working directory or environment that can't be escaped for cmd.exe
+                build_execute_command(
+                    &job.payload.installer_path,
+                    job.payload.working_directory.as_deref(),
+                    &job.payload.environment,
+                )
+                .map_err(|e| e.to_string())?;
+                return Err("Direct execution is not supported by this worker".to_string());
+            }
+        }
+
+        if job.payload.working_directory.is_some() || !job.payload.environment.is_empty() {
+            return Err("workingDirectory and environment are only supported for Execute jobs".to_string());
+        }
+
+        // SECURITY: turning off antivirus must be allowed on the worker and only applies to installs
+        if job.payload.disable_av_during_install {
+            if job.job_type != JobType::MsiInstall {
+                return Err("disableAvDuringInstall is only supported for MSI install jobs".to_string());
+            }
+            if !self.config.allow_av_disable {
+                return Err("Turning off antivirus during install is not allowed on this worker (allow_av_disable)".to_string());
+            }
+        }
+
+        // SECURITY: opening the target's firewall must be allowed on the worker
+        if job.payload.enable_firewall_rules_for_deploy && !self.config.allow_firewall_changes {
+            return Err("Changing target firewall rules is not allowed on this worker (allow_firewall_changes)".to_string());
+        }
+
+        let mut vault_refs: Vec<&str> = vec![job.payload.vault_ref.as_str()];
+        for target in &job.payload.targets {
+            if let Some(vault_ref) = target.vault_ref.as_deref() {
+                if !vault_refs.contains(&vault_ref) {
+                    vault_refs.push(vault_ref);
+                }
+            }
+        }
+
+        for vault_ref in vault_refs {
+            if let Err(e) = self.resolve_credentials(vault_ref, job.payload.inline_credentials.as_ref()) {
+                return Err(format!("Credentials '{}' unavailable on this worker: {}", vault_ref, e));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a finalized failed result for a job that could not be executed.
+    pub fn failed_result(&self, job_id: Uuid, message: String) -> JobResult {
+        let mut result = JobResult::new(job_id, self.worker_id.clone(), Utc::now());
+        result.error_message = Some(message);
+        result.finalize();
+        result
+    }
+
+    /// Execute a job on a pre-selected list of targets.
+    ///
+    /// `targets` is usually the job's own target list, but may be a subset
+    /// (e.g. the failed targets of a retried job).
+    #[instrument(skip(self, job, targets), fields(job_id = %job.id, job_type = ?job.job_type))]
+    pub async fn execute_targets(&self, job: &DeploymentJob, targets: &[DeploymentTarget]) -> JobResult {
         let started_at = Utc::now();
         let mut result = JobResult::new(job.id, self.worker_id.clone(), started_at);
 
         info!(
-            targets = job.payload.targets.len(),
+            targets = targets.len(),
             "Starting job execution"
         );
 
@@ -115,9 +348,17 @@ impl JobExecutor {
             return result;
         }
 
+        // Retry jobs must be resolved against their original job first
+        if job.job_type == JobType::Retry {
+            result.error_message = Some("Retry job was not resolved against its original job".to_string());
+            result.status = JobStatus::Failed;
+            result.finalize();
+            return result;
+        }
+
         // Execute on each target sequentially
-        for target in &job.payload.targets {
-            let target_result = self.execute_on_target(&job, target).await;
+        for target in targets {
+            let target_result = self.execute_on_target(job, target).await;
 
             // Audit: Target completed
             audit_event(
@@ -164,13 +405,26 @@ impl JobExecutor {
     /// Execute the job on a single target.
     #[instrument(skip(self, job), fields(target = %target.hostname))]
     async fn execute_on_target(&self, job: &DeploymentJob, target: &DeploymentTarget) -> TargetResult {
+        let mut timer = PhaseTimer::new();
+        let mut target_result = self.run_on_target(job, target, &mut timer).await;
+        timer.finish(&mut target_result);
+        target_result
+    }
+
+    /// Run all phases on a single target, recording phase boundaries in `timer`.
+    async fn run_on_target(
+        &self,
+        job: &DeploymentJob,
+        target: &DeploymentTarget,
+        timer: &mut PhaseTimer,
+    ) -> TargetResult {
         let start = Instant::now();
         let hostname = &target.hostname;
         let machine_id = target.machine_id.clone();
 
         info!("Starting execution on target");
 
-        // Step 1: Reachability check
+        // Step 1: Reachability check, waking the target if it has a MAC address
         debug!("Checking target reachability");
         if let Err(e) = check_reachability(
             hostname,
@@ -178,15 +432,23 @@ impl JobExecutor {
         )
         .await
         {
-            return TargetResult::failure(
-                hostname.clone(),
-                machine_id,
-                format!("Target unreachable: {}", e),
-                start.elapsed().as_secs(),
-                ExecutionPhase::ReachabilityCheck,
-            );
+            let woken = match target.mac_address.as_deref() {
+                Some(mac) if self.config.wake_on_lan_wait_seconds > 0 => self.wake_target(hostname, mac).await,
+                _ => Err(format!("Target unreachable: {}", e)),
+            };
+            if let Err(message) = woken {
+                return TargetResult::failure(
+                    hostname.clone(),
+                    machine_id,
+                    message,
+                    start.elapsed().as_secs(),
+                    ExecutionPhase::ReachabilityCheck,
+                );
+            }
         }
 
+        timer.record(ExecutionPhase::ReachabilityCheck);
+
         // Audit: Target connected
         audit_event(
             AuditEvent::new(AuditEventType::TargetConnected, "connected", "Target is reachable")
@@ -214,14 +476,78 @@ impl JobExecutor {
             }
         };
 
+        timer.record(ExecutionPhase::CredentialResolution);
+
+        // Checks a Validate job could not complete, reported with its result
+        let mut validate_warnings = Vec::new();
+
+        // Step 2b: Record security products that might block the install (best effort;
+        // always queried by Validate jobs, whose purpose is reporting them before deploying)
+        let security_products = if job.job_type == JobType::Validate
+            || (self.config.detect_security_products
+                && matches!(job.job_type, JobType::MsiInstall | JobType::MsiUninstall))
+        {
+            debug!("Querying security products on target");
+            match query_security_products(hostname, &credentials, SECURITY_PRODUCTS_TIMEOUT).await {
+                Ok(products) => products,
+                Err(e) => {
+                    warn!(error = %e, "Could not query security products, continuing");
+                    validate_warnings.push(format!("Security products unknown: {}", e));
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        // Step 2b': Record whether the target is a VM (best effort; always queried by Validate jobs)
+        let virtualization = if job.job_type == JobType::Validate
+            || (self.config.detect_virtualization
+                && matches!(job.job_type, JobType::MsiInstall | JobType::MsiUninstall))
+        {
+            debug!("Querying virtualization on target");
+            match query_virtualization(hostname, &credentials, VIRTUALIZATION_TIMEOUT).await {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    warn!(error = %e, "Could not determine whether target is a VM, continuing");
+                    validate_warnings.push(format!("Virtualization unknown: {}", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Step 2c: Open the firewall for deployment (opt-in, reverted below).
+        // The worker setting is checked again here since retries skip preflight
+        let firewall_opened = if job.payload.enable_firewall_rules_for_deploy && self.config.allow_firewall_changes {
+            match self.set_deploy_firewall_rules(job, hostname, &credentials, true).await {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!(error = %e, "Could not turn on deployment firewall rules, continuing");
+                    false
+                }
+            }
+        } else {
+            false
+        };
+        timer.mark();
+
         // Step 3: Execute based on job type
-        match job.job_type {
+        let mut target_result = match job.job_type {
             JobType::MsiInstall => {
-                self.execute_msi_install(job, target, &credentials, start).await
+                self.execute_msi_install(job, target, &credentials, start, timer).await
             }
             JobType::MsiUninstall => {
-                self.execute_msi_uninstall(job, target, &credentials, start).await
+                self.execute_msi_uninstall(job, target, &credentials, start, timer).await
             }
+            JobType::ReadFile => {
+                self.execute_read_file(job, target, &credentials, start, timer).await
+            }
+            JobType::CollectLogs => {
+                self.execute_collect_logs(job, target, &credentials, start, timer).await
+            }
+            JobType::Validate => self.execute_validate(target, &credentials, start).await,
             JobType::Execute => {
                 // Direct execution not yet implemented
                 TargetResult::failure(
@@ -232,6 +558,86 @@ impl JobExecutor {
                     ExecutionPhase::ServiceExecution,
                 )
             }
+            JobType::Retry => {
+                // Resolved to the original job type before reaching here
+                TargetResult::failure(
+                    hostname.clone(),
+                    machine_id,
+                    "Unresolved retry job".to_string(),
+                    start.elapsed().as_secs(),
+                    ExecutionPhase::ServiceExecution,
+                )
+            }
+        };
+        target_result.security_products = security_products;
+        target_result.virtualization = virtualization;
+        if job.job_type == JobType::Validate && target_result.success && !validate_warnings.is_empty() {
+            target_result.error_message = Some(validate_warnings.join("; "));
+        }
+
+        // Step 4: Restore the firewall, whatever the result
+        if firewall_opened {
+            let mut restore_error = None;
+            for attempt in 1..=FIREWALL_RESTORE_ATTEMPTS {
+                match self.set_deploy_firewall_rules(job, hostname, &credentials, false).await {
+                    Ok(()) => {
+                        restore_error = None;
+                        break;
+                    }
+                    Err(e) => {
+                        warn!(attempt, error = %e, "Failed to turn deployment firewall rules back off");
+                        restore_error = Some(e);
+                    }
+                }
+            }
+            timer.record(ExecutionPhase::Cleanup);
+            if let Some(e) = restore_error {
+                error!(host = %hostname, error = %e, "Deployment firewall rules are still ON on target");
+                let warning = format!("Firewall rules could not be restored: {}", e);
+                target_result.error_message = Some(match target_result.error_message.take() {
+                    Some(message) => format!("{}; {}", message, warning),
+                    None => warning,
+                });
+            }
+        }
+        target_result
+    }
+
+    /// Wake a target with Wake-on-LAN and wait until it is reachable.
+    ///
+    /// Polls the reachability check every `WAKE_POLL_INTERVAL` for up to
+    /// `wake_on_lan_wait_seconds`.
+    async fn wake_target(&self, hostname: &str, mac_address: &str) -> Result<(), String> {
+        let broadcast = self
+            .config
+            .wake_on_lan_broadcast
+            .parse()
+            .map_err(|_| format!("Invalid wake_on_lan_broadcast: {}", self.config.wake_on_lan_broadcast))?;
+
+        info!(mac = %mac_address, "Target unreachable, sending Wake-on-LAN");
+        wake_on_lan(mac_address, broadcast)
+            .await
+            .map_err(|e| format!("Target unreachable, Wake-on-LAN failed: {}", e))?;
+
+        let wait = Duration::from_secs(self.config.wake_on_lan_wait_seconds);
+        let reachability_timeout = Duration::from_secs(self.config.reachability_timeout_seconds);
+        let woken_at = Instant::now();
+        loop {
+            tokio::time::sleep(WAKE_POLL_INTERVAL).await;
+            match check_reachability(hostname, reachability_timeout).await {
+                Ok(()) => {
+                    info!(waited_secs = woken_at.elapsed().as_secs(), "Target woke up");
+                    return Ok(());
+                }
+                Err(e) if woken_at.elapsed() >= wait => {
+                    return Err(format!(
+                        "Target unreachable {}s after Wake-on-LAN: {}",
+                        wait.as_secs(),
+                        e
+                    ));
+                }
+                Err(_) => {}
+            }
         }
     }
 
@@ -242,24 +648,100 @@ impl JobExecutor {
         target: &DeploymentTarget,
         credentials: &Credential,
         start: Instant,
+        timer: &mut PhaseTimer,
     ) -> TargetResult {
         let hostname = &target.hostname;
         let machine_id = target.machine_id.clone();
         let payload = &job.payload;
 
+        let remote_share = format!("\\\\{}\\ADMIN$\\Temp", hostname);
+
+        // Pre-flight: fail before copying if the target can't hold the installer
+        if self.config.disk_space_multiplier > 0.0 {
+            debug!("Checking free disk space on target");
+            match check_free_space(
+                &payload.installer_path,
+                &remote_share,
+                credentials,
+                self.config.disk_space_multiplier,
+                DISK_SPACE_CHECK_TIMEOUT,
+            )
+            .await
+            {
+                Ok(_) => {}
+                Err(e @ SmbError::InsufficientSpace { .. }) => {
+                    return TargetResult::failure(
+                        hostname.clone(),
+                        machine_id,
+                        e.to_string(),
+                        start.elapsed().as_secs(),
+                        ExecutionPhase::DiskSpaceCheck,
+                    );
+                }
+                Err(e) => {
+                    // Not conclusive; the copy reports its own error if it fails
+                    warn!(error = %e, "Could not check free disk space, continuing");
+                }
+            }
+            timer.record(ExecutionPhase::DiskSpaceCheck);
+        }
+
+        // Pre-flight: hardened networks may negotiate a session their policy
+        // then refuses to copy over; fail here with the reason instead
+        if self.config.require_smb_signing || self.config.require_smb_encryption {
+            debug!("Checking SMB signing/encryption on target");
+            if let Err(e) = check_session_security(
+                &remote_share,
+                credentials,
+                self.config.require_smb_signing,
+                self.config.require_smb_encryption,
+                SMB_SECURITY_CHECK_TIMEOUT,
+            )
+            .await
+            {
+                return TargetResult::failure(
+                    hostname.clone(),
+                    machine_id,
+                    format!("SMB security check failed: {}", e),
+                    start.elapsed().as_secs(),
+                    ExecutionPhase::SmbCopy,
+                );
+            }
+        }
+
         // Step 3a: Copy MSI to target
         debug!("Copying MSI to target");
-        let remote_share = format!("\\\\{}\\ADMIN$\\Temp", hostname);
 
-        let remote_msi_path = match copy_file(
+        self.copy_progress.send_replace(None);
+        let copy_started = Instant::now();
+        let copy_result = copy_file_with_progress(
             &payload.installer_path,
             &remote_share,
             credentials,
             Duration::from_secs(self.config.smb_copy_timeout_seconds),
+            Some(self.copy_progress.clone()),
         )
-        .await
-        {
+        .await;
+        let copy_elapsed = copy_started.elapsed();
+
+        // Last progress report = bytes copied (all of them on success)
+        let copied = *self.copy_progress.borrow();
+        let copy_throughput = copied.and_then(|progress| progress.throughput(copy_elapsed));
+        if let Some(progress) = copied {
+            debug!(
+                transferred = progress.transferred,
+                total = progress.total,
+                percent = progress.percent(),
+                elapsed_ms = copy_elapsed.as_millis() as u64,
+                bytes_per_sec = copy_throughput,
+                "SMB copy finished"
+            );
+        }
+        self.copy_progress.send_replace(None);
+
+        let remote_msi_path = match copy_result {
             Ok(path) => {
+                timer.record(ExecutionPhase::SmbCopy);
                 audit_event(
                     AuditEvent::new(AuditEventType::FileCopied, "success", "MSI copied to target")
                         .with_job_id(job.id)
@@ -268,13 +750,17 @@ impl JobExecutor {
                 path
             }
             Err(e) => {
-                return TargetResult::failure(
+                let mut target_result = TargetResult::failure(
                     hostname.clone(),
                     machine_id,
                     format!("SMB copy failed: {}", e),
                     start.elapsed().as_secs(),
                     ExecutionPhase::SmbCopy,
                 );
+                // A slow link that timed out is worth knowing about too
+                target_result.copied_bytes = copied.map(|progress| progress.transferred);
+                target_result.copy_throughput_bytes_per_sec = copy_throughput;
+                return target_result;
             }
         };
 
@@ -284,11 +770,14 @@ impl JobExecutor {
             .replace(&format!("\\\\{}\\ADMIN$", hostname), "C:\\Windows")
             .replace(&format!("\\\\{}\\admin$", hostname), "C:\\Windows");
 
-        // Step 3b: Build MSI command
+        // Step 3b: Build MSI command (always logging, left on the target for diagnosis)
+        let log_name = get_msi_log_name(job.id, job.job_type);
+        let local_log_path = get_local_log_path(&log_name);
         let msi_command = match build_msi_install_command(
             &local_msi_path,
-            payload.install_args.as_deref(),
+            payload.install_args_for(target),
             payload.enroll_token.as_deref(),
+            Some((&local_log_path, payload.msi_log_level)),
         ) {
             Ok(cmd) => cmd,
             Err(e) => {
@@ -304,7 +793,14 @@ impl JobExecutor {
             }
         };
 
-        let service_command = wrap_for_service_execution(&msi_command);
+        // Console output is kept on the target next to the MSI log for CollectLogs jobs
+        let [stdout_name, stderr_name] = get_service_output_names(job.id, job.job_type);
+        let service_command = wrap_for_service_execution_with_output(
+            &msi_command,
+            &get_local_log_path(&stdout_name),
+            &get_local_log_path(&stderr_name),
+        );
+        timer.record(ExecutionPhase::ServiceCreation);
 
         // Step 3c: Execute via service
         debug!(command = %service_command, "Executing MSI via service");
@@ -315,6 +811,29 @@ impl JobExecutor {
                 .with_target(hostname),
         );
 
+        // Keep the ADMIN$ session alive during long installs so cleanup can still reach the MSI
+        let keepalive = start_keepalive(
+            &remote_share,
+            credentials,
+            Duration::from_secs(self.config.smb_keepalive_seconds),
+        );
+
+        // SECURITY: the target is unprotected from here until protection is
+        // restored below; nothing in between may return early. The worker
+        // setting is checked again here since retries skip preflight
+        let av_disabled = if payload.disable_av_during_install && self.config.allow_av_disable {
+            match self.set_defender_realtime(job, hostname, credentials, false).await {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!(error = %e, "Could not turn off Defender real-time protection, installing anyway");
+                    false
+                }
+            }
+        } else {
+            false
+        };
+
+        let log_tail = self.start_msi_log_tail(job.id, hostname, &log_name, credentials);
         let execution_result = execute_msi_via_service(
             hostname,
             &service_command,
@@ -322,21 +841,66 @@ impl JobExecutor {
             Duration::from_secs(self.config.service_execution_timeout_seconds),
         )
         .await;
+        if let Some(log_tail) = log_tail {
+            log_tail.finish().await;
+        }
 
-        // Step 3d: Cleanup (always run)
-        debug!("Cleaning up remote files");
-        if let Err(e) = delete_file(&remote_msi_path, credentials).await {
-            warn!(error = %e, "Failed to cleanup MSI file");
+        // Always turn protection back on, whatever the install result
+        let mut av_restore_error = None;
+        if av_disabled {
+            for attempt in 1..=DEFENDER_RESTORE_ATTEMPTS {
+                match self.set_defender_realtime(job, hostname, credentials, true).await {
+                    Ok(()) => {
+                        av_restore_error = None;
+                        break;
+                    }
+                    Err(e) => {
+                        warn!(attempt, error = %e, "Failed to turn Defender real-time protection back on");
+                        av_restore_error = Some(e);
+                    }
+                }
+            }
+            if let Some(e) = &av_restore_error {
+                error!(host = %hostname, error = %e, "Defender real-time protection is still OFF on target");
+            }
         }
 
-        audit_event(
-            AuditEvent::new(AuditEventType::CleanupCompleted, "completed", "Cleanup finished")
-                .with_job_id(job.id)
-                .with_target(hostname),
-        );
+        if let Some(keepalive) = keepalive {
+            keepalive.stop();
+        }
+        timer.record(ExecutionPhase::ServiceExecution);
+        if let Ok(result) = &execution_result {
+            audit_service_cleanup(job, hostname, result);
+        }
+
+        // Step 3d: Cleanup (always run on success; on failure unless the job
+        // keeps the MSI for investigation). The temporary service is removed
+        // by the execution itself either way
+        let install_failed = !matches!(&execution_result, Ok(result) if MsiExitCode::from(result.exit_code).is_success());
+        let preserved_installer_path = if install_failed && !payload.cleanup_on_failure {
+            info!(path = %remote_msi_path, "Keeping MSI on failed target for investigation");
+            audit_event(
+                AuditEvent::new(AuditEventType::CleanupCompleted, "preserved", "MSI kept on failed target")
+                    .with_job_id(job.id)
+                    .with_target(hostname),
+            );
+            Some(remote_msi_path.clone())
+        } else {
+            debug!("Cleaning up remote files");
+            if let Err(e) = delete_file(&remote_msi_path, credentials).await {
+                warn!(error = %e, "Failed to cleanup MSI file");
+            }
+            audit_event(
+                AuditEvent::new(AuditEventType::CleanupCompleted, "completed", "Cleanup finished")
+                    .with_job_id(job.id)
+                    .with_target(hostname),
+            );
+            None
+        };
+        timer.record(ExecutionPhase::Cleanup);
 
         // Process result
-        match execution_result {
+        let mut target_result = match execution_result {
             Ok(result) => {
                 let exit_code = MsiExitCode::from(result.exit_code);
                 TargetResult::success(
@@ -355,7 +919,155 @@ impl JobExecutor {
                     ExecutionPhase::ServiceExecution,
                 )
             }
+        };
+        target_result.msi_log_path = Some(get_remote_log_path(hostname, &log_name));
+        target_result.copied_bytes = copied.map(|progress| progress.transferred);
+        target_result.copy_throughput_bytes_per_sec = copy_throughput;
+        target_result.preserved_installer_path = preserved_installer_path;
+        if let Some(e) = av_restore_error {
+            let warning = format!("Defender real-time protection could not be turned back on: {}", e);
+            target_result.error_message = Some(match target_result.error_message.take() {
+                Some(message) => format!("{}; {}", message, warning),
+                None => warning,
+            });
         }
+        target_result
+    }
+
+    /// Start forwarding new lines of the target's MSI log while msiexec runs.
+    ///
+    /// Returns None when tailing is disabled or nobody receives the lines.
+    fn start_msi_log_tail(
+        &self,
+        job_id: Uuid,
+        hostname: &str,
+        log_name: &str,
+        credentials: &Credential,
+    ) -> Option<SmbLogTail> {
+        let sender = self.msi_log_sender.clone()?;
+        let host = hostname.to_string();
+
+        start_log_tail(
+            &get_remote_log_path(hostname, log_name),
+            credentials,
+            Duration::from_secs(self.config.msi_log_tail_seconds),
+            move |offset, content| {
+                // The receiver only goes away with the poller; the log stays on the target
+                let _ = sender.send(MsiLogChunk {
+                    job_id,
+                    hostname: host.clone(),
+                    offset,
+                    content,
+                });
+            },
+        )
+    }
+
+    /// Turn Defender real-time protection on or off on a target via a temporary service.
+    ///
+    /// SECURITY: only used for jobs with `disable_av_during_install` on workers
+    /// with `allow_av_disable`; every attempt and its outcome is audited.
+    async fn set_defender_realtime(
+        &self,
+        job: &DeploymentJob,
+        hostname: &str,
+        credentials: &Credential,
+        enabled: bool,
+    ) -> Result<(), String> {
+        let action = if enabled { "enable" } else { "disable" };
+        audit_event(
+            AuditEvent::new(
+                AuditEventType::Security,
+                &format!("av_{}_requested", action),
+                &format!("Turning Defender real-time protection {} for installation", if enabled { "on" } else { "off" }),
+            )
+            .with_job_id(job.id)
+            .with_target(hostname)
+            .with_worker_id(&self.worker_id),
+        );
+
+        let command = wrap_for_service_execution(&build_defender_realtime_command(!enabled));
+        let outcome = match execute_msi_via_service(hostname, &command, credentials, DEFENDER_TOGGLE_TIMEOUT).await {
+            Ok(result) => {
+                audit_service_cleanup(job, hostname, &result);
+                if result.exit_code == 0 {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "setting did not take effect (exit code {}, Tamper Protection may be on)",
+                        result.exit_code
+                    ))
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+
+        let (status, details) = match &outcome {
+            Ok(()) => (format!("av_{}d", action), format!("Defender real-time protection {}d", action)),
+            Err(e) => (format!("av_{}_failed", action), format!("Failed to {} Defender real-time protection: {}", action, e)),
+        };
+        audit_event(
+            AuditEvent::new(AuditEventType::Security, &status, &details)
+                .with_job_id(job.id)
+                .with_target(hostname)
+                .with_worker_id(&self.worker_id),
+        );
+        outcome
+    }
+
+    /// Turn the deployment firewall rules on (saving which were off) or restore
+    /// them on a target via a temporary service.
+    ///
+    /// SECURITY: only used for jobs with `enable_firewall_rules_for_deploy` on
+    /// workers with `allow_firewall_changes`; every attempt and its outcome is audited.
+    async fn set_deploy_firewall_rules(
+        &self,
+        job: &DeploymentJob,
+        hostname: &str,
+        credentials: &Credential,
+        enable: bool,
+    ) -> Result<(), String> {
+        let action = if enable { "open" } else { "restore" };
+        audit_event(
+            AuditEvent::new(
+                AuditEventType::Security,
+                &format!("firewall_{}_requested", action),
+                if enable {
+                    "Turning on deployment firewall rules"
+                } else {
+                    "Restoring deployment firewall rules"
+                },
+            )
+            .with_job_id(job.id)
+            .with_target(hostname)
+            .with_worker_id(&self.worker_id),
+        );
+
+        let command = wrap_for_service_execution(&build_firewall_rules_command(job.id, enable));
+        let outcome = match execute_msi_via_service(hostname, &command, credentials, FIREWALL_TOGGLE_TIMEOUT).await {
+            Ok(result) => {
+                audit_service_cleanup(job, hostname, &result);
+                if result.exit_code == 0 {
+                    Ok(())
+                } else {
+                    Err(format!("firewall command failed (exit code {})", result.exit_code))
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+
+        let (status, details) = match (&outcome, enable) {
+            (Ok(()), true) => ("firewall_opened".to_string(), "Deployment firewall rules turned on".to_string()),
+            (Ok(()), false) => ("firewall_restored".to_string(), "Deployment firewall rules restored".to_string()),
+            (Err(e), _) => (format!("firewall_{}_failed", action), format!("Failed to {} deployment firewall rules: {}", action, e)),
+        };
+        audit_event(
+            AuditEvent::new(AuditEventType::Security, &status, &details)
+                .with_job_id(job.id)
+                .with_target(hostname)
+                .with_worker_id(&self.worker_id),
+        );
+        outcome
     }
 
     /// Execute an MSI uninstall on a target.
@@ -365,6 +1077,7 @@ impl JobExecutor {
         target: &DeploymentTarget,
         credentials: &Credential,
         start: Instant,
+        timer: &mut PhaseTimer,
     ) -> TargetResult {
         let hostname = &target.hostname;
         let machine_id = target.machine_id.clone();
@@ -384,10 +1097,13 @@ impl JobExecutor {
             }
         };
 
-        // Build uninstall command
+        // Build uninstall command (always logging, left on the target for diagnosis)
+        let log_name = get_msi_log_name(job.id, job.job_type);
+        let local_log_path = get_local_log_path(&log_name);
         let msi_command = match build_msi_uninstall_command(
             &product_code,
             payload.install_args.as_deref(),
+            Some((&local_log_path, payload.msi_log_level)),
         ) {
             Ok(cmd) => cmd,
             Err(e) => {
@@ -401,11 +1117,19 @@ impl JobExecutor {
             }
         };
 
-        let service_command = wrap_for_service_execution(&msi_command);
+        // Console output is kept on the target next to the MSI log for CollectLogs jobs
+        let [stdout_name, stderr_name] = get_service_output_names(job.id, job.job_type);
+        let service_command = wrap_for_service_execution_with_output(
+            &msi_command,
+            &get_local_log_path(&stdout_name),
+            &get_local_log_path(&stderr_name),
+        );
+        timer.record(ExecutionPhase::ServiceCreation);
 
         // Execute via service
         debug!(command = %service_command, "Executing uninstall via service");
 
+        let log_tail = self.start_msi_log_tail(job.id, hostname, &log_name, credentials);
         let execution_result = execute_msi_via_service(
             hostname,
             &service_command,
@@ -413,8 +1137,15 @@ impl JobExecutor {
             Duration::from_secs(self.config.service_execution_timeout_seconds),
         )
         .await;
+        if let Some(log_tail) = log_tail {
+            log_tail.finish().await;
+        }
+        timer.record(ExecutionPhase::ServiceExecution);
+        if let Ok(result) = &execution_result {
+            audit_service_cleanup(job, hostname, result);
+        }
 
-        match execution_result {
+        let mut target_result = match execution_result {
             Ok(result) => {
                 TargetResult::success(
                     hostname.clone(),
@@ -432,13 +1163,248 @@ impl JobExecutor {
                     ExecutionPhase::ServiceExecution,
                 )
             }
+        };
+        target_result.msi_log_path = Some(get_remote_log_path(hostname, &log_name));
+        target_result
+    }
+
+    /// Check that a target could be deployed to, without changing it.
+    ///
+    /// Reachability and credentials were checked by `run_on_target`, which also
+    /// attaches the security products and virtualization; this connects to ADMIN$ like an install
+    /// would and applies the worker's SMB signing/encryption policy.
+    async fn execute_validate(
+        &self,
+        target: &DeploymentTarget,
+        credentials: &Credential,
+        start: Instant,
+    ) -> TargetResult {
+        let hostname = &target.hostname;
+        let machine_id = target.machine_id.clone();
+        let remote_share = format!("\\\\{}\\ADMIN$\\Temp", hostname);
+
+        debug!("Checking ADMIN$ access on target");
+        if let Err(e) = check_session_security(
+            &remote_share,
+            credentials,
+            self.config.require_smb_signing,
+            self.config.require_smb_encryption,
+            SMB_SECURITY_CHECK_TIMEOUT,
+        )
+        .await
+        {
+            return TargetResult::failure(
+                hostname.clone(),
+                machine_id,
+                format!("ADMIN$ check failed: {}", e),
+                start.elapsed().as_secs(),
+                ExecutionPhase::SmbCopy,
+            );
+        }
+
+        let mut target_result = TargetResult::success(hostname.clone(), machine_id, 0, start.elapsed().as_secs());
+        target_result.exit_code = None;
+        target_result
+    }
+
+    /// Read the end of a file on a target (restricted to `READ_FILE_ALLOWED_ROOTS`).
+    async fn execute_read_file(
+        &self,
+        job: &DeploymentJob,
+        target: &DeploymentTarget,
+        credentials: &Credential,
+        start: Instant,
+        timer: &mut PhaseTimer,
+    ) -> TargetResult {
+        let hostname = &target.hostname;
+        let machine_id = target.machine_id.clone();
+
+        let Some(local_path) = job.payload.file_path.as_deref() else {
+            return TargetResult::failure(
+                hostname.clone(),
+                machine_id,
+                "File path required for read".to_string(),
+                start.elapsed().as_secs(),
+                ExecutionPhase::FileRead,
+            );
+        };
+
+        let read_result = match admin_share_path(hostname, local_path, READ_FILE_ALLOWED_ROOTS) {
+            Ok(unc_path) => {
+                debug!(path = %unc_path, "Reading file from target");
+                read_file_tail(
+                    &unc_path,
+                    credentials,
+                    READ_FILE_MAX_BYTES,
+                    job.payload.tail_lines,
+                    READ_FILE_TIMEOUT,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        timer.record(ExecutionPhase::FileRead);
+
+        match read_result {
+            Ok(tail) => {
+                let mut target_result =
+                    TargetResult::success(hostname.clone(), machine_id, 0, start.elapsed().as_secs());
+                target_result.exit_code = None;
+                target_result.file_content = Some(FileContent {
+                    path: local_path.to_string(),
+                    content: tail.content,
+                    file_size: tail.file_size,
+                    truncated: tail.truncated,
+                });
+                target_result
+            }
+            Err(e) => TargetResult::failure(
+                hostname.clone(),
+                machine_id,
+                format!("File read failed: {}", e),
+                start.elapsed().as_secs(),
+                ExecutionPhase::FileRead,
+            ),
+        }
+    }
+
+    /// Collect the MSI logs, service output and deployment event log entries of a job from a target.
+    ///
+    /// The MSI logs and service stdout/stderr of `logs_of` are read from
+    /// ADMIN$\Temp: the end of each file goes into the result and the complete
+    /// file is copied to `collect_logs_dir\<job id>\<hostname>` on the worker.
+    /// A file is only deleted from the target once it is in that bundle.
+    /// Missing files (the job never reached msiexec) are skipped. The target
+    /// only fails if nothing could be collected.
+    async fn execute_collect_logs(
+        &self,
+        job: &DeploymentJob,
+        target: &DeploymentTarget,
+        credentials: &Credential,
+        start: Instant,
+        timer: &mut PhaseTimer,
+    ) -> TargetResult {
+        let hostname = &target.hostname;
+        let machine_id = target.machine_id.clone();
+
+        let Some(source_job_id) = job.payload.logs_of else {
+            return TargetResult::failure(
+                hostname.clone(),
+                machine_id,
+                "Source job (logsOf) required for log collection".to_string(),
+                start.elapsed().as_secs(),
+                ExecutionPhase::LogCollection,
+            );
+        };
+
+        let host_folder: String = hostname
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        let bundle_dir = Path::new(&self.config.collect_logs_dir)
+            .join(source_job_id.to_string())
+            .join(host_folder);
+        let copy_timeout = Duration::from_secs(self.config.smb_copy_timeout_seconds);
+
+        let mut errors = Vec::new();
+        let mut collected_files = Vec::new();
+        let mut bundled = 0;
+        for job_type in [JobType::MsiInstall, JobType::MsiUninstall] {
+            let [stdout_name, stderr_name] = get_service_output_names(source_job_id, job_type);
+            for log_name in [get_msi_log_name(source_job_id, job_type), stdout_name, stderr_name] {
+                let unc_path = get_remote_log_path(hostname, &log_name);
+                debug!(path = %unc_path, "Collecting log");
+
+                match read_file_tail(&unc_path, credentials, COLLECT_LOGS_MAX_BYTES, None, COLLECT_LOGS_TIMEOUT).await {
+                    Ok(tail) => collected_files.push(FileContent {
+                        path: get_local_log_path(&log_name),
+                        content: tail.content,
+                        file_size: tail.file_size,
+                        truncated: tail.truncated,
+                    }),
+                    Err(SmbError::PathNotFound(_)) => continue,
+                    Err(e) => {
+                        errors.push(format!("{}: {}", log_name, e));
+                        continue;
+                    }
+                }
+
+                // The target's copy is the only complete one until it is in the bundle
+                match download_file(&unc_path, &bundle_dir, credentials, copy_timeout).await {
+                    Ok(_) => {
+                        bundled += 1;
+                        if let Err(e) = delete_file(&unc_path, credentials).await {
+                            warn!(error = %e, path = %unc_path, "Failed to delete collected log");
+                        }
+                    }
+                    Err(e) => {
+                        warn!(error = %e, path = %unc_path, "Failed to copy log to the bundle, leaving it on the target");
+                        errors.push(format!("{} (left on target): {}", log_name, e));
+                    }
+                }
+            }
         }
+
+        let since = job
+            .payload
+            .logs_since
+            .unwrap_or_else(|| Utc::now() - chrono::Duration::hours(EVENT_LOG_DEFAULT_HOURS));
+        let event_log_entries =
+            match query_deployment_events(hostname, credentials, since, EVENT_LOG_MAX_ENTRIES, EVENT_LOG_TIMEOUT)
+                .await
+            {
+                Ok(entries) => entries,
+                Err(e) => {
+                    errors.push(format!("event log: {}", e));
+                    Vec::new()
+                }
+            };
+        timer.record(ExecutionPhase::LogCollection);
+
+        audit_event(
+            AuditEvent::new(
+                AuditEventType::CleanupCompleted,
+                "logs_collected",
+                &format!(
+                    "Collected {} log files ({} copied to {}) and {} event log entries for job {}",
+                    collected_files.len(),
+                    bundled,
+                    bundle_dir.display(),
+                    event_log_entries.len(),
+                    source_job_id
+                ),
+            )
+            .with_job_id(job.id)
+            .with_target(hostname),
+        );
+
+        let mut target_result = if collected_files.is_empty() && event_log_entries.is_empty() && !errors.is_empty() {
+            TargetResult::failure(
+                hostname.clone(),
+                machine_id,
+                format!("Log collection failed: {}", errors.join("; ")),
+                start.elapsed().as_secs(),
+                ExecutionPhase::LogCollection,
+            )
+        } else {
+            let mut result = TargetResult::success(hostname.clone(), machine_id, 0, start.elapsed().as_secs());
+            result.exit_code = None;
+            if !errors.is_empty() {
+                result.error_message = Some(format!("Partially collected: {}", errors.join("; ")));
+            }
+            result
+        };
+        target_result.collected_files = collected_files;
+        target_result.log_bundle_path = (bundled > 0).then(|| bundle_dir.display().to_string());
+        target_result.event_log_entries = event_log_entries;
+        target_result
     }
 
     /// Resolve credentials from vault or inline credentials.
     ///
     /// If vault_ref is "__inline__", uses the inline credentials from the job payload.
-    /// Otherwise, looks up credentials in Windows Credential Manager.
+    /// Otherwise, looks up credentials in Windows Credential Manager, reusing a
+    /// lookup younger than `credential_cache_ttl_seconds`.
     fn resolve_credentials(
         &self,
         vault_ref: &str,
@@ -462,16 +1428,79 @@ impl JobExecutor {
             }
         }
 
-        // Fall back to vault lookup
-        #[cfg(all(windows, feature = "mock-mode"))]
-        {
-            return CredentialVault::get_mock_credential(vault_ref);
+        // Fall back to vault lookup (cached, targets of a batch usually share a vault_ref)
+        CredentialVault::get_cached_credential(
+            vault_ref,
+            Duration::from_secs(self.config.credential_cache_ttl_seconds),
+        )
+    }
+}
+
+/// Records how long each execution phase of a target took.
+///
+/// Each `record` measures the time since the previous boundary (`record` or
+/// `mark`), so phases are timed back to back without overlapping.
+struct PhaseTimer {
+    last: Instant,
+    timings: HashMap<ExecutionPhase, u64>,
+}
+
+impl PhaseTimer {
+    fn new() -> Self {
+        Self {
+            last: Instant::now(),
+            timings: HashMap::new(),
         }
+    }
 
-        #[cfg(not(all(windows, feature = "mock-mode")))]
-        {
-            CredentialVault::get_credential(vault_ref)
+    /// Record the end of `phase` (accumulates if the phase repeats).
+    fn record(&mut self, phase: ExecutionPhase) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_millis() as u64;
+        *self.timings.entry(phase).or_insert(0) += elapsed;
+        self.last = now;
+    }
+
+    /// Start timing from now without recording (work that is not a phase).
+    fn mark(&mut self) {
+        self.last = Instant::now();
+    }
+
+    /// Attach the timings to a result, including the phase it failed in.
+    fn finish(mut self, result: &mut TargetResult) {
+        if let Some(phase) = result.failed_phase {
+            if !self.timings.contains_key(&phase) {
+                self.record(phase);
+            }
         }
+        result.phase_timings = self.timings;
+    }
+}
+
+/// Audit the temporary service cleanup of an execution.
+///
+/// Records each orphaned service removed from the target and flags a temporary
+/// service that could not be deleted, so it can be removed by hand.
+fn audit_service_cleanup(job: &DeploymentJob, hostname: &str, result: &ServiceExecutionResult) {
+    for orphan in &result.orphans_removed {
+        audit_event(
+            AuditEvent::new(
+                AuditEventType::CleanupCompleted,
+                "completed",
+                &format!("Removed orphaned service {}", orphan),
+            )
+            .with_job_id(job.id)
+            .with_target(hostname),
+        );
+    }
+
+    if !result.cleanup_success {
+        warn!(host = %hostname, "Temporary service may still be registered on target");
+        audit_event(
+            AuditEvent::new(AuditEventType::Error, "failed", "Temporary service could not be deleted")
+                .with_job_id(job.id)
+                .with_target(hostname),
+        );
     }
 }
 
@@ -489,6 +1518,23 @@ mod tests {
             smb_copy_timeout_seconds: 60,
             service_execution_timeout_seconds: 300,
             reachability_timeout_seconds: 5,
+            smb_keepalive_seconds: 0,
+            heartbeat_interval_seconds: 60,
+            disk_space_multiplier: 3.0,
+            pending_results_capacity: 50,
+            detect_security_products: true,
+            detect_virtualization: true,
+            poll_batch_size: 1,
+            allow_av_disable: false,
+            allow_firewall_changes: false,
+            credential_cache_ttl_seconds: 60,
+            worker_tags: Vec::new(),
+            require_smb_signing: false,
+            require_smb_encryption: false,
+            wake_on_lan_wait_seconds: 0,
+            wake_on_lan_broadcast: "255.255.255.255".to_string(),
+            msi_log_tail_seconds: 0,
+            collect_logs_dir: std::env::temp_dir().join("collected-logs").to_string_lossy().into_owned(),
         }
     }
 
@@ -498,4 +1544,142 @@ mod tests {
         let executor = JobExecutor::new(config, "test-worker".to_string());
         assert_eq!(executor.worker_id, "test-worker");
     }
+
+    fn create_test_job(job_type: &str, inline_credentials: bool) -> DeploymentJob {
+        let mut json = serde_json::json!({
+            "id": "550e8400-e29b-41d4-a716-446655440000",
+            "jobType": job_type,
+            "createdAt": "2025-01-01T12:00:00Z",
+            "payload": {
+                "installerPath": "\\\\server\\share\\installer.msi",
+                "vaultRef": "__inline__",
+                "targets": [{ "hostname": "target-01" }]
+            }
+        });
+        if inline_credentials {
+            json["payload"]["inlineCredentials"] =
+                serde_json::json!({ "username": "DOMAIN\\admin", "password": "secret" });
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_preflight() {
+        let executor = JobExecutor::new(create_test_config(), "test-worker".to_string());
+
+        assert!(executor.preflight(&create_test_job("msi_install", true)).is_ok());
+        assert!(executor.preflight(&create_test_job("validate", true)).is_ok());
+        // Unsupported job type
+        assert!(executor.preflight(&create_test_job("execute", true)).is_err());
+        // Unescapable environment is refused with its own reason
+        let mut job = create_test_job("execute", true);
+        job.payload.environment = vec![("X".to_string(), "a\" & calc".to_string())];
+        assert!(executor.preflight(&job).unwrap_err().contains("Invalid environment variable"));
+        // Working directory and environment only apply to Execute jobs
+        let mut job = create_test_job("msi_install", true);
+        job.payload.working_directory = Some("C:\\Scripts".to_string());
+        assert!(executor.preflight(&job).is_err());
+        // Missing credentials
+        assert!(executor.preflight(&create_test_job("msi_install", false)).is_err());
+
+        // Turning off antivirus needs allow_av_disable and an install job
+        let mut job = create_test_job("msi_install", true);
+        job.payload.disable_av_during_install = true;
+        assert!(executor.preflight(&job).is_err());
+
+        let mut config = create_test_config();
+        config.allow_av_disable = true;
+        let executor = JobExecutor::new(config, "test-worker".to_string());
+        assert!(executor.preflight(&job).is_ok());
+        job.job_type = JobType::MsiUninstall;
+        assert!(executor.preflight(&job).is_err());
+
+        // Opening the target firewall needs allow_firewall_changes
+        let mut job = create_test_job("msi_install", true);
+        job.payload.enable_firewall_rules_for_deploy = true;
+        assert!(executor.preflight(&job).is_err());
+
+        let mut config = create_test_config();
+        config.allow_firewall_changes = true;
+        let executor = JobExecutor::new(config, "test-worker".to_string());
+        assert!(executor.preflight(&job).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_execute_without_targets() {
+        let executor = JobExecutor::new(create_test_config(), "test-worker".to_string());
+        let mut job = create_test_job("msi_install", true);
+        job.payload.targets.clear();
+
+        let result = executor.execute(job).await;
+
+        assert_eq!(result.status, JobStatus::Failed);
+        assert_eq!(result.error_message.as_deref(), Some("Job has no targets"));
+        assert!(result.target_results.is_empty());
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_msi_install_reports_copied_bytes() {
+        let executor = JobExecutor::new(create_test_config(), "test-worker".to_string());
+        let job = create_test_job("msi_install", true);
+        let credentials = Credential::new("DOMAIN\\admin".to_string(), "secret".to_string());
+        let mut timer = PhaseTimer::new();
+
+        // Straight to the install: the reachability check needs a real target
+        let result = executor
+            .execute_msi_install(&job, &job.payload.targets[0], &credentials, Instant::now(), &mut timer)
+            .await;
+
+        // The mock copy reports a 1 MB installer
+        assert_eq!(result.copied_bytes, Some(1024 * 1024));
+        assert!(result.copy_throughput_bytes_per_sec.is_some());
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_collect_logs_bundles_service_output() {
+        let executor = JobExecutor::new(create_test_config(), "test-worker".to_string());
+        let mut job = create_test_job("collect_logs", true);
+        let source_job_id = Uuid::new_v4();
+        job.payload.logs_of = Some(source_job_id);
+        let credentials = Credential::new("DOMAIN\\admin".to_string(), "secret".to_string());
+        let mut timer = PhaseTimer::new();
+
+        let result = executor
+            .execute_collect_logs(&job, &job.payload.targets[0], &credentials, Instant::now(), &mut timer)
+            .await;
+
+        // MSI log, stdout and stderr of both the install and the uninstall
+        assert_eq!(result.collected_files.len(), 6);
+        let [stdout_name, _] = get_service_output_names(source_job_id, JobType::MsiInstall);
+        assert!(result.collected_files.iter().any(|file| file.path.ends_with(&stdout_name)));
+        let bundle = result.log_bundle_path.expect("complete files copied to a bundle");
+        assert!(bundle.contains(&source_job_id.to_string()));
+        assert!(bundle.ends_with("target-01"));
+    }
+
+    #[test]
+    fn test_phase_timer() {
+        let mut timer = PhaseTimer::new();
+        timer.record(ExecutionPhase::ReachabilityCheck);
+        timer.mark();
+        timer.record(ExecutionPhase::CredentialResolution);
+
+        let mut result = TargetResult::failure(
+            "host".to_string(),
+            None,
+            "copy failed".to_string(),
+            1,
+            ExecutionPhase::SmbCopy,
+        );
+        timer.finish(&mut result);
+
+        assert_eq!(result.phase_timings.len(), 3);
+        assert!(result.phase_timings.contains_key(&ExecutionPhase::SmbCopy));
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json["phaseTimings"]["reachability_check"].is_u64());
+        assert!(json["phaseTimings"]["smb_copy"].is_u64());
+    }
 }
@@ -1,6 +1,8 @@
 pub mod executor;
 pub mod poller;
+pub mod queue;
 pub mod reporter;
+pub mod supervisor;
 
 pub use executor::JobExecutor;
 pub use poller::{create_shutdown_channel, JobPoller};
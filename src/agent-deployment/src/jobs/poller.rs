@@ -1,19 +1,28 @@
 //! Background job polling loop.
 //!
 //! Polls the backend for deployment jobs and executes them,
-//! with graceful shutdown support.
+//! with graceful shutdown support. Before each job the poller tops a
+//! `JobQueue` up to `poll_batch_size` claimed jobs and runs the
+//! highest-priority one (see `queue` for the ordering), so urgent jobs
+//! queued meanwhile still run first. Queued jobs whose claim is about to
+//! time out, and those still queued at shutdown, are returned to the backend.
 
+use std::future::Future;
 use std::time::Duration;
 
-use tokio::sync::watch;
+use tokio::sync::{mpsc, watch};
+use tokio::time::Instant;
 use tracing::{debug, error, info, instrument, warn};
 
+use crate::api::types::{DeploymentJob, JobResult, JobType, MsiLogChunk};
 use crate::api::{ApiClient, ApiError};
 use crate::audit::{audit_event, AuditEvent, AuditEventType};
 use crate::config::WorkerConfig;
+use crate::execution::smb::CopyProgress;
 
 use super::executor::JobExecutor;
-use super::reporter::ResultReporter;
+use super::queue::{JobQueue, MAX_QUEUED_CLAIM_AGE};
+use super::reporter::{PendingResults, ReportError, ResultReporter};
 
 /// Job poller that continuously polls for and executes jobs.
 pub struct JobPoller {
@@ -22,6 +31,14 @@ pub struct JobPoller {
     reporter: ResultReporter,
     config: WorkerConfig,
     shutdown_rx: watch::Receiver<bool>,
+    /// Consecutive failed heartbeats (drives heartbeat backoff)
+    heartbeat_failures: u32,
+    /// Results whose report failed on a transient error, re-sent while polling
+    pending_results: PendingResults,
+    /// Claimed jobs waiting to run, highest priority first
+    queue: JobQueue,
+    /// MSI log lines from the executor, forwarded while a job runs
+    msi_log_rx: Option<mpsc::UnboundedReceiver<MsiLogChunk>>,
 }
 
 impl JobPoller {
@@ -38,12 +55,22 @@ impl JobPoller {
         config: WorkerConfig,
         shutdown_rx: watch::Receiver<bool>,
     ) -> Self {
+        let pending_results = PendingResults::new(
+            config.pending_results_capacity,
+            Duration::from_secs(config.max_backoff_seconds),
+        );
+        let (msi_log_tx, msi_log_rx) = mpsc::unbounded_channel();
+
         Self {
             api_client,
-            executor,
+            executor: executor.with_msi_log_tail(msi_log_tx),
             reporter: ResultReporter::new(),
             config,
             shutdown_rx,
+            heartbeat_failures: 0,
+            pending_results,
+            queue: JobQueue::new(),
+            msi_log_rx: Some(msi_log_rx),
         }
     }
 
@@ -51,7 +78,8 @@ impl JobPoller {
     ///
     /// This method blocks until a shutdown signal is received.
     /// It polls for jobs at the configured interval and executes
-    /// any jobs that are received.
+    /// any jobs that are received. Heartbeats run on their own
+    /// deadline so they don't reset the poll timer.
     #[instrument(skip(self), name = "poller_run")]
     pub async fn run(&mut self) {
         info!(
@@ -63,17 +91,28 @@ impl JobPoller {
         let max_backoff = Duration::from_secs(self.config.max_backoff_seconds);
         let mut current_interval = base_interval;
         let mut consecutive_empty = 0u32;
+        let mut next_poll = Instant::now() + current_interval;
+        let mut next_heartbeat = Instant::now() + self.heartbeat_delay();
 
         loop {
+            let report_retry_at = self.pending_results.next_due();
+
             tokio::select! {
                 // Wait for next poll interval
-                _ = tokio::time::sleep(current_interval) => {
+                _ = tokio::time::sleep_until(next_poll) => {
                     match self.poll_and_execute().await {
                         PollResult::JobExecuted => {
                             // Reset backoff after successful job
                             current_interval = base_interval;
                             consecutive_empty = 0;
                         }
+                        PollResult::JobRejected => {
+                            // Back off so the rejected job goes to another worker first
+                            current_interval = self.calculate_backoff(
+                                current_interval,
+                                max_backoff,
+                            );
+                        }
                         PollResult::NoJobs => {
                             // Increase backoff when no jobs available
                             consecutive_empty += 1;
@@ -109,6 +148,21 @@ impl JobPoller {
                             );
                         }
                     }
+                    // Run the rest of a claimed batch without waiting
+                    next_poll = if self.queue.is_empty() {
+                        Instant::now() + current_interval
+                    } else {
+                        Instant::now()
+                    };
+                }
+                // Re-send results whose report failed (transient network drop)
+                _ = tokio::time::sleep_until(report_retry_at.unwrap_or(next_poll)), if report_retry_at.is_some() => {
+                    self.retry_pending_results().await;
+                }
+                // Send heartbeat (slows down while the backend is unreachable)
+                _ = tokio::time::sleep_until(next_heartbeat) => {
+                    self.send_heartbeat().await;
+                    next_heartbeat = Instant::now() + self.heartbeat_delay();
                 }
                 // Check for shutdown signal
                 _ = self.shutdown_rx.changed() => {
@@ -120,52 +174,175 @@ impl JobPoller {
             }
         }
 
+        self.return_queued_jobs().await;
+
+        if !self.pending_results.is_empty() {
+            warn!(
+                pending = self.pending_results.len(),
+                "Stopping with unreported job results"
+            );
+        }
+
         info!("Job poller stopped");
     }
 
-    /// Poll for a job and execute it if available.
-    async fn poll_and_execute(&mut self) -> PollResult {
-        debug!("Polling for next job");
-
-        // Poll for next job
-        let job = match self.api_client.poll_next_job().await {
-            Ok(Some(job)) => job,
-            Ok(None) => {
-                debug!("No jobs available");
-                return PollResult::NoJobs;
-            }
-            Err(ApiError::RateLimited { retry_after_seconds }) => {
-                return PollResult::RateLimited(retry_after_seconds);
-            }
-            Err(ApiError::AuthenticationFailed(msg)) => {
-                error!(error = %msg, "Authentication failed");
-                // Try to refresh token
-                if let Err(e) = self.api_client.refresh_token() {
-                    error!(error = %e, "Failed to refresh token");
+    /// Claim jobs until the queue holds `poll_batch_size` jobs.
+    ///
+    /// Stops at the first empty poll or error; an error is only returned
+    /// if no job was claimed.
+    async fn claim_jobs(&mut self) -> Result<usize, PollResult> {
+        let batch_size = self.config.poll_batch_size.max(1);
+        let mut claimed = 0;
+
+        while self.queue.len() < batch_size {
+            debug!("Polling for next job");
+
+            let job = match self.api_client.poll_next_job().await {
+                Ok(Some(job)) => job,
+                Ok(None) => break,
+                Err(e) if claimed > 0 => {
+                    debug!(error = %e, claimed, "Stopped claiming jobs");
+                    break;
+                }
+                Err(ApiError::RateLimited { retry_after_seconds }) => {
+                    return Err(PollResult::RateLimited(retry_after_seconds));
                 }
-                return PollResult::Error;
+                Err(ApiError::AuthenticationFailed(msg)) => {
+                    error!(error = %msg, "Authentication failed");
+                    // Try to refresh token
+                    if let Err(e) = self.api_client.refresh_token() {
+                        error!(error = %e, "Failed to refresh token");
+                    }
+                    return Err(PollResult::Error);
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to poll for jobs");
+                    return Err(PollResult::Error);
+                }
+            };
+
+            info!(
+                job_id = %job.id,
+                job_type = ?job.job_type,
+                priority = job.priority,
+                "Received job"
+            );
+
+            // Audit: Job received
+            audit_event(
+                AuditEvent::new(
+                    AuditEventType::JobReceived,
+                    "received",
+                    &format!(
+                        "Job type: {:?}, priority: {}, targets: {}",
+                        job.job_type,
+                        job.priority,
+                        job.payload.targets.len()
+                    ),
+                )
+                .with_job_id(job.id),
+            );
+
+            self.queue.push(job, std::time::Instant::now());
+            claimed += 1;
+        }
+
+        Ok(claimed)
+    }
+
+    /// Return jobs still queued at shutdown so other workers can run them.
+    async fn return_queued_jobs(&mut self) {
+        for job in self.queue.drain() {
+            info!(job_id = %job.id, "Returning queued job to the backend");
+            if let Err(e) = self.api_client.nak_job(job.id, "Worker shutting down").await {
+                // The backend will time out the claim
+                error!(job_id = %job.id, error = %e, "Failed to return job to queue");
             }
-            Err(e) => {
-                error!(error = %e, "Failed to poll for jobs");
-                return PollResult::Error;
+        }
+    }
+
+    /// Return queued jobs that waited too long to be started within their claim.
+    async fn return_expired_claims(&mut self) {
+        let expired = self
+            .queue
+            .take_expired(std::time::Instant::now(), MAX_QUEUED_CLAIM_AGE);
+        for job in expired {
+            warn!(
+                job_id = %job.id,
+                priority = job.priority,
+                "Queued job waited too long, returning it to the backend"
+            );
+            let reason = "Claim expired while queued behind other jobs";
+            audit_event(
+                AuditEvent::new(AuditEventType::JobRejected, "rejected", reason).with_job_id(job.id),
+            );
+            if let Err(e) = self.api_client.nak_job(job.id, reason).await {
+                // The backend will time out the claim
+                error!(job_id = %job.id, error = %e, "Failed to return job to queue");
             }
+        }
+    }
+
+    /// Poll for new jobs, then execute the highest-priority queued job.
+    ///
+    /// Polling happens before every job, not only when the queue is empty,
+    /// so a high-priority job queued meanwhile runs ahead of claimed ones.
+    async fn poll_and_execute(&mut self) -> PollResult {
+        match self.claim_jobs().await {
+            Ok(_) => {}
+            Err(result) if self.queue.is_empty() => return result,
+            // Already logged; run what is queued
+            Err(_) => {}
+        }
+        self.return_expired_claims().await;
+
+        let Some(job) = self.queue.pop() else {
+            debug!("No jobs available");
+            return PollResult::NoJobs;
         };
 
         let job_id = job.id;
-        info!(job_id = %job_id, job_type = ?job.job_type, "Received job");
-
-        // Audit: Job received
-        audit_event(
-            AuditEvent::new(
-                AuditEventType::JobReceived,
-                "received",
-                &format!("Job type: {:?}, targets: {}", job.job_type, job.payload.targets.len()),
-            )
-            .with_job_id(job_id),
+        debug!(
+            job_id = %job_id,
+            priority = job.priority,
+            queued = self.queue.len(),
+            "Executing next job by priority"
         );
 
-        // Execute the job
-        let result = self.executor.execute(job).await;
+        // Pre-flight: return jobs this worker cannot run to the queue
+        if let Err(reason) = self.executor.preflight(&job) {
+            warn!(job_id = %job_id, reason = %reason, "Cannot run job, returning it to the queue");
+
+            audit_event(
+                AuditEvent::new(AuditEventType::JobRejected, "rejected", &reason)
+                    .with_job_id(job_id),
+            );
+
+            return match self.api_client.nak_job(job_id, &reason).await {
+                Ok(()) => PollResult::JobRejected,
+                Err(e) => {
+                    // The backend will time out the claim
+                    error!(job_id = %job_id, error = %e, "Failed to return job to queue");
+                    PollResult::Error
+                }
+            };
+        }
+
+        // Execute the job (retry jobs first fetch the job they retry), sending
+        // copy progress and the live MSI log meanwhile since heartbeats wait for the job
+        let mut msi_log_rx = self.msi_log_rx.take();
+        let copy_progress = self.executor.copy_progress();
+        let progress_interval = Duration::from_secs(self.config.heartbeat_interval_seconds);
+        let execution = async {
+            if job.job_type == JobType::Retry {
+                self.execute_retry(job).await
+            } else {
+                self.executor.execute(job).await
+            }
+        };
+        let execution = forward_msi_log(&self.api_client, msi_log_rx.as_mut(), execution);
+        let result = report_copy_progress(&self.api_client, copy_progress, progress_interval, execution).await;
+        self.msi_log_rx = msi_log_rx;
 
         // Report result
         info!(
@@ -174,19 +351,76 @@ impl JobPoller {
             "Reporting job result"
         );
 
-        if let Err(e) = self.reporter.report_with_retry(&self.api_client, &result).await {
-            error!(
-                job_id = %job_id,
-                error = %e,
-                "Failed to report job result"
-            );
-            // Job was executed but result couldn't be reported
-            // The backend should handle this via timeouts
+        match self.reporter.report_with_retry(&self.api_client, &result).await {
+            Ok(()) => {}
+            Err(e @ ReportError::MaxRetriesExceeded { .. }) => {
+                // Transient failure: keep the result and re-send it while polling continues
+                warn!(
+                    job_id = %job_id,
+                    error = %e,
+                    "Failed to report job result, keeping it for retry"
+                );
+                if let Some(dropped) = self.pending_results.push(result, Instant::now()) {
+                    error!(
+                        job_id = %dropped,
+                        capacity = self.config.pending_results_capacity,
+                        "Pending results full, dropped unreported job result"
+                    );
+                }
+            }
+            Err(e) => {
+                error!(
+                    job_id = %job_id,
+                    error = %e,
+                    "Failed to report job result"
+                );
+                // Job was executed but result couldn't be reported
+                // The backend should handle this via timeouts
+            }
         }
 
         PollResult::JobExecuted
     }
 
+    /// Re-send pending results that are due.
+    async fn retry_pending_results(&mut self) {
+        let client = &self.api_client;
+        let delivered = self
+            .pending_results
+            .retry_due(Instant::now(), |result| {
+                let result = result.clone();
+                async move { client.report_result(&result).await }
+            })
+            .await;
+
+        debug!(
+            delivered,
+            pending = self.pending_results.len(),
+            "Retried pending job results"
+        );
+    }
+
+    /// Resolve a retry job against its original job and execute it.
+    async fn execute_retry(&self, job: DeploymentJob) -> JobResult {
+        let Some(original_id) = job.payload.retry_of else {
+            return self
+                .executor
+                .failed_result(job.id, "Retry job is missing the original job id".to_string());
+        };
+
+        match self.api_client.get_job(original_id).await {
+            Ok(original) => self.executor.execute_retry(job, original).await,
+            Err(e) => {
+                error!(job_id = %job.id, retry_of = %original_id, error = %e, "Failed to fetch original job");
+                let mut result = self
+                    .executor
+                    .failed_result(job.id, format!("Failed to fetch original job {}: {}", original_id, e));
+                result.retry_of = Some(original_id);
+                result
+            }
+        }
+    }
+
     /// Calculate the next backoff interval.
     fn calculate_backoff(&self, current: Duration, max: Duration) -> Duration {
         let next = current * 2;
@@ -200,18 +434,139 @@ impl JobPoller {
     /// Send a heartbeat to the backend.
     ///
     /// Call this periodically to signal the worker is still alive.
-    pub async fn send_heartbeat(&self) {
-        if let Err(e) = self.api_client.send_heartbeat(None).await {
-            debug!(error = %e, "Heartbeat failed (non-critical)");
+    /// Includes SMB copy progress when a copy is running and the number of
+    /// poll loop restarts (see `supervisor`). Failures are
+    /// never propagated; they only lengthen the next heartbeat delay.
+    pub async fn send_heartbeat(&mut self) {
+        let copy_progress = *self.executor.copy_progress().borrow();
+        match self
+            .api_client
+            .send_heartbeat(None, copy_progress, super::supervisor::poller_restarts())
+            .await
+        {
+            Ok(()) => {
+                if self.heartbeat_failures > 0 {
+                    info!(
+                        failures = self.heartbeat_failures,
+                        "Heartbeat recovered, resuming normal cadence"
+                    );
+                }
+                self.heartbeat_failures = 0;
+            }
+            Err(e) => {
+                self.heartbeat_failures = self.heartbeat_failures.saturating_add(1);
+                debug!(
+                    error = %e,
+                    failures = self.heartbeat_failures,
+                    "Heartbeat failed (non-critical)"
+                );
+            }
+        }
+    }
+
+    /// Delay until the next heartbeat.
+    ///
+    /// Normal cadence while heartbeats succeed; after failures, the same
+    /// doubling as the poll loop (capped at max backoff) plus jitter so many
+    /// workers don't hit a restarting backend in lockstep.
+    fn heartbeat_delay(&self) -> Duration {
+        let base = Duration::from_secs(self.config.heartbeat_interval_seconds);
+        if self.heartbeat_failures == 0 {
+            return base;
+        }
+
+        let max = Duration::from_secs(self.config.max_backoff_seconds).max(base);
+        let mut delay = base;
+        for _ in 0..self.heartbeat_failures.min(16) {
+            delay = self.calculate_backoff(delay, max);
         }
+        with_jitter(delay, max)
     }
 }
 
+/// Run a job execution while sending SMB copy progress in heartbeats.
+///
+/// While a copy runs, a heartbeat with the latest progress goes out every
+/// `interval`. Failures are only logged; the regular heartbeat resumes after the job.
+async fn report_copy_progress<T>(
+    api_client: &ApiClient,
+    copy_progress: watch::Receiver<Option<CopyProgress>>,
+    interval: Duration,
+    execution: impl Future<Output = T>,
+) -> T {
+    tokio::pin!(execution);
+    let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            result = &mut execution => return result,
+            _ = ticker.tick() => {
+                let Some(progress) = *copy_progress.borrow() else {
+                    continue;
+                };
+                let restarts = super::supervisor::poller_restarts();
+                if let Err(e) = api_client.send_heartbeat(None, Some(progress), restarts).await {
+                    debug!(error = %e, "Failed to send copy progress heartbeat");
+                }
+            }
+        }
+    }
+}
+
+/// Run a job execution while sending the MSI log chunks it produces.
+///
+/// Log chunks are best effort: failures are logged and the chunk dropped.
+async fn forward_msi_log<T>(
+    api_client: &ApiClient,
+    msi_log_rx: Option<&mut mpsc::UnboundedReceiver<MsiLogChunk>>,
+    execution: impl Future<Output = T>,
+) -> T {
+    let Some(rx) = msi_log_rx else {
+        return execution.await;
+    };
+    tokio::pin!(execution);
+
+    loop {
+        tokio::select! {
+            result = &mut execution => {
+                // The final read of each tail is queued before the execution returns
+                while let Ok(chunk) = rx.try_recv() {
+                    send_msi_log_chunk(api_client, &chunk).await;
+                }
+                return result;
+            }
+            Some(chunk) = rx.recv() => send_msi_log_chunk(api_client, &chunk).await,
+        }
+    }
+}
+
+async fn send_msi_log_chunk(api_client: &ApiClient, chunk: &MsiLogChunk) {
+    if let Err(e) = api_client.send_msi_log(chunk).await {
+        debug!(
+            job_id = %chunk.job_id,
+            host = %chunk.hostname,
+            error = %e,
+            "Failed to send MSI log chunk"
+        );
+    }
+}
+
+/// Apply +/-25% random jitter to a delay, capped at `max`.
+fn with_jitter(delay: Duration, max: Duration) -> Duration {
+    // A random v4 UUID is a convenient entropy source without pulling in `rand`
+    let fraction = (uuid::Uuid::new_v4().as_u128() % 1000) as f64 / 1000.0;
+    let factor = 0.75 + fraction * 0.5;
+    delay.mul_f64(factor).min(max)
+}
+
 /// Result of a poll attempt
 #[derive(Debug)]
 enum PollResult {
     /// A job was received and executed
     JobExecuted,
+    /// A job was received but returned to the queue
+    JobRejected,
     /// No jobs were available
     NoJobs,
     /// An error occurred
@@ -242,6 +597,23 @@ mod tests {
             smb_copy_timeout_seconds: 300,
             service_execution_timeout_seconds: 600,
             reachability_timeout_seconds: 5,
+            smb_keepalive_seconds: 0,
+            heartbeat_interval_seconds: 60,
+            disk_space_multiplier: 3.0,
+            pending_results_capacity: 50,
+            detect_security_products: true,
+            detect_virtualization: true,
+            poll_batch_size: 1,
+            allow_av_disable: false,
+            allow_firewall_changes: false,
+            credential_cache_ttl_seconds: 60,
+            worker_tags: Vec::new(),
+            require_smb_signing: false,
+            require_smb_encryption: false,
+            wake_on_lan_wait_seconds: 0,
+            wake_on_lan_broadcast: "255.255.255.255".to_string(),
+            msi_log_tail_seconds: 0,
+            collect_logs_dir: std::env::temp_dir().join("collected-logs").to_string_lossy().into_owned(),
         };
 
         let (_, shutdown_rx) = create_shutdown_channel();
@@ -260,6 +632,23 @@ mod tests {
         assert_eq!(next, Duration::from_secs(300));
     }
 
+    #[test]
+    fn test_with_jitter_bounds() {
+        let delay = Duration::from_secs(120);
+        let max = Duration::from_secs(300);
+
+        for _ in 0..100 {
+            let jittered = with_jitter(delay, max);
+            assert!(jittered >= Duration::from_secs(90));
+            assert!(jittered <= Duration::from_secs(150));
+        }
+
+        // Never exceeds the cap
+        for _ in 0..100 {
+            assert!(with_jitter(max, max) <= max);
+        }
+    }
+
     #[test]
     fn test_shutdown_channel() {
         let (tx, rx) = create_shutdown_channel();
@@ -0,0 +1,187 @@
+//! Priority queue of claimed jobs.
+//!
+//! Jobs claimed in one poll cycle are executed in priority order rather than
+//! in the order the backend handed them out:
+//! - lower `priority` value first (0 = most urgent, default 5)
+//! - on equal priority, the oldest job (`created_at`) first
+//! - on equal priority and age, the job claimed first
+//!
+//! Priority only orders jobs this worker has claimed; it does not interrupt
+//! a job that is already running. The poller tops the queue up before each
+//! job, so an urgent job queued later still runs before routine ones.
+//!
+//! The backend has no claim renewal: a claimed job must finish within
+//! `JOB_EXECUTION_TIMEOUT_MINUTES` (5) of the claim. Jobs that waited longer
+//! than `MAX_QUEUED_CLAIM_AGE` are handed back (see `take_expired`) instead of
+//! started so another worker can claim them fresh.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use crate::api::types::DeploymentJob;
+
+/// How long a claimed job may wait in the queue before it is returned
+pub const MAX_QUEUED_CLAIM_AGE: Duration = Duration::from_secs(120);
+
+/// A claimed job with its claim time and claim order (tie-breaker).
+struct QueuedJob {
+    job: DeploymentJob,
+    claimed_at: Instant,
+    sequence: u64,
+}
+
+impl QueuedJob {
+    /// Sort key; smaller runs first
+    fn key(&self) -> (u8, chrono::DateTime<chrono::Utc>, u64) {
+        (self.job.priority, self.job.created_at, self.sequence)
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap: reverse so the smallest key pops first
+        other.key().cmp(&self.key())
+    }
+}
+
+/// Claimed jobs waiting to run, highest priority first.
+#[derive(Default)]
+pub struct JobQueue {
+    heap: BinaryHeap<QueuedJob>,
+    next_sequence: u64,
+}
+
+impl JobQueue {
+    /// Create an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a job claimed at `claimed_at`.
+    pub fn push(&mut self, job: DeploymentJob, claimed_at: Instant) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.heap.push(QueuedJob { job, claimed_at, sequence });
+    }
+
+    /// Take the job that should run next.
+    pub fn pop(&mut self) -> Option<DeploymentJob> {
+        self.heap.pop().map(|queued| queued.job)
+    }
+
+    /// Remove jobs claimed `max_age` or longer before `now` (in priority order).
+    pub fn take_expired(&mut self, now: Instant, max_age: Duration) -> Vec<DeploymentJob> {
+        let (mut expired, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.heap)
+            .into_iter()
+            .partition(|queued| now.saturating_duration_since(queued.claimed_at) >= max_age);
+        self.heap = kept.into_iter().collect();
+        expired.sort_by_key(QueuedJob::key);
+        expired.into_iter().map(|queued| queued.job).collect()
+    }
+
+    /// Remove all queued jobs (in priority order).
+    pub fn drain(&mut self) -> Vec<DeploymentJob> {
+        std::iter::from_fn(|| self.pop()).collect()
+    }
+
+    /// Number of queued jobs.
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Whether no jobs are queued.
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn job(priority: u8, age_minutes: i64) -> DeploymentJob {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "jobType": "msi_install",
+            "createdAt": Utc::now() - chrono::Duration::minutes(age_minutes),
+            "priority": priority,
+            "payload": { "targets": [] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_job_queue_priority_order() {
+        let mut queue = JobQueue::new();
+        queue.push(job(5, 10), Instant::now());
+        queue.push(job(1, 0), Instant::now());
+        queue.push(job(9, 30), Instant::now());
+        queue.push(job(0, 5), Instant::now());
+
+        let order: Vec<u8> = queue.drain().iter().map(|j| j.priority).collect();
+        assert_eq!(order, vec![0, 1, 5, 9]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_job_queue_ties() {
+        let mut queue = JobQueue::new();
+        let newer = job(5, 1);
+        let older = job(5, 60);
+        let (newer_id, older_id) = (newer.id, older.id);
+        queue.push(newer, Instant::now());
+        queue.push(older, Instant::now());
+        assert_eq!(queue.pop().unwrap().id, older_id);
+        assert_eq!(queue.pop().unwrap().id, newer_id);
+
+        // Same priority and age: claim order
+        let first = job(3, 0);
+        let mut second = job(3, 0);
+        second.created_at = first.created_at;
+        let (first_id, second_id) = (first.id, second.id);
+        queue.push(first, Instant::now());
+        queue.push(second, Instant::now());
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop().unwrap().id, first_id);
+        assert_eq!(queue.pop().unwrap().id, second_id);
+    }
+
+    #[test]
+    fn test_job_queue_take_expired() {
+        let now = Instant::now();
+        let max_age = Duration::from_secs(120);
+        let claimed = |seconds_ago: u64| now.checked_sub(Duration::from_secs(seconds_ago)).unwrap();
+
+        let mut queue = JobQueue::new();
+        let fresh = job(0, 0);
+        let stale_low = job(9, 0);
+        let stale_high = job(1, 0);
+        let (fresh_id, stale_low_id, stale_high_id) = (fresh.id, stale_low.id, stale_high.id);
+        queue.push(fresh, claimed(30));
+        queue.push(stale_low, claimed(300));
+        queue.push(stale_high, claimed(120));
+
+        let expired: Vec<Uuid> = queue.take_expired(now, max_age).iter().map(|j| j.id).collect();
+        assert_eq!(expired, vec![stale_high_id, stale_low_id]);
+        assert_eq!(queue.len(), 1);
+        assert!(queue.take_expired(now, max_age).is_empty());
+        assert_eq!(queue.pop().unwrap().id, fresh_id);
+    }
+}
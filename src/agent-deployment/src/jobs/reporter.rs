@@ -1,16 +1,25 @@
 //! Result reporting with retry logic.
 //!
 //! Handles reporting job results back to the backend with
-//! exponential backoff on failures.
+//! exponential backoff on failures. Results that still can't be
+//! reported because of a transient failure are kept in memory
+//! (`PendingResults`) and re-sent while the poller keeps running.
 
+use std::collections::VecDeque;
+use std::future::Future;
 use std::time::Duration;
 
 use thiserror::Error;
+use tokio::time::Instant;
 use tracing::{debug, error, info, instrument, warn};
+use uuid::Uuid;
 
 use crate::api::{ApiClient, ApiError};
 use crate::api::types::JobResult;
 
+/// Delay before the first re-send of a pending result
+const PENDING_RETRY_INITIAL_DELAY: Duration = Duration::from_secs(15);
+
 /// Errors from result reporting
 #[derive(Debug, Error)]
 pub enum ReportError {
@@ -137,25 +146,7 @@ impl ResultReporter {
 
     /// Check if an error is retryable.
     fn should_retry(&self, error: &ApiError) -> bool {
-        match error {
-            // Network errors are usually transient
-            ApiError::RequestFailed(_) => true,
-            // Server errors may be temporary
-            ApiError::ServerError { status_code, .. } => {
-                // Retry 5xx errors except 501 (Not Implemented)
-                *status_code >= 500 && *status_code != 501
-            }
-            // Rate limiting should be retried
-            ApiError::RateLimited { .. } => true,
-            // Auth errors should not be retried (token refresh needed)
-            ApiError::AuthenticationFailed(_) => false,
-            // Credential errors should not be retried
-            ApiError::CredentialError(_) => false,
-            // Invalid response might be server-side issue
-            ApiError::InvalidResponse(_) => true,
-            // Job already claimed is not retryable
-            ApiError::JobAlreadyClaimed => false,
-        }
+        is_retryable(error)
     }
 
     /// Calculate the next delay using exponential backoff.
@@ -166,12 +157,170 @@ impl ResultReporter {
     }
 }
 
+/// Check if a reporting error is transient and worth retrying.
+fn is_retryable(error: &ApiError) -> bool {
+    match error {
+        // Network errors are usually transient
+        ApiError::RequestFailed(_) => true,
+        // Server errors may be temporary
+        ApiError::ServerError { status_code, .. } => {
+            // Retry 5xx errors except 501 (Not Implemented)
+            *status_code >= 500 && *status_code != 501
+        }
+        // Rate limiting should be retried
+        ApiError::RateLimited { .. } => true,
+        // Auth errors should not be retried (token refresh needed)
+        ApiError::AuthenticationFailed(_) => false,
+        // Credential errors should not be retried
+        ApiError::CredentialError(_) => false,
+        // Invalid response might be server-side issue
+        ApiError::InvalidResponse(_) => true,
+        // Job already claimed is not retryable
+        ApiError::JobAlreadyClaimed => false,
+    }
+}
+
 impl Default for ResultReporter {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// A result whose report failed with a transient error
+#[derive(Debug)]
+struct PendingResult {
+    result: JobResult,
+    /// Re-send attempts so far
+    attempts: u32,
+    next_attempt: Instant,
+}
+
+/// In-memory queue of job results awaiting a successful report.
+///
+/// Covers transient network drops within a session: the poller keeps
+/// polling while these are re-sent with exponential backoff. The queue is
+/// capped; when full the oldest result is dropped (the backend eventually
+/// times out its claim).
+#[derive(Debug)]
+pub struct PendingResults {
+    entries: VecDeque<PendingResult>,
+    capacity: usize,
+    max_delay: Duration,
+}
+
+impl PendingResults {
+    /// Create an empty queue holding at most `capacity` results.
+    pub fn new(capacity: usize, max_delay: Duration) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            max_delay,
+        }
+    }
+
+    /// Number of results waiting to be reported.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no results are waiting.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Queue a result for re-sending.
+    ///
+    /// # Returns
+    /// The job ID of a result dropped to stay within capacity, if any.
+    pub fn push(&mut self, result: JobResult, now: Instant) -> Option<Uuid> {
+        if self.capacity == 0 {
+            return Some(result.job_id);
+        }
+
+        let dropped = if self.entries.len() >= self.capacity {
+            self.entries.pop_front().map(|entry| entry.result.job_id)
+        } else {
+            None
+        };
+
+        self.entries.push_back(PendingResult {
+            result,
+            attempts: 0,
+            next_attempt: now + self.retry_delay(0),
+        });
+        dropped
+    }
+
+    /// When the next re-send is due, if any results are pending.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.entries.iter().map(|entry| entry.next_attempt).min()
+    }
+
+    /// Re-send every result that is due.
+    ///
+    /// Delivered results are removed, transient failures are rescheduled
+    /// with backoff and non-retryable failures are dropped.
+    ///
+    /// # Returns
+    /// The number of results delivered.
+    pub async fn retry_due<F, Fut>(&mut self, now: Instant, mut send: F) -> usize
+    where
+        F: FnMut(&JobResult) -> Fut,
+        Fut: Future<Output = Result<(), ApiError>>,
+    {
+        let mut delivered = 0;
+        let mut remaining = VecDeque::with_capacity(self.entries.len());
+
+        while let Some(mut entry) = self.entries.pop_front() {
+            if entry.next_attempt > now {
+                remaining.push_back(entry);
+                continue;
+            }
+
+            match send(&entry.result).await {
+                Ok(()) => {
+                    info!(
+                        job_id = %entry.result.job_id,
+                        attempts = entry.attempts + 1,
+                        "Pending result reported"
+                    );
+                    delivered += 1;
+                }
+                Err(e) if is_retryable(&e) => {
+                    entry.attempts += 1;
+                    let delay = self.retry_delay(entry.attempts);
+                    entry.next_attempt = now + delay;
+                    warn!(
+                        job_id = %entry.result.job_id,
+                        attempts = entry.attempts,
+                        delay_secs = delay.as_secs(),
+                        error = %e,
+                        "Pending result still not reported"
+                    );
+                    remaining.push_back(entry);
+                }
+                Err(e) => {
+                    error!(
+                        job_id = %entry.result.job_id,
+                        error = %e,
+                        "Dropping pending result (non-retryable error)"
+                    );
+                }
+            }
+        }
+
+        self.entries = remaining;
+        delivered
+    }
+
+    /// Backoff before the next re-send after `attempts` failed re-sends.
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        PENDING_RETRY_INITIAL_DELAY
+            .saturating_mul(2u32.saturating_pow(attempts.min(16)))
+            .min(self.max_delay)
+    }
+}
+
 /// Report a result with default retry settings.
 ///
 /// Convenience function for simple reporting.
@@ -237,6 +386,95 @@ mod tests {
         }));
     }
 
+    fn pending_result() -> JobResult {
+        JobResult::new(Uuid::new_v4(), "test-worker".to_string(), chrono::Utc::now())
+    }
+
+    #[test]
+    fn test_pending_results_capacity() {
+        let now = Instant::now();
+        let mut pending = PendingResults::new(2, Duration::from_secs(300));
+
+        let first = pending_result();
+        let first_id = first.job_id;
+        assert_eq!(pending.push(first, now), None);
+        assert_eq!(pending.push(pending_result(), now), None);
+
+        // Full: the oldest result is dropped
+        assert_eq!(pending.push(pending_result(), now), Some(first_id));
+        assert_eq!(pending.len(), 2);
+
+        // Zero capacity keeps nothing
+        let mut disabled = PendingResults::new(0, Duration::from_secs(300));
+        let result = pending_result();
+        let id = result.job_id;
+        assert_eq!(disabled.push(result, now), Some(id));
+        assert!(disabled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_results_retry_on_report_failure() {
+        let now = Instant::now();
+        let mut pending = PendingResults::new(10, Duration::from_secs(300));
+        pending.push(pending_result(), now);
+
+        // Not due yet: nothing is sent
+        let mut sends = 0;
+        let delivered = pending
+            .retry_due(now, |_| {
+                sends += 1;
+                async { Ok(()) }
+            })
+            .await;
+        assert_eq!((delivered, sends), (0, 0));
+
+        // Network still down: kept and rescheduled with a longer delay
+        let first_due = pending.next_due().unwrap();
+        let delivered = pending
+            .retry_due(first_due, |_| async {
+                Err(ApiError::ServerError {
+                    status_code: 503,
+                    message: "unavailable".to_string(),
+                })
+            })
+            .await;
+        assert_eq!(delivered, 0);
+        assert_eq!(pending.len(), 1);
+        let second_due = pending.next_due().unwrap();
+        assert!(second_due - first_due > PENDING_RETRY_INITIAL_DELAY);
+
+        // Network back: delivered and removed
+        let delivered = pending.retry_due(second_due, |_| async { Ok(()) }).await;
+        assert_eq!(delivered, 1);
+        assert!(pending.is_empty());
+        assert_eq!(pending.next_due(), None);
+    }
+
+    #[tokio::test]
+    async fn test_pending_results_drop_non_retryable() {
+        let now = Instant::now();
+        let mut pending = PendingResults::new(10, Duration::from_secs(300));
+        pending.push(pending_result(), now);
+
+        let due = pending.next_due().unwrap();
+        let delivered = pending
+            .retry_due(due, |_| async {
+                Err(ApiError::AuthenticationFailed("Invalid token".to_string()))
+            })
+            .await;
+        assert_eq!(delivered, 0);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_pending_retry_delay_capped() {
+        let pending = PendingResults::new(10, Duration::from_secs(60));
+        assert_eq!(pending.retry_delay(0), PENDING_RETRY_INITIAL_DELAY);
+        assert_eq!(pending.retry_delay(1), PENDING_RETRY_INITIAL_DELAY * 2);
+        assert_eq!(pending.retry_delay(10), Duration::from_secs(60));
+        assert_eq!(pending.retry_delay(u32::MAX), Duration::from_secs(60));
+    }
+
     #[test]
     fn test_report_error_display() {
         let err = ReportError::MaxRetriesExceeded {
@@ -0,0 +1,80 @@
+//! Poll loop supervision.
+//!
+//! `run_worker` runs the poller on its own task. If the task panics (e.g. an
+//! unexpected Win32 state in the executor) the worker would otherwise keep
+//! "running" without processing jobs, so the loop is restarted with backoff
+//! a bounded number of times before the worker exits with an error and SCM's
+//! recovery actions restart the service.
+//!
+//! Jobs claimed by the failed loop are not resumed; the backend times out
+//! their claims.
+
+use std::any::Any;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Poll loop restarts allowed before the worker gives up
+pub const MAX_POLLER_RESTARTS: u32 = 5;
+
+/// A poll loop that ran at least this long before failing resets the restart budget
+pub const POLLER_STABLE_RUNTIME: Duration = Duration::from_secs(600);
+
+/// Delay before the first restart; doubles with each consecutive restart
+const POLLER_RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// Poll loop restarts since the worker started (reported in heartbeats)
+static POLLER_RESTARTS: AtomicU32 = AtomicU32::new(0);
+
+/// Record a poll loop restart.
+pub fn record_poller_restart() {
+    POLLER_RESTARTS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Number of poll loop restarts since the worker started.
+pub fn poller_restarts() -> u32 {
+    POLLER_RESTARTS.load(Ordering::Relaxed)
+}
+
+/// Delay before restart number `restart` (1-based), capped at `max`.
+pub fn restart_delay(restart: u32, max: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(restart.saturating_sub(1));
+    POLLER_RESTART_DELAY.saturating_mul(factor).min(max)
+}
+
+/// Extract the message of a panic payload.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_delay() {
+        let max = Duration::from_secs(300);
+        assert_eq!(restart_delay(1, max), Duration::from_secs(5));
+        assert_eq!(restart_delay(2, max), Duration::from_secs(10));
+        assert_eq!(restart_delay(4, max), Duration::from_secs(40));
+        assert_eq!(restart_delay(10, max), max);
+        assert_eq!(restart_delay(u32::MAX, max), max);
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let payload = std::panic::catch_unwind(|| panic!("boom")).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "boom");
+
+        let payload = std::panic::catch_unwind(|| panic!("code {}", 5)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "code 5");
+
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(42)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "unknown panic");
+    }
+}
@@ -9,6 +9,8 @@
 //! - **Console Mode** (`--console`): Runs interactively for debugging
 //! - **Install** (`--install`): Installs the Windows Service
 //! - **Uninstall** (`--uninstall`): Removes the Windows Service
+//! - **Show worker ID** (`--show-worker-id`): Prints the ID this worker polls with
+//! - **Rotate worker ID** (`--rotate-worker-id`): Generates a new installation ID
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
@@ -28,6 +30,9 @@ use tracing::{error, info, warn};
 use crate::api::ApiClient;
 use crate::audit::{audit_event, init_logging, AuditEvent, AuditEventType};
 use crate::config::Config;
+use crate::jobs::supervisor::{
+    panic_message, record_poller_restart, restart_delay, MAX_POLLER_RESTARTS, POLLER_STABLE_RUNTIME,
+};
 use crate::jobs::{create_shutdown_channel, JobExecutor, JobPoller};
 
 /// Service name for Windows Service registration
@@ -81,6 +86,26 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            "--show-worker-id" => {
+                println!("{}", load_config_for_cli().get_worker_id());
+                return;
+            }
+            "--rotate-worker-id" => {
+                let config = load_config_for_cli();
+                match Config::rotate_installation_id() {
+                    Ok(worker_id) => {
+                        println!("New installation ID generated, derived worker ID: {}", worker_id);
+                        if let Some(configured) = &config.worker.worker_id {
+                            println!("Note: worker.worker_id = {:?} is configured and takes precedence", configured);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to rotate installation ID: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                return;
+            }
             "--help" | "-h" => {
                 print_help();
                 return;
@@ -123,15 +148,26 @@ fn print_help() {
     println!("Usage: agent-deployment [OPTIONS]");
     println!();
     println!("Options:");
-    println!("  --console, -c     Run in console mode (interactive)");
-    println!("  --install, -i     Install Windows Service");
-    println!("  --uninstall, -u   Uninstall Windows Service");
-    println!("  --help, -h        Show this help message");
-    println!("  --version, -v     Show version information");
+    println!("  --console, -c       Run in console mode (interactive)");
+    println!("  --install, -i       Install Windows Service");
+    println!("  --uninstall, -u     Uninstall Windows Service");
+    println!("  --show-worker-id    Print the worker ID used when polling");
+    println!("  --rotate-worker-id  Generate a new installation ID (new derived worker ID)");
+    println!("  --help, -h          Show this help message");
+    println!("  --version, -v       Show version information");
     println!();
     println!("Without options, runs as a Windows Service.");
 }
 
+/// Load the configuration for a one-shot CLI command
+/// Falls back to defaults (no `worker.worker_id` override) if it can't be loaded.
+fn load_config_for_cli() -> Config {
+    Config::load().unwrap_or_else(|e| {
+        eprintln!("Warning: failed to load configuration ({}), using defaults", e);
+        Config::default()
+    })
+}
+
 /// Run in console mode (for debugging and development)
 fn run_console_mode() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration
@@ -172,14 +208,8 @@ async fn run_worker(config: Config) -> Result<(), Box<dyn std::error::Error>> {
             .with_worker_id(&worker_id),
     );
 
-    // Create API client
-    let api_client = ApiClient::new(config.api.clone(), worker_id.clone()).await?;
-
-    // Create executor
-    let executor = JobExecutor::new(config.worker.clone(), worker_id.clone());
-
     // Create shutdown channel
-    let (shutdown_tx, shutdown_rx) = create_shutdown_channel();
+    let (shutdown_tx, mut shutdown_rx) = create_shutdown_channel();
 
     // Setup Ctrl+C handler for console mode
     let shutdown_tx_clone = shutdown_tx.clone();
@@ -192,11 +222,63 @@ async fn run_worker(config: Config) -> Result<(), Box<dyn std::error::Error>> {
         let _ = shutdown_tx_clone.send(true);
     });
 
-    // Create and run poller
-    let mut poller = JobPoller::new(api_client, executor, config.worker.clone(), shutdown_rx);
+    // Run the poller, restarting it (bounded, with backoff) if it panics
+    let max_backoff = Duration::from_secs(config.worker.max_backoff_seconds);
+    let mut restarts = 0u32;
+    loop {
+        // Create API client and executor (fresh state on every restart)
+        let api_client = ApiClient::new(config.api.clone(), worker_id.clone())
+            .await?
+            .with_worker_tags(config.worker.worker_tags.clone());
+        let executor = JobExecutor::new(config.worker.clone(), worker_id.clone());
+        let mut poller = JobPoller::new(api_client, executor, config.worker.clone(), shutdown_rx.clone());
+
+        info!("Worker started, beginning poll loop");
+        let started = std::time::Instant::now();
+        let outcome = tokio::spawn(async move { poller.run().await }).await;
+
+        // The poller only returns on shutdown
+        let failure = match outcome {
+            Ok(()) => break,
+            Err(e) if e.is_panic() => panic_message(e.into_panic().as_ref()),
+            Err(e) => e.to_string(),
+        };
+
+        if started.elapsed() >= POLLER_STABLE_RUNTIME {
+            restarts = 0;
+        }
+        restarts += 1;
+
+        error!(error = %failure, restarts, "Poll loop failed");
+        audit_event(
+            AuditEvent::new(
+                AuditEventType::Error,
+                "failed",
+                &format!("Poll loop failed ({}/{}): {}", restarts, MAX_POLLER_RESTARTS, failure),
+            )
+            .with_worker_id(&worker_id),
+        );
+
+        if restarts > MAX_POLLER_RESTARTS {
+            audit_event(
+                AuditEvent::new(AuditEventType::WorkerStopped, "failed", "Poll loop restart limit reached")
+                    .with_worker_id(&worker_id),
+            );
+            return Err(format!("Poll loop failed {} times, last error: {}", restarts, failure).into());
+        }
+
+        record_poller_restart();
+        let delay = restart_delay(restarts, max_backoff);
+        warn!(delay_secs = delay.as_secs(), restarts, "Restarting poll loop");
 
-    info!("Worker started, beginning poll loop");
-    poller.run().await;
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown_rx.changed() => {}
+        }
+        if *shutdown_rx.borrow() {
+            break;
+        }
+    }
 
     // Audit: Worker stopped
     audit_event(
@@ -350,7 +432,10 @@ use windows_service_impl::run_as_service;
 fn install_service() -> Result<(), Box<dyn std::error::Error>> {
     use std::ffi::OsStr;
     use windows_service::{
-        service::{ServiceAccess, ServiceErrorControl, ServiceInfo, ServiceStartType, ServiceType},
+        service::{
+            ServiceAccess, ServiceAction, ServiceActionType, ServiceErrorControl, ServiceFailureActions,
+            ServiceFailureResetPeriod, ServiceInfo, ServiceStartType, ServiceType,
+        },
         service_manager::{ServiceManager, ServiceManagerAccess},
     };
 
@@ -371,11 +456,25 @@ fn install_service() -> Result<(), Box<dyn std::error::Error>> {
         account_password: None,
     };
 
-    let service = manager.create_service(&service_info, ServiceAccess::CHANGE_CONFIG)?;
+    let service = manager.create_service(&service_info, ServiceAccess::CHANGE_CONFIG | ServiceAccess::START)?;
 
     // Set service description
     service.set_description(SERVICE_DESCRIPTION)?;
 
+    // Restart the service when it fails, including when the worker exits with an
+    // error after exhausting its poll loop restarts (a non-crash failure)
+    let restart = |delay_secs| ServiceAction {
+        action_type: ServiceActionType::Restart,
+        delay: Duration::from_secs(delay_secs),
+    };
+    service.update_failure_actions(ServiceFailureActions {
+        reset_period: ServiceFailureResetPeriod::After(Duration::from_secs(24 * 60 * 60)),
+        reboot_msg: None,
+        command: None,
+        actions: Some(vec![restart(60), restart(300), restart(900)]),
+    })?;
+    service.set_failure_actions_on_non_crash_failures(true)?;
+
     println!("Service '{}' installed successfully", SERVICE_NAME);
     println!("Start with: sc start {}", SERVICE_NAME);
 
@@ -29,6 +29,38 @@ pub struct AutostartEnableResult {
     pub was_created: bool,
 }
 
+/// Where the running executable lives, relative to a proper install
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallLocationStatus {
+    /// Whether the executable is under Program Files or the per-user install dir
+    pub is_properly_installed: bool,
+    /// Full path of the running executable
+    pub path: String,
+    /// Location category: "installed", "temp", "downloads" or "other"
+    pub location: String,
+    /// Advice for the user when not properly installed
+    pub recommendation: Option<String>,
+}
+
+/// A value under the current user's Run key
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunEntry {
+    /// Registry value name
+    pub name: String,
+    /// Command line that runs at login
+    pub value: String,
+    /// False when disabled via Task Manager's Startup tab (StartupApproved)
+    pub enabled: bool,
+    /// Whether this is our own auto-start entry
+    pub is_ours: bool,
+}
+
+/// Command-line flag passed by the auto-start entry, so a launch at logon
+/// can be told apart from the user opening the app
+pub const AUTOSTART_ARG: &str = "--autostart";
+
 /// Registry key path for auto-start
 const REGISTRY_RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
 
@@ -52,6 +84,12 @@ fn get_registry_entry_info() -> Result<(String, String), String> {
     Ok((app_name.to_string(), exe_path_str))
 }
 
+/// Command line the auto-start entry runs
+#[cfg(target_os = "windows")]
+fn autostart_command(exe_path: &str) -> String {
+    format!("\"{}\" {}", exe_path, AUTOSTART_ARG)
+}
+
 /// Check current auto-start status
 ///
 /// Returns detailed status including whether auto-start is enabled,
@@ -109,7 +147,8 @@ pub fn check_autostart_status() -> Result<AutostartStatus, String> {
             .trim_end_matches('\0')
             .to_string();
 
-        let enabled = actual_exe_path == expected_exe_path;
+        // Entries written before the flag was added (bare path) still start the app
+        let enabled = actual_exe_path == autostart_command(&expected_exe_path) || actual_exe_path == expected_exe_path;
 
         Ok(AutostartStatus {
             enabled,
@@ -147,6 +186,7 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
     use windows::core::PCSTR;
 
     let (value_name, exe_path) = get_registry_entry_info()?;
+    let command = autostart_command(&exe_path);
 
     unsafe {
         // First, check if entry already exists
@@ -179,7 +219,7 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
                     .trim_end_matches('\0')
                     .to_string();
 
-                if existing_path == exe_path {
+                if existing_path == command {
                     // Entry already exists with correct value - idempotent success
                     return Ok(AutostartEnableResult {
                         success: true,
@@ -187,7 +227,7 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
                         message: "Auto-start already configured (idempotent)".to_string(),
                         was_created: false,
                     });
-                } else {
+                } else if existing_path != exe_path {
                     // Entry exists but points to different executable
                     // DO NOT overwrite - report warning to user
                     return Ok(AutostartEnableResult {
@@ -195,7 +235,7 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
                         enabled: false,
                         message: format!(
                             "Auto-start entry exists but points to different path.\nCurrent: {}\nThis app: {}\n\nPlease remove the existing entry manually and try again.",
-                            existing_path, exe_path
+                            existing_path, command
                         ),
                         was_created: false,
                     });
@@ -203,7 +243,7 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
             }
         }
 
-        // Entry doesn't exist - create it
+        // Entry doesn't exist (or is our bare-path entry without the flag) - create it
         let mut h_key: HKEY = HKEY::default();
         let open_result = RegOpenKeyExA(
             HKEY_CURRENT_USER,
@@ -217,16 +257,16 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
             return Err(format!("Failed to open registry key for writing: {:?}", open_result));
         }
 
-        // Convert exe_path to null-terminated bytes for registry
-        let mut exe_path_bytes = exe_path.as_bytes().to_vec();
-        exe_path_bytes.push(0); // Null terminator
+        // Convert the command to null-terminated bytes for registry
+        let mut command_bytes = command.as_bytes().to_vec();
+        command_bytes.push(0); // Null terminator
 
         let set_result = RegSetValueExA(
             h_key,
             PCSTR(value_name.as_ptr()),
             0,
             REG_SZ,
-            Some(&exe_path_bytes),
+            Some(&command_bytes),
         );
 
         if set_result.is_err() {
@@ -236,7 +276,7 @@ pub fn enable_autostart() -> Result<AutostartEnableResult, String> {
         Ok(AutostartEnableResult {
             success: true,
             enabled: true,
-            message: format!("Auto-start enabled: {}", exe_path),
+            message: format!("Auto-start enabled: {}", command),
             was_created: true,
         })
     }
@@ -310,3 +350,214 @@ pub fn disable_autostart() -> Result<AutostartEnableResult, String> {
         was_created: false,
     })
 }
+
+/// Normalize a path for prefix comparison (lowercase, backslashes, trailing separator)
+#[cfg(target_os = "windows")]
+fn normalize_dir(path: &std::path::Path) -> String {
+    let mut normalized = path.to_string_lossy().replace('/', "\\").to_lowercase();
+    if !normalized.ends_with('\\') {
+        normalized.push('\\');
+    }
+    normalized
+}
+
+/// Check whether the app is running from a proper install location
+///
+/// A copy launched from a temp or Downloads folder registers auto-start
+/// against a path that may be deleted, and in-place updates misbehave.
+#[cfg(target_os = "windows")]
+pub fn get_install_location_status() -> Result<InstallLocationStatus, String> {
+    use std::env;
+    use std::path::PathBuf;
+
+    let exe_path = env::current_exe()
+        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+    let exe = normalize_dir(&exe_path);
+
+    let env_dir = |name: &str| env::var_os(name).map(PathBuf::from);
+
+    // Machine-wide installs plus the NSIS per-user default (%LOCALAPPDATA%\Programs)
+    let install_roots: Vec<PathBuf> = [
+        env_dir("ProgramFiles"),
+        env_dir("ProgramFiles(x86)"),
+        env_dir("ProgramW6432"),
+        env_dir("LOCALAPPDATA").map(|p| p.join("Programs")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    let downloads = env_dir("USERPROFILE").map(|p| p.join("Downloads"));
+
+    let location = if install_roots.iter().any(|root| exe.starts_with(&normalize_dir(root))) {
+        "installed"
+    } else if exe.starts_with(&normalize_dir(&env::temp_dir())) {
+        "temp"
+    } else if downloads.map(|d| exe.starts_with(&normalize_dir(&d))).unwrap_or(false) {
+        "downloads"
+    } else {
+        "other"
+    };
+
+    let is_properly_installed = location == "installed";
+
+    let recommendation = match location {
+        "installed" => None,
+        "temp" | "downloads" => Some(
+            "The app is running from a temporary or download folder. Run the installer so auto-start and updates keep working.".to_string(),
+        ),
+        _ => Some(
+            "The app is not running from its installed location. Run the installer to install it properly.".to_string(),
+        ),
+    };
+
+    Ok(InstallLocationStatus {
+        is_properly_installed,
+        path: exe_path.to_string_lossy().to_string(),
+        location: location.to_string(),
+        recommendation,
+    })
+}
+
+/// Check install location (stub for non-Windows)
+#[cfg(not(target_os = "windows"))]
+pub fn get_install_location_status() -> Result<InstallLocationStatus, String> {
+    let exe_path = std::env::current_exe()
+        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+
+    Ok(InstallLocationStatus {
+        is_properly_installed: true,
+        path: exe_path.to_string_lossy().to_string(),
+        location: "other".to_string(),
+        recommendation: None,
+    })
+}
+
+/// Registry key holding the Startup tab's enable/disable flags for Run entries
+#[cfg(target_os = "windows")]
+const STARTUP_APPROVED_RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved\Run";
+
+/// Enumerate the (name, raw data, value type) of every value under a HKCU key
+#[cfg(target_os = "windows")]
+fn read_hkcu_values(key_path: &str) -> Result<Vec<(String, Vec<u8>, u32)>, String> {
+    read_registry_values(windows::Win32::System::Registry::HKEY_CURRENT_USER, key_path)
+}
+
+/// Enumerate the (name, raw data, value type) of every value under a key
+#[cfg(target_os = "windows")]
+pub(crate) fn read_registry_values(
+    root: windows::Win32::System::Registry::HKEY,
+    key_path: &str,
+) -> Result<Vec<(String, Vec<u8>, u32)>, String> {
+    use windows::core::{PCWSTR, PWSTR};
+    use windows::Win32::Foundation::ERROR_NO_MORE_ITEMS;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegEnumValueW, RegOpenKeyExW, HKEY, KEY_READ,
+    };
+
+    let key_wide: Vec<u16> = key_path.encode_utf16().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        let mut h_key = HKEY::default();
+        if RegOpenKeyExW(root, PCWSTR(key_wide.as_ptr()), 0, KEY_READ, &mut h_key).is_err() {
+            // Key absent (e.g. StartupApproved never written) - no values
+            return Ok(Vec::new());
+        }
+
+        let mut values = Vec::new();
+        let mut index = 0u32;
+        loop {
+            let mut name_buf = vec![0u16; 16_384];
+            let mut name_len = name_buf.len() as u32;
+            let mut data_buf = vec![0u8; 4096];
+            let mut data_len = data_buf.len() as u32;
+            let mut value_type = 0u32;
+
+            let result = RegEnumValueW(
+                h_key,
+                index,
+                PWSTR(name_buf.as_mut_ptr()),
+                &mut name_len,
+                None,
+                Some(&mut value_type as *mut u32),
+                Some(data_buf.as_mut_ptr()),
+                Some(&mut data_len as *mut u32),
+            );
+
+            if result == ERROR_NO_MORE_ITEMS {
+                break;
+            }
+            index += 1;
+            if result.is_err() {
+                // Skip values we can't read (e.g. oversized data) rather than failing
+                continue;
+            }
+
+            data_buf.truncate(data_len as usize);
+            values.push((String::from_utf16_lossy(&name_buf[..name_len as usize]), data_buf, value_type));
+        }
+
+        let _ = RegCloseKey(h_key);
+        Ok(values)
+    }
+}
+
+/// Decode REG_SZ / REG_EXPAND_SZ data (UTF-16LE, NUL-terminated)
+#[cfg(target_os = "windows")]
+pub(crate) fn registry_string(data: &[u8]) -> String {
+    let wide: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&wide).trim_end_matches('\0').to_string()
+}
+
+/// Whether an entry is enabled according to StartupApproved values
+/// Startup tab flag: first byte even (0x02/0x06) = enabled, odd (0x03) = disabled;
+/// no flag means the entry was never toggled and is enabled
+#[cfg(target_os = "windows")]
+pub(crate) fn is_startup_approved(approved: &[(String, Vec<u8>, u32)], name: &str) -> bool {
+    approved
+        .iter()
+        .find(|(approved_name, _, _)| approved_name.eq_ignore_ascii_case(name))
+        .and_then(|(_, flags, _)| flags.first())
+        .map(|flag| flag % 2 == 0)
+        .unwrap_or(true)
+}
+
+/// List every auto-start entry under HKCU Run with its Startup tab state
+///
+/// Helps diagnose why auto-start silently doesn't fire: the entry may be
+/// disabled via Task Manager (StartupApproved flag with an odd first byte) or a
+/// stale install may have left a conflicting value.
+#[cfg(target_os = "windows")]
+pub fn list_run_entries() -> Result<Vec<RunEntry>, String> {
+    use windows::Win32::System::Registry::{REG_EXPAND_SZ, REG_SZ};
+
+    let (our_name, _) = get_registry_entry_info()?;
+    let approved = read_hkcu_values(STARTUP_APPROVED_RUN_KEY)?;
+
+    let entries = read_hkcu_values(REGISTRY_RUN_KEY)?
+        .into_iter()
+        .filter(|(_, _, value_type)| *value_type == REG_SZ.0 || *value_type == REG_EXPAND_SZ.0)
+        .map(|(name, data, _)| {
+            let value = registry_string(&data);
+            let enabled = is_startup_approved(&approved, &name);
+
+            RunEntry {
+                is_ours: name.eq_ignore_ascii_case(&our_name),
+                name,
+                value,
+                enabled,
+            }
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// List Run entries (stub for non-Windows)
+#[cfg(not(target_os = "windows"))]
+pub fn list_run_entries() -> Result<Vec<RunEntry>, String> {
+    Err("Run entry listing is only supported on Windows".to_string())
+}
@@ -0,0 +1,187 @@
+//! Capture Loop Module
+//!
+//! Server-driven capture at a fixed cadence. When the frontend times captures
+//! itself, every frame interval absorbs its event-loop jitter and playback
+//! looks uneven; here a `tokio::time::interval` ticks on a fixed schedule
+//! (compensating for drift) and each frame is pushed as a `capture-frame`
+//! event. The binary WebSocket stream (`stream_server`) paces itself and does
+//! not need this.
+//!
+//! At most one loop runs per monitor; starting a loop for a monitor replaces
+//! the running one (e.g. to change the frame rate).
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+use tokio::time::MissedTickBehavior;
+
+use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
+
+/// Event carrying one frame
+const CAPTURE_FRAME_EVENT: &str = "capture-frame";
+
+/// Event carrying a capture error (the loop keeps running)
+const CAPTURE_ERROR_EVENT: &str = "capture-loop-error";
+
+/// Maximum frame rate of a loop
+const MAX_LOOP_FPS: u32 = 60;
+
+/// Running loops: monitor id and stop signal
+static CAPTURE_LOOPS: Mutex<Vec<(usize, watch::Sender<bool>)>> = Mutex::new(Vec::new());
+
+/// Payload of a `capture-frame` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureFrame {
+    monitor_id: usize,
+    /// Frame number within this loop, starting at 0 (gaps mean skipped ticks)
+    sequence: u64,
+    /// Capture completion time (Unix ms)
+    captured_at_ms: f64,
+    /// Base64-encoded JPEG
+    image: String,
+}
+
+/// Capture frames on every tick until stopped
+async fn run_capture_loop(
+    app: AppHandle,
+    monitor_id: usize,
+    period: Duration,
+    profile: &'static str,
+    sampling_factor: jpeg_encoder::SamplingFactor,
+    chroma: &'static str,
+    mut stop: watch::Receiver<bool>,
+) {
+    let mut ticker = tokio::time::interval(period);
+    // A slow capture skips the missed ticks instead of bursting to catch up
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut sequence: u64 = 0;
+
+    loop {
+        tokio::select! {
+            _ = stop.changed() => break,
+            _ = ticker.tick() => {}
+        }
+
+        let tick = sequence;
+        sequence += 1;
+
+        // Bandwidth budget used up: drop this tick
+        if !crate::stream_adaptation::budget_allows_frame() {
+            continue;
+        }
+
+        // Another capture of this monitor is still running: drop this tick
+        let Some(guard) = crate::InFlightCapture::try_acquire(monitor_id) else {
+            continue;
+        };
+        let result = tokio::task::spawn_blocking(move || {
+            let _guard = guard;
+            crate::capture_stream_jpeg(monitor_id, profile, sampling_factor, chroma)
+        })
+        .await
+        .map_err(|e| format!("Capture task failed: {}", e))
+        .and_then(|frame| frame);
+
+        // Stopped while capturing: don't emit a frame from a stale loop
+        if *stop.borrow() {
+            break;
+        }
+
+        match result {
+            Ok(jpeg) => {
+                crate::stream_adaptation::record_stream_bytes(jpeg.len() as u64);
+                crate::session_recording::submit_frame(monitor_id, &jpeg);
+                let frame = CaptureFrame {
+                    monitor_id,
+                    sequence: tick,
+                    captured_at_ms: crate::unix_time_ms(),
+                    image: general_purpose::STANDARD.encode(&jpeg),
+                };
+                let _ = app.emit(CAPTURE_FRAME_EVENT, frame);
+            }
+            Err(e) => {
+                debug_eprintln!("[capture_loop] Monitor {} capture failed: {}", monitor_id, e);
+                let _ = app.emit(
+                    CAPTURE_ERROR_EVENT,
+                    serde_json::json!({ "monitorId": monitor_id, "error": e }),
+                );
+            }
+        }
+    }
+
+    debug_eprintln!("[capture_loop] Monitor {} loop stopped", monitor_id);
+}
+
+/// Start capturing a monitor at a fixed frame rate
+/// Frames arrive as `capture-frame` events: `{ monitorId, sequence, capturedAtMs, image }`;
+/// failed captures as `capture-loop-error`: `{ monitorId, error }`.
+/// Replaces any loop already running for the monitor.
+/// `fps`: 1-60; `profile`: "standard" (default), "high" or "extreme";
+/// `chroma`: see `jpeg_sampling_factor`
+#[tauri::command]
+pub fn start_capture_loop(
+    app: AppHandle,
+    monitor_id: usize,
+    fps: u32,
+    profile: Option<String>,
+    chroma: Option<String>,
+) -> Result<(), String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let profile = match profile.as_deref() {
+        None => "standard",
+        Some(name) => crate::stream_profile(name).ok_or_else(|| format!("Unknown stream profile: {}", name))?,
+    };
+    let (sampling_factor, chroma) = crate::jpeg_sampling_factor(chroma.as_deref())?;
+    let fps = fps.clamp(1, MAX_LOOP_FPS);
+    let period = Duration::from_micros(1_000_000 / fps as u64);
+
+    let mut loops = CAPTURE_LOOPS
+        .lock()
+        .map_err(|e| format!("Failed to lock capture loops: {}", e))?;
+    if let Some(index) = loops.iter().position(|(id, _)| *id == monitor_id) {
+        let (_, stop) = loops.swap_remove(index);
+        let _ = stop.send(true);
+    }
+
+    let (stop_tx, stop_rx) = watch::channel(false);
+    tauri::async_runtime::spawn(run_capture_loop(
+        app,
+        monitor_id,
+        period,
+        profile,
+        sampling_factor,
+        chroma,
+        stop_rx,
+    ));
+    loops.push((monitor_id, stop_tx));
+
+    debug_eprintln!("[capture_loop] Monitor {} loop started at {} fps ({})", monitor_id, fps, profile);
+    Ok(())
+}
+
+/// Stop the capture loop of a monitor, or all loops if `monitor_id` is None
+/// Returns the number of loops stopped
+#[tauri::command]
+pub fn stop_capture_loop(monitor_id: Option<usize>) -> Result<usize, String> {
+    let mut loops = CAPTURE_LOOPS
+        .lock()
+        .map_err(|e| format!("Failed to lock capture loops: {}", e))?;
+
+    let mut stopped = 0;
+    loops.retain(|(id, stop)| {
+        if monitor_id.map_or(true, |m| m == *id) {
+            let _ = stop.send(true);
+            stopped += 1;
+            false
+        } else {
+            true
+        }
+    });
+    Ok(stopped)
+}
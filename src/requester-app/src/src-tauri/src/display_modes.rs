@@ -0,0 +1,378 @@
+//! Display Modes Module
+//!
+//! Enumeration of a monitor's supported display modes (resolution /
+//! refresh rate / bit depth) and its active mode via `EnumDisplaySettingsW`.
+//! Helps spot a user stuck at 30Hz or at the wrong resolution. Complements
+//! `get_monitors` (same monitor indices).
+//!
+//! Mode changes (`ChangeDisplaySettingsExW`) are validated against the
+//! enumerated modes, and the original mode is kept for `revert_display_mode`.
+
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// A single display mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayMode {
+    pub width: u32,
+    pub height: u32,
+    /// Refresh rate in Hz (0 = unknown / hardware default)
+    pub refresh_rate: u32,
+    pub bits_per_pixel: u32,
+}
+
+/// Display modes for one monitor
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayModes {
+    pub monitor_id: usize,
+    /// GDI device name (e.g. `\\.\DISPLAY1`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    /// Display adapter description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adapter: Option<String>,
+    /// Currently active mode
+    pub current: DisplayMode,
+    /// Supported modes (sorted, de-duplicated)
+    pub modes: Vec<DisplayMode>,
+}
+
+/// A desktop-attached display device matched to a monitor index
+#[cfg(target_os = "windows")]
+struct DisplayDevice {
+    /// Null-terminated GDI device name
+    name: Vec<u16>,
+    adapter: String,
+    current: windows::Win32::Graphics::Gdi::DEVMODEW,
+}
+
+#[cfg(target_os = "windows")]
+impl DisplayDevice {
+    fn name_string(&self) -> String {
+        wide_to_string(&self.name)
+    }
+
+    fn name_pcwstr(&self) -> windows::core::PCWSTR {
+        windows::core::PCWSTR::from_raw(self.name.as_ptr())
+    }
+
+    /// Enumerate the device's supported modes (raw DEVMODEW, driver order)
+    fn enum_modes(&self) -> Vec<windows::Win32::Graphics::Gdi::DEVMODEW> {
+        use windows::Win32::Graphics::Gdi::{EnumDisplaySettingsW, DEVMODEW, ENUM_DISPLAY_SETTINGS_MODE};
+
+        let mut modes = Vec::new();
+        let mut mode_index = 0u32;
+        loop {
+            let mut devmode = DEVMODEW {
+                dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+                ..Default::default()
+            };
+            let found = unsafe {
+                EnumDisplaySettingsW(self.name_pcwstr(), ENUM_DISPLAY_SETTINGS_MODE(mode_index), &mut devmode)
+            };
+            if !found.as_bool() {
+                break;
+            }
+            modes.push(devmode);
+            mode_index += 1;
+        }
+        modes
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn wide_to_string(buffer: &[u16]) -> String {
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    String::from_utf16_lossy(&buffer[..len])
+}
+
+#[cfg(target_os = "windows")]
+fn to_mode(devmode: &windows::Win32::Graphics::Gdi::DEVMODEW) -> DisplayMode {
+    DisplayMode {
+        width: devmode.dmPelsWidth,
+        height: devmode.dmPelsHeight,
+        // 0 and 1 both mean "hardware default"
+        refresh_rate: if devmode.dmDisplayFrequency > 1 { devmode.dmDisplayFrequency } else { 0 },
+        bits_per_pixel: devmode.dmBitsPerPel,
+    }
+}
+
+/// Find the desktop-attached display device whose position matches the monitor
+#[cfg(target_os = "windows")]
+fn find_display_device(monitor_id: usize) -> Result<DisplayDevice, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{
+        EnumDisplayDevicesW, EnumDisplaySettingsW, DEVMODEW, DISPLAY_DEVICEW,
+        DISPLAY_DEVICE_ATTACHED_TO_DESKTOP, ENUM_CURRENT_SETTINGS,
+    };
+
+    let (x, y, _, _) = crate::get_monitor_rect(monitor_id)?;
+
+    unsafe {
+        let mut device_index = 0u32;
+        loop {
+            let mut device = DISPLAY_DEVICEW {
+                cb: std::mem::size_of::<DISPLAY_DEVICEW>() as u32,
+                ..Default::default()
+            };
+            if !EnumDisplayDevicesW(PCWSTR::null(), device_index, &mut device, 0).as_bool() {
+                return Err(format!("No display device found for monitor {}", monitor_id));
+            }
+            device_index += 1;
+
+            if device.StateFlags & DISPLAY_DEVICE_ATTACHED_TO_DESKTOP == 0 {
+                continue;
+            }
+
+            let mut current = DEVMODEW {
+                dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+                ..Default::default()
+            };
+            if !EnumDisplaySettingsW(PCWSTR::from_raw(device.DeviceName.as_ptr()), ENUM_CURRENT_SETTINGS, &mut current).as_bool() {
+                continue;
+            }
+
+            let position = current.Anonymous1.Anonymous2.dmPosition;
+            if position.x != x || position.y != y {
+                continue;
+            }
+
+            return Ok(DisplayDevice {
+                name: device.DeviceName.to_vec(),
+                adapter: wide_to_string(&device.DeviceString),
+                current,
+            });
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn query_display_modes(monitor_id: usize) -> Result<DisplayModes, String> {
+    let device = find_display_device(monitor_id)?;
+
+    let mut modes: Vec<DisplayMode> = device.enum_modes().iter().map(to_mode).collect();
+    modes.sort_unstable_by(|a, b| b.cmp(a));
+    modes.dedup();
+
+    let device_name = device.name_string();
+    debug_eprintln!("[display_modes] {} modes for {} (monitor {})", modes.len(), device_name, monitor_id);
+
+    Ok(DisplayModes {
+        monitor_id,
+        device_name: Some(device_name),
+        adapter: Some(device.adapter.clone()).filter(|s| !s.is_empty()),
+        current: to_mode(&device.current),
+        modes,
+    })
+}
+
+/// Non-Windows: only the current resolution is known
+#[cfg(not(target_os = "windows"))]
+fn query_display_modes(monitor_id: usize) -> Result<DisplayModes, String> {
+    let (_, _, width, height) = crate::get_monitor_rect(monitor_id)?;
+
+    Ok(DisplayModes {
+        monitor_id,
+        device_name: None,
+        adapter: None,
+        current: DisplayMode {
+            width: width as u32,
+            height: height as u32,
+            refresh_rate: 0,
+            bits_per_pixel: 0,
+        },
+        modes: Vec::new(),
+    })
+}
+
+/// Get the active and supported display modes of a monitor
+/// Returns JSON: `{ monitorId, deviceName?, adapter?, current, modes }`
+/// (modes are `{ width, height, refreshRate, bitsPerPixel }`, highest first)
+#[tauri::command]
+pub async fn get_display_modes(monitor_id: usize) -> Result<String, String> {
+    let modes = tokio::task::spawn_blocking(move || query_display_modes(monitor_id))
+        .await
+        .map_err(|e| format!("Display mode query task failed: {}", e))??;
+
+    serde_json::to_string(&modes)
+        .map_err(|e| format!("Failed to serialize display modes: {}", e))
+}
+
+// ============================================================================
+// Mode Changes
+// ============================================================================
+
+/// Modes in effect before the first `set_display_mode` call, keyed by device name
+/// Kept until `revert_display_mode` restores them.
+#[cfg(target_os = "windows")]
+static PREVIOUS_MODES: std::sync::Mutex<Vec<(String, DisplayMode)>> = std::sync::Mutex::new(Vec::new());
+
+/// Test and apply a mode on a device (CDS_TEST first, then CDS_UPDATEREGISTRY)
+#[cfg(target_os = "windows")]
+fn apply_mode(
+    device: &DisplayDevice,
+    mut devmode: windows::Win32::Graphics::Gdi::DEVMODEW,
+) -> Result<(), String> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{
+        ChangeDisplaySettingsExW, CDS_TEST, CDS_UPDATEREGISTRY, DISP_CHANGE_RESTART,
+        DISP_CHANGE_SUCCESSFUL, DM_BITSPERPEL, DM_DISPLAYFREQUENCY, DM_PELSHEIGHT, DM_PELSWIDTH,
+    };
+
+    devmode.dmFields = DM_PELSWIDTH | DM_PELSHEIGHT | DM_BITSPERPEL | DM_DISPLAYFREQUENCY;
+
+    unsafe {
+        let test = ChangeDisplaySettingsExW(device.name_pcwstr(), Some(&devmode as *const _), HWND::default(), CDS_TEST, None);
+        if test != DISP_CHANGE_SUCCESSFUL {
+            return Err(format!("Display mode rejected by driver (code {})", test.0));
+        }
+
+        match ChangeDisplaySettingsExW(device.name_pcwstr(), Some(&devmode as *const _), HWND::default(), CDS_UPDATEREGISTRY, None) {
+            result if result == DISP_CHANGE_SUCCESSFUL => Ok(()),
+            result if result == DISP_CHANGE_RESTART => Err("Display mode change requires a restart".to_string()),
+            result => Err(format!("Failed to change display mode (code {})", result.0)),
+        }
+    }
+}
+
+/// Find a supported mode; `refresh_hz` None picks the highest refresh rate
+/// Bit depth is kept at the current value.
+#[cfg(target_os = "windows")]
+fn find_supported_mode(
+    device: &DisplayDevice,
+    width: u32,
+    height: u32,
+    refresh_hz: Option<u32>,
+) -> Option<windows::Win32::Graphics::Gdi::DEVMODEW> {
+    let bits_per_pixel = device.current.dmBitsPerPel;
+    device
+        .enum_modes()
+        .into_iter()
+        .filter(|m| m.dmPelsWidth == width && m.dmPelsHeight == height && m.dmBitsPerPel == bits_per_pixel)
+        .filter(|m| refresh_hz.map_or(true, |hz| to_mode(m).refresh_rate == hz))
+        .max_by_key(|m| m.dmDisplayFrequency)
+}
+
+/// Change a monitor's resolution (and optionally refresh rate)
+/// The mode must be one reported by `get_display_modes`; the original mode is
+/// remembered so `revert_display_mode` can restore it.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn set_display_mode(
+    monitor_id: usize,
+    width: u32,
+    height: u32,
+    refresh_hz: Option<u32>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let device = find_display_device(monitor_id)?;
+
+        let devmode = find_supported_mode(&device, width, height, refresh_hz).ok_or_else(|| {
+            format!(
+                "Unsupported display mode {}x{}{} on monitor {}",
+                width,
+                height,
+                refresh_hz.map(|hz| format!(" @ {}Hz", hz)).unwrap_or_default(),
+                monitor_id
+            )
+        })?;
+
+        let previous = to_mode(&device.current);
+        apply_mode(&device, devmode)?;
+
+        let device_name = device.name_string();
+        let mut previous_modes = PREVIOUS_MODES
+            .lock()
+            .map_err(|e| format!("Failed to lock display mode state: {}", e))?;
+        // Keep the original mode across repeated changes
+        if !previous_modes.iter().any(|(name, _)| *name == device_name) {
+            previous_modes.push((device_name.clone(), previous));
+        }
+        crate::invalidate_monitor_rects();
+
+        let applied = to_mode(&devmode);
+        debug_eprintln!("[display_modes] {} set to {}x{} @ {}Hz", device_name, applied.width, applied.height, applied.refresh_rate);
+
+        serde_json::to_string(&applied)
+            .map_err(|e| format!("Failed to serialize display mode: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Display mode task failed: {}", e))?
+}
+
+/// Restore every display changed by `set_display_mode` to its original mode
+/// Returns the number of displays restored.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn revert_display_mode() -> Result<usize, String> {
+    tokio::task::spawn_blocking(|| {
+        let mut previous_modes = PREVIOUS_MODES
+            .lock()
+            .map_err(|e| format!("Failed to lock display mode state: {}", e))?;
+
+        let monitor_count = xcap::Monitor::all()
+            .map_err(|e| format!("Failed to get monitors: {}", e))?
+            .len();
+
+        let mut restored = 0;
+        let mut errors = Vec::new();
+        previous_modes.retain(|(device_name, mode)| {
+            let device = (0..monitor_count)
+                .filter_map(|id| find_display_device(id).ok())
+                .find(|d| d.name_string() == *device_name);
+
+            let Some(device) = device else {
+                errors.push(format!("{}: display not found", device_name));
+                return true;
+            };
+
+            let refresh = (mode.refresh_rate > 0).then_some(mode.refresh_rate);
+            let result = find_supported_mode(&device, mode.width, mode.height, refresh)
+                .ok_or_else(|| "original mode no longer supported".to_string())
+                .and_then(|devmode| apply_mode(&device, devmode));
+
+            match result {
+                Ok(()) => {
+                    restored += 1;
+                    false
+                }
+                Err(e) => {
+                    errors.push(format!("{}: {}", device_name, e));
+                    true
+                }
+            }
+        });
+        crate::invalidate_monitor_rects();
+
+        if errors.is_empty() {
+            Ok(restored)
+        } else {
+            Err(format!("Failed to restore display mode: {}", errors.join("; ")))
+        }
+    })
+    .await
+    .map_err(|e| format!("Display mode task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn set_display_mode(
+    _monitor_id: usize,
+    _width: u32,
+    _height: u32,
+    _refresh_hz: Option<u32>,
+) -> Result<String, String> {
+    Err("Changing the display mode is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn revert_display_mode() -> Result<usize, String> {
+    Err("Changing the display mode is only supported on Windows".to_string())
+}
@@ -0,0 +1,654 @@
+//! Elevated Broker Module
+//!
+//! Runs specific privileged operations elevated without elevating the whole UI
+//! (relaunching elevated would drop the remote session).
+//!
+//! Architecture:
+//! - `start_elevated_broker` relaunches this executable via `ShellExecuteExW`
+//!   with the `runas` verb and `--elevated-broker <pipe> <parent-pid>`
+//! - The broker serves newline-delimited JSON requests on a local named pipe
+//!   and exits when the parent process exits
+//! - `broker_exec(op, args)` sends one request per connection
+//!
+//! SECURITY:
+//! - Only the whitelisted operations in `BrokerOp` exist; arguments are
+//!   validated and tools are run by absolute System32 path
+//! - Service operations only act on the services in `BROKER_SERVICES`, so the
+//!   broker can't be used to stop Defender, the firewall or the event log
+//! - The broker only serves the parent process (GetNamedPipeClientProcessId)
+//!   and the app only talks to the broker it launched (GetNamedPipeServerProcessId)
+//! - Remote pipe clients are rejected
+
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use serde::Deserialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Command-line flag that starts this executable as the broker
+#[cfg(target_os = "windows")]
+const BROKER_ARG: &str = "--elevated-broker";
+
+/// Win32 error returned when the user declines the UAC prompt
+#[cfg(target_os = "windows")]
+const ERROR_CANCELLED: i32 = 1223;
+
+/// Win32 error returned while all pipe instances are busy
+#[cfg(target_os = "windows")]
+const ERROR_PIPE_BUSY: i32 = 231;
+
+/// How long to wait for a freshly launched broker to open its pipe
+#[cfg(target_os = "windows")]
+const BROKER_STARTUP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Upper bound for a single broker operation (gpupdate can be slow)
+#[cfg(target_os = "windows")]
+const BROKER_OP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Services the broker may start, stop or restart: the ones the app's
+/// remote-support fixes manage (print queue, Windows Update)
+#[cfg(target_os = "windows")]
+const BROKER_SERVICES: &[&str] = &["Spooler", "wuauserv", "BITS"];
+
+/// A request sent to the broker
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BrokerRequest {
+    op: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+/// The broker's reply to one request
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrokerResponse {
+    ok: bool,
+    #[serde(default)]
+    output: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Broker status returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerStatus {
+    pub running: bool,
+    /// Process ID of the elevated broker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+}
+
+/// Whitelisted broker operations
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BrokerOp {
+    /// Health check
+    Ping,
+    /// Stop the broker
+    Shutdown,
+    StartService(String),
+    StopService(String),
+    RestartService(String),
+    /// ipconfig /flushdns
+    FlushDns,
+    /// gpupdate /force
+    GroupPolicyUpdate,
+}
+
+#[cfg(target_os = "windows")]
+impl BrokerOp {
+    /// Validate an operation name and its arguments against the whitelist
+    fn parse(op: &str, args: &[String]) -> Result<Self, String> {
+        let no_args = |op: BrokerOp| {
+            if args.is_empty() {
+                Ok(op)
+            } else {
+                Err(format!("Operation {} takes no arguments", op_name(op)))
+            }
+        };
+        let service = || match args {
+            [name] if !is_valid_service_name(name) => Err(format!("Invalid service name: {}", name)),
+            [name] => BROKER_SERVICES
+                .iter()
+                .find(|allowed| allowed.eq_ignore_ascii_case(name))
+                .map(|allowed| allowed.to_string())
+                .ok_or_else(|| format!("Service not allowed: {}", name)),
+            _ => Err(format!("Operation {} takes exactly one service name", op)),
+        };
+
+        match op {
+            "ping" => no_args(BrokerOp::Ping),
+            "shutdown" => no_args(BrokerOp::Shutdown),
+            "start_service" => service().map(BrokerOp::StartService),
+            "stop_service" => service().map(BrokerOp::StopService),
+            "restart_service" => service().map(BrokerOp::RestartService),
+            "flush_dns" => no_args(BrokerOp::FlushDns),
+            "gpupdate" => no_args(BrokerOp::GroupPolicyUpdate),
+            _ => Err(format!("Operation not allowed: {}", op)),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn op_name(op: BrokerOp) -> &'static str {
+    match op {
+        BrokerOp::Ping => "ping",
+        BrokerOp::Shutdown => "shutdown",
+        BrokerOp::StartService(_) => "start_service",
+        BrokerOp::StopService(_) => "stop_service",
+        BrokerOp::RestartService(_) => "restart_service",
+        BrokerOp::FlushDns => "flush_dns",
+        BrokerOp::GroupPolicyUpdate => "gpupdate",
+    }
+}
+
+/// Service key names: letters, digits, '_', '-', '.' (no spaces or switches)
+#[cfg(target_os = "windows")]
+fn is_valid_service_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 256
+        && !name.starts_with(['-', '/'])
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+// ============================================================================
+// Broker process (elevated side)
+// ============================================================================
+
+/// Check if the current process was launched as the elevated broker
+#[cfg(target_os = "windows")]
+pub fn is_broker() -> bool {
+    std::env::args().nth(1).as_deref() == Some(BROKER_ARG)
+}
+
+/// Non-Windows: the broker is never used
+#[cfg(not(target_os = "windows"))]
+pub fn is_broker() -> bool {
+    false
+}
+
+/// Run a System32 tool without a console window and capture its output
+#[cfg(target_os = "windows")]
+pub(crate) fn run_system_tool(tool: &str, args: &[&str]) -> Result<String, String> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // SECURITY: absolute path so an elevated process never resolves tools via PATH
+    let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let path = std::path::Path::new(&system_root).join("System32").join(tool);
+
+    let output = std::process::Command::new(&path)
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", tool, e))?;
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .trim()
+    .to_string();
+
+    if output.status.success() {
+        Ok(text)
+    } else {
+        Err(format!("{} failed ({}): {}", tool, output.status, text))
+    }
+}
+
+/// Execute a validated operation in the broker
+#[cfg(target_os = "windows")]
+fn execute_op(op: &BrokerOp) -> Result<String, String> {
+    match op {
+        BrokerOp::Ping | BrokerOp::Shutdown => Ok(String::new()),
+        BrokerOp::StartService(name) => run_system_tool("net.exe", &["start", name]),
+        BrokerOp::StopService(name) => run_system_tool("net.exe", &["stop", name]),
+        BrokerOp::RestartService(name) => {
+            // `net stop` fails if already stopped; start regardless
+            let stopped = run_system_tool("net.exe", &["stop", name]).unwrap_or_default();
+            let started = run_system_tool("net.exe", &["start", name])?;
+            Ok(format!("{}\n{}", stopped, started).trim().to_string())
+        }
+        BrokerOp::FlushDns => run_system_tool("ipconfig.exe", &["/flushdns"]),
+        BrokerOp::GroupPolicyUpdate => run_system_tool("gpupdate.exe", &["/force"]),
+    }
+}
+
+/// Create a pipe instance the non-elevated parent can open
+///
+/// The default DACL of a pipe created by an elevated process only gives the
+/// parent's (filtered) token read access, so grant interactive users
+/// read/write at medium integrity. The client PID check does the actual
+/// authentication.
+#[cfg(target_os = "windows")]
+fn create_pipe_instance(
+    pipe_name: &str,
+    first: bool,
+) -> Result<tokio::net::windows::named_pipe::NamedPipeServer, String> {
+    use std::sync::OnceLock;
+    use windows::core::PCWSTR;
+    use windows::Win32::Security::Authorization::ConvertStringSecurityDescriptorToSecurityDescriptorW;
+    use windows::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+
+    // Kept for the lifetime of the broker process
+    static SECURITY_DESCRIPTOR: OnceLock<usize> = OnceLock::new();
+
+    let descriptor = match SECURITY_DESCRIPTOR.get() {
+        Some(sd) => *sd,
+        None => {
+            let sddl: Vec<u16> = "D:P(A;;GA;;;SY)(A;;GA;;;BA)(A;;GRGW;;;IU)S:(ML;;NW;;;ME)"
+                .encode_utf16()
+                .chain(std::iter::once(0))
+                .collect();
+            let mut sd = PSECURITY_DESCRIPTOR::default();
+            unsafe {
+                ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                    PCWSTR(sddl.as_ptr()),
+                    1, // SDDL_REVISION_1
+                    &mut sd,
+                    None,
+                )
+                .map_err(|e| format!("Failed to build pipe security descriptor: {}", e))?;
+            }
+            *SECURITY_DESCRIPTOR.get_or_init(|| sd.0 as usize)
+        }
+    };
+
+    let mut attributes = SECURITY_ATTRIBUTES {
+        nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+        lpSecurityDescriptor: descriptor as *mut std::ffi::c_void,
+        bInheritHandle: false.into(),
+    };
+
+    unsafe {
+        tokio::net::windows::named_pipe::ServerOptions::new()
+            .first_pipe_instance(first)
+            .reject_remote_clients(true)
+            .create_with_security_attributes_raw(
+                pipe_name,
+                &mut attributes as *mut SECURITY_ATTRIBUTES as *mut std::ffi::c_void,
+            )
+            .map_err(|e| format!("Failed to create broker pipe: {}", e))
+    }
+}
+
+/// Serve one connected client: verify it is the parent, then answer requests
+#[cfg(target_os = "windows")]
+async fn serve_broker_client(
+    pipe: tokio::net::windows::named_pipe::NamedPipeServer,
+    parent_pid: u32,
+) {
+    use std::os::windows::io::AsRawHandle;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Pipes::GetNamedPipeClientProcessId;
+
+    let mut client_pid = 0u32;
+    let handle = HANDLE(pipe.as_raw_handle() as *mut std::ffi::c_void);
+    if unsafe { GetNamedPipeClientProcessId(handle, &mut client_pid) }.is_err() || client_pid != parent_pid {
+        debug_eprintln!("[broker] SECURITY: Rejected client pid {} (expected {})", client_pid, parent_pid);
+        return;
+    }
+
+    let (reader, mut writer) = tokio::io::split(pipe);
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let parsed = serde_json::from_str::<BrokerRequest>(&line)
+            .map_err(|e| format!("Invalid broker request: {}", e))
+            .and_then(|request| BrokerOp::parse(&request.op, &request.args));
+
+        let shutdown = matches!(parsed, Ok(BrokerOp::Shutdown));
+        let result = match parsed {
+            Ok(op) => {
+                debug_eprintln!("[broker] Executing {:?}", op);
+                tokio::task::spawn_blocking(move || execute_op(&op))
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Broker task failed: {}", e)))
+            }
+            Err(e) => Err(e),
+        };
+
+        let response = match result {
+            Ok(output) => BrokerResponse { ok: true, output, error: None },
+            Err(e) => BrokerResponse { ok: false, output: String::new(), error: Some(e) },
+        };
+        let mut reply = serde_json::to_string(&response).unwrap_or_default();
+        reply.push('\n');
+        if writer.write_all(reply.as_bytes()).await.is_err() {
+            break;
+        }
+        let _ = writer.flush().await;
+
+        if shutdown {
+            debug_eprintln!("[broker] Shutdown requested");
+            std::process::exit(0);
+        }
+    }
+}
+
+/// Exit the broker as soon as the parent process exits
+#[cfg(target_os = "windows")]
+fn exit_with_parent(parent_pid: u32) {
+    use windows::Win32::System::Threading::{OpenProcess, WaitForSingleObject, INFINITE, PROCESS_SYNCHRONIZE};
+
+    std::thread::spawn(move || unsafe {
+        match OpenProcess(PROCESS_SYNCHRONIZE, false, parent_pid) {
+            Ok(handle) => {
+                WaitForSingleObject(handle, INFINITE);
+            }
+            Err(_e) => debug_eprintln!("[broker] Parent {} not found: {}", parent_pid, _e),
+        }
+        std::process::exit(0);
+    });
+}
+
+/// Run the broker loop (called from `run()` when `is_broker()`)
+#[cfg(target_os = "windows")]
+pub fn run_broker() {
+    let args: Vec<String> = std::env::args().collect();
+    let (Some(pipe_name), Some(parent_pid)) = (
+        args.get(2).filter(|name| name.starts_with("\\\\.\\pipe\\")),
+        args.get(3).and_then(|pid| pid.parse::<u32>().ok()),
+    ) else {
+        eprintln!("[broker] Usage: {} <pipe-name> <parent-pid>", BROKER_ARG);
+        return;
+    };
+
+    exit_with_parent(parent_pid);
+
+    let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime for broker");
+    let result: Result<(), String> = rt.block_on(async {
+        let mut server = create_pipe_instance(pipe_name, true)?;
+        debug_eprintln!("[broker] Serving {} for parent {}", pipe_name, parent_pid);
+
+        loop {
+            server
+                .connect()
+                .await
+                .map_err(|e| format!("Broker pipe connect failed: {}", e))?;
+            // Open the next instance before handing this one off
+            let connected = std::mem::replace(&mut server, create_pipe_instance(pipe_name, false)?);
+            tokio::spawn(serve_broker_client(connected, parent_pid));
+        }
+    });
+
+    if let Err(_e) = result {
+        debug_eprintln!("[broker] Stopped: {}", _e);
+    }
+}
+
+/// Non-Windows: nothing to run
+#[cfg(not(target_os = "windows"))]
+pub fn run_broker() {}
+
+// ============================================================================
+// Client side (main app)
+// ============================================================================
+
+/// Pipe name and process ID of the broker launched by this app
+#[cfg(target_os = "windows")]
+#[derive(Clone)]
+struct BrokerHandle {
+    pipe_name: String,
+    pid: u32,
+}
+
+#[cfg(target_os = "windows")]
+static BROKER: tokio::sync::Mutex<Option<BrokerHandle>> = tokio::sync::Mutex::const_new(None);
+
+/// Send one request to the broker and wait for its response
+#[cfg(target_os = "windows")]
+async fn send_request(broker: &BrokerHandle, request: &BrokerRequest) -> Result<BrokerResponse, String> {
+    use std::os::windows::io::AsRawHandle;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::windows::named_pipe::ClientOptions;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Pipes::GetNamedPipeServerProcessId;
+
+    let client = loop {
+        match ClientOptions::new().open(&broker.pipe_name) {
+            Ok(client) => break client,
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+            Err(e) => return Err(format!("Failed to connect to broker: {}", e)),
+        }
+    };
+
+    // SECURITY: Only talk to the broker process we launched
+    let mut server_pid = 0u32;
+    let handle = HANDLE(client.as_raw_handle() as *mut std::ffi::c_void);
+    if unsafe { GetNamedPipeServerProcessId(handle, &mut server_pid) }.is_err() || server_pid != broker.pid {
+        return Err(format!(
+            "Security error: broker pipe is served by pid {} (expected {})",
+            server_pid, broker.pid
+        ));
+    }
+
+    let (reader, mut writer) = tokio::io::split(client);
+    let mut line = serde_json::to_string(request).map_err(|e| format!("Failed to serialize request: {}", e))?;
+    line.push('\n');
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to send broker request: {}", e))?;
+
+    let reply = tokio::time::timeout(BROKER_OP_TIMEOUT, BufReader::new(reader).lines().next_line())
+        .await
+        .map_err(|_| format!("Broker operation {} timed out", request.op))?
+        .map_err(|e| format!("Failed to read broker response: {}", e))?
+        .ok_or_else(|| "Broker closed the connection".to_string())?;
+
+    serde_json::from_str(&reply).map_err(|e| format!("Invalid broker response: {}", e))
+}
+
+/// Launch this executable elevated as the broker; returns its process ID
+/// Blocks until the user answers the UAC prompt.
+#[cfg(target_os = "windows")]
+fn launch_broker(pipe_name: &str) -> Result<u32, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::GetProcessId;
+    use windows::Win32::UI::Shell::{
+        ShellExecuteExW, SEE_MASK_NOASYNC, SEE_MASK_NOCLOSEPROCESS, SHELLEXECUTEINFOW,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+
+    let exe_path = std::env::current_exe()
+        .map_err(|e| format!("Failed to get executable path: {}", e))?;
+
+    let to_wide = |s: &str| -> Vec<u16> { s.encode_utf16().chain(std::iter::once(0)).collect() };
+    let verb = to_wide("runas");
+    let file = to_wide(&exe_path.to_string_lossy());
+    let parameters = to_wide(&format!("{} {} {}", BROKER_ARG, pipe_name, std::process::id()));
+
+    let mut info = SHELLEXECUTEINFOW {
+        cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+        fMask: SEE_MASK_NOCLOSEPROCESS | SEE_MASK_NOASYNC,
+        lpVerb: PCWSTR(verb.as_ptr()),
+        lpFile: PCWSTR(file.as_ptr()),
+        lpParameters: PCWSTR(parameters.as_ptr()),
+        nShow: SW_HIDE.0,
+        ..Default::default()
+    };
+
+    unsafe {
+        if let Err(e) = ShellExecuteExW(&mut info) {
+            if e.code() == windows::core::HRESULT::from_win32(ERROR_CANCELLED as u32) {
+                return Err("Elevation was declined".to_string());
+            }
+            return Err(format!("Failed to launch elevated broker: {}", e));
+        }
+
+        if info.hProcess.is_invalid() {
+            return Err("Elevated broker did not start".to_string());
+        }
+        let pid = GetProcessId(info.hProcess);
+        let _ = CloseHandle(info.hProcess);
+        Ok(pid)
+    }
+}
+
+/// Start the elevated broker (shows a UAC prompt; no-op if already running)
+/// Returns `{ running, pid }`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn start_elevated_broker() -> Result<BrokerStatus, String> {
+    let mut broker = BROKER.lock().await;
+
+    if let Some(existing) = broker.as_ref() {
+        let ping = BrokerRequest { op: "ping".to_string(), args: Vec::new() };
+        if send_request(existing, &ping).await.map(|r| r.ok).unwrap_or(false) {
+            return Ok(BrokerStatus { running: true, pid: Some(existing.pid) });
+        }
+        debug_eprintln!("[broker] Previous broker is gone, relaunching");
+        *broker = None;
+    }
+
+    let pipe_name = format!(
+        "\\\\.\\pipe\\supportcenter-broker-{}-{}",
+        std::process::id(),
+        uuid::Uuid::new_v4().simple()
+    );
+
+    let launch_name = pipe_name.clone();
+    let pid = tokio::task::spawn_blocking(move || launch_broker(&launch_name))
+        .await
+        .map_err(|e| format!("Broker launch task failed: {}", e))??;
+
+    // Wait for the broker to open its pipe
+    let handle = BrokerHandle { pipe_name, pid };
+    let ping = BrokerRequest { op: "ping".to_string(), args: Vec::new() };
+    let started = std::time::Instant::now();
+    loop {
+        match send_request(&handle, &ping).await {
+            Ok(_) => break,
+            Err(e) if started.elapsed() >= BROKER_STARTUP_TIMEOUT => {
+                return Err(format!("Elevated broker did not respond: {}", e));
+            }
+            Err(_) => tokio::time::sleep(std::time::Duration::from_millis(100)).await,
+        }
+    }
+
+    debug_eprintln!("[broker] Elevated broker running (pid {})", pid);
+    *broker = Some(handle);
+    Ok(BrokerStatus { running: true, pid: Some(pid) })
+}
+
+/// Run a whitelisted operation in the elevated broker
+///
+/// Operations: `ping`, `start_service`/`stop_service`/`restart_service` (args:
+/// `[serviceName]`, one of `BROKER_SERVICES`), `flush_dns`, `gpupdate`.
+/// Returns the tool output.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn broker_exec(op: String, args: Option<Vec<String>>) -> Result<String, String> {
+    let args = args.unwrap_or_default();
+
+    // Validate locally too, so bad input never reaches the elevated process
+    let parsed = BrokerOp::parse(&op, &args)?;
+    if parsed == BrokerOp::Shutdown {
+        return Err("Use stop_elevated_broker to stop the broker".to_string());
+    }
+
+    let broker = BROKER
+        .lock()
+        .await
+        .clone()
+        .ok_or_else(|| "Elevated broker is not running".to_string())?;
+
+    let response = send_request(&broker, &BrokerRequest { op, args }).await?;
+    if response.ok {
+        Ok(response.output)
+    } else {
+        Err(response.error.unwrap_or_else(|| "Broker operation failed".to_string()))
+    }
+}
+
+/// Stop the elevated broker if running
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn stop_elevated_broker() -> Result<(), String> {
+    if let Some(broker) = BROKER.lock().await.take() {
+        let shutdown = BrokerRequest { op: "shutdown".to_string(), args: Vec::new() };
+        // The broker exits right after replying; a dropped connection is fine
+        let _ = send_request(&broker, &shutdown).await;
+    }
+    Ok(())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn start_elevated_broker() -> Result<BrokerStatus, String> {
+    Err("Elevated broker is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn broker_exec(_op: String, _args: Option<Vec<String>>) -> Result<String, String> {
+    Err("Elevated broker is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn stop_elevated_broker() -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_is_valid_service_name() {
+        assert!(is_valid_service_name("Spooler"));
+        assert!(is_valid_service_name("MSSQL.Instance_1-a"));
+        assert!(!is_valid_service_name(""));
+        assert!(!is_valid_service_name("/y"));
+        assert!(!is_valid_service_name("-x"));
+        assert!(!is_valid_service_name("Spooler & calc"));
+        assert!(!is_valid_service_name(&"a".repeat(257)));
+    }
+
+    #[test]
+    fn test_parse_operations() {
+        assert_eq!(BrokerOp::parse("ping", &[]), Ok(BrokerOp::Ping));
+        assert_eq!(BrokerOp::parse("flush_dns", &[]), Ok(BrokerOp::FlushDns));
+        assert!(BrokerOp::parse("ping", &args(&["extra"])).is_err());
+        assert!(BrokerOp::parse("reg_add", &[]).is_err());
+    }
+
+    #[test]
+    fn test_parse_service_allow_list() {
+        // Canonical name regardless of the caller's casing
+        assert_eq!(
+            BrokerOp::parse("restart_service", &args(&["spooler"])),
+            Ok(BrokerOp::RestartService("Spooler".to_string()))
+        );
+        assert!(BrokerOp::parse("start_service", &args(&["wuauserv"])).is_ok());
+
+        // Security services are never reachable through the broker
+        for name in ["WinDefend", "mpssvc", "EventLog"] {
+            assert!(BrokerOp::parse("stop_service", &args(&[name])).is_err());
+        }
+        assert!(BrokerOp::parse("stop_service", &args(&["Spooler /y"])).is_err());
+        assert!(BrokerOp::parse("stop_service", &[]).is_err());
+        assert!(BrokerOp::parse("stop_service", &args(&["Spooler", "BITS"])).is_err());
+    }
+}
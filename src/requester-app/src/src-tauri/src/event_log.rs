@@ -0,0 +1,243 @@
+//! Windows Event Log Query Module
+//!
+//! Pulls recent entries from the Application/System event logs via the
+//! Windows Event Log API (EvtQuery/EvtNext/EvtRender) so a technician can see
+//! "what errored in the last N minutes" without opening Event Viewer.
+//!
+//! Read-only. Results are capped at MAX_EVENTS to keep payloads small.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Channels that may be queried (Security requires elevation and is excluded)
+#[cfg(target_os = "windows")]
+const ALLOWED_CHANNELS: &[&str] = &["Application", "System", "Setup"];
+
+/// Maximum number of events returned per query
+#[cfg(target_os = "windows")]
+const MAX_EVENTS: usize = 200;
+
+/// Maximum look-back window (24 hours)
+#[cfg(target_os = "windows")]
+const MAX_SINCE_MINUTES: u32 = 24 * 60;
+
+/// A single event log entry
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventLogEntry {
+    pub provider: String,
+    pub event_id: u32,
+    pub level: u32,
+    pub level_name: String,
+    pub time_created: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Map a level name to the highest (least severe) numeric level to include
+///
+/// Event levels: 1=Critical, 2=Error, 3=Warning, 4=Information, 5=Verbose.
+/// A level filter includes that level and everything more severe.
+#[cfg(target_os = "windows")]
+fn max_level_for(level: Option<&str>) -> Result<u32, String> {
+    match level.map(|l| l.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("warning") => Ok(3),
+        Some("critical") => Ok(1),
+        Some("error") => Ok(2),
+        Some("information") | Some("info") => Ok(4),
+        Some("verbose") => Ok(5),
+        Some(other) => Err(format!("Unknown event level: {}", other)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn level_name(level: u32) -> &'static str {
+    match level {
+        0 | 4 => "Information",
+        1 => "Critical",
+        2 => "Error",
+        3 => "Warning",
+        5 => "Verbose",
+        _ => "Unknown",
+    }
+}
+
+/// Extract an attribute value from the first occurrence of `<tag ... attr='value'`
+#[cfg(target_os = "windows")]
+fn xml_attr(xml: &str, tag: &str, attr: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}", tag))?;
+    let element = &xml[start..];
+    let element = &element[..element.find('>')?];
+    let attr_start = element.find(&format!("{}=", attr))? + attr.len() + 1;
+    let quote = element[attr_start..].chars().next()?;
+    let value_start = attr_start + 1;
+    let value_end = value_start + element[value_start..].find(quote)?;
+    Some(element[value_start..value_end].to_string())
+}
+
+/// Extract the text content of the first `<tag>...</tag>` element
+#[cfg(target_os = "windows")]
+fn xml_text(xml: &str, tag: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}", tag))?;
+    let content_start = start + xml[start..].find('>')? + 1;
+    let content_end = content_start + xml[content_start..].find(&format!("</{}>", tag))?;
+    Some(xml[content_start..content_end].to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn query_events(channel: &str, since_minutes: u32, max_level: u32) -> Result<Vec<EventLogEntry>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::EventLog::{
+        EvtClose, EvtFormatMessage, EvtFormatMessageEvent, EvtNext, EvtOpenPublisherMetadata,
+        EvtQuery, EvtQueryChannelPath, EvtQueryReverseDirection, EvtRender, EvtRenderEventXml,
+        EVT_HANDLE,
+    };
+
+    fn to_wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Render an event handle to its XML representation
+    unsafe fn render_xml(event: EVT_HANDLE) -> Option<String> {
+        let mut used = 0u32;
+        let mut count = 0u32;
+        // First call sizes the buffer
+        let _ = EvtRender(None, event, EvtRenderEventXml.0, 0, None, &mut used, &mut count);
+        if used == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; (used as usize / 2) + 1];
+        EvtRender(
+            None,
+            event,
+            EvtRenderEventXml.0,
+            (buffer.len() * 2) as u32,
+            Some(buffer.as_mut_ptr() as *mut _),
+            &mut used,
+            &mut count,
+        )
+        .ok()?;
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+
+    /// Format the human-readable event message using the provider's metadata
+    unsafe fn format_message(provider: &str, event: EVT_HANDLE) -> Option<String> {
+        let provider_wide = to_wide(provider);
+        let metadata = EvtOpenPublisherMetadata(None, PCWSTR::from_raw(provider_wide.as_ptr()), None, 0, 0).ok()?;
+
+        let mut used = 0u32;
+        let _ = EvtFormatMessage(metadata, event, 0, None, EvtFormatMessageEvent.0, None, &mut used);
+        let message = if used > 0 {
+            let mut buffer = vec![0u16; used as usize];
+            EvtFormatMessage(metadata, event, 0, None, EvtFormatMessageEvent.0, Some(&mut buffer), &mut used)
+                .ok()
+                .map(|_| {
+                    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+                    String::from_utf16_lossy(&buffer[..len]).trim().to_string()
+                })
+        } else {
+            None
+        };
+
+        let _ = EvtClose(metadata);
+        message.filter(|m| !m.is_empty())
+    }
+
+    let level_filter = (1..=max_level)
+        .map(|l| format!("Level={}", l))
+        .collect::<Vec<_>>()
+        .join(" or ");
+    let xpath = format!(
+        "*[System[({}) and TimeCreated[timediff(@SystemTime) <= {}]]]",
+        level_filter,
+        since_minutes as u64 * 60 * 1000
+    );
+
+    let channel_wide = to_wide(channel);
+    let xpath_wide = to_wide(&xpath);
+
+    unsafe {
+        let query = EvtQuery(
+            None,
+            PCWSTR::from_raw(channel_wide.as_ptr()),
+            PCWSTR::from_raw(xpath_wide.as_ptr()),
+            EvtQueryChannelPath.0 | EvtQueryReverseDirection.0,
+        )
+        .map_err(|e| format!("Failed to query event log '{}': {}", channel, e))?;
+
+        let mut entries = Vec::new();
+        let mut handles = [0isize; 32];
+
+        while entries.len() < MAX_EVENTS {
+            let mut returned = 0u32;
+            // ERROR_NO_MORE_ITEMS ends the loop
+            if EvtNext(query, &mut handles, 1000, 0, &mut returned).is_err() || returned == 0 {
+                break;
+            }
+
+            for raw in &handles[..returned as usize] {
+                let event = EVT_HANDLE(*raw);
+                if entries.len() < MAX_EVENTS {
+                    if let Some(xml) = render_xml(event) {
+                        let provider = xml_attr(&xml, "Provider", "Name").unwrap_or_default();
+                        let level = xml_text(&xml, "Level").and_then(|l| l.parse().ok()).unwrap_or(0);
+                        entries.push(EventLogEntry {
+                            event_id: xml_text(&xml, "EventID").and_then(|id| id.parse().ok()).unwrap_or(0),
+                            level,
+                            level_name: level_name(level).to_string(),
+                            time_created: xml_attr(&xml, "TimeCreated", "SystemTime").unwrap_or_default(),
+                            message: format_message(&provider, event),
+                            provider,
+                        });
+                    }
+                }
+                // Always close every handle in the batch, even past the limit
+                let _ = EvtClose(event);
+            }
+        }
+
+        let _ = EvtClose(query);
+        debug_eprintln!("[event_log] {} entries from {} (last {} min)", entries.len(), channel, since_minutes);
+        Ok(entries)
+    }
+}
+
+/// Query recent Windows Event Log entries
+///
+/// Arguments:
+/// - `channel`: "Application", "System" or "Setup"
+/// - `since_minutes`: look-back window (max 24h)
+/// - `level`: "critical", "error", "warning" (default), "information" or "verbose";
+///   includes that level and everything more severe
+///
+/// Returns a JSON array (newest first, at most 200 entries).
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn query_event_log(channel: String, since_minutes: u32, level: Option<String>) -> Result<String, String> {
+    let channel = ALLOWED_CHANNELS
+        .iter()
+        .find(|c| c.eq_ignore_ascii_case(channel.trim()))
+        .map(|c| c.to_string())
+        .ok_or_else(|| format!("Unsupported event log channel: {} (allowed: {:?})", channel, ALLOWED_CHANNELS))?;
+    let since_minutes = since_minutes.clamp(1, MAX_SINCE_MINUTES);
+    let max_level = max_level_for(level.as_deref())?;
+
+    let entries = tokio::task::spawn_blocking(move || query_events(&channel, since_minutes, max_level))
+        .await
+        .map_err(|e| format!("Event log query task failed: {}", e))??;
+
+    serde_json::to_string(&entries)
+        .map_err(|e| format!("Failed to serialize event log entries: {}", e))
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn query_event_log(_channel: String, _since_minutes: u32, _level: Option<String>) -> Result<String, String> {
+    Err("Event log query is only supported on Windows".to_string())
+}
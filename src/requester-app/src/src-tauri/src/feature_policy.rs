@@ -0,0 +1,190 @@
+//! Feature Policy Module
+//!
+//! Lets IT admins lock features down per deployment, e.g. no remote input for
+//! a read-only support tier or no screen capture in a high-security area.
+//! The policy is machine-wide and read once at startup from:
+//! - `HKLM\SOFTWARE\Policies\SupportCenter\Requester`: DWORD values
+//!   `RemoteInput` / `ScreenCapture`, 0 disables (what GPO deploys)
+//! - `%ProgramData%\SupportCenter\policy.json`: `{ "remoteInput": false }`
+//!
+//! A registry value wins over the file for the same feature. Both locations
+//! need admin rights to change and nothing per-user is read, so the user
+//! can't re-enable a feature. Gated commands fail with `DisabledByPolicy`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::debug_eprintln;
+
+/// Error prefix returned by commands whose feature is disabled by policy
+pub const DISABLED_BY_POLICY_ERROR: &str = "DisabledByPolicy";
+
+/// Policy key under HKLM
+#[cfg(target_os = "windows")]
+const POLICY_REGISTRY_KEY: &str = r"SOFTWARE\Policies\SupportCenter\Requester";
+
+/// Features an admin can disable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// Technician control: `remote_*` commands
+    RemoteInput,
+    /// Screenshots, streams, recordings: `capture_*` and friends
+    ScreenCapture,
+}
+
+impl Feature {
+    const ALL: [Feature; 2] = [Feature::RemoteInput, Feature::ScreenCapture];
+
+    /// Key in policy.json and in the frontend
+    fn key(self) -> &'static str {
+        match self {
+            Feature::RemoteInput => "remoteInput",
+            Feature::ScreenCapture => "screenCapture",
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn registry_value(self) -> &'static str {
+        match self {
+            Feature::RemoteInput => "RemoteInput",
+            Feature::ScreenCapture => "ScreenCapture",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Feature::RemoteInput => "Remote control",
+            Feature::ScreenCapture => "Screen capture",
+        }
+    }
+}
+
+/// Effective feature policy
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeaturePolicy {
+    pub remote_input: bool,
+    pub screen_capture: bool,
+    /// Where each feature's setting came from ("registry" or "file");
+    /// features left at the default (enabled) are absent
+    pub sources: BTreeMap<&'static str, &'static str>,
+}
+
+impl Default for FeaturePolicy {
+    fn default() -> Self {
+        Self {
+            remote_input: true,
+            screen_capture: true,
+            sources: BTreeMap::new(),
+        }
+    }
+}
+
+impl FeaturePolicy {
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::RemoteInput => self.remote_input,
+            Feature::ScreenCapture => self.screen_capture,
+        }
+    }
+
+    fn set(&mut self, feature: Feature, enabled: bool, source: &'static str) {
+        match feature {
+            Feature::RemoteInput => self.remote_input = enabled,
+            Feature::ScreenCapture => self.screen_capture = enabled,
+        }
+        self.sources.insert(feature.key(), source);
+    }
+}
+
+static POLICY: OnceLock<FeaturePolicy> = OnceLock::new();
+
+/// Location of the policy file
+fn policy_file_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let program_data = std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".to_string());
+        PathBuf::from(program_data).join("SupportCenter").join("policy.json")
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        PathBuf::from("/etc/support-center/policy.json")
+    }
+}
+
+/// Read the policy from the file, then the registry (blocking)
+fn load_policy() -> FeaturePolicy {
+    let mut policy = FeaturePolicy::default();
+
+    let path = policy_file_path();
+    match std::fs::read_to_string(&path) {
+        Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(file) => {
+                for feature in Feature::ALL {
+                    if let Some(enabled) = file.get(feature.key()).and_then(|v| v.as_bool()) {
+                        policy.set(feature, enabled, "file");
+                    }
+                }
+            }
+            Err(_e) => {
+                debug_eprintln!("[feature_policy] Ignoring invalid policy file {:?}: {}", path, _e);
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(_e) => {
+            debug_eprintln!("[feature_policy] Failed to read policy file {:?}: {}", path, _e);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::Registry::HKEY_LOCAL_MACHINE;
+
+        for feature in Feature::ALL {
+            let value = crate::notification_check::registry_dword(
+                HKEY_LOCAL_MACHINE,
+                POLICY_REGISTRY_KEY,
+                feature.registry_value(),
+            );
+            if let Some(value) = value {
+                policy.set(feature, value != 0, "registry");
+            }
+        }
+    }
+
+    debug_eprintln!(
+        "[feature_policy] remoteInput={} screenCapture={} sources={:?}",
+        policy.remote_input,
+        policy.screen_capture,
+        policy.sources
+    );
+    policy
+}
+
+/// The policy, loaded on first use (called at startup so it's read once)
+pub(crate) fn policy() -> &'static FeaturePolicy {
+    POLICY.get_or_init(load_policy)
+}
+
+/// Gate for sensitive commands: Ok unless the feature is disabled by policy
+pub(crate) fn require(feature: Feature) -> Result<(), String> {
+    if policy().is_enabled(feature) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: {} is disabled by your administrator",
+            DISABLED_BY_POLICY_ERROR,
+            feature.description()
+        ))
+    }
+}
+
+/// Get the feature policy so the frontend can hide disabled features
+/// Returns `{ remoteInput, screenCapture, sources: { <feature>: "registry" | "file" } }`.
+#[tauri::command]
+pub fn get_feature_policy() -> Result<FeaturePolicy, String> {
+    Ok(policy().clone())
+}
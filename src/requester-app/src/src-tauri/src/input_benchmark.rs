@@ -0,0 +1,265 @@
+//! Input Benchmark Module
+//!
+//! Quantifies "remote control feels laggy" on the controlled machine itself:
+//! injects no-op mouse moves (to the current cursor position) and measures
+//! how long each `SendInput` call takes and how long the event needs to reach
+//! a low-level mouse hook. Slow numbers here point at the machine's input
+//! stack (hooks from other software, a busy session) rather than the network
+//! or the capture pipeline.
+//!
+//! The benchmark's own events are marked and swallowed by the hook, so
+//! applications never see them.
+
+#[cfg(target_os = "windows")]
+use std::sync::mpsc;
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+#[cfg(target_os = "windows")]
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Most iterations accepted for one run
+const MAX_ITERATIONS: u32 = 5000;
+
+/// `dwExtraInfo` marking the benchmark's injected events
+#[cfg(target_os = "windows")]
+const BENCHMARK_MARKER: usize = 0x5343_4245; // "SCBE"
+
+/// How long to wait for an event to reach the hook before counting it as lost
+#[cfg(target_os = "windows")]
+const DELIVERY_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Receives the arrival time of marked events while a benchmark runs
+#[cfg(target_os = "windows")]
+static HOOK_ARRIVALS: Mutex<Option<mpsc::Sender<Instant>>> = Mutex::new(None);
+
+/// min/avg/max/p99 of a set of samples in microseconds
+#[cfg(target_os = "windows")]
+fn summarize(mut samples: Vec<u64>) -> serde_json::Value {
+    if samples.is_empty() {
+        return serde_json::Value::Null;
+    }
+    samples.sort_unstable();
+    let p99_index = (samples.len() * 99).div_ceil(100) - 1;
+    serde_json::json!({
+        "samples": samples.len(),
+        "minUs": samples[0],
+        "avgUs": samples.iter().sum::<u64>() / samples.len() as u64,
+        "maxUs": samples[samples.len() - 1],
+        "p99Us": samples[p99_index],
+    })
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn benchmark_hook(
+    code: i32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::LRESULT;
+    use windows::Win32::UI::WindowsAndMessaging::{CallNextHookEx, HC_ACTION, LLMHF_INJECTED, MSLLHOOKSTRUCT};
+
+    if code == HC_ACTION as i32 {
+        let event = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        if event.flags & LLMHF_INJECTED != 0 && event.dwExtraInfo == BENCHMARK_MARKER {
+            let arrived = Instant::now();
+            if let Ok(arrivals) = HOOK_ARRIVALS.lock() {
+                if let Some(tx) = arrivals.as_ref() {
+                    let _ = tx.send(arrived);
+                }
+            }
+            // Swallow our own events
+            return LRESULT(1);
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Low-level mouse hook running on its own message-loop thread
+#[cfg(target_os = "windows")]
+struct HookThread {
+    thread_id: u32,
+    handle: std::thread::JoinHandle<()>,
+}
+
+#[cfg(target_os = "windows")]
+impl HookThread {
+    fn start() -> Result<Self, String> {
+        use windows::Win32::Foundation::HINSTANCE;
+        use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+        use windows::Win32::System::Threading::GetCurrentThreadId;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            GetMessageW, PeekMessageW, SetWindowsHookExW, UnhookWindowsHookEx, MSG, PM_NOREMOVE, WH_MOUSE_LL,
+            WM_USER,
+        };
+
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<u32, String>>();
+        let handle = std::thread::spawn(move || unsafe {
+            let hook = GetModuleHandleW(None)
+                .and_then(|module| SetWindowsHookExW(WH_MOUSE_LL, Some(benchmark_hook), HINSTANCE(module.0), 0));
+            let hook = match hook {
+                Ok(hook) => hook,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(format!("Failed to install mouse hook: {}", e)));
+                    return;
+                }
+            };
+
+            // Create the message queue before reporting ready so WM_QUIT can be posted
+            let mut msg = MSG::default();
+            let _ = PeekMessageW(&mut msg, None, WM_USER, WM_USER, PM_NOREMOVE);
+            let _ = ready_tx.send(Ok(GetCurrentThreadId()));
+
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {}
+            let _ = UnhookWindowsHookEx(hook);
+        });
+
+        let thread_id = ready_rx
+            .recv()
+            .map_err(|_| "Mouse hook thread exited unexpectedly".to_string())??;
+        Ok(Self { thread_id, handle })
+    }
+
+    fn stop(self) {
+        use windows::Win32::Foundation::{LPARAM, WPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_QUIT};
+
+        let _ = unsafe { PostThreadMessageW(self.thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) };
+        let _ = self.handle.join();
+    }
+}
+
+/// Cursor position normalized to the virtual desktop (0-65535)
+#[cfg(target_os = "windows")]
+fn cursor_virtual_position() -> Result<(i32, i32), String> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetCursorPos, GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
+        SM_YVIRTUALSCREEN,
+    };
+
+    unsafe {
+        let mut point = POINT::default();
+        GetCursorPos(&mut point).map_err(|e| format!("Failed to get cursor position: {}", e))?;
+
+        let (left, top) = (GetSystemMetrics(SM_XVIRTUALSCREEN), GetSystemMetrics(SM_YVIRTUALSCREEN));
+        let width = GetSystemMetrics(SM_CXVIRTUALSCREEN).max(2);
+        let height = GetSystemMetrics(SM_CYVIRTUALSCREEN).max(2);
+        Ok((
+            ((point.x - left) * 65535) / (width - 1),
+            ((point.y - top) * 65535) / (height - 1),
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn run_benchmark(iterations: u32) -> Result<serde_json::Value, String> {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{
+        SendInput, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_MOVE, MOUSEEVENTF_VIRTUALDESK,
+        MOUSEINPUT,
+    };
+
+    let (abs_x, abs_y) = cursor_virtual_position()?;
+    let input = INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx: abs_x,
+                dy: abs_y,
+                mouseData: 0,
+                dwFlags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
+                time: 0,
+                dwExtraInfo: BENCHMARK_MARKER,
+            },
+        },
+    };
+
+    let (tx, rx) = mpsc::channel();
+    {
+        let mut arrivals = HOOK_ARRIVALS
+            .lock()
+            .map_err(|e| format!("Failed to lock benchmark state: {}", e))?;
+        if arrivals.is_some() {
+            return Err("An input benchmark is already running".to_string());
+        }
+        *arrivals = Some(tx);
+    }
+    let hook = match HookThread::start() {
+        Ok(hook) => hook,
+        Err(e) => {
+            if let Ok(mut arrivals) = HOOK_ARRIVALS.lock() {
+                *arrivals = None;
+            }
+            return Err(e);
+        }
+    };
+
+    let mut send_us = Vec::with_capacity(iterations as usize);
+    let mut delivery_us = Vec::with_capacity(iterations as usize);
+    let mut failed_sends = 0u32;
+    let mut lost = 0u32;
+
+    for _ in 0..iterations {
+        let sent = Instant::now();
+        let injected = unsafe { SendInput(&[input], std::mem::size_of::<INPUT>() as i32) };
+        send_us.push(sent.elapsed().as_micros() as u64);
+        if injected == 0 {
+            failed_sends += 1;
+            continue;
+        }
+
+        match rx.recv_timeout(DELIVERY_TIMEOUT) {
+            Ok(arrived) => delivery_us.push(arrived.saturating_duration_since(sent).as_micros() as u64),
+            Err(_) => lost += 1,
+        }
+    }
+
+    hook.stop();
+    if let Ok(mut arrivals) = HOOK_ARRIVALS.lock() {
+        *arrivals = None;
+    }
+
+    debug_eprintln!(
+        "[input_benchmark] {} iterations, {} failed sends, {} lost",
+        iterations,
+        failed_sends,
+        lost
+    );
+
+    Ok(serde_json::json!({
+        "iterations": iterations,
+        "sendInput": summarize(send_us),
+        "delivery": summarize(delivery_us),
+        "failedSends": failed_sends,
+        "lostEvents": lost,
+    }))
+}
+
+/// Benchmark input injection on this machine
+/// Injects `iterations` (1-5000) no-op mouse moves. Returns `{ iterations,
+/// sendInput, delivery, failedSends, lostEvents }`, where `sendInput` (time
+/// per `SendInput` call) and `delivery` (SendInput to low-level hook) are
+/// `{ samples, minUs, avgUs, maxUs, p99Us }` or null without samples.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn benchmark_input_latency(iterations: u32) -> Result<serde_json::Value, String> {
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return Err(format!("Iterations must be 1-{}", MAX_ITERATIONS));
+    }
+
+    tokio::task::spawn_blocking(move || run_benchmark(iterations))
+        .await
+        .map_err(|e| format!("Input benchmark task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn benchmark_input_latency(iterations: u32) -> Result<serde_json::Value, String> {
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return Err(format!("Iterations must be 1-{}", MAX_ITERATIONS));
+    }
+    Err("Input benchmarking is only supported on Windows".to_string())
+}
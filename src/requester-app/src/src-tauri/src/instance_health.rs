@@ -0,0 +1,169 @@
+//! Instance Health Module
+//!
+//! The single-instance plugin should keep one app per user, yet users
+//! occasionally report two floating icons (a second user session, a stale
+//! lock). This lists every running process of our executable from a Toolhelp
+//! snapshot with its PID, session and start time, and warns when more than
+//! one main instance runs in our session.
+//!
+//! The watchdog and the elevated broker run the same executable; they are
+//! told apart as children of a running instance and reported as "helper".
+
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+#[cfg(target_os = "windows")]
+use tauri::Emitter;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// A running process of the app's executable
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppInstance {
+    pub pid: u32,
+    pub parent_pid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u32>,
+    /// "main" or "helper" (watchdog / elevated broker, child of an instance)
+    pub role: &'static str,
+    /// Process start time (Unix ms), absent if the process can't be opened
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at_ms: Option<u64>,
+    /// This process
+    pub current: bool,
+}
+
+/// Session of a process, None if it can't be queried
+#[cfg(target_os = "windows")]
+fn process_session(pid: u32) -> Option<u32> {
+    use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+
+    let mut session = 0u32;
+    unsafe { ProcessIdToSessionId(pid, &mut session) }.ok()?;
+    Some(session)
+}
+
+/// Start time of a process in Unix ms
+#[cfg(target_os = "windows")]
+fn process_start_ms(pid: u32) -> Option<u64> {
+    use windows::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows::Win32::System::Threading::{GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    // 100ns intervals between 1601-01-01 and 1970-01-01
+    const UNIX_EPOCH_FILETIME: u64 = 116_444_736_000_000_000;
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let (mut created, mut exited, mut kernel, mut user) =
+            (FILETIME::default(), FILETIME::default(), FILETIME::default(), FILETIME::default());
+        let result = GetProcessTimes(process, &mut created, &mut exited, &mut kernel, &mut user);
+        let _ = CloseHandle(process);
+        result.ok()?;
+
+        let ticks = ((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64;
+        Some(ticks.checked_sub(UNIX_EPOCH_FILETIME)? / 10_000)
+    }
+}
+
+/// Processes whose executable name matches `exe_name`: (pid, parent pid)
+#[cfg(target_os = "windows")]
+fn find_processes(exe_name: &str) -> Result<Vec<(u32, u32)>, String> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+    };
+
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)
+            .map_err(|e| format!("Failed to snapshot processes: {}", e))?;
+
+        let mut processes = Vec::new();
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut found = Process32FirstW(snapshot, &mut entry).is_ok();
+        while found {
+            let len = entry.szExeFile.iter().position(|&c| c == 0).unwrap_or(entry.szExeFile.len());
+            let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+            if name.eq_ignore_ascii_case(exe_name) {
+                processes.push((entry.th32ProcessID, entry.th32ParentProcessID));
+            }
+            found = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+
+        let _ = CloseHandle(snapshot);
+        Ok(processes)
+    }
+}
+
+/// Report running instances of the app
+/// Returns `{ exeName, currentPid, currentSessionId?, instances: [{ pid, parentPid,
+/// sessionId?, role, startedAtMs?, current }], mainInstancesInSession, duplicateDetected }`.
+/// Emits `duplicate-instance-detected` (same payload) when more than one main
+/// instance runs in this session.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn check_instance_health(app: AppHandle) -> Result<serde_json::Value, String> {
+    let report = tokio::task::spawn_blocking(|| {
+        let exe_name = std::env::current_exe()
+            .map_err(|e| format!("Failed to get executable path: {}", e))?
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| "Executable path has no file name".to_string())?;
+        let current_pid = std::process::id();
+        let current_session = process_session(current_pid);
+
+        let processes = find_processes(&exe_name)?;
+        let instances: Vec<AppInstance> = processes
+            .iter()
+            .map(|&(pid, parent_pid)| AppInstance {
+                pid,
+                parent_pid,
+                session_id: process_session(pid),
+                // Watchdog and broker are started by a running instance
+                role: if processes.iter().any(|&(other, _)| other == parent_pid) {
+                    "helper"
+                } else {
+                    "main"
+                },
+                started_at_ms: process_start_ms(pid),
+                current: pid == current_pid,
+            })
+            .collect();
+
+        let main_in_session = instances
+            .iter()
+            .filter(|i| i.role == "main" && i.session_id == current_session)
+            .count();
+
+        Ok::<_, String>(serde_json::json!({
+            "exeName": exe_name,
+            "currentPid": current_pid,
+            "currentSessionId": current_session,
+            "instances": instances,
+            "mainInstancesInSession": main_in_session,
+            "duplicateDetected": main_in_session > 1,
+        }))
+    })
+    .await
+    .map_err(|e| format!("Instance check task failed: {}", e))??;
+
+    if report["duplicateDetected"].as_bool() == Some(true) {
+        debug_eprintln!("[instance_health] Duplicate instances in this session: {}", report["instances"]);
+        let _ = app.emit("duplicate-instance-detected", report.clone());
+    }
+
+    Ok(report)
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn check_instance_health(_app: AppHandle) -> Result<serde_json::Value, String> {
+    Err("Instance health checks are only supported on Windows".to_string())
+}
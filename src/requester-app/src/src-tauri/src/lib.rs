@@ -33,13 +33,23 @@
 // ============================================================================
 
 use std::env;
-use std::sync::OnceLock;
+use std::sync::RwLock;
 use tauri::{AppHandle, Emitter, Listener, Manager};
 use base64::{Engine as _, engine::general_purpose};
+use feature_policy::Feature;
 
 // Remote input injection module
 mod remote_input;
 
+// Remote consent module (user consent gate for remote input commands)
+mod remote_consent;
+
+// Feature policy module (admin-disabled features from HKLM / ProgramData)
+mod feature_policy;
+
+// Input benchmark module (SendInput and hook delivery latency diagnostics)
+mod input_benchmark;
+
 // UAC detection module
 mod uac_detector;
 
@@ -64,6 +74,100 @@ mod debug_macros;
 // Watchdog module for auto-restart functionality
 mod watchdog;
 
+// Instance health module (duplicate app processes despite the single-instance lock)
+mod instance_health;
+
+// Proxy configuration module (system proxy + persisted override)
+mod proxy;
+
+// Windows Update diagnostics module (pending updates query)
+mod windows_update;
+
+// Windows Event Log query module (recent Application/System entries)
+mod event_log;
+
+// Power management module (sleep prevention, battery status)
+mod power;
+
+// Display modes module (resolution / refresh rate enumeration)
+mod display_modes;
+
+// Update flow module (download -> verify -> launch in one command)
+mod updater;
+
+// Scheduled tasks module (Task Scheduler listing and run/stop)
+mod scheduled_tasks;
+
+// Startup programs module (Run keys, Startup folders, logon tasks)
+mod startup_programs;
+
+// Services module (Service Control Manager listing and start/stop/restart)
+mod services;
+
+// Print queue module (printers, queued jobs, clearing a stuck queue)
+mod print_queue;
+
+// Network drives module (mapped drives and server shares)
+mod network_drives;
+
+// Security products module (antivirus/EDR registered with Security Center)
+mod security_products;
+
+// Network fixes module (flush DNS, Winsock reset, DHCP renew)
+mod network_fixes;
+
+// Network overrides module (temporary DNS servers and hosts entries)
+mod network_overrides;
+
+// Network config module (per-adapter addresses, gateways, DNS via GetAdaptersAddresses)
+mod network_config;
+
+// Notification check module (sample toast + Focus Assist / settings diagnosis)
+mod notification_check;
+
+// Resource usage module (memory and handle counts for leak diagnosis)
+mod resource_usage;
+
+// Remote open module (open a URL or document for the technician)
+mod remote_open;
+
+// Scroll capture module (stitched screenshot of a long, scrollable window)
+mod scroll_capture;
+
+// Stream server module (raw JPEG frames over a local WebSocket)
+mod stream_server;
+
+// Protected content module (black frames from DRM / capture-excluded windows)
+mod protected_content;
+
+// Stream adaptation module (profile/chroma from link loss, RTT, encode time and bandwidth budget)
+mod stream_adaptation;
+
+// Capture loop module (fixed-cadence capture pushed as events)
+mod capture_loop;
+
+// Navigation history module (ticket navigations from notifications / deep links)
+mod navigation_history;
+
+// Session recording module (capture loop frames to a timestamped MJPEG AVI)
+mod session_recording;
+
+// Scheduled capture module (screenshots to the logs directory at a set time)
+mod scheduled_capture;
+
+// Elevated broker module (privileged operations without elevating the UI)
+mod elevation_broker;
+
+// Locale module (user's UI language and region for localized support)
+mod locale;
+
+// Mock capture module (synthetic frames + canned monitors/windows for frontend dev)
+#[cfg(feature = "mock-capture")]
+mod mock_capture;
+
+#[cfg(all(feature = "mock-capture", not(debug_assertions)))]
+compile_error!("The mock-capture feature must not be enabled in release builds");
+
 // ============================================================================
 // PERFORMANCE OPTIMIZATION: Screen Dimension Caching for Mouse Positioning
 // ============================================================================
@@ -72,21 +176,31 @@ mod watchdog;
 // 1. SendInput with MOUSEEVENTF_ABSOLUTE uses SM_CXSCREEN/SM_CYSCREEN coordinate space
 // 2. GetSystemMetrics returns DPI-aware dimensions that Windows uses for input
 // 3. xcap might return different values on high-DPI displays
+// The caches are cleared on WM_DISPLAYCHANGE (docking, monitor hot-plug,
+// resolution change) and by `refresh_monitors`, see `invalidate_display_caches`.
 
-#[cfg(target_os = "windows")]
-static CACHED_SCREEN_DIMS: OnceLock<(i32, i32)> = OnceLock::new();
+static CACHED_SCREEN_DIMS: RwLock<Option<(i32, i32)>> = RwLock::new(None);
 
-#[cfg(not(target_os = "windows"))]
-static CACHED_SCREEN_DIMS: OnceLock<(i32, i32)> = OnceLock::new();
+/// Read a cached value, computing and storing it if the cache is empty
+fn cached_or_init<T: Copy>(cache: &RwLock<Option<T>>, init: impl FnOnce() -> T) -> T {
+    if let Some(value) = cache.read().ok().and_then(|cached| *cached) {
+        return value;
+    }
+    let value = init();
+    if let Ok(mut cached) = cache.write() {
+        *cached = Some(value);
+    }
+    value
+}
 
 /// Get screen dimensions for mouse positioning (width, height)
 /// Uses GetSystemMetrics on Windows for accurate mouse coordinate mapping.
-/// Cached after first call for performance.
+/// Cached until the display configuration changes.
 #[cfg(target_os = "windows")]
 fn get_screen_dims_for_mouse() -> (i32, i32) {
     use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
 
-    *CACHED_SCREEN_DIMS.get_or_init(|| {
+    cached_or_init(&CACHED_SCREEN_DIMS, || {
         unsafe {
             let w = GetSystemMetrics(SM_CXSCREEN);
             let h = GetSystemMetrics(SM_CYSCREEN);
@@ -98,7 +212,7 @@ fn get_screen_dims_for_mouse() -> (i32, i32) {
 
 #[cfg(not(target_os = "windows"))]
 fn get_screen_dims_for_mouse() -> (i32, i32) {
-    *CACHED_SCREEN_DIMS.get_or_init(|| {
+    cached_or_init(&CACHED_SCREEN_DIMS, || {
         // Fallback for non-Windows: use xcap
         match xcap::Monitor::all() {
             Ok(monitors) => {
@@ -141,12 +255,12 @@ fn set_window_as_tool_window(hwnd: windows::Win32::Foundation::HWND) -> Result<(
 }
 
 // Keep the xcap-based function for other uses (window positioning, etc.)
-static CACHED_MONITOR_DIMS: OnceLock<(i32, i32, i32, i32)> = OnceLock::new();
+static CACHED_MONITOR_DIMS: RwLock<Option<(i32, i32, i32, i32)>> = RwLock::new(None);
 
 /// Get primary monitor dimensions from xcap (x, y, width, height)
 /// Used for window positioning, NOT for mouse coordinate mapping.
 fn get_primary_monitor_dims() -> (i32, i32, i32, i32) {
-    *CACHED_MONITOR_DIMS.get_or_init(|| {
+    cached_or_init(&CACHED_MONITOR_DIMS, || {
         match xcap::Monitor::all() {
             Ok(monitors) => {
                 if let Some(monitor) = monitors.first() {
@@ -164,6 +278,78 @@ fn get_primary_monitor_dims() -> (i32, i32, i32, i32) {
     })
 }
 
+/// Emitted after the display configuration changed; payload `{ width, height }`
+/// (primary screen in mouse coordinates, re-read after the change)
+const DISPLAY_CHANGED_EVENT: &str = "display-changed";
+
+/// Clear every cached screen/monitor dimension so the next use re-reads it
+fn invalidate_display_caches() {
+    if let Ok(mut cached) = CACHED_SCREEN_DIMS.write() {
+        *cached = None;
+    }
+    if let Ok(mut cached) = CACHED_MONITOR_DIMS.write() {
+        *cached = None;
+    }
+    invalidate_monitor_rects();
+}
+
+/// Handle a display configuration change: drop stale dimensions and tell the frontend
+#[cfg(target_os = "windows")]
+fn on_display_changed(app: &AppHandle) {
+    invalidate_display_caches();
+    let (width, height) = get_screen_dims_for_mouse();
+    debug_eprintln!("[display] Display configuration changed: {}x{}", width, height);
+    let _ = app.emit(DISPLAY_CHANGED_EVENT, serde_json::json!({ "width": width, "height": height }));
+}
+
+/// App handle for the display change window hook
+#[cfg(target_os = "windows")]
+static DISPLAY_CHANGE_APP: std::sync::OnceLock<AppHandle> = std::sync::OnceLock::new();
+
+/// Window subclass procedure watching for WM_DISPLAYCHANGE
+/// (broadcast to all top-level windows, hidden ones included)
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn display_change_subclass_proc(
+    hwnd: windows::Win32::Foundation::HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+    _id: usize,
+    _data: usize,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::UI::Shell::DefSubclassProc;
+    use windows::Win32::UI::WindowsAndMessaging::WM_DISPLAYCHANGE;
+
+    if msg == WM_DISPLAYCHANGE {
+        if let Some(app) = DISPLAY_CHANGE_APP.get() {
+            on_display_changed(app);
+        }
+    }
+    DefSubclassProc(hwnd, msg, wparam, lparam)
+}
+
+/// Listen for display changes (docking, monitor hot-plug, resolution changes)
+/// on a window that lives for the whole app lifetime
+#[cfg(target_os = "windows")]
+fn install_display_change_listener(app: &AppHandle, window: &tauri::WebviewWindow) {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::SetWindowSubclass;
+
+    /// Subclass id of the display change hook
+    const DISPLAY_CHANGE_SUBCLASS_ID: usize = 0x4453_4348; // "DSCH"
+
+    let _ = DISPLAY_CHANGE_APP.set(app.clone());
+    if let Ok(hwnd_raw) = window.hwnd() {
+        let hwnd = HWND(hwnd_raw.0 as *mut std::ffi::c_void);
+        let installed = unsafe {
+            SetWindowSubclass(hwnd, Some(display_change_subclass_proc), DISPLAY_CHANGE_SUBCLASS_ID, 0)
+        };
+        if !installed.as_bool() {
+            debug_eprintln!("[display] Failed to install display change listener");
+        }
+    }
+}
+
 /// Greet command example - can be removed or extended
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -182,16 +368,90 @@ fn show_window(app: tauri::AppHandle) {
         }
         let _ = window.show();
         let _ = window.set_always_on_top(true);
-        let _ = window.set_focus();
+        force_focus(&window);
     }
 }
 
 /// Hide the main application window (minimize to tray)
+/// Gives focus back to the window saved by `save_foreground_state`, if any
 #[tauri::command]
 fn hide_window(app: tauri::AppHandle) {
     if let Some(window) = app.get_webview_window("main") {
         let _ = window.hide();
     }
+    restore_foreground_state_for(&app);
+}
+
+/// Duration of the fade-out used by `hide_to_tray`
+const HIDE_FADE_DURATION_MS: u64 = 150;
+
+/// Number of opacity steps in the fade-out
+const HIDE_FADE_STEPS: u64 = 6;
+
+/// Set a window's opacity through layered-window alpha (255 removes layering)
+#[cfg(target_os = "windows")]
+fn set_window_alpha(hwnd: windows::Win32::Foundation::HWND, alpha: u8) -> Result<(), String> {
+    use windows::Win32::Foundation::COLORREF;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE, LWA_ALPHA,
+        WS_EX_LAYERED,
+    };
+
+    unsafe {
+        let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        if alpha == 255 {
+            // Fully opaque: drop the layered style so the webview renders normally
+            SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style & !(WS_EX_LAYERED.0 as isize));
+            return Ok(());
+        }
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style | WS_EX_LAYERED.0 as isize);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA)
+            .map_err(|e| format!("Failed to set window opacity: {}", e))
+    }
+}
+
+/// Hide the main window to the tray, optionally fading it out first
+/// The fade runs on a background thread; opacity is restored once hidden so
+/// the next `show_window` is fully opaque. Non-Windows hides immediately.
+#[tauri::command]
+fn hide_to_tray(app: tauri::AppHandle, animate: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window not found".to_string())?;
+
+    if !animate || !window.is_visible().unwrap_or(false) {
+        return window.hide().map_err(|e| format!("Failed to hide window: {}", e));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::HWND;
+
+        let hwnd_raw = window.hwnd().map_err(|e| format!("Failed to get window handle: {}", e))?;
+        let hwnd_value = hwnd_raw.0 as isize;
+
+        std::thread::spawn(move || {
+            let hwnd = HWND(hwnd_value as *mut std::ffi::c_void);
+            let step_delay = std::time::Duration::from_millis(HIDE_FADE_DURATION_MS / HIDE_FADE_STEPS);
+
+            for step in 1..HIDE_FADE_STEPS {
+                let alpha = 255 - (255 * step / HIDE_FADE_STEPS) as u8;
+                if set_window_alpha(hwnd, alpha).is_err() {
+                    break;
+                }
+                std::thread::sleep(step_delay);
+            }
+
+            let _ = window.hide();
+            let _ = set_window_alpha(hwnd, 255);
+        });
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        window.hide().map_err(|e| format!("Failed to hide window: {}", e))
+    }
 }
 
 /// Toggle the main application window visibility
@@ -210,21 +470,330 @@ fn toggle_window(app: tauri::AppHandle) {
             }
             let _ = window.show();
             let _ = window.set_always_on_top(true);
-            let _ = window.set_focus();
+            force_focus(&window);
+        }
+    }
+}
+
+// ============================================================================
+// Foreground State (restore the user's focus after we pop up)
+// ============================================================================
+
+/// Window that had focus before we brought the main window up
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy)]
+struct ForegroundState {
+    /// HWND of the previously-foreground window (stored as isize to be Send)
+    hwnd: isize,
+    /// Whether our main window was topmost before we forced it on top
+    was_topmost: bool,
+}
+
+#[cfg(target_os = "windows")]
+static FOREGROUND_STATE: std::sync::Mutex<Option<ForegroundState>> = std::sync::Mutex::new(None);
+
+/// Record the current foreground window unless it belongs to this process
+/// Returns true if a foreign window was recorded
+#[cfg(target_os = "windows")]
+fn save_foreground_state_for(app: &AppHandle) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowLongPtrW, GetWindowThreadProcessId, GWL_EXSTYLE,
+        WS_EX_TOPMOST,
+    };
+
+    unsafe {
+        let foreground = GetForegroundWindow();
+        if foreground.0.is_null() {
+            return false;
+        }
+
+        // Our own window is already in front - nothing to give back later
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(foreground, Some(&mut pid as *mut u32));
+        if pid == std::process::id() {
+            return false;
+        }
+
+        let was_topmost = app
+            .get_webview_window("main")
+            .and_then(|window| window.hwnd().ok())
+            .map(|hwnd_raw| {
+                let hwnd = HWND(hwnd_raw.0 as *mut std::ffi::c_void);
+                GetWindowLongPtrW(hwnd, GWL_EXSTYLE) & WS_EX_TOPMOST.0 as isize != 0
+            })
+            .unwrap_or(false);
+
+        if let Ok(mut state) = FOREGROUND_STATE.lock() {
+            *state = Some(ForegroundState {
+                hwnd: foreground.0 as isize,
+                was_topmost,
+            });
+            debug_eprintln!("[foreground] Saved foreground window {:?} (topmost={})", foreground.0, was_topmost);
+            return true;
+        }
+        false
+    }
+}
+
+/// Re-focus the saved foreground window and reset our topmost flag
+/// Returns true if a saved state was restored
+#[cfg(target_os = "windows")]
+fn restore_foreground_state_for(app: &AppHandle) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{IsWindow, SetForegroundWindow};
+
+    let saved = match FOREGROUND_STATE.lock() {
+        Ok(mut state) => state.take(),
+        Err(_) => None,
+    };
+    let Some(saved) = saved else {
+        return false;
+    };
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_always_on_top(saved.was_topmost);
+    }
+
+    unsafe {
+        let hwnd = HWND(saved.hwnd as *mut std::ffi::c_void);
+        // The window may have closed while our window was up
+        if IsWindow(hwnd).as_bool() {
+            let _ = SetForegroundWindow(hwnd);
+            debug_eprintln!("[foreground] Restored foreground window {:?}", hwnd.0);
+        }
+    }
+    true
+}
+
+#[cfg(not(target_os = "windows"))]
+fn save_foreground_state_for(_app: &AppHandle) -> bool {
+    false
+}
+
+#[cfg(not(target_os = "windows"))]
+fn restore_foreground_state_for(_app: &AppHandle) -> bool {
+    false
+}
+
+/// Record the window the user was working in before we bring ours up
+/// Returns true if a foreign window was recorded (no-op on non-Windows)
+#[tauri::command]
+fn save_foreground_state(app: AppHandle) -> bool {
+    save_foreground_state_for(&app)
+}
+
+/// Give focus back to the recorded window and reset our topmost flag
+/// Returns true if a saved state was restored (no-op on non-Windows)
+#[tauri::command]
+fn restore_foreground_state(app: AppHandle) -> bool {
+    restore_foreground_state_for(&app)
+}
+
+// ============================================================================
+// Foreground Lock Timeout (focus-stealing prevention)
+// ============================================================================
+
+/// Read SPI_GETFOREGROUNDLOCKTIMEOUT (ms)
+#[cfg(target_os = "windows")]
+fn read_foreground_lock_timeout() -> Result<u32, String> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETFOREGROUNDLOCKTIMEOUT, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    let mut timeout = 0u32;
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETFOREGROUNDLOCKTIMEOUT,
+            0,
+            Some(&mut timeout as *mut u32 as *mut std::ffi::c_void),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    }
+    .map_err(|e| format!("Failed to read foreground lock timeout: {}", e))?;
+    Ok(timeout)
+}
+
+/// Set SPI_SETFOREGROUNDLOCKTIMEOUT; `persist` also writes the user profile
+#[cfg(target_os = "windows")]
+fn write_foreground_lock_timeout(ms: u32, persist: bool) -> Result<(), String> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPIF_SENDCHANGE, SPIF_UPDATEINIFILE, SPI_SETFOREGROUNDLOCKTIMEOUT,
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    let flags = if persist {
+        SPIF_UPDATEINIFILE | SPIF_SENDCHANGE
+    } else {
+        SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0)
+    };
+    // The new value is passed in pvParam itself, not pointed to
+    unsafe { SystemParametersInfoW(SPI_SETFOREGROUNDLOCKTIMEOUT, 0, Some(ms as usize as *mut std::ffi::c_void), flags) }
+        .map_err(|e| format!("Failed to set foreground lock timeout: {}", e))
+}
+
+/// Focus a window despite focus-stealing prevention
+/// Windows refuses SetForegroundWindow while the foreground lock timeout
+/// runs, leaving only a flashing taskbar button. The standard workaround:
+/// drop the timeout to 0 for the focus call, then restore it.
+fn force_focus(window: &tauri::WebviewWindow) {
+    #[cfg(target_os = "windows")]
+    {
+        let previous = read_foreground_lock_timeout().ok().filter(|&ms| ms != 0);
+        if let Some(_ms) = previous {
+            if let Err(_e) = write_foreground_lock_timeout(0, false) {
+                debug_eprintln!("[foreground] Could not lift foreground lock ({}ms): {}", _ms, _e);
+            }
+        }
+
+        let _ = window.set_focus();
+
+        if let Some(ms) = previous {
+            if let Err(_e) = write_foreground_lock_timeout(ms, false) {
+                debug_eprintln!("[foreground] Failed to restore foreground lock timeout: {}", _e);
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = window.set_focus();
+    }
+}
+
+/// Get the foreground lock timeout in ms (how long after user input Windows
+/// blocks other apps from taking focus; 200000 by default)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_foreground_lock_timeout() -> Result<u32, String> {
+    read_foreground_lock_timeout()
+}
+
+/// Set the foreground lock timeout in ms and save it to the user's profile
+/// Lets support fix a machine where the app can't come to the front.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn set_foreground_lock_timeout(ms: u32) -> Result<(), String> {
+    write_foreground_lock_timeout(ms, true)?;
+    debug_eprintln!("[foreground] Foreground lock timeout set to {}ms", ms);
+    Ok(())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_foreground_lock_timeout() -> Result<u32, String> {
+    Err("Foreground lock timeout is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn set_foreground_lock_timeout(_ms: u32) -> Result<(), String> {
+    Err("Foreground lock timeout is only supported on Windows".to_string())
+}
+
+// ============================================================================
+// Graceful Shutdown
+// ============================================================================
+
+/// Emitted to the frontend before exit so it can persist UI state
+const SHUTTING_DOWN_EVENT: &str = "shutting-down";
+
+/// Emitted by the frontend once its state is persisted (ends the wait early)
+const SHUTDOWN_READY_EVENT: &str = "shutdown-ready";
+
+/// How long to wait for the frontend by default, and the upper bound
+const DEFAULT_SHUTDOWN_WAIT_MS: u64 = 2000;
+const MAX_SHUTDOWN_WAIT_MS: u64 = 10_000;
+
+/// Set when a shutdown sequence starts, so concurrent quit requests don't repeat it
+static SHUTDOWN_STARTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Set when the sequence is done; exit requests after this are let through
+static SHUTDOWN_COMPLETE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Save state and exit:
+/// 1. End any active remote session (release held modifiers, stop frame streaming)
+/// 2. Stop the elevated broker
+/// 3. Emit `shutting-down` and wait (bounded) for the frontend's `shutdown-ready`
+/// 4. Sync the storage file (after the frontend had its chance to write to it)
+/// 5. Flush logs, then exit
+async fn run_graceful_quit(app: AppHandle, wait_ms: Option<u64>) {
+    use std::sync::atomic::Ordering;
+
+    if SHUTDOWN_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    debug_eprintln!("[shutdown] Graceful quit started");
+
+    if let Err(_e) = remote_input::release_modifiers() {
+        debug_eprintln!("[shutdown] Failed to release modifiers: {}", _e);
+    }
+    if let Err(_e) = stream_server::stop_stream_server() {
+        debug_eprintln!("[shutdown] Failed to stop stream server: {}", _e);
+    }
+    if let Err(_e) = capture_loop::stop_capture_loop(None) {
+        debug_eprintln!("[shutdown] Failed to stop capture loops: {}", _e);
+    }
+    // Finalize a running recording so the file stays playable
+    let _ = session_recording::stop_session_recording().await;
+    if let Err(_e) = elevation_broker::stop_elevated_broker().await {
+        debug_eprintln!("[shutdown] Failed to stop elevated broker: {}", _e);
+    }
+    #[cfg(target_os = "windows")]
+    if let Err(_e) = network_overrides::revert_all() {
+        debug_eprintln!("[shutdown] Failed to revert network changes: {}", _e);
+    }
+
+    let wait = std::time::Duration::from_millis(
+        wait_ms.unwrap_or(DEFAULT_SHUTDOWN_WAIT_MS).min(MAX_SHUTDOWN_WAIT_MS),
+    );
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel::<()>();
+    let listener = app.once(SHUTDOWN_READY_EVENT, move |_event| {
+        let _ = ready_tx.send(());
+    });
+    match app.emit(SHUTTING_DOWN_EVENT, ()) {
+        Ok(()) => {
+            if tokio::time::timeout(wait, ready_rx).await.is_err() {
+                debug_eprintln!("[shutdown] Frontend did not confirm within {:?}", wait);
+            }
         }
+        Err(_e) => debug_eprintln!("[shutdown] Failed to emit {}: {}", SHUTTING_DOWN_EVENT, _e),
     }
+    app.unlisten(listener);
+
+    if let Err(_e) = storage::get_store(&app).and_then(|store| {
+        store.save().map_err(|e| format!("Failed to save store: {}", e))
+    }) {
+        debug_eprintln!("[shutdown] Failed to sync storage: {}", _e);
+    }
+    if let Err(_e) = logging::log_flush(app.clone()) {
+        debug_eprintln!("[shutdown] Failed to flush logs: {}", _e);
+    }
+
+    debug_eprintln!("[shutdown] Graceful quit complete, exiting");
+    SHUTDOWN_COMPLETE.store(true, Ordering::SeqCst);
+    app.exit(0);
+}
+
+/// Save state and exit (see `run_graceful_quit`)
+/// `wait_ms` bounds how long to wait for the frontend (default 2s, max 10s)
+#[tauri::command]
+async fn graceful_quit(app: AppHandle, wait_ms: Option<u64>) -> Result<(), String> {
+    run_graceful_quit(app, wait_ms).await;
+    Ok(())
 }
 
 /// Quit the application completely
 #[tauri::command]
 fn quit_app(app: tauri::AppHandle) {
-    app.exit(0);
+    tauri::async_runtime::spawn(run_graceful_quit(app, None));
 }
 /// Handle clean shutdown with user sign-out
 #[tauri::command]
 async fn handle_shutdown(app: AppHandle) -> Result<(), String> {
-  tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-  app.exit(0);
+  run_graceful_quit(app, None).await;
   Ok(())
 }
 
@@ -262,129 +831,289 @@ fn is_trusted_download_url(url: &str) -> bool {
 /// Download installer from URL to temporary directory
 /// Returns the path to the downloaded file
 /// SECURITY: Only allows downloads from trusted hosts (supportcenter.andalusiagroup.net)
+/// and rejects (deletes) an installer whose file version isn't `target_version`
 #[tauri::command]
-async fn download_installer(url: String, target_version: String) -> Result<String, String> {
-    use tokio::io::AsyncWriteExt;
-    use std::path::PathBuf;
-
+async fn download_installer(app: AppHandle, url: String, target_version: String) -> Result<String, String> {
     debug_eprintln!("[update] Starting download from: {}", url);
     debug_eprintln!("[update] Target version: {}", target_version);
 
-    // SECURITY: Validate URL is from a trusted host
-    if !is_trusted_download_url(&url) {
-        debug_eprintln!("[update] SECURITY: Rejected download from untrusted host: {}", url);
-        return Err(format!(
-            "Security error: Downloads only allowed from trusted hosts: {:?}",
-            TRUSTED_DOWNLOAD_HOSTS
-        ));
+    let (download_path, _bytes) = updater::download_to_temp(&app, &url, &target_version, false).await?;
+
+    // Don't hand out an installer for a different build than requested
+    if let Err(e) = updater::check_installer_version(&download_path, &target_version).await {
+        let _ = tokio::fs::remove_file(&download_path).await;
+        return Err(e);
     }
 
-    // Create temp directory for downloads
-    let temp_dir = std::env::temp_dir();
-    let filename = format!("it-support-center-{}-setup.exe", target_version);
-    let download_path: PathBuf = temp_dir.join(&filename);
+    Ok(download_path.to_string_lossy().to_string())
+}
 
-    debug_eprintln!("[update] Download path: {:?}", download_path);
+/// Installer technologies with known silent-install flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InstallerKind {
+    /// NSIS installer (.exe) - uses /S
+    Nsis,
+    /// Inno Setup installer (.exe) - uses /VERYSILENT
+    Inno,
+    /// Windows Installer package (.msi) - launched via msiexec with /qn
+    Msi,
+}
 
-    // Download the file using reqwest (blocking for simplicity)
-    // Note: For production, consider using async download with progress
-    let response = reqwest::get(&url).await
-        .map_err(|e| format!("Download failed: {}", e))?;
+impl InstallerKind {
+    /// Parse an installer kind name as sent by the frontend ("nsis", "inno", "msi")
+    fn parse(kind: &str) -> Result<Self, String> {
+        match kind.trim().to_lowercase().as_str() {
+            "nsis" => Ok(InstallerKind::Nsis),
+            "inno" | "innosetup" => Ok(InstallerKind::Inno),
+            "msi" => Ok(InstallerKind::Msi),
+            other => Err(format!("Unknown installer kind: {}", other)),
+        }
+    }
 
-    if !response.status().is_success() {
-        return Err(format!("Download failed with status: {}", response.status()));
+    /// Default silent-install arguments for this installer kind
+    fn silent_args(self) -> &'static str {
+        match self {
+            InstallerKind::Nsis => "/S",
+            InstallerKind::Inno => "/VERYSILENT /SUPPRESSMSGBOXES /NORESTART",
+            InstallerKind::Msi => "/qn /norestart",
+        }
     }
+}
 
-    let bytes = response.bytes().await
-        .map_err(|e| format!("Failed to read response body: {}", e))?;
+/// Get the silent-install arguments for an installer kind name
+/// Unknown kinds fall back to the NSIS preset (the format we ship)
+fn silent_args_for(kind: &str) -> Vec<String> {
+    InstallerKind::parse(kind)
+        .unwrap_or(InstallerKind::Nsis)
+        .silent_args()
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect()
+}
 
-    if bytes.is_empty() {
-        return Err("Downloaded file is empty".to_string());
+/// Maximum number of installer arguments accepted from the frontend
+const MAX_INSTALLER_ARGS: usize = 16;
+
+/// Validate pre-split installer arguments
+/// SECURITY: Each argument is passed to the installer as a single argv entry (never
+/// re-split), and must be a switch (/X, -X) or a KEY=VALUE property made of a
+/// restricted character set. Quotes and shell metacharacters are rejected so a
+/// value can't smuggle in extra tokens via the installer's own command-line parser;
+/// spaces are only allowed inside property values (e.g. INSTALLDIR=C:\Program Files\X).
+fn validate_installer_args(args: &[String]) -> Result<Vec<String>, String> {
+    if args.len() > MAX_INSTALLER_ARGS {
+        return Err(format!(
+            "Too many installer arguments ({} > {})",
+            args.len(),
+            MAX_INSTALLER_ARGS
+        ));
     }
 
-    debug_eprintln!("[update] Downloaded {} bytes", bytes.len());
-
-    // Write to temp file
-    let mut file = tokio::fs::File::create(&download_path).await
-        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    let mut tokens = Vec::with_capacity(args.len());
+    for arg in args {
+        let token = arg.trim();
+        if token.is_empty() {
+            continue;
+        }
 
-    file.write_all(&bytes).await
-        .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        let is_switch = token.starts_with('/') || token.starts_with('-');
+        let property_key = token
+            .split_once('=')
+            .map(|(key, _)| key)
+            .filter(|key| !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
 
-    file.sync_all().await
-        .map_err(|e| format!("Failed to sync file: {}", e))?;
+        if !is_switch && property_key.is_none() {
+            return Err(format!("Invalid installer argument: {}", token));
+        }
 
-    // Verify file exists and has content
-    let metadata = tokio::fs::metadata(&download_path).await
-        .map_err(|e| format!("Failed to verify downloaded file: {}", e))?;
+        let allowed = token.chars().all(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(c, '/' | '-' | '_' | '=' | '.' | ':' | '\\' | '*' | '+')
+                || (c == ' ' && property_key.is_some())
+        });
+        if !allowed {
+            return Err(format!("Installer argument contains disallowed characters: {}", token));
+        }
 
-    if metadata.len() == 0 {
-        return Err("Downloaded file is empty after write".to_string());
+        tokens.push(token.to_string());
     }
 
-    debug_eprintln!("[update] Successfully downloaded {} bytes to {:?}", metadata.len(), download_path);
-
-    Ok(download_path.to_string_lossy().to_string())
+    Ok(tokens)
 }
 
-/// Execute the NSIS installer silently and exit the app
+/// Default number of attempts to spawn the installer
+const INSTALLER_SPAWN_ATTEMPTS: u32 = 3;
+
+/// Upper bound for a caller-supplied number of spawn attempts
+const MAX_INSTALLER_SPAWN_ATTEMPTS: u32 = 10;
+
+/// Default delay before the first spawn retry; doubles with each retry
+const INSTALLER_SPAWN_RETRY_DELAY_MS: u64 = 1000;
+
+/// Upper bound for a caller-supplied spawn retry delay
+const MAX_INSTALLER_SPAWN_RETRY_DELAY_MS: u64 = 10_000;
+
+/// How long the installer must keep running (or exit successfully) before the app exits
+const INSTALLER_CONFIRM_DELAY_MS: u64 = 500;
+
+/// Execute the installer silently and exit the app
 /// This spawns the installer and then exits - the installer will continue after app closes
+///
+/// The downloaded file can be briefly locked (e.g. Defender scanning it right
+/// after download), so a failed spawn is retried with backoff. The app only
+/// exits once the installer is confirmed running (or finished successfully);
+/// if every attempt fails the error is returned and the app keeps running.
+///
+/// Arguments:
+/// - `installer_kind`: "nsis" (default), "inno" or "msi" - selects the silent-arg preset
+/// - `silent_args`: Optional explicit override of the preset arguments, pre-split
+///   (one entry per argument, passed through without re-parsing)
+/// - `spawn_attempts`: Attempts to start the installer (default 3, max 10)
+/// - `retry_delay_ms`: Delay before the first retry, doubling after each (default 1000, max 10000)
 #[tauri::command]
 async fn execute_installer_and_exit(
     app: AppHandle,
     installer_path: String,
-    silent_args: String,
+    installer_kind: Option<String>,
+    silent_args: Option<Vec<String>>,
+    spawn_attempts: Option<u32>,
+    retry_delay_ms: Option<u64>,
 ) -> Result<(), String> {
-    use std::process::Command;
+    let kind_name = installer_kind.unwrap_or_else(|| "nsis".to_string());
+    let kind = InstallerKind::parse(&kind_name)?;
+
+    // Explicit override wins; otherwise use the preset for this installer kind
+    let silent_args = match silent_args {
+        Some(args) if args.iter().any(|a| !a.trim().is_empty()) => args,
+        _ => silent_args_for(&kind_name),
+    };
 
     debug_eprintln!("[update] Preparing to execute installer: {}", installer_path);
-    debug_eprintln!("[update] Silent args: {}", silent_args);
+    debug_eprintln!("[update] Installer kind: {:?}", kind);
+    debug_eprintln!("[update] Silent args: {:?}", silent_args);
 
     // Verify installer file exists
     if !std::path::Path::new(&installer_path).exists() {
         return Err(format!("Installer file not found: {}", installer_path));
     }
 
-    // Parse silent args into individual arguments
-    let args: Vec<&str> = silent_args.split_whitespace().collect();
+    // SECURITY: Only run a file this process downloaded from a trusted host
+    if !updater::is_downloaded_installer(std::path::Path::new(&installer_path)) {
+        debug_eprintln!("[update] SECURITY: Rejected installer that was not downloaded by this app: {}", installer_path);
+        return Err("Security error: Installer was not downloaded by this app".to_string());
+    }
+
+    // SECURITY: Validate args one by one before handing them to the installer
+    let args = validate_installer_args(&silent_args)?;
+
+    launch_installer(&installer_path, kind, &args, spawn_attempts, retry_delay_ms).await?;
+
+    debug_eprintln!("[update] Exiting app for update...");
+
+    // Exit the app - installer will continue running
+    app.exit(0);
+    Ok(())
+}
 
-    // Execute NSIS installer directly
-    // NSIS uses /S for silent mode
+/// Spawn an installer, retrying failed spawns with backoff, and confirm it is running
+/// Returns an error (and the caller must not exit) if every attempt fails or
+/// the installer exits with a failure right after starting.
+async fn launch_installer(
+    installer_path: &str,
+    kind: InstallerKind,
+    args: &[String],
+    attempts: Option<u32>,
+    retry_delay_ms: Option<u64>,
+) -> Result<(), String> {
+    let attempts = attempts
+        .unwrap_or(INSTALLER_SPAWN_ATTEMPTS)
+        .clamp(1, MAX_INSTALLER_SPAWN_ATTEMPTS);
+    let mut delay = std::time::Duration::from_millis(
+        retry_delay_ms
+            .unwrap_or(INSTALLER_SPAWN_RETRY_DELAY_MS)
+            .min(MAX_INSTALLER_SPAWN_RETRY_DELAY_MS),
+    );
+
+    let mut attempt = 1;
+    let mut child = loop {
+        match spawn_installer(installer_path, kind, args) {
+            Ok(child) => break child,
+            Err(e) if attempt < attempts => {
+                debug_eprintln!(
+                    "[update] Spawn attempt {}/{} failed, retrying in {:?}: {}",
+                    attempt, attempts, delay, e
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => {
+                debug_eprintln!("[update] All {} spawn attempts failed, not exiting", attempts);
+                return Err(e);
+            }
+        }
+    };
+
+    // Confirm the installer is running before exiting: an installer that
+    // fails immediately (bad package, blocked by policy) keeps the app alive
+    tokio::time::sleep(std::time::Duration::from_millis(INSTALLER_CONFIRM_DELAY_MS)).await;
+    match child.try_wait() {
+        Ok(None) => debug_eprintln!("[update] Installer is running"),
+        Ok(Some(status)) if status.success() => {
+            debug_eprintln!("[update] Installer already finished successfully");
+        }
+        Ok(Some(status)) => {
+            return Err(format!("Installer exited immediately with {}", status));
+        }
+        Err(e) => {
+            return Err(format!("Failed to confirm installer is running: {}", e));
+        }
+    }
+    Ok(())
+}
+
+/// Spawn an installer detached with already-validated arguments
+/// NSIS/Inno installers run directly; MSI packages go through msiexec.
+fn spawn_installer(
+    installer_path: &str,
+    kind: InstallerKind,
+    args: &[String],
+) -> Result<std::process::Child, String> {
     #[cfg(target_os = "windows")]
     {
-        let mut cmd = Command::new(&installer_path);
-        for arg in &args {
+        use std::process::Command;
+
+        let mut cmd = match kind {
+            InstallerKind::Msi => {
+                let mut cmd = Command::new("msiexec");
+                cmd.arg("/i").arg(&installer_path);
+                cmd
+            }
+            InstallerKind::Nsis | InstallerKind::Inno => Command::new(&installer_path),
+        };
+        for arg in args {
             cmd.arg(arg);
         }
 
-        debug_eprintln!("[update] Spawning installer: {} {}", installer_path, silent_args);
+        debug_eprintln!("[update] Spawning installer: {} {}", installer_path, args.join(" "));
 
         // Spawn the installer (detached, won't block)
         match cmd.spawn() {
-            Ok(_) => {
+            Ok(child) => {
                 debug_eprintln!("[update] Installer spawned successfully");
+                Ok(child)
             }
-            Err(e) => {
-                return Err(format!("Failed to spawn installer: {}", e));
-            }
+            Err(e) => Err(format!("Failed to spawn installer: {}", e)),
         }
     }
 
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (installer_path, kind, args);
         // NSIS installation not supported on this platform
         debug_eprintln!("[update] NSIS installation not supported on this platform");
-        return Err("NSIS installation is only supported on Windows".to_string());
+        Err("NSIS installation is only supported on Windows".to_string())
     }
-
-    // Brief delay to ensure installer process starts
-    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-
-    debug_eprintln!("[update] Exiting app for update...");
-
-    // Exit the app - installer will continue running
-    app.exit(0);
-    Ok(())
 }
 
 /// Check if running with admin privileges (Windows only)
@@ -424,10 +1153,195 @@ fn get_app_version(app: AppHandle) -> String {
     app.package_info().version.to_string()
 }
 
+/// Get the system proxy settings, persisted override and effective proxy
+/// Used by the settings UI to diagnose update failures behind corporate proxies
+#[tauri::command]
+fn get_system_proxy(app: AppHandle) -> Result<proxy::ProxyStatus, String> {
+    proxy::get_proxy_status(&app)
+}
+
+/// Set (or clear with null) the proxy override used for outbound HTTP requests
+#[tauri::command]
+fn set_proxy_override(app: AppHandle, url: Option<String>) -> Result<proxy::ProxyStatus, String> {
+    proxy::set_proxy_override(&app, url)?;
+    proxy::get_proxy_status(&app)
+}
+
+/// True if a CSP's connect-src allows any localhost port (the dev-server
+/// wildcards the production build should not carry, see Finding #43 above)
+fn has_localhost_connect_wildcard(csp: &str) -> bool {
+    csp.split(';')
+        .map(str::trim)
+        .filter(|directive| directive.starts_with("connect-src"))
+        .flat_map(str::split_whitespace)
+        .any(|source| {
+            source.ends_with(":*") && (source.contains("://localhost") || source.contains("://127.0.0.1"))
+        })
+}
+
+/// Get the security posture of this build for the diagnostics panel
+/// Returns `{ csp, localhostConnectWildcard, buildProfile, elevated }`; `csp`
+/// is the policy Tauri applied (devCsp in debug builds when set), null if none.
+#[tauri::command]
+async fn get_security_info(app: AppHandle) -> Result<serde_json::Value, String> {
+    let security = &app.config().app.security;
+    let csp = if cfg!(debug_assertions) {
+        security.dev_csp.as_ref().or(security.csp.as_ref())
+    } else {
+        security.csp.as_ref()
+    }
+    .map(|csp| csp.to_string());
+
+    let elevated = tokio::task::spawn_blocking(is_elevated)
+        .await
+        .map_err(|e| format!("Elevation check task failed: {}", e))?;
+
+    Ok(serde_json::json!({
+        "localhostConnectWildcard": csp.as_deref().is_some_and(has_localhost_connect_wildcard),
+        "csp": csp,
+        "buildProfile": if cfg!(debug_assertions) { "debug" } else { "release" },
+        "elevated": elevated,
+    }))
+}
+
+/// Windows-only features in the capability manifest
+/// Each backs a group of commands that return "only supported on Windows"
+/// elsewhere. Keep in sync with the handler list in `run()`.
+const WINDOWS_ONLY_CAPABILITIES: &[&str] = &[
+    "remoteInput",
+    "keyboardLayout",
+    "inputBenchmark",
+    "windowCapture",
+    "scrollCapture",
+    "uacDetection",
+    "autostart",
+    "watchdog",
+    "instanceHealth",
+    "foregroundLock",
+    "installerUpdates",
+    "windowsUpdate",
+    "eventLog",
+    "services",
+    "scheduledTasks",
+    "startupPrograms",
+    "printQueue",
+    "networkDrives",
+    "networkFixes",
+    "networkOverrides",
+    "securityProducts",
+    "displayModes",
+    "power",
+    "resourceUsage",
+    "remoteOpen",
+    "elevationBroker",
+];
+
+/// Features available on every platform
+const CROSS_PLATFORM_CAPABILITIES: &[&str] = &[
+    "notifications",
+    "remoteConsent",
+    "sessionLogs",
+    "scheduledCapture",
+    "sessionRecording",
+    "streamServer",
+    "navigationHistory",
+    "networkConfig",
+    "proxy",
+];
+
+/// Get which features this build supports on this machine
+/// Read once at startup so the UI can hide unsupported affordances instead of
+/// probing each command. Returns `{ platform, arch, elevated, capabilities:
+/// { <feature>: { supported, reason? } } }`.
+#[tauri::command]
+async fn get_capabilities() -> Result<serde_json::Value, String> {
+    let windows = cfg!(target_os = "windows");
+
+    // Capture needs at least one monitor (none in some RDP/service sessions)
+    let monitors = if cfg!(feature = "mock-capture") {
+        1
+    } else {
+        tokio::task::spawn_blocking(|| xcap::Monitor::all().map(|m| m.len()).unwrap_or(0))
+            .await
+            .map_err(|e| format!("Capability check task failed: {}", e))?
+    };
+    let elevated = tokio::task::spawn_blocking(is_elevated)
+        .await
+        .map_err(|e| format!("Elevation check task failed: {}", e))?;
+
+    let mut capabilities = serde_json::Map::new();
+    for &name in WINDOWS_ONLY_CAPABILITIES {
+        let supported = windows || (name == "windowCapture" && cfg!(feature = "mock-capture"));
+        let entry = if supported {
+            serde_json::json!({ "supported": true })
+        } else {
+            serde_json::json!({ "supported": false, "reason": "Windows only" })
+        };
+        capabilities.insert(name.to_string(), entry);
+    }
+    for &name in CROSS_PLATFORM_CAPABILITIES {
+        capabilities.insert(name.to_string(), serde_json::json!({ "supported": true }));
+    }
+    capabilities.insert(
+        "screenCapture".to_string(),
+        if monitors > 0 {
+            serde_json::json!({ "supported": true })
+        } else {
+            serde_json::json!({ "supported": false, "reason": "No monitors available" })
+        },
+    );
+
+    // Features an admin disabled are reported as unsupported
+    let policy = feature_policy::policy();
+    for (name, feature) in [("remoteInput", Feature::RemoteInput), ("screenCapture", Feature::ScreenCapture)] {
+        if !policy.is_enabled(feature) {
+            capabilities.insert(
+                name.to_string(),
+                serde_json::json!({ "supported": false, "reason": "Disabled by policy" }),
+            );
+        }
+    }
+
+    Ok(serde_json::json!({
+        "platform": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "elevated": elevated,
+        "capabilities": capabilities,
+    }))
+}
+
 // ============================================================================
 // END PHASE 8 Commands
 // ============================================================================
 
+// ============================================================================
+// LAUNCH REASON
+// ============================================================================
+
+/// Why this instance was started, detected once in `run()`
+static LAUNCH_REASON: std::sync::OnceLock<&'static str> = std::sync::OnceLock::new();
+
+/// Classify a launch from its command-line arguments (first one is the executable)
+/// "autostart" (Run entry flag), "deepLink" (a URL argument) or "user"
+fn launch_reason_from_args(args: &[String]) -> &'static str {
+    let args = &args[args.len().min(1)..];
+    if args.iter().any(|arg| arg == autostart::AUTOSTART_ARG) {
+        "autostart"
+    } else if args.iter().any(|arg| arg.contains("://")) {
+        "deepLink"
+    } else {
+        "user"
+    }
+}
+
+/// Get why the app was launched: "autostart", "deepLink" or "user"
+/// The frontend uses this to start hidden in the tray after logon but show
+/// the window when the user opens the app.
+#[tauri::command]
+fn get_launch_reason() -> String {
+    LAUNCH_REASON.get().copied().unwrap_or("user").to_string()
+}
+
 // ============================================================================
 // WATCHDOG COMMANDS (Auto-Restart)
 // ============================================================================
@@ -539,13 +1453,20 @@ fn get_system_username() -> Result<String, String> {
 /// This function uses the `hostname` command as a fallback.
 /// - Command string is hardcoded ("hostname") - NO USER INPUT
 /// - No arguments passed - command string is completely static
-/// - Primary method uses environment variables (COMPUTERNAME/HOSTNAME)
+/// - Primary method on Windows is `GetComputerNameExW` (NetBIOS name), then
+///   environment variables (COMPUTERNAME/HOSTNAME)
 /// - Shell command is only a fallback mechanism
 ///
-/// TODO (Future enhancement): Consider replacing with Windows API call:
-/// `GetComputerNameW()` from kernel32.dll for native hostname retrieval
+/// Domain-joined machines should use `get_computer_names` to also get the FQDN.
 #[tauri::command]
 fn get_computer_name() -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    if let Ok(name) = windows_computer_name(windows::Win32::System::SystemInformation::ComputerNameNetBIOS) {
+        if !name.is_empty() {
+            return Ok(name);
+        }
+    }
+
     // Try Windows COMPUTERNAME first, then Unix HOSTNAME
     let computer_name = env::var("COMPUTERNAME")
         .or_else(|_| env::var("HOSTNAME"))
@@ -559,11 +1480,86 @@ fn get_computer_name() -> Result<String, String> {
         })
         .map_err(|_| "Could not determine computer name".to_string())?;
 
-    if computer_name.is_empty() {
-        return Err("Computer name is empty".to_string());
+    if computer_name.is_empty() {
+        return Err("Computer name is empty".to_string());
+    }
+
+    Ok(computer_name)
+}
+
+/// Read one form of the computer name with `GetComputerNameExW`
+#[cfg(target_os = "windows")]
+fn windows_computer_name(
+    format: windows::Win32::System::SystemInformation::COMPUTER_NAME_FORMAT,
+) -> Result<String, String> {
+    use windows::core::PWSTR;
+    use windows::Win32::System::SystemInformation::GetComputerNameExW;
+
+    unsafe {
+        // First call fails with ERROR_MORE_DATA and reports the size (incl. NUL)
+        let mut size = 0u32;
+        let _ = GetComputerNameExW(format, PWSTR::null(), &mut size);
+        if size == 0 {
+            return Err(format!("Failed to query computer name size ({:?})", format));
+        }
+
+        let mut buffer = vec![0u16; size as usize];
+        GetComputerNameExW(format, PWSTR(buffer.as_mut_ptr()), &mut size)
+            .map_err(|e| format!("Failed to get computer name ({:?}): {}", format, e))?;
+        Ok(String::from_utf16_lossy(&buffer[..size as usize]))
     }
+}
 
-    Ok(computer_name)
+/// Computer name in the forms needed to match AD-based inventory
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ComputerNames {
+    /// NetBIOS name (what `get_computer_name` returns)
+    netbios: String,
+    /// Fully-qualified DNS name (e.g. "PC-042.corp.example.com"); same as
+    /// `dns_hostname` on machines without a primary DNS suffix
+    fqdn: String,
+    /// DNS host name without the domain (e.g. "PC-042")
+    dns_hostname: String,
+}
+
+/// Get the NetBIOS name, FQDN and DNS host name of this machine
+/// Returns `{ netbios, fqdn, dnsHostname }`. The FQDN is what AD and
+/// DNS-based inventory use; the NetBIOS name is truncated to 15 characters.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+fn get_computer_names() -> Result<ComputerNames, String> {
+    use windows::Win32::System::SystemInformation::{ComputerNameDnsFullyQualified, ComputerNameDnsHostname};
+
+    let netbios = get_computer_name()?;
+    let dns_hostname = windows_computer_name(ComputerNameDnsHostname).unwrap_or_else(|_e| {
+        debug_eprintln!("[computer_name] DNS host name unavailable: {}", _e);
+        netbios.clone()
+    });
+    let fqdn = windows_computer_name(ComputerNameDnsFullyQualified).unwrap_or_else(|_e| {
+        debug_eprintln!("[computer_name] FQDN unavailable: {}", _e);
+        dns_hostname.clone()
+    });
+
+    Ok(ComputerNames { netbios, fqdn, dns_hostname })
+}
+
+/// Non-Windows: the host name in every form (`hostname -f` for the FQDN)
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+fn get_computer_names() -> Result<ComputerNames, String> {
+    let netbios = get_computer_name()?;
+    // SECURITY: Hardcoded command and argument - no user input involved
+    let fqdn = std::process::Command::new("hostname")
+        .arg("-f")
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| netbios.clone());
+    let dns_hostname = fqdn.split('.').next().unwrap_or(&fqdn).to_string();
+
+    Ok(ComputerNames { netbios, fqdn, dns_hostname })
 }
 
 /// Get OS information for device tracking
@@ -584,8 +1580,8 @@ fn get_os_info() -> String {
 /// - All command strings are completely static - NO USER INPUT
 /// - Output is parsed for IP addresses only, validated against known patterns
 ///
-/// TODO (Future enhancement): Consider replacing with Windows API calls:
-/// `GetAdaptersAddresses()` from iphlpapi.dll for native network info
+/// For the full per-adapter picture use `network_config::get_network_config`
+/// (GetAdaptersAddresses).
 #[tauri::command]
 fn get_local_ip() -> Result<String, String> {
     // Try to get local IP using platform-specific methods
@@ -674,44 +1670,145 @@ fn get_server_config_unlock_key() -> Result<String, String> {
     Err("Server configuration unlock key not configured".to_string())
 }
 
+/// Capture every monitor and composite them into a single virtual-desktop image
+///
+/// Monitors are placed at their virtual-screen coordinates relative to the
+/// top-left corner of the bounding box, so layouts with monitors left of or
+/// above the primary (negative origins) are handled correctly. Gaps between
+/// monitors of different sizes are left black.
+fn capture_virtual_desktop() -> Result<image::RgbaImage, String> {
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?;
+
+    if monitors.is_empty() {
+        return Err("No monitors found".to_string());
+    }
+
+    // Capture each monitor along with its virtual-screen origin
+    let mut captures: Vec<(i32, i32, image::RgbaImage)> = Vec::with_capacity(monitors.len());
+    for (idx, monitor) in monitors.iter().enumerate() {
+        let image = monitor
+            .capture_image()
+            .map_err(|e| format!("Failed to capture monitor {}: {}", idx, e))?;
+        let x = monitor.x().unwrap_or(0);
+        let y = monitor.y().unwrap_or(0);
+        captures.push((x, y, image));
+    }
+
+    // Single monitor: no compositing needed
+    if captures.len() == 1 {
+        return Ok(captures.remove(0).2);
+    }
+
+    // Bounding box of the virtual desktop (may start at negative coordinates)
+    let min_x = captures.iter().map(|(x, _, _)| *x).min().unwrap_or(0);
+    let min_y = captures.iter().map(|(_, y, _)| *y).min().unwrap_or(0);
+    let max_x = captures
+        .iter()
+        .map(|(x, _, img)| *x as i64 + img.width() as i64)
+        .max()
+        .unwrap_or(0);
+    let max_y = captures
+        .iter()
+        .map(|(_, y, img)| *y as i64 + img.height() as i64)
+        .max()
+        .unwrap_or(0);
+
+    let total_width = (max_x - min_x as i64).max(1) as u32;
+    let total_height = (max_y - min_y as i64).max(1) as u32;
+
+    debug_eprintln!(
+        "[capture_screen] Compositing {} monitors into {}x{} (origin {}, {})",
+        captures.len(), total_width, total_height, min_x, min_y
+    );
+
+    let mut canvas = image::RgbaImage::from_pixel(total_width, total_height, image::Rgba([0, 0, 0, 255]));
+    for (x, y, img) in &captures {
+        let offset_x = *x as i64 - min_x as i64;
+        let offset_y = *y as i64 - min_y as i64;
+        image::imageops::overlay(&mut canvas, img, offset_x, offset_y);
+    }
+
+    Ok(canvas)
+}
+
+/// Default delay between hiding the main window and capturing, so it is off screen
+const CAPTURE_HIDE_DELAY_MS: u64 = 150;
+
+/// Upper bound for a caller-supplied hide delay
+const MAX_CAPTURE_HIDE_DELAY_MS: u64 = 2000;
+
+/// Give up on a full-desktop capture after this long (window is restored either way)
+const CAPTURE_SCREEN_TIMEOUT_SECS: u64 = 15;
+
+/// Re-shows a window hidden for a capture when dropped
+/// Covers every exit path (success, error, timeout, or a panicked capture task).
+struct HiddenWindowGuard {
+    window: tauri::WebviewWindow,
+}
+
+impl Drop for HiddenWindowGuard {
+    fn drop(&mut self) {
+        if let Err(_e) = self.window.show() {
+            debug_eprintln!("[capture] Failed to restore window after capture: {}", _e);
+        }
+        let _ = self.window.set_focus();
+    }
+}
+
 /// Capture desktop screenshot with instant, professional screen capture
+/// Captures the full virtual desktop (all monitors composited)
 /// Returns base64-encoded PNG image
+///
+/// The main window is hidden during the capture only if it is currently shown,
+/// and is restored on any exit path. `hide_delay_ms` (default 150, max 2000)
+/// is how long to wait after hiding before capturing.
 #[tauri::command]
-async fn capture_screen(app: AppHandle) -> Result<String, String> {
+async fn capture_screen(app: AppHandle, hide_delay_ms: Option<u64>) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
     let window = app
         .get_webview_window("main")
         .ok_or_else(|| "Main window not found".to_string())?;
 
-    window.hide().map_err(|e| format!("Failed to hide window: {}", e))?;
-    tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+    // Nothing to hide (and no flicker) when already hidden or minimized
+    let needs_hide = window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false);
+
+    let _guard = if needs_hide {
+        window.hide().map_err(|e| format!("Failed to hide window: {}", e))?;
+        let guard = HiddenWindowGuard { window };
+        let delay = hide_delay_ms
+            .unwrap_or(CAPTURE_HIDE_DELAY_MS)
+            .min(MAX_CAPTURE_HIDE_DELAY_MS);
+        tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
+        Some(guard)
+    } else {
+        None
+    };
 
-    let capture_result = tokio::task::spawn_blocking(move || {
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| format!("Failed to get monitors: {}", e))?;
-        let monitor = monitors
-            .into_iter()
-            .next()
-            .ok_or_else(|| "No monitors found".to_string())?;
-        let image = monitor
-            .capture_image()
-            .map_err(|e| format!("Failed to capture screen: {}", e))?;
+    let capture_task = tokio::task::spawn_blocking(move || {
+        let image = capture_virtual_desktop()?;
         let mut png_buffer = Vec::new();
         image
             .write_to(&mut std::io::Cursor::new(&mut png_buffer), image::ImageFormat::Png)
             .map_err(|e| format!("Failed to encode PNG: {}", e))?;
         Ok::<String, String>(general_purpose::STANDARD.encode(&png_buffer))
-    })
-    .await
-    .map_err(|e| format!("Capture task failed: {}", e))?;
+    });
 
-    window.show().map_err(|e| format!("Failed to show window: {}", e))?;
-    window.set_focus().ok();
-    capture_result
+    // The blocking capture can't be cancelled; on timeout it finishes in the
+    // background and its result is discarded
+    tokio::time::timeout(
+        std::time::Duration::from_secs(CAPTURE_SCREEN_TIMEOUT_SECS),
+        capture_task,
+    )
+    .await
+    .map_err(|_| format!("Screen capture timed out after {}s", CAPTURE_SCREEN_TIMEOUT_SECS))?
+    .map_err(|e| format!("Capture task failed: {}", e))?
 }
 
 /// Capture a specific region of the screen
 #[tauri::command]
 async fn capture_screen_region(x: i32, y: i32, width: u32, height: u32) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
     tokio::task::spawn_blocking(move || {
         use image::GenericImageView;
         let monitors = xcap::Monitor::all()
@@ -743,8 +1840,11 @@ async fn capture_screen_region(x: i32, y: i32, width: u32, height: u32) -> Resul
 }
 
 /// Refresh monitor cache - call this if monitors are added/removed
+/// Also drops the cached screen dimensions used for mouse mapping and window positioning.
 #[tauri::command]
 fn refresh_monitors() -> Result<String, String> {
+    invalidate_display_caches();
+
     let monitors = xcap::Monitor::all()
         .map_err(|e| format!("Failed to get monitors: {}", e))?;
 
@@ -771,6 +1871,7 @@ fn refresh_monitors() -> Result<String, String> {
 
 /// Get all available monitors with their properties
 /// Returns JSON array of monitor information
+#[cfg(not(feature = "mock-capture"))]
 #[tauri::command]
 fn get_monitors() -> Result<String, String> {
     let monitors = xcap::Monitor::all()
@@ -797,9 +1898,16 @@ fn get_monitors() -> Result<String, String> {
         .map_err(|e| format!("Failed to serialize monitors: {}", e))
 }
 
+/// Canned monitors (`mock-capture` feature)
+#[cfg(feature = "mock-capture")]
+#[tauri::command]
+fn get_monitors() -> Result<String, String> {
+    mock_capture::monitors_json()
+}
+
 /// Get all visible windows with their properties
 /// Returns JSON array of window information
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "mock-capture")))]
 #[tauri::command]
 fn get_windows() -> Result<String, String> {
     use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
@@ -886,384 +1994,1236 @@ fn get_windows() -> Result<String, String> {
     serde_json::to_string(&*result).map_err(|e| format!("Failed to serialize: {}", e))
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(all(not(target_os = "windows"), not(feature = "mock-capture")))]
 #[tauri::command]
 fn get_windows() -> Result<String, String> {
     // Return empty array on non-Windows platforms
     Ok("[]".to_string())
 }
 
+/// Canned windows (`mock-capture` feature)
+#[cfg(feature = "mock-capture")]
+#[tauri::command]
+fn get_windows() -> Result<String, String> {
+    mock_capture::windows_json()
+}
+
+/// Capture a monitor as a preview JPEG (max 640px wide, blocking)
+/// Returns the JPEG and the monitor's full width and height
+#[cfg(not(feature = "mock-capture"))]
+fn capture_preview_jpeg(monitor_id: usize) -> Result<(Vec<u8>, u32, u32), String> {
+    use image::imageops::FilterType;
+    use image::codecs::jpeg::JpegEncoder;
+
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?;
+
+    let monitor = monitors
+        .get(monitor_id)
+        .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+
+    let image = monitor
+        .capture_image()
+        .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+
+    // Resize for preview (max 640px width, maintain aspect ratio)
+    let (width, height) = (image.width(), image.height());
+    let max_width = 640u32;
+    let (new_width, new_height) = if width > max_width {
+        let ratio = max_width as f32 / width as f32;
+        (max_width, (height as f32 * ratio) as u32)
+    } else {
+        (width, height)
+    };
+
+    let resized = image::imageops::resize(&image, new_width, new_height, FilterType::Nearest);
+
+    // Convert RGBA to RGB (JPEG doesn't support alpha channel)
+    let rgb_image: image::RgbImage = image::DynamicImage::ImageRgba8(resized).to_rgb8();
+
+    // Use JPEG for faster encoding (quality 75 for previews)
+    let mut jpeg_buffer = Vec::with_capacity(100_000); // Pre-allocate ~100KB
+    let mut encoder = JpegEncoder::new_with_quality(&mut jpeg_buffer, 75);
+    encoder.encode(
+        rgb_image.as_raw(),
+        new_width,
+        new_height,
+        image::ExtendedColorType::Rgb8
+    ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+
+    Ok((jpeg_buffer, width, height))
+}
+
+/// Synthetic preview JPEG (`mock-capture` feature)
+#[cfg(feature = "mock-capture")]
+fn capture_preview_jpeg(monitor_id: usize) -> Result<(Vec<u8>, u32, u32), String> {
+    mock_capture::preview_jpeg(monitor_id)
+}
+
 /// Capture a specific monitor by index
 /// Returns base64-encoded JPEG image (smaller resolution for preview)
 /// OPTIMIZED: Uses JPEG encoding for faster performance
 #[tauri::command]
 async fn capture_monitor_preview(monitor_id: usize) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
     tokio::task::spawn_blocking(move || {
-        use image::imageops::FilterType;
-        use image::codecs::jpeg::JpegEncoder;
+        capture_preview_jpeg(monitor_id).map(|(jpeg, _, _)| general_purpose::STANDARD.encode(&jpeg))
+    })
+    .await
+    .map_err(|e| format!("Capture task failed: {}", e))?
+}
 
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+// ============================================================================
+// Capture Backpressure (In-Flight Frame Tracking)
+// ============================================================================
+// If the transport is slower than capture, the frontend can request a new frame
+// while the previous one for the same monitor is still being captured/encoded.
+// Instead of piling up spawn_blocking tasks, such requests get the last frame
+// for that monitor/profile (or a busy error if none is cached yet).
 
-        let monitor = monitors
-            .get(monitor_id)
-            .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+/// Number of stream captures currently running
+static CAPTURE_IN_FLIGHT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
 
-        let image = monitor
-            .capture_image()
-            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+/// Monitors with a stream capture currently running
+static CAPTURE_BUSY_MONITORS: std::sync::Mutex<Vec<usize>> = std::sync::Mutex::new(Vec::new());
 
-        // Resize for preview (max 640px width, maintain aspect ratio)
-        let (width, height) = (image.width(), image.height());
-        let max_width = 640u32;
-        let (new_width, new_height) = if width > max_width {
-            let ratio = max_width as f32 / width as f32;
-            (max_width, (height as f32 * ratio) as u32)
-        } else {
-            (width, height)
-        };
+/// Last encoded stream frame per (monitor, profile)
+static LAST_STREAM_FRAMES: std::sync::Mutex<Vec<((usize, &'static str), String)>> = std::sync::Mutex::new(Vec::new());
 
-        let resized = image::imageops::resize(&image, new_width, new_height, FilterType::Nearest);
+/// Error returned when a capture is in flight and no cached frame exists yet
+const CAPTURE_BUSY_ERROR: &str = "busy";
 
-        // Convert RGBA to RGB (JPEG doesn't support alpha channel)
-        let rgb_image: image::RgbImage = image::DynamicImage::ImageRgba8(resized).to_rgb8();
+/// Error returned when the bandwidth budget is used up and no cached frame exists yet
+const BANDWIDTH_BUDGET_ERROR: &str = "bandwidth-budget-exhausted";
 
-        // Use JPEG for faster encoding (quality 75 for previews)
-        let mut jpeg_buffer = Vec::with_capacity(100_000); // Pre-allocate ~100KB
-        let mut encoder = JpegEncoder::new_with_quality(&mut jpeg_buffer, 75);
-        encoder.encode(
-            rgb_image.as_raw(),
-            new_width,
-            new_height,
-            image::ExtendedColorType::Rgb8
-        ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+/// Marks a monitor as having a capture in flight; released on drop
+struct InFlightCapture {
+    monitor_id: usize,
+}
+
+impl InFlightCapture {
+    /// Claim the monitor, or return None if a capture is already running for it
+    fn try_acquire(monitor_id: usize) -> Option<Self> {
+        let mut busy = CAPTURE_BUSY_MONITORS.lock().ok()?;
+        if busy.contains(&monitor_id) {
+            return None;
+        }
+        busy.push(monitor_id);
+        CAPTURE_IN_FLIGHT.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Some(Self { monitor_id })
+    }
+}
+
+impl Drop for InFlightCapture {
+    fn drop(&mut self) {
+        if let Ok(mut busy) = CAPTURE_BUSY_MONITORS.lock() {
+            busy.retain(|id| *id != self.monitor_id);
+        }
+        CAPTURE_IN_FLIGHT.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+/// Get the cached last frame for a monitor/profile
+fn get_cached_stream_frame(monitor_id: usize, profile: &'static str) -> Option<String> {
+    let frames = LAST_STREAM_FRAMES.lock().ok()?;
+    frames
+        .iter()
+        .find(|(key, _)| *key == (monitor_id, profile))
+        .map(|(_, frame)| frame.clone())
+}
+
+/// Store the last frame for a monitor/profile
+fn cache_stream_frame(monitor_id: usize, profile: &'static str, frame: &str) {
+    if let Ok(mut frames) = LAST_STREAM_FRAMES.lock() {
+        match frames.iter_mut().find(|(key, _)| *key == (monitor_id, profile)) {
+            Some((_, cached)) => {
+                cached.clear();
+                cached.push_str(frame);
+            }
+            None => frames.push(((monitor_id, profile), frame.to_string())),
+        }
+    }
+}
+
+/// Run a stream capture on a blocking thread unless one is already in flight for the monitor
+///
+/// The in-flight guard moves into the blocking task, so it is only released once
+/// the capture actually finishes (even if the invoking future is dropped).
+async fn with_capture_backpressure<F>(
+    monitor_id: usize,
+    profile: &'static str,
+    capture: F,
+) -> Result<String, String>
+where
+    F: FnOnce() -> Result<String, String> + Send + 'static,
+{
+    // Bandwidth budget used up: repeat the last frame instead of a new capture
+    if !stream_adaptation::budget_allows_frame() {
+        return get_cached_stream_frame(monitor_id, profile)
+            .ok_or_else(|| BANDWIDTH_BUDGET_ERROR.to_string());
+    }
+
+    let guard = match InFlightCapture::try_acquire(monitor_id) {
+        Some(guard) => guard,
+        None => {
+            return get_cached_stream_frame(monitor_id, profile)
+                .ok_or_else(|| CAPTURE_BUSY_ERROR.to_string());
+        }
+    };
 
-        Ok::<String, String>(general_purpose::STANDARD.encode(&jpeg_buffer))
+    let frame = tokio::task::spawn_blocking(move || {
+        let _guard = guard;
+        run_capture(monitor_id, profile, capture)
     })
     .await
-    .map_err(|e| format!("Capture task failed: {}", e))?
+    .map_err(|e| format!("Capture task failed: {}", e))??;
+
+    cache_stream_frame(monitor_id, profile, &frame);
+    // Base64 -> JPEG bytes
+    stream_adaptation::record_stream_bytes(frame.len() as u64 * 3 / 4);
+    Ok(frame)
 }
 
-/// Capture a specific monitor at standard resolution (960x540) for streaming
-/// Returns base64-encoded JPEG image at quality 97 optimized for real-time streaming
-/// OPTIMIZED: fast_image_resize (SIMD) + jpeg-encoder (SIMD) for best quality/performance
+/// Run a stream capture closure
+#[cfg(not(feature = "mock-capture"))]
+fn run_capture<F>(_monitor_id: usize, _profile: &'static str, capture: F) -> Result<String, String>
+where
+    F: FnOnce() -> Result<String, String>,
+{
+    capture()
+}
+
+/// Replace the stream capture with a synthetic frame (`mock-capture` feature)
+/// Backpressure and frame caching still apply, so the UI sees real behavior.
+#[cfg(feature = "mock-capture")]
+fn run_capture<F>(monitor_id: usize, profile: &'static str, _capture: F) -> Result<String, String>
+where
+    F: FnOnce() -> Result<String, String>,
+{
+    mock_capture::stream_frame(monitor_id, profile)
+}
+
+/// Get the number of stream captures currently in flight
+/// The frontend can use this to throttle its frame requests
 #[tauri::command]
-async fn capture_monitor_stream(monitor_id: usize) -> Result<String, String> {
-    // Use spawn_blocking with increased priority for real-time performance
-    tokio::task::spawn_blocking(move || {
-        use fast_image_resize::{images::Image, Resizer, ResizeOptions, ResizeAlg, FilterType};
-        use std::time::Instant;
+fn get_capture_in_flight_count() -> usize {
+    CAPTURE_IN_FLIGHT.load(std::sync::atomic::Ordering::SeqCst)
+}
 
-        let _t0 = Instant::now();
+// ============================================================================
+// Capture Timing Stats
+// ============================================================================
+// Per-stage timings of the most recent stream frame plus an exponential moving
+// average, queryable via `get_capture_stats` (e.g. for an "encode: 12ms" overlay
+// or diagnostics bundles).
+
+/// Weight of the newest frame in the moving average
+const CAPTURE_STATS_EMA_ALPHA: f64 = 0.1;
+
+/// Per-stage timings of one frame, in milliseconds
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureTimings {
+    capture_ms: f64,
+    resize_ms: f64,
+    encode_ms: f64,
+    total_ms: f64,
+}
 
-        // Get monitor (cached operation, ~0ms)
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+impl CaptureTimings {
+    /// Build timings from the stage boundaries of a capture
+    /// (t0 start, t1 monitor lookup, t2 captured, t3 resized, t4 encoded)
+    fn from_instants(t: [std::time::Instant; 5]) -> Self {
+        let ms = |from: std::time::Instant, to: std::time::Instant| to.duration_since(from).as_secs_f64() * 1000.0;
+        Self {
+            capture_ms: ms(t[1], t[2]),
+            resize_ms: ms(t[2], t[3]),
+            encode_ms: ms(t[3], t[4]),
+            total_ms: ms(t[0], t[4]),
+        }
+    }
+
+    fn blend(&self, latest: &Self, alpha: f64) -> Self {
+        let ema = |avg: f64, new: f64| avg + alpha * (new - avg);
+        Self {
+            capture_ms: ema(self.capture_ms, latest.capture_ms),
+            resize_ms: ema(self.resize_ms, latest.resize_ms),
+            encode_ms: ema(self.encode_ms, latest.encode_ms),
+            total_ms: ema(self.total_ms, latest.total_ms),
+        }
+    }
+}
+
+/// Timing stats of the stream capture pipeline
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureStats {
+    /// Profile of the most recent frame ("standard", "high" or "extreme")
+    profile: &'static str,
+    /// JPEG chroma subsampling of the most recent frame ("444" or "420")
+    chroma: &'static str,
+    monitor_id: usize,
+    /// Frames recorded since the profile or chroma last changed
+    frames: u64,
+    /// Size of the most recent encoded frame
+    frame_bytes: usize,
+    last: CaptureTimings,
+    average: CaptureTimings,
+}
+
+static CAPTURE_STATS: std::sync::Mutex<Option<CaptureStats>> = std::sync::Mutex::new(None);
+
+/// Record the timings of a stream frame
+/// The average restarts when the profile or chroma changes, so frame sizes of
+/// 4:4:4 and 4:2:0 can be compared side by side in the stats and timing log.
+fn record_capture_timings(
+    monitor_id: usize,
+    profile: &'static str,
+    chroma: &'static str,
+    timings: CaptureTimings,
+    frame_bytes: usize,
+) {
+    debug_eprintln!(
+        "[capture] {} {} frame: {} bytes, encode {:.1}ms, total {:.1}ms",
+        profile, chroma, frame_bytes, timings.encode_ms, timings.total_ms
+    );
+
+    if let Ok(mut stats) = CAPTURE_STATS.lock() {
+        match stats.as_mut().filter(|s| s.profile == profile && s.chroma == chroma) {
+            Some(current) => {
+                current.monitor_id = monitor_id;
+                current.frames += 1;
+                current.frame_bytes = frame_bytes;
+                current.average = current.average.blend(&timings, CAPTURE_STATS_EMA_ALPHA);
+                current.last = timings;
+            }
+            None => {
+                *stats = Some(CaptureStats {
+                    profile,
+                    chroma,
+                    monitor_id,
+                    frames: 1,
+                    frame_bytes,
+                    last: timings,
+                    average: timings,
+                });
+            }
+        }
+    }
+}
+
+/// Upper bounds (ms) of the latency histogram buckets; a final bucket catches the rest
+const LATENCY_BUCKET_BOUNDS_MS: [f64; 10] = [10.0, 20.0, 35.0, 50.0, 75.0, 100.0, 150.0, 250.0, 500.0, 1000.0];
+
+/// Latency distribution of timed captures
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LatencyHistogram {
+    samples: u64,
+    sum_ms: f64,
+    max_ms: f64,
+    /// Counts per bucket of `LATENCY_BUCKET_BOUNDS_MS`, plus one for anything slower
+    counts: [u64; LATENCY_BUCKET_BOUNDS_MS.len() + 1],
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, ms: f64) {
+        let bucket = LATENCY_BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(LATENCY_BUCKET_BOUNDS_MS.len());
+        self.counts[bucket] += 1;
+        self.samples += 1;
+        self.sum_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+}
+
+/// Latency histograms fed by `capture_monitor_stream_timed`
+/// - server: request received to frame ready (capture + resize + encode + queueing)
+/// - roundTrip: frontend call to frontend receipt, reported back by the frontend
+#[derive(Debug, Clone, Copy, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureLatencyStats {
+    server: LatencyHistogram,
+    round_trip: LatencyHistogram,
+}
+
+static CAPTURE_LATENCY: std::sync::Mutex<Option<CaptureLatencyStats>> = std::sync::Mutex::new(None);
+
+/// Milliseconds since the Unix epoch (same clock as the frontend's `Date.now()`)
+fn unix_time_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
+}
+
+/// Get the capture pipeline's timing stats
+/// Returns `{ profile, chroma, monitorId, frames, frameBytes, last, average, latency? }` with
+/// `{ captureMs, resizeMs, encodeMs, totalMs }` timings, or null before the first frame.
+/// `latency` (`{ server, roundTrip }` histograms with `{ samples, sumMs, maxMs, counts }`
+/// and `bucketBoundsMs`) is present once timed captures have been made.
+/// `buffers` reports frame buffer reuse (see `capture_buffer_stats`), `retries`
+/// `{ recovered, failed }` counts transient capture failures (see `retry_monitor_capture`).
+#[tauri::command]
+fn get_capture_stats() -> Result<serde_json::Value, String> {
+    let stats = CAPTURE_STATS
+        .lock()
+        .map_err(|e| format!("Failed to lock capture stats: {}", e))?;
+    let mut value = serde_json::to_value(&*stats)
+        .map_err(|e| format!("Failed to serialize capture stats: {}", e))?;
+
+    let latency = *CAPTURE_LATENCY
+        .lock()
+        .map_err(|e| format!("Failed to lock capture latency stats: {}", e))?;
+    if let (Some(object), Some(latency)) = (value.as_object_mut(), latency) {
+        let mut latency = serde_json::to_value(latency)
+            .map_err(|e| format!("Failed to serialize capture latency stats: {}", e))?;
+        latency["bucketBoundsMs"] = serde_json::json!(LATENCY_BUCKET_BOUNDS_MS);
+        object.insert("latency".to_string(), latency);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("buffers".to_string(), capture_buffer_stats()?);
+        object.insert(
+            "retries".to_string(),
+            serde_json::json!({
+                "recovered": CAPTURE_RETRIES_RECOVERED.load(std::sync::atomic::Ordering::Relaxed),
+                "failed": CAPTURE_RETRIES_FAILED.load(std::sync::atomic::Ordering::Relaxed),
+            }),
+        );
+    }
+    Ok(value)
+}
+
+// ============================================================================
+// Capture Buffer Pool
+// ============================================================================
+// Stream frames reuse their resize, RGB and JPEG buffers per monitor instead of
+// allocating ~10 MB of fresh Vecs per 1080p frame, which showed up as periodic
+// stutter at 30+ FPS. Buffers are taken out of the pool for the duration of an
+// encode (parallel monitor captures don't hold the lock while encoding) and put
+// back afterwards. `reset_capture_buffers` releases them when a session ends.
+
+/// Whether stream frames reuse pooled buffers (off = allocate per frame, for comparison)
+static CAPTURE_BUFFER_POOLING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+
+/// Reusable buffers of one monitor's stream frames
+#[derive(Default)]
+struct CaptureBuffers {
+    /// Resize destination (RGBA)
+    resized: Vec<u8>,
+    /// Encoder input (RGB)
+    rgb: Vec<u8>,
+    /// Encoded JPEG
+    jpeg: Vec<u8>,
+}
+
+impl CaptureBuffers {
+    fn capacity(&self) -> usize {
+        self.resized.capacity() + self.rgb.capacity() + self.jpeg.capacity()
+    }
+
+    /// Size the buffers for a frame of the given dimensions
+    /// Returns the bytes that had to be allocated (0 when every buffer was reused).
+    fn prepare(&mut self, width: u32, height: u32, jpeg_capacity: usize) -> usize {
+        let pixels = width as usize * height as usize;
+        let mut allocated = 0;
+        for (buffer, needed) in [
+            (&mut self.resized, pixels * 4),
+            (&mut self.rgb, pixels * 3),
+            (&mut self.jpeg, jpeg_capacity),
+        ] {
+            buffer.clear();
+            if buffer.capacity() < needed {
+                buffer.reserve_exact(needed);
+                allocated += needed;
+            }
+        }
+        // The resizer writes into an initialized buffer of exactly this size
+        self.resized.resize(pixels * 4, 0);
+        allocated
+    }
+}
+
+/// Per-monitor buffers plus allocation counters for the timing stats
+struct CaptureBufferPool {
+    buffers: std::collections::BTreeMap<usize, CaptureBuffers>,
+    /// Frames encoded since the last reset
+    frames: u64,
+    /// Frames that needed no allocation
+    reused_frames: u64,
+    /// Bytes allocated for frame buffers since the last reset
+    allocated_bytes: u64,
+}
+
+static CAPTURE_BUFFER_POOL: std::sync::Mutex<CaptureBufferPool> = std::sync::Mutex::new(CaptureBufferPool {
+    buffers: std::collections::BTreeMap::new(),
+    frames: 0,
+    reused_frames: 0,
+    allocated_bytes: 0,
+});
+
+/// Take a monitor's buffers out of the pool (empty buffers if none are pooled)
+fn take_capture_buffers(monitor_id: usize) -> CaptureBuffers {
+    if !CAPTURE_BUFFER_POOLING.load(std::sync::atomic::Ordering::SeqCst) {
+        return CaptureBuffers::default();
+    }
+    CAPTURE_BUFFER_POOL
+        .lock()
+        .ok()
+        .and_then(|mut pool| pool.buffers.remove(&monitor_id))
+        .unwrap_or_default()
+}
+
+/// Put a monitor's buffers back and count the frame's allocations
+/// Buffers of a frame that failed to encode are simply dropped.
+fn return_capture_buffers(monitor_id: usize, buffers: CaptureBuffers, allocated: usize) {
+    if allocated > 0 {
+        debug_eprintln!(
+            "[capture] Monitor {} frame buffers: allocated {} KB",
+            monitor_id,
+            allocated / 1024
+        );
+    }
+
+    if let Ok(mut pool) = CAPTURE_BUFFER_POOL.lock() {
+        pool.frames += 1;
+        pool.allocated_bytes += allocated as u64;
+        if allocated == 0 {
+            pool.reused_frames += 1;
+        }
+        if CAPTURE_BUFFER_POOLING.load(std::sync::atomic::Ordering::SeqCst) {
+            pool.buffers.insert(monitor_id, buffers);
+        }
+    }
+}
+
+/// Buffer pool state for `get_capture_stats`
+/// `{ pooling, monitors, pooledBytes, frames, reusedFrames, allocatedBytes }`
+fn capture_buffer_stats() -> Result<serde_json::Value, String> {
+    let pool = CAPTURE_BUFFER_POOL
+        .lock()
+        .map_err(|e| format!("Failed to lock capture buffer pool: {}", e))?;
+    Ok(serde_json::json!({
+        "pooling": CAPTURE_BUFFER_POOLING.load(std::sync::atomic::Ordering::SeqCst),
+        "monitors": pool.buffers.len(),
+        "pooledBytes": pool.buffers.values().map(CaptureBuffers::capacity).sum::<usize>(),
+        "frames": pool.frames,
+        "reusedFrames": pool.reused_frames,
+        "allocatedBytes": pool.allocated_bytes,
+    }))
+}
+
+/// Enable or disable frame buffer reuse (disabling also releases pooled buffers)
+/// Returns whether pooling is enabled.
+#[tauri::command]
+fn set_capture_buffer_pooling(enabled: bool) -> Result<bool, String> {
+    CAPTURE_BUFFER_POOLING.store(enabled, std::sync::atomic::Ordering::SeqCst);
+    if !enabled {
+        reset_capture_buffers()?;
+    }
+    Ok(enabled)
+}
+
+/// Release all pooled frame buffers and reset the allocation counters
+/// Call when a streaming session ends. Returns the number of bytes released.
+#[tauri::command]
+fn reset_capture_buffers() -> Result<usize, String> {
+    let mut pool = CAPTURE_BUFFER_POOL
+        .lock()
+        .map_err(|e| format!("Failed to lock capture buffer pool: {}", e))?;
+    let released = pool.buffers.values().map(CaptureBuffers::capacity).sum();
+    pool.buffers.clear();
+    pool.frames = 0;
+    pool.reused_frames = 0;
+    pool.allocated_bytes = 0;
+
+    debug_eprintln!("[capture] Released {} KB of frame buffers", released / 1024);
+    Ok(released)
+}
+
+/// Parse the JPEG chroma subsampling requested by the frontend
+///
+/// - "444" (default): full-resolution color, keeps colored text and thin UI
+///   lines sharp at the cost of larger frames
+/// - "420": color at half resolution in both directions - smaller frames but
+///   colored fringing around text
+///
+/// Returns the encoder sampling factor and the label recorded in capture stats.
+fn jpeg_sampling_factor(chroma: Option<&str>) -> Result<(jpeg_encoder::SamplingFactor, &'static str), String> {
+    match chroma.map(|c| c.trim().replace(':', "")).as_deref() {
+        None | Some("") | Some("444") => Ok((jpeg_encoder::SamplingFactor::R_4_4_4, "444")),
+        Some("420") => Ok((jpeg_encoder::SamplingFactor::R_4_2_0, "420")),
+        Some(other) => Err(format!("Unsupported chroma subsampling: {} (use \"444\" or \"420\")", other)),
+    }
+}
+
+/// Output size, JPEG quality and buffer capacity of a stream profile
+/// - standard: 960x540 @ 97 for balanced quality/performance
+/// - high: 1280x720 @ 98, good quality with moderate bandwidth
+/// - extreme: 1920x1080 @ 100, pristine text/UI clarity on a local network (near-lossless)
+fn stream_profile_params(profile: &str) -> (u32, u32, u8, usize) {
+    match profile {
+        "high" => (1280, 720, 98, 800_000),
+        "extreme" => (1920, 1080, 100, 1_500_000),
+        _ => (960, 540, 97, 500_000),
+    }
+}
+
+/// Map a profile name from the frontend to its static label
+fn stream_profile(name: &str) -> Option<&'static str> {
+    match name {
+        "standard" => Some("standard"),
+        "high" => Some("high"),
+        "extreme" => Some("extreme"),
+        _ => None,
+    }
+}
+
+// ============================================================================
+// Capture Retry
+// ============================================================================
+// `capture_image` fails transiently during resolution changes, session switches
+// and GPU driver resets. Instead of dropping the stream, a failed stream capture
+// re-fetches the monitor list (it may have changed) and retries a few times.
+// If the monitor is gone, `MONITOR_NOT_FOUND_ERROR` tells the frontend to
+// re-query the monitors rather than keep retrying.
+
+/// Error returned when the requested monitor no longer exists
+const MONITOR_NOT_FOUND_ERROR: &str = "monitor-not-found";
+
+/// Retries after a failed capture before the error is surfaced
+const CAPTURE_RETRY_ATTEMPTS: u32 = 2;
+
+/// Delay before each retry, long enough for a mode switch to settle
+const CAPTURE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(150);
+
+/// Captures that failed and then succeeded on retry
+static CAPTURE_RETRIES_RECOVERED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Captures that still failed after all retries (monitor gone included)
+static CAPTURE_RETRIES_FAILED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Retry a failed capture against a fresh monitor list (blocking)
+fn retry_monitor_capture(monitor_id: usize, first_error: String) -> Result<(xcap::Monitor, image::RgbaImage), String> {
+    use std::sync::atomic::Ordering;
+
+    debug_eprintln!("[capture] Monitor {} capture failed, retrying: {}", monitor_id, first_error);
+
+    let mut last_error = first_error;
+    for attempt in 1..=CAPTURE_RETRY_ATTEMPTS {
+        std::thread::sleep(CAPTURE_RETRY_DELAY);
+
+        let monitor = match xcap::Monitor::all() {
+            Ok(monitors) => monitors.into_iter().nth(monitor_id),
+            Err(e) => {
+                last_error = format!("Failed to get monitors: {}", e);
+                continue;
+            }
+        };
+        let Some(monitor) = monitor else {
+            debug_eprintln!("[capture] Monitor {} disappeared", monitor_id);
+            CAPTURE_RETRIES_FAILED.fetch_add(1, Ordering::Relaxed);
+            return Err(MONITOR_NOT_FOUND_ERROR.to_string());
+        };
+
+        match monitor.capture_image() {
+            Ok(captured) => {
+                debug_eprintln!("[capture] Monitor {} capture recovered on retry {}", monitor_id, attempt);
+                CAPTURE_RETRIES_RECOVERED.fetch_add(1, Ordering::Relaxed);
+                return Ok((monitor, captured));
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+
+    CAPTURE_RETRIES_FAILED.fetch_add(1, Ordering::Relaxed);
+    Err(format!("Failed to capture monitor: {}", last_error))
+}
+
+/// Capture a monitor and encode it as a JPEG stream frame for a profile (blocking)
+/// OPTIMIZED: fast_image_resize (SIMD) + jpeg-encoder (SIMD) for best quality/performance
+fn encode_monitor_frame(
+    monitor_id: usize,
+    profile: &'static str,
+    sampling_factor: jpeg_encoder::SamplingFactor,
+    chroma: &'static str,
+) -> Result<Vec<u8>, String> {
+    use fast_image_resize::{images::Image, Resizer, ResizeOptions, ResizeAlg, FilterType};
+    use std::time::Instant;
+
+    let (dst_width, dst_height, quality, capacity) = stream_profile_params(profile);
+
+    let t0 = Instant::now();
+
+    // Get monitor (cached operation, ~0ms)
+    let monitors = xcap::Monitor::all()
+        .map_err(|e| format!("Failed to get monitors: {}", e))?;
 
-        let _t1 = Instant::now();
+    let t1 = Instant::now();
 
-        let monitor = monitors
-            .get(monitor_id)
-            .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+    let monitor = monitors
+        .into_iter()
+        .nth(monitor_id)
+        .ok_or_else(|| MONITOR_NOT_FOUND_ERROR.to_string())?;
 
-        // Capture screen (bottleneck ~120-140ms)
-        let captured = monitor
-            .capture_image()
-            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+    // Capture screen (bottleneck ~120-140ms), retried on transient failures
+    let (monitor, captured) = match monitor.capture_image() {
+        Ok(captured) => (monitor, captured),
+        Err(e) => retry_monitor_capture(monitor_id, e.to_string())?,
+    };
 
-        let _t2 = Instant::now();
-
-        // Source dimensions
-        let src_width = captured.width();
-        let src_height = captured.height();
-
-        // Target 960x540 for balanced quality/performance
-        let dst_width = 960u32;
-        let dst_height = 540u32;
-
-        // Create source image from captured RGBA data
-        let src_image = Image::from_vec_u8(
-            src_width,
-            src_height,
-            captured.into_raw(),
-            fast_image_resize::PixelType::U8x4,
-        ).map_err(|e| format!("Failed to create source image: {}", e))?;
-
-        // Create destination image
-        let mut dst_image = Image::new(
-            dst_width,
-            dst_height,
-            fast_image_resize::PixelType::U8x4,
-        );
+    let t2 = Instant::now();
 
-        // Resize using Lanczos3 (high quality, sharp for text/icons)
-        let mut resizer = Resizer::new();
-        resizer.resize(
-            &src_image,
-            &mut dst_image,
-            &ResizeOptions::new().resize_alg(ResizeAlg::Convolution(FilterType::Lanczos3)),
-        ).map_err(|e| format!("Failed to resize: {}", e))?;
+    // Source dimensions
+    let src_width = captured.width();
+    let src_height = captured.height();
 
-        let _t3 = Instant::now();
+    // Note windows that came out black because their content is protected
+    #[cfg(target_os = "windows")]
+    protected_content::inspect_frame(
+        monitor_id,
+        (monitor.x().unwrap_or(0), monitor.y().unwrap_or(0)),
+        captured.as_raw(),
+        src_width,
+        src_height,
+    );
+
+    // Create source image from captured RGBA data
+    let src_image = Image::from_vec_u8(
+        src_width,
+        src_height,
+        captured.into_raw(),
+        fast_image_resize::PixelType::U8x4,
+    ).map_err(|e| format!("Failed to create source image: {}", e))?;
+
+    // Create destination image in the monitor's pooled buffer
+    let mut buffers = take_capture_buffers(monitor_id);
+    let allocated = buffers.prepare(dst_width, dst_height, capacity);
+    let mut dst_image = Image::from_vec_u8(
+        dst_width,
+        dst_height,
+        std::mem::take(&mut buffers.resized),
+        fast_image_resize::PixelType::U8x4,
+    ).map_err(|e| format!("Failed to create destination image: {}", e))?;
+
+    // Resize using Lanczos3 (high quality, sharp for text/icons)
+    let mut resizer = Resizer::new();
+    resizer.resize(
+        &src_image,
+        &mut dst_image,
+        &ResizeOptions::new().resize_alg(ResizeAlg::Convolution(FilterType::Lanczos3)),
+    ).map_err(|e| format!("Failed to resize: {}", e))?;
+
+    let t3 = Instant::now();
+
+    // Convert RGBA to RGB for JPEG
+    buffers.resized = dst_image.into_vec();
+    for chunk in buffers.resized.chunks_exact(4) {
+        buffers.rgb.extend_from_slice(&chunk[..3]); // R, G, B
+    }
 
-        // Convert RGBA to RGB for JPEG
-        let rgba_data = dst_image.into_vec();
-        let mut rgb_data = Vec::with_capacity((dst_width * dst_height * 3) as usize);
-        for chunk in rgba_data.chunks(4) {
-            rgb_data.push(chunk[0]); // R
-            rgb_data.push(chunk[1]); // G
-            rgb_data.push(chunk[2]); // B
-        }
+    // Use jpeg-encoder with SIMD
+    let mut encoder = jpeg_encoder::Encoder::new(&mut buffers.jpeg, quality);
+    encoder.set_sampling_factor(sampling_factor);
+    encoder.encode(
+        &buffers.rgb,
+        dst_width as u16,
+        dst_height as u16,
+        jpeg_encoder::ColorType::Rgb,
+    ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+
+    // Exact-size copy; the pooled buffer keeps its capacity for the next frame
+    let jpeg_buffer = buffers.jpeg.to_vec();
+    return_capture_buffers(monitor_id, buffers, allocated);
+
+    let t4 = Instant::now();
+
+    record_capture_timings(
+        monitor_id,
+        profile,
+        chroma,
+        CaptureTimings::from_instants([t0, t1, t2, t3, t4]),
+        jpeg_buffer.len(),
+    );
+
+    Ok(jpeg_buffer)
+}
 
-        // Use jpeg-encoder with SIMD (quality 97 for sharp text/UI)
-        let mut jpeg_buffer = Vec::with_capacity(500_000);
-        let encoder = jpeg_encoder::Encoder::new(&mut jpeg_buffer, 97);
-        encoder.encode(
-            &rgb_data,
-            dst_width as u16,
-            dst_height as u16,
-            jpeg_encoder::ColorType::Rgb,
-        ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+/// Capture one raw JPEG stream frame (blocking), used by the binary WebSocket stream
+#[cfg(not(feature = "mock-capture"))]
+fn capture_stream_jpeg(
+    monitor_id: usize,
+    profile: &'static str,
+    sampling_factor: jpeg_encoder::SamplingFactor,
+    chroma: &'static str,
+) -> Result<Vec<u8>, String> {
+    encode_monitor_frame(monitor_id, profile, sampling_factor, chroma)
+}
 
-        let jpeg_data = jpeg_buffer;
-
-        let _t4 = Instant::now();
-
-        // Log timing breakdown (only occasionally, debug builds only)
-        #[cfg(debug_assertions)]
-        {
-            static FRAME_COUNT: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
-            let frame_num = FRAME_COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            if frame_num % 30 == 0 {
-                eprintln!(
-                    "[capture_monitor_stream] Frame {}: Monitor::all={:?}ms, capture={:?}ms, resize={:?}ms, encode={:?}ms, total={:?}ms",
-                    frame_num,
-                    _t1.duration_since(_t0).as_millis(),
-                    _t2.duration_since(_t1).as_millis(),
-                    _t3.duration_since(_t2).as_millis(),
-                    _t4.duration_since(_t3).as_millis(),
-                    _t4.duration_since(_t0).as_millis()
-                );
-            }
-        }
+/// Synthetic raw JPEG stream frame (`mock-capture` feature)
+#[cfg(feature = "mock-capture")]
+fn capture_stream_jpeg(
+    monitor_id: usize,
+    profile: &'static str,
+    _sampling_factor: jpeg_encoder::SamplingFactor,
+    _chroma: &'static str,
+) -> Result<Vec<u8>, String> {
+    mock_capture::stream_frame_jpeg(monitor_id, profile)
+}
 
-        Ok::<String, String>(general_purpose::STANDARD.encode(&jpeg_data))
+/// Capture a specific monitor at standard resolution (960x540) for streaming
+/// Returns base64-encoded JPEG image at quality 97 optimized for real-time streaming
+/// `chroma`: "444" (default, sharp text) or "420" (smaller frames), see `jpeg_sampling_factor`
+#[tauri::command]
+async fn capture_monitor_stream(monitor_id: usize, chroma: Option<String>) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let (sampling_factor, chroma) = jpeg_sampling_factor(chroma.as_deref())?;
+
+    // Runs on a blocking thread; skipped if this monitor still has a capture in flight
+    with_capture_backpressure(monitor_id, "standard", move || {
+        encode_monitor_frame(monitor_id, "standard", sampling_factor, chroma)
+            .map(|jpeg| general_purpose::STANDARD.encode(&jpeg))
     })
     .await
-    .map_err(|e| format!("Capture task failed: {}", e))?
 }
 
 /// Capture a specific monitor at HIGH resolution (1280x720) for bandwidth fallback
 /// Returns base64-encoded JPEG image at 1280x720 with quality 98
 /// Use this profile when 1080p is too heavy but 540p is too blurry
+/// `chroma`: "444" (default, sharp text) or "420" (smaller frames), see `jpeg_sampling_factor`
 #[tauri::command]
-async fn capture_monitor_stream_high(monitor_id: usize) -> Result<String, String> {
-    tokio::task::spawn_blocking(move || {
-        use fast_image_resize::{images::Image, Resizer, ResizeOptions, ResizeAlg, FilterType};
-        use std::time::Instant;
+async fn capture_monitor_stream_high(monitor_id: usize, chroma: Option<String>) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let (sampling_factor, chroma) = jpeg_sampling_factor(chroma.as_deref())?;
 
-        let _t0 = Instant::now();
+    with_capture_backpressure(monitor_id, "high", move || {
+        encode_monitor_frame(monitor_id, "high", sampling_factor, chroma)
+            .map(|jpeg| general_purpose::STANDARD.encode(&jpeg))
+    })
+    .await
+}
 
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+/// Capture a specific monitor at EXTREME resolution for local network streaming
+/// Returns base64-encoded JPEG image at 1920x1080 with quality 100 for best visual fidelity
+/// Use this profile for local network connections where bandwidth is not a concern
+/// `chroma`: "444" (default, sharp text) or "420" (smaller frames), see `jpeg_sampling_factor`
+#[tauri::command]
+async fn capture_monitor_stream_extreme(monitor_id: usize, chroma: Option<String>) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let (sampling_factor, chroma) = jpeg_sampling_factor(chroma.as_deref())?;
 
-        let _t1 = Instant::now();
+    with_capture_backpressure(monitor_id, "extreme", move || {
+        encode_monitor_frame(monitor_id, "extreme", sampling_factor, chroma)
+            .map(|jpeg| general_purpose::STANDARD.encode(&jpeg))
+    })
+    .await
+}
 
-        let monitor = monitors
-            .get(monitor_id)
-            .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+/// A stream frame with the timestamps needed to split its latency
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimedStreamFrame {
+    /// Base64-encoded JPEG (same as `capture_monitor_stream`)
+    image: String,
+    /// `client_timestamp_ms` echoed back
+    client_timestamp_ms: f64,
+    /// When the request reached the backend (Unix ms)
+    server_received_ms: f64,
+    /// When the frame was ready to send (Unix ms)
+    server_complete_ms: f64,
+    /// `server_complete_ms - server_received_ms`
+    server_processing_ms: f64,
+    /// Part of the frame is black because a window's content is protected
+    /// (DRM video, or a window excluded from capture)
+    protected_content_detected: bool,
+    /// Title of the protected window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protected_window_title: Option<String>,
+}
 
-        let captured = monitor
-            .capture_image()
-            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+/// Capture a stream frame with latency timestamps (latency probe)
+///
+/// The frontend passes `Date.now()` as `client_timestamp_ms`; on receipt,
+/// `Date.now() - clientTimestampMs` is the round trip and `serverProcessingMs`
+/// the backend's share, so the remainder is IPC/transport. Passing the previous
+/// round trip as `previous_round_trip_ms` adds it to the histogram in
+/// `get_capture_stats` without an extra call.
+/// `profile`: "standard" (default), "high" or "extreme"; `chroma`: see `jpeg_sampling_factor`
+#[tauri::command]
+async fn capture_monitor_stream_timed(
+    monitor_id: usize,
+    client_timestamp_ms: f64,
+    profile: Option<String>,
+    chroma: Option<String>,
+    previous_round_trip_ms: Option<f64>,
+) -> Result<TimedStreamFrame, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let server_received_ms = unix_time_ms();
+
+    let profile = match profile.as_deref() {
+        None => "standard",
+        Some(name) => stream_profile(name).ok_or_else(|| format!("Unknown stream profile: {}", name))?,
+    };
+    let (sampling_factor, chroma) = jpeg_sampling_factor(chroma.as_deref())?;
 
-        let _t2 = Instant::now();
+    let image = with_capture_backpressure(monitor_id, profile, move || {
+        encode_monitor_frame(monitor_id, profile, sampling_factor, chroma)
+            .map(|jpeg| general_purpose::STANDARD.encode(&jpeg))
+    })
+    .await?;
 
-        let src_width = captured.width();
-        let src_height = captured.height();
+    let server_complete_ms = unix_time_ms();
+    let server_processing_ms = (server_complete_ms - server_received_ms).max(0.0);
 
-        // HIGH: Target 1280x720 for good quality with moderate bandwidth
-        let dst_width = 1280u32;
-        let dst_height = 720u32;
+    if let Ok(mut latency) = CAPTURE_LATENCY.lock() {
+        let latency = latency.get_or_insert_with(CaptureLatencyStats::default);
+        latency.server.record(server_processing_ms);
+        if let Some(round_trip_ms) = previous_round_trip_ms.filter(|ms| ms.is_finite() && *ms >= 0.0) {
+            latency.round_trip.record(round_trip_ms);
+        }
+    }
 
-        let src_image = Image::from_vec_u8(
-            src_width,
-            src_height,
-            captured.into_raw(),
-            fast_image_resize::PixelType::U8x4,
-        ).map_err(|e| format!("Failed to create source image: {}", e))?;
+    let protected_window = protected_content::last_detection(monitor_id);
 
-        let mut dst_image = Image::new(
-            dst_width,
-            dst_height,
-            fast_image_resize::PixelType::U8x4,
-        );
+    Ok(TimedStreamFrame {
+        image,
+        client_timestamp_ms,
+        server_received_ms,
+        server_complete_ms,
+        server_processing_ms,
+        protected_content_detected: protected_window.is_some(),
+        protected_window_title: protected_window.map(|w| w.title),
+    })
+}
 
-        // Resize using Lanczos3 (high quality, sharp for text/icons)
-        let mut resizer = Resizer::new();
-        resizer.resize(
-            &src_image,
-            &mut dst_image,
-            &ResizeOptions::new().resize_alg(ResizeAlg::Convolution(FilterType::Lanczos3)),
-        ).map_err(|e| format!("Failed to resize: {}", e))?;
+// ============================================================================
+// Multi-Monitor Capture
+// ============================================================================
+// Captures every monitor in one call with capture+resize+encode running in
+// parallel on blocking threads, so a frame set costs the slowest monitor
+// instead of the sum of all of them. Parallelism is capped (default: available
+// cores) because the SIMD resizer already saturates a core per frame.
+
+/// Max monitors encoded at once (0 = default, see `capture_parallelism`)
+static CAPTURE_PARALLELISM: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Effective parallelism: the tunable if set, else the number of available cores
+fn capture_parallelism() -> usize {
+    match CAPTURE_PARALLELISM.load(std::sync::atomic::Ordering::SeqCst) {
+        0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2),
+        n => n,
+    }
+}
 
-        let _t3 = Instant::now();
+/// Number of connected monitors
+#[cfg(not(feature = "mock-capture"))]
+fn monitor_count() -> Result<usize, String> {
+    xcap::Monitor::all()
+        .map(|monitors| monitors.len())
+        .map_err(|e| format!("Failed to get monitors: {}", e))
+}
 
-        // Convert RGBA to RGB for JPEG
-        let rgba_data = dst_image.into_vec();
-        let mut rgb_data = Vec::with_capacity((dst_width * dst_height * 3) as usize);
-        for chunk in rgba_data.chunks(4) {
-            rgb_data.push(chunk[0]); // R
-            rgb_data.push(chunk[1]); // G
-            rgb_data.push(chunk[2]); // B
-        }
+/// Number of canned monitors (`mock-capture` feature)
+#[cfg(feature = "mock-capture")]
+fn monitor_count() -> Result<usize, String> {
+    Ok(mock_capture::monitor_count())
+}
 
-        // HIGH: Use quality 98 for sharp text
-        let mut jpeg_buffer = Vec::with_capacity(800_000);
-        let encoder = jpeg_encoder::Encoder::new(&mut jpeg_buffer, 98);
-        encoder.encode(
-            &rgb_data,
-            dst_width as u16,
-            dst_height as u16,
-            jpeg_encoder::ColorType::Rgb,
-        ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+/// One monitor's result in a multi-monitor capture
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorFrame {
+    monitor_id: usize,
+    /// Base64-encoded JPEG (absent if this monitor failed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
-        let jpeg_data = jpeg_buffer;
-
-        let _t4 = Instant::now();
-
-        #[cfg(debug_assertions)]
-        {
-            static FRAME_COUNT_HIGH: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
-            let frame_num = FRAME_COUNT_HIGH.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            if frame_num % 30 == 0 {
-                eprintln!(
-                    "[capture_monitor_stream_high] Frame {}: Monitor::all={:?}ms, capture={:?}ms, resize={:?}ms, encode={:?}ms, total={:?}ms, size={}KB",
-                    frame_num,
-                    _t1.duration_since(_t0).as_millis(),
-                    _t2.duration_since(_t1).as_millis(),
-                    _t3.duration_since(_t2).as_millis(),
-                    _t4.duration_since(_t3).as_millis(),
-                    _t4.duration_since(_t0).as_millis(),
-                    jpeg_data.len() / 1024
-                );
+/// Set how many monitors may be encoded in parallel
+/// `threads`: 1 = sequential, None or 0 = default (available cores)
+/// Returns the effective parallelism
+#[tauri::command]
+fn set_capture_parallelism(threads: Option<usize>) -> usize {
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2);
+    CAPTURE_PARALLELISM.store(threads.unwrap_or(0).min(cores), std::sync::atomic::Ordering::SeqCst);
+    capture_parallelism()
+}
+
+/// Capture all monitors for streaming, encoding them in parallel
+/// Returns `[{ monitorId, image?, error? }]` in monitor order; a failing or busy
+/// monitor reports its error without failing the others.
+/// `profile`: "standard" (default), "high" or "extreme"; `chroma`: see `jpeg_sampling_factor`
+#[tauri::command]
+async fn capture_all_monitors_stream(
+    profile: Option<String>,
+    chroma: Option<String>,
+) -> Result<Vec<MonitorFrame>, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let profile = match profile.as_deref() {
+        None => "standard",
+        Some(name) => stream_profile(name).ok_or_else(|| format!("Unknown stream profile: {}", name))?,
+    };
+    let (sampling_factor, chroma) = jpeg_sampling_factor(chroma.as_deref())?;
+
+    let count = tokio::task::spawn_blocking(monitor_count)
+        .await
+        .map_err(|e| format!("Monitor query task failed: {}", e))??;
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(capture_parallelism().max(1)));
+
+    let captures = (0..count).map(|monitor_id| {
+        let permits = permits.clone();
+        async move {
+            let result = match permits.acquire().await {
+                Ok(_permit) => {
+                    with_capture_backpressure(monitor_id, profile, move || {
+                        encode_monitor_frame(monitor_id, profile, sampling_factor, chroma)
+                            .map(|jpeg| general_purpose::STANDARD.encode(&jpeg))
+                    })
+                    .await
+                }
+                Err(e) => Err(format!("Capture pool closed: {}", e)),
+            };
+            match result {
+                Ok(image) => MonitorFrame { monitor_id, image: Some(image), error: None },
+                Err(error) => MonitorFrame { monitor_id, image: None, error: Some(error) },
             }
         }
+    });
 
-        Ok::<String, String>(general_purpose::STANDARD.encode(&jpeg_data))
-    })
-    .await
-    .map_err(|e| format!("Capture task failed: {}", e))?
+    Ok(futures_util::future::join_all(captures).await)
 }
 
-/// Capture a specific monitor at EXTREME resolution for local network streaming
-/// Returns base64-encoded JPEG image at 1920x1080 with quality 100 for best visual fidelity
-/// Use this profile for local network connections where bandwidth is not a concern
+/// One monitor's thumbnail in a preview montage
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorPreview {
+    monitor_id: usize,
+    /// Full monitor size (the thumbnail is at most 640px wide)
+    width: u32,
+    height: u32,
+    /// Base64-encoded JPEG thumbnail (absent if this monitor failed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    jpeg_base64: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Capture a low-resolution thumbnail of every monitor, in parallel
+/// Returns `[{ monitorId, width, height, jpegBase64?, error? }]` in monitor
+/// order, for an overview before picking a monitor to stream. A failing
+/// monitor reports its error without failing the others.
 #[tauri::command]
-async fn capture_monitor_stream_extreme(monitor_id: usize) -> Result<String, String> {
-    tokio::task::spawn_blocking(move || {
-        use fast_image_resize::{images::Image, Resizer, ResizeOptions, ResizeAlg, FilterType};
-        use std::time::Instant;
+async fn capture_all_monitors_preview() -> Result<Vec<MonitorPreview>, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let count = tokio::task::spawn_blocking(monitor_count)
+        .await
+        .map_err(|e| format!("Monitor query task failed: {}", e))??;
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(capture_parallelism().max(1)));
+
+    let captures = (0..count).map(|monitor_id| {
+        let permits = permits.clone();
+        async move {
+            let result = match permits.acquire().await {
+                Ok(_permit) => tokio::task::spawn_blocking(move || capture_preview_jpeg(monitor_id))
+                    .await
+                    .map_err(|e| format!("Capture task failed: {}", e))
+                    .and_then(|preview| preview),
+                Err(e) => Err(format!("Capture pool closed: {}", e)),
+            };
+            match result {
+                Ok((jpeg, width, height)) => MonitorPreview {
+                    monitor_id,
+                    width,
+                    height,
+                    jpeg_base64: Some(general_purpose::STANDARD.encode(&jpeg)),
+                    error: None,
+                },
+                Err(error) => MonitorPreview {
+                    monitor_id,
+                    width: 0,
+                    height: 0,
+                    jpeg_base64: None,
+                    error: Some(error),
+                },
+            }
+        }
+    });
 
-        let _t0 = Instant::now();
+    Ok(futures_util::future::join_all(captures).await)
+}
 
-        let monitors = xcap::Monitor::all()
-            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+// ============================================================================
+// Active Window Capture (region of interest follows the foreground window)
+// ============================================================================
 
-        let _t1 = Instant::now();
+/// Pseudo monitor id used for active-window captures in the backpressure/frame cache
+#[cfg(target_os = "windows")]
+const ACTIVE_WINDOW_CAPTURE_ID: usize = usize::MAX;
 
-        let monitor = monitors
-            .get(monitor_id)
-            .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+/// Last foreign foreground window followed by `capture_active_window_stream`
+/// (kept while our own window has focus, e.g. the technician's chat)
+#[cfg(target_os = "windows")]
+static ACTIVE_CAPTURE_HWND: std::sync::Mutex<Option<isize>> = std::sync::Mutex::new(None);
 
-        let captured = monitor
-            .capture_image()
-            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+/// Resolve the window to capture: the foreground window, or the previously
+/// followed one while the foreground window belongs to this app
+#[cfg(target_os = "windows")]
+fn resolve_active_capture_window() -> Option<windows::Win32::Foundation::HWND> {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId, IsWindow};
 
-        let _t2 = Instant::now();
+    unsafe {
+        let foreground = GetForegroundWindow();
+        let mut pid = 0u32;
+        if !foreground.0.is_null() {
+            GetWindowThreadProcessId(foreground, Some(&mut pid as *mut u32));
+        }
 
-        let src_width = captured.width();
-        let src_height = captured.height();
+        let mut followed = ACTIVE_CAPTURE_HWND.lock().ok()?;
+        if !foreground.0.is_null() && pid != std::process::id() {
+            *followed = Some(foreground.0 as isize);
+            return Some(foreground);
+        }
 
-        // EXTREME: Target 1920x1080 for maximum quality on local network
-        let dst_width = 1920u32;
-        let dst_height = 1080u32;
+        let previous = HWND((*followed)? as *mut std::ffi::c_void);
+        IsWindow(previous).as_bool().then_some(previous)
+    }
+}
 
-        // Create source image from captured RGBA data
-        let src_image = Image::from_vec_u8(
-            src_width,
-            src_height,
-            captured.into_raw(),
-            fast_image_resize::PixelType::U8x4,
-        ).map_err(|e| format!("Failed to create source image: {}", e))?;
+/// Capture only the active (foreground) window for streaming
+///
+/// Follows focus changes: each call captures whichever window is in front,
+/// keeping the last followed window while this app itself has focus.
+/// Returns JSON: `{ image, title, x, y, width, height }` where `image` is a
+/// base64 JPEG of the window region and the bounds are screen coordinates.
+/// `chroma`: "444" (default, sharp text) or "420" (smaller frames), see `jpeg_sampling_factor`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+async fn capture_active_window_stream(chroma: Option<String>) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let (sampling_factor, _chroma) = jpeg_sampling_factor(chroma.as_deref())?;
 
-        // Create destination image
-        let mut dst_image = Image::new(
-            dst_width,
-            dst_height,
-            fast_image_resize::PixelType::U8x4,
-        );
+    with_capture_backpressure(ACTIVE_WINDOW_CAPTURE_ID, "window", move || {
+        use image::GenericImageView;
+        use windows::Win32::Foundation::RECT;
+        use windows::Win32::UI::WindowsAndMessaging::{GetWindowRect, GetWindowTextW};
+
+        let hwnd = resolve_active_capture_window()
+            .ok_or_else(|| "No active window to capture".to_string())?;
+
+        let (rect, title) = unsafe {
+            let mut rect = RECT::default();
+            GetWindowRect(hwnd, &mut rect).map_err(|e| format!("Failed to get window bounds: {}", e))?;
+            let mut title_buf = [0u16; 512];
+            let len = GetWindowTextW(hwnd, &mut title_buf);
+            (rect, String::from_utf16_lossy(&title_buf[..len.max(0) as usize]))
+        };
 
-        // Resize using Lanczos3 (high quality, sharp for text/icons)
-        let mut resizer = Resizer::new();
-        resizer.resize(
-            &src_image,
-            &mut dst_image,
-            &ResizeOptions::new().resize_alg(ResizeAlg::Convolution(FilterType::Lanczos3)),
-        ).map_err(|e| format!("Failed to resize: {}", e))?;
+        // Capture the monitor holding the window's center, then crop to the window
+        let center_x = rect.left + (rect.right - rect.left) / 2;
+        let center_y = rect.top + (rect.bottom - rect.top) / 2;
 
-        let _t3 = Instant::now();
+        let monitors = xcap::Monitor::all()
+            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+        let monitor = monitors
+            .iter()
+            .find(|m| {
+                let (x, y) = (m.x().unwrap_or(0), m.y().unwrap_or(0));
+                let (w, h) = (m.width().unwrap_or(0) as i32, m.height().unwrap_or(0) as i32);
+                center_x >= x && center_x < x + w && center_y >= y && center_y < y + h
+            })
+            .or_else(|| monitors.first())
+            .ok_or_else(|| "No monitors found".to_string())?;
 
-        // Convert RGBA to RGB for JPEG
-        let rgba_data = dst_image.into_vec();
-        let mut rgb_data = Vec::with_capacity((dst_width * dst_height * 3) as usize);
-        for chunk in rgba_data.chunks(4) {
-            rgb_data.push(chunk[0]); // R
-            rgb_data.push(chunk[1]); // G
-            rgb_data.push(chunk[2]); // B
+        let (monitor_x, monitor_y) = (monitor.x().unwrap_or(0), monitor.y().unwrap_or(0));
+        let captured = monitor
+            .capture_image()
+            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+        let (img_width, img_height) = captured.dimensions();
+
+        // Clip the window rect to the captured monitor
+        let left = (rect.left - monitor_x).clamp(0, img_width as i32) as u32;
+        let top = (rect.top - monitor_y).clamp(0, img_height as i32) as u32;
+        let right = (rect.right - monitor_x).clamp(0, img_width as i32) as u32;
+        let bottom = (rect.bottom - monitor_y).clamp(0, img_height as i32) as u32;
+        if right <= left || bottom <= top {
+            return Err("Active window is not on screen".to_string());
         }
 
-        // EXTREME: Use quality 100 for pristine text/UI clarity (near-lossless)
-        let mut jpeg_buffer = Vec::with_capacity(1_500_000); // Larger buffer for 1080p at max quality
-        let encoder = jpeg_encoder::Encoder::new(&mut jpeg_buffer, 100);
+        let region = captured.view(left, top, right - left, bottom - top).to_image();
+        let (width, height) = region.dimensions();
+        let rgb_image = image::DynamicImage::ImageRgba8(region).to_rgb8();
+
+        let mut jpeg_buffer = Vec::with_capacity(300_000);
+        let mut encoder = jpeg_encoder::Encoder::new(&mut jpeg_buffer, 95);
+        encoder.set_sampling_factor(sampling_factor);
         encoder.encode(
-            &rgb_data,
-            dst_width as u16,
-            dst_height as u16,
+            rgb_image.as_raw(),
+            width as u16,
+            height as u16,
             jpeg_encoder::ColorType::Rgb,
         ).map_err(|e| format!("Failed to encode JPEG: {}", e))?;
 
-        let jpeg_data = jpeg_buffer;
-
-        let _t4 = Instant::now();
-
-        // Log timing breakdown (only occasionally, debug builds only)
-        #[cfg(debug_assertions)]
-        {
-            static FRAME_COUNT_EXTREME: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
-            let frame_num = FRAME_COUNT_EXTREME.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-            if frame_num % 30 == 0 {
-                eprintln!(
-                    "[capture_monitor_stream_extreme] Frame {}: Monitor::all={:?}ms, capture={:?}ms, resize={:?}ms, encode={:?}ms, total={:?}ms, size={}KB",
-                    frame_num,
-                    _t1.duration_since(_t0).as_millis(),
-                    _t2.duration_since(_t1).as_millis(),
-                    _t3.duration_since(_t2).as_millis(),
-                    _t4.duration_since(_t3).as_millis(),
-                    _t4.duration_since(_t0).as_millis(),
-                    jpeg_data.len() / 1024
-                );
-            }
-        }
-
-        Ok::<String, String>(general_purpose::STANDARD.encode(&jpeg_data))
+        let payload = serde_json::json!({
+            "image": general_purpose::STANDARD.encode(&jpeg_buffer),
+            "title": title,
+            "x": monitor_x + left as i32,
+            "y": monitor_y + top as i32,
+            "width": width,
+            "height": height,
+        });
+        serde_json::to_string(&payload).map_err(|e| format!("Failed to serialize capture: {}", e))
     })
     .await
-    .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+async fn capture_active_window_stream(_chroma: Option<String>) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    Err("Active window capture is only supported on Windows".to_string())
+}
+
+/// Map a notification sound name to a WinRT toast audio source
+///
+/// Accepts short names ("default", "mail", "reminder", "alarm", "call", "silent", ...)
+/// as well as full `ms-winsoundevent:Notification.*` URIs.
+/// Returns `Ok(None)` for "silent".
+#[cfg(target_os = "windows")]
+fn toast_sound_from_name(name: &str) -> Result<Option<tauri_winrt_notification::Sound>, String> {
+    use tauri_winrt_notification::{LoopableSound, Sound};
+
+    let normalized = name.trim().to_lowercase();
+    let normalized = normalized
+        .strip_prefix("ms-winsoundevent:notification.")
+        .unwrap_or(&normalized);
+
+    match normalized {
+        "silent" | "none" => Ok(None),
+        "default" => Ok(Some(Sound::Default)),
+        "im" => Ok(Some(Sound::IM)),
+        "mail" => Ok(Some(Sound::Mail)),
+        "reminder" => Ok(Some(Sound::Reminder)),
+        "sms" => Ok(Some(Sound::SMS)),
+        "alarm" | "looping.alarm" => Ok(Some(Sound::Loop(LoopableSound::Alarm))),
+        "call" | "looping.call" => Ok(Some(Sound::Loop(LoopableSound::Call))),
+        other => Err(format!("Unknown notification sound: {}", other)),
+    }
 }
 
 /// Show a system notification with click-to-open functionality (Windows)
 /// Uses tauri-winrt-notification for native Windows toast notifications with action callbacks
+///
+/// Optional parameters:
+/// - `sound`: toast audio ("default", "mail", "alarm", "call", "silent" or an
+///   `ms-winsoundevent:` URI). Defaults to the system notification sound.
+/// - `priority`: "high"/"urgent" keeps the toast on screen longer using the
+///   reminder scenario; anything else shows a normal toast.
 #[cfg(target_os = "windows")]
 #[tauri::command]
 async fn show_system_notification(
@@ -1272,8 +3232,20 @@ async fn show_system_notification(
     body: String,
     _notification_type: Option<String>,
     ticket_id: Option<String>,
+    sound: Option<String>,
+    priority: Option<String>,
 ) -> Result<(), String> {
-    use tauri_winrt_notification::Toast;
+    use tauri_winrt_notification::{Duration, Scenario, Toast};
+
+    let toast_sound = match sound.as_deref() {
+        Some(name) => toast_sound_from_name(name)?,
+        None => Some(tauri_winrt_notification::Sound::Default),
+    };
+
+    let is_high_priority = matches!(
+        priority.as_deref().map(|p| p.to_lowercase()).as_deref(),
+        Some("high") | Some("urgent")
+    );
 
     let app_handle = app.clone();
     let ticket_id_clone = ticket_id.clone();
@@ -1285,13 +3257,25 @@ async fn show_system_notification(
         body
     };
 
-    Toast::new("supportcenter.requester")
+    let mut toast = Toast::new("supportcenter.requester")
         .title(&title)
         .text1(&truncated_body)
+        .sound(toast_sound);
+
+    // High priority: stay on screen until dismissed (reminder scenario) with long duration
+    if is_high_priority {
+        toast = toast.duration(Duration::Long).scenario(Scenario::Reminder);
+    }
+
+    toast
         .on_activated(move |_action| {
             // Emit navigation event when notification is clicked
             if let Some(ref tid) = ticket_id_clone {
                 let _ = app_handle.emit("navigate-to-chat", tid.clone());
+                navigation_history::record(&app_handle, tid, "notification");
+
+                // Remember what the user was focused on so dismissing gives it back
+                save_foreground_state_for(&app_handle);
 
                 // Show and focus the main window
                 if let Some(window) = app_handle.get_webview_window("main") {
@@ -1303,7 +3287,7 @@ async fn show_system_notification(
                     let _ = window.show();
                     // Temporarily set always on top to bring to front, then reset
                     let _ = window.set_always_on_top(true);
-                    let _ = window.set_focus();
+                    force_focus(&window);
                     // Reset always_on_top after a short delay to avoid persistent top behavior
                     let _ = window.set_always_on_top(false);
                 }
@@ -1323,6 +3307,8 @@ async fn show_system_notification(
     body: String,
     _notification_type: Option<String>,
     _ticket_id: Option<String>,
+    sound: Option<String>,
+    _priority: Option<String>,
 ) -> Result<(), String> {
     use tauri_plugin_notification::NotificationExt;
 
@@ -1333,10 +3319,17 @@ async fn show_system_notification(
         body
     };
 
-    app.notification()
+    let mut builder = app.notification()
         .builder()
         .title(title)
-        .body(truncated_body)
+        .body(truncated_body);
+
+    // Priority has no equivalent here; only pass through an explicit sound name
+    if let Some(sound) = sound.filter(|s| !s.eq_ignore_ascii_case("silent")) {
+        builder = builder.sound(sound);
+    }
+
+    builder
         .show()
         .map_err(|e| format!("Failed to show notification: {}", e))
 }
@@ -1432,7 +3425,7 @@ fn setup_floating_icon(app: &AppHandle) {
                 }
                 let _ = main_window.show();
                 let _ = main_window.set_always_on_top(true);
-                let _ = main_window.set_focus();
+                force_focus(&main_window);
             }
         }
     });
@@ -1502,15 +3495,196 @@ fn position_window_with_known_icon_pos(
     }));
 }
 
+// ============================================================================
+// Floating Icon Position Persistence
+// ============================================================================
+
+/// Floating icon margins from the screen edge for the default bottom-right position
+const FLOATING_ICON_MARGIN: i32 = 20;
+/// Extra bottom offset to keep the default position above the Windows taskbar
+const FLOATING_ICON_TASKBAR_OFFSET: i32 = 50;
+
+/// Set once startup positioning is finished; moves before this are ours, not the user's
+static FLOATING_ICON_TRACKING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+/// Incremented on every floating icon move; used to debounce saves while dragging
+static FLOATING_ICON_MOVE_GEN: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Default floating icon position: bottom-right corner of the primary monitor (above taskbar)
+fn default_floating_icon_position(icon_width: i32, icon_height: i32) -> (i32, i32) {
+    let (monitor_x, monitor_y, screen_width, screen_height) = get_primary_monitor_dims();
+    (
+        monitor_x + screen_width - icon_width - FLOATING_ICON_MARGIN,
+        monitor_y + screen_height - icon_height - FLOATING_ICON_MARGIN - FLOATING_ICON_TASKBAR_OFFSET,
+    )
+}
+
+/// Check that an icon rectangle lies fully within one of the connected monitors
+fn is_floating_icon_position_visible(x: i32, y: i32, width: i32, height: i32) -> bool {
+    let monitors = match xcap::Monitor::all() {
+        Ok(monitors) => monitors,
+        Err(_) => return false,
+    };
+
+    monitors.iter().any(|m| {
+        let mx = m.x().unwrap_or(0);
+        let my = m.y().unwrap_or(0);
+        let mw = m.width().unwrap_or(0) as i32;
+        let mh = m.height().unwrap_or(0) as i32;
+        x >= mx && y >= my && x + width <= mx + mw && y + height <= my + mh
+    })
+}
+
+/// Load the saved floating icon position, if it is still on a connected monitor
+fn load_floating_icon_position(app: &AppHandle, icon_width: i32, icon_height: i32) -> Option<(i32, i32)> {
+    let value = storage::get_value(app, storage::KEY_FLOATING_ICON_POSITION).ok()??;
+    let x = value.get("x")?.as_i64()? as i32;
+    let y = value.get("y")?.as_i64()? as i32;
+
+    if is_floating_icon_position_visible(x, y, icon_width, icon_height) {
+        Some((x, y))
+    } else {
+        debug_eprintln!("[floating-icon] Saved position ({}, {}) is off-screen, using default", x, y);
+        None
+    }
+}
+
+/// Record a floating icon move (called from the Moved window event)
+/// Saves are debounced so dragging doesn't write the store on every pixel.
+fn on_floating_icon_moved(app: &AppHandle, position: tauri::PhysicalPosition<i32>) {
+    use std::sync::atomic::Ordering;
+
+    if !FLOATING_ICON_TRACKING.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let generation = FLOATING_ICON_MOVE_GEN.fetch_add(1, Ordering::SeqCst) + 1;
+    let app_handle = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+        if FLOATING_ICON_MOVE_GEN.load(Ordering::SeqCst) != generation {
+            return; // A newer move superseded this one
+        }
+        let value = serde_json::json!({ "x": position.x, "y": position.y });
+        if let Err(_e) = storage::set_value(&app_handle, storage::KEY_FLOATING_ICON_POSITION, value) {
+            debug_eprintln!("[floating-icon] Failed to save position: {}", _e);
+        }
+    });
+}
+
+/// Forget the saved floating icon position and move it back to the default corner
+#[tauri::command]
+fn reset_floating_icon_position(app: AppHandle) -> Result<(), String> {
+    storage::delete_value(&app, storage::KEY_FLOATING_ICON_POSITION)?;
+
+    let floating_icon = app
+        .get_webview_window("floating-icon")
+        .ok_or_else(|| "Floating icon window not found".to_string())?;
+    let size = floating_icon
+        .outer_size()
+        .map_err(|e| format!("Failed to get floating icon size: {}", e))?;
+    let (x, y) = default_floating_icon_position(size.width as i32, size.height as i32);
+
+    floating_icon
+        .set_position(tauri::Position::Physical(tauri::PhysicalPosition { x, y }))
+        .map_err(|e| format!("Failed to move floating icon: {}", e))
+}
+
 // ============================================================================
 // Remote Input Injection Commands
 // ============================================================================
 
+/// Monitor rectangles (x, y, width, height) in virtual-desktop coordinates
+/// Cached because remote mouse moves arrive at high frequency; cleared by `refresh_monitors`.
+static CACHED_MONITOR_RECTS: std::sync::Mutex<Option<Vec<(i32, i32, i32, i32)>>> = std::sync::Mutex::new(None);
+
+/// Get the rectangle of a monitor by index (same indices as `get_monitors`)
+fn get_monitor_rect(monitor_id: usize) -> Result<(i32, i32, i32, i32), String> {
+    let mut cache = CACHED_MONITOR_RECTS
+        .lock()
+        .map_err(|_| "Monitor cache lock poisoned".to_string())?;
+
+    if cache.is_none() {
+        let monitors = xcap::Monitor::all()
+            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+        let rects = monitors
+            .iter()
+            .map(|m| (
+                m.x().unwrap_or(0),
+                m.y().unwrap_or(0),
+                m.width().unwrap_or(1920) as i32,
+                m.height().unwrap_or(1080) as i32,
+            ))
+            .collect();
+        *cache = Some(rects);
+    }
+
+    cache
+        .as_ref()
+        .and_then(|rects| rects.get(monitor_id).copied())
+        .ok_or_else(|| format!("Monitor {} not found", monitor_id))
+}
+
+/// Clear the cached monitor rectangles (call when the display layout changes)
+fn invalidate_monitor_rects() {
+    if let Ok(mut cache) = CACHED_MONITOR_RECTS.lock() {
+        *cache = None;
+    }
+}
+
+/// Get the virtual desktop rectangle (x, y, width, height) spanning all monitors
+#[cfg(target_os = "windows")]
+fn get_virtual_screen_rect() -> (i32, i32, i32, i32) {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetSystemMetrics, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    };
+
+    unsafe {
+        (
+            GetSystemMetrics(SM_XVIRTUALSCREEN),
+            GetSystemMetrics(SM_YVIRTUALSCREEN),
+            GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        )
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn get_virtual_screen_rect() -> (i32, i32, i32, i32) {
+    let (width, height) = get_screen_dims_for_mouse();
+    (0, 0, width, height)
+}
+
+/// Map normalized (0.0-1.0) coordinates on a monitor to absolute 0-65535
+/// coordinates over the whole virtual desktop (for MOUSEEVENTF_VIRTUALDESK).
+/// Coordinates are clamped to the monitor so the cursor can't land on a neighbour.
+fn map_to_virtual_desktop(monitor_id: usize, x: f64, y: f64) -> Result<(i32, i32), String> {
+    let (mon_x, mon_y, mon_w, mon_h) = get_monitor_rect(monitor_id)?;
+    let (virt_x, virt_y, virt_w, virt_h) = get_virtual_screen_rect();
+
+    let px = mon_x + (x.clamp(0.0, 1.0) * (mon_w - 1).max(0) as f64) as i32;
+    let py = mon_y + (y.clamp(0.0, 1.0) * (mon_h - 1).max(0) as f64) as i32;
+
+    let abs_x = ((px - virt_x) as i64 * 65535 / (virt_w - 1).max(1) as i64) as i32;
+    let abs_y = ((py - virt_y) as i64 * 65535 / (virt_h - 1).max(1) as i64) as i32;
+
+    Ok((abs_x.clamp(0, 65535), abs_y.clamp(0, 65535)))
+}
+
 /// Inject mouse move event at normalized coordinates (0.0-1.0)
 /// Normalized coords from agent are mapped to actual screen position.
 /// Uses GetSystemMetrics-based dimensions for accurate Windows input mapping.
+///
+/// When `monitor_id` is given, coordinates are relative to that monitor and are
+/// mapped over the whole virtual desktop so secondary displays are reachable.
 #[tauri::command]
-fn remote_mouse_move(x: f64, y: f64) -> Result<(), String> {
+fn remote_mouse_move(x: f64, y: f64, monitor_id: Option<usize>) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
+    if let Some(monitor_id) = monitor_id {
+        let (abs_x, abs_y) = map_to_virtual_desktop(monitor_id, x, y)?;
+        return remote_input::inject_mouse_move_virtual(abs_x, abs_y);
+    }
+
     // Use GetSystemMetrics-based dimensions for accurate mouse positioning
     let (width, height) = get_screen_dims_for_mouse();
     let screen_x = (x * width as f64) as i32;
@@ -1522,19 +3696,31 @@ fn remote_mouse_move(x: f64, y: f64) -> Result<(), String> {
 /// Inject mouse button down event
 #[tauri::command]
 fn remote_mouse_down(button: u32) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
     remote_input::inject_mouse_down(button)
 }
 
 /// Inject mouse button up event
 #[tauri::command]
 fn remote_mouse_up(button: u32) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
     remote_input::inject_mouse_up(button)
 }
 
 /// Inject mouse click at normalized coordinates (0.0-1.0)
 /// Normalized coords from agent are mapped to actual screen position.
+/// `monitor_id` selects the target monitor the same way as `remote_mouse_move`.
 #[tauri::command]
-fn remote_mouse_click(x: f64, y: f64, button: u32) -> Result<(), String> {
+fn remote_mouse_click(x: f64, y: f64, button: u32, monitor_id: Option<usize>) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
+    if let Some(monitor_id) = monitor_id {
+        let (abs_x, abs_y) = map_to_virtual_desktop(monitor_id, x, y)?;
+        return remote_input::inject_mouse_click_virtual(abs_x, abs_y, button);
+    }
+
     // Use GetSystemMetrics-based dimensions for accurate mouse positioning
     let (width, height) = get_screen_dims_for_mouse();
     let screen_x = (x * width as f64) as i32;
@@ -1546,19 +3732,50 @@ fn remote_mouse_click(x: f64, y: f64, button: u32) -> Result<(), String> {
 /// Inject mouse wheel scroll
 #[tauri::command]
 fn remote_mouse_wheel(delta: i32) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
     remote_input::inject_mouse_wheel(delta)
 }
 
 /// Inject keyboard key down event
+/// `key` (the character typed on the controller, e.g. KeyboardEvent.key) is
+/// optional; when given it is mapped through this machine's keyboard layout.
 #[tauri::command]
-fn remote_key_down(code: String, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
-    remote_input::inject_key_down(&code, ctrl, shift, alt)
+fn remote_key_down(code: String, key: Option<String>, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
+    remote_input::inject_key_down(&code, key.as_deref(), ctrl, shift, alt)
 }
 
 /// Inject keyboard key up event
 #[tauri::command]
-fn remote_key_up(code: String, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
-    remote_input::inject_key_up(&code, ctrl, shift, alt)
+fn remote_key_up(code: String, key: Option<String>, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
+    remote_input::inject_key_up(&code, key.as_deref(), ctrl, shift, alt)
+}
+
+/// Longest text accepted by `remote_type_text`
+const MAX_REMOTE_TEXT_CHARS: usize = 4096;
+
+/// Type text on this machine, mapped through the active keyboard layout
+/// Characters the layout can't type are sent as Unicode input.
+#[tauri::command]
+fn remote_type_text(text: String) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    remote_consent::require_consent()?;
+    if text.chars().count() > MAX_REMOTE_TEXT_CHARS {
+        return Err(format!("Text is limited to {} characters", MAX_REMOTE_TEXT_CHARS));
+    }
+    remote_input::inject_text(&text)
+}
+
+/// Get the keyboard layout of the foreground window and the installed layouts
+/// Returns JSON: `{ active: "0000040C", activeLangId: "040C", installed: [..] }`
+/// (KLID-style hex; low word = language ID).
+#[tauri::command]
+fn get_keyboard_layout() -> Result<String, String> {
+    remote_input::keyboard_layout_info()
 }
 
 // ============================================================================
@@ -1566,11 +3783,10 @@ fn remote_key_up(code: String, ctrl: bool, shift: bool, alt: bool) -> Result<(),
 // ============================================================================
 
 /// Start UAC detection and emit events when UAC state changes
+/// Uses the shared detector, so calling this while already running is a no-op
 #[tauri::command]
 async fn start_uac_detection(app: AppHandle) -> Result<(), String> {
-    let detector = uac_detector::UACDetector::new();
-
-    detector.start(move |is_active| {
+    uac_detector::global().start(move |is_active| {
         let event_type = if is_active { "uac_detected" } else { "uac_dismissed" };
         let _ = app.emit(event_type, ());
     }).await;
@@ -1578,12 +3794,21 @@ async fn start_uac_detection(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
-/// Check if UAC is currently active (one-time check)
+/// Stop UAC detection (no-op if not running)
+#[tauri::command]
+fn stop_uac_detection() -> Result<(), String> {
+    let detector = uac_detector::global();
+    if detector.is_running() {
+        detector.stop();
+        debug_eprintln!("[uac] UAC detection stopped");
+    }
+    Ok(())
+}
+
+/// Check if UAC is currently active (as last seen by the running detector)
 #[tauri::command]
 fn is_uac_active() -> Result<bool, String> {
-    // Create a temporary detector to check current state
-    let detector = uac_detector::UACDetector::new();
-    Ok(detector.is_active())
+    Ok(uac_detector::global().is_active())
 }
 
 // ============================================================================
@@ -1616,6 +3841,20 @@ fn disable_autostart() -> Result<autostart::AutostartEnableResult, String> {
     autostart::disable_autostart()
 }
 
+/// List all HKCU Run entries (ours and other apps') with their Startup tab state
+/// Returns `[{ name, value, enabled, isOurs }]` for auto-start conflict diagnosis
+#[tauri::command]
+fn list_run_entries() -> Result<Vec<autostart::RunEntry>, String> {
+    autostart::list_run_entries()
+}
+
+/// Check whether the app runs from a proper install location (vs. temp/Downloads)
+/// Returns `{ isProperlyInstalled, path, location, recommendation }`
+#[tauri::command]
+fn get_install_location_status() -> Result<autostart::InstallLocationStatus, String> {
+    autostart::get_install_location_status()
+}
+
 /// Mark profile setup as complete and enable auto-start
 ///
 /// This is the primary entry point for enabling auto-start after user completes profile setup.
@@ -1722,6 +3961,31 @@ fn storage_has(app: AppHandle, key: String) -> Result<bool, String> {
     storage::has_key(&app, &key)
 }
 
+/// Verify the store file and repair it if corrupted
+/// Returns JSON: `{ healthy, repaired, backupPath, lostKeys }`
+#[tauri::command]
+fn storage_verify_and_repair(app: AppHandle) -> Result<String, String> {
+    let report = storage::verify_and_repair(&app)?;
+    serde_json::to_string(&report)
+        .map_err(|e| format!("Failed to serialize repair report: {}", e))
+}
+
+/// Export the non-secret settings as a portable JSON profile (auth keys excluded)
+#[tauri::command]
+fn export_settings_profile(app: AppHandle) -> Result<String, String> {
+    storage::export_settings_profile(&app)
+}
+
+/// Import a settings profile from `export_settings_profile`
+/// `overwrite`: replace values already set on this machine
+/// Returns JSON: `{ imported, kept, rejected }` (key lists)
+#[tauri::command]
+fn import_settings_profile(app: AppHandle, json: String, overwrite: bool) -> Result<String, String> {
+    let report = storage::import_settings_profile(&app, &json, overwrite)?;
+    serde_json::to_string(&report)
+        .map_err(|e| format!("Failed to serialize import report: {}", e))
+}
+
 /// Migrate data from localStorage to Tauri Store (one-time operation)
 #[tauri::command]
 fn storage_migrate_from_local(app: AppHandle, data: serde_json::Value) -> Result<(), String> {
@@ -1778,9 +4042,19 @@ pub fn run() {
         return;
     }
 
+    // Check if running as the elevated broker (launched by start_elevated_broker)
+    if elevation_broker::is_broker() {
+        elevation_broker::run_broker();
+        return;
+    }
+
     // Initialize watchdog for auto-restart (main process only)
     watchdog::init_watchdog();
 
+    let launch_reason = launch_reason_from_args(&env::args().collect::<Vec<_>>());
+    let _ = LAUNCH_REASON.set(launch_reason);
+    debug_eprintln!("[App] Launch reason: {}", launch_reason);
+
     // Try to load .env file from various locations (works in both debug and release)
     // This supports local testing with release builds - just place .env next to the exe
     // In production deployments, environment variables should be set externally
@@ -1814,10 +4088,17 @@ pub fn run() {
             if !args.is_empty() {
                 let _ = app.emit("second-instance-launched", serde_json::json!({
                     "args": args,
-                    "cwd": cwd
+                    "cwd": cwd,
+                    "launchReason": launch_reason_from_args(&args)
                 }));
             }
 
+            // A duplicate autostart launch (e.g. Run entry plus logon task) shouldn't pop the window
+            if launch_reason_from_args(&args) == "autostart" {
+                debug_eprintln!("[Single Instance] Autostart launch, leaving window state as is");
+                return;
+            }
+
             // Bring the main window to the foreground
             if let Some(main_window) = app.get_webview_window("main") {
                 // Restore if minimized
@@ -1836,7 +4117,7 @@ pub fn run() {
 
                 // Bring to front and focus
                 let _ = main_window.set_always_on_top(true);
-                let _ = main_window.set_focus();
+                force_focus(&main_window);
 
                 debug_eprintln!("[Single Instance] Main window focused");
             } else {
@@ -1853,21 +4134,31 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         // Register the shell plugin for opening URLs
         .plugin(tauri_plugin_shell::init())
-        // Register commands
+        // Register commands (platform-specific groups are listed in
+        // WINDOWS_ONLY_CAPABILITIES for get_capabilities)
         .invoke_handler(tauri::generate_handler![
             greet,
             get_system_username,
             get_computer_name,
+            get_computer_names,
             get_os_info,
+            locale::get_user_locale,
             get_local_ip,
             get_server_config_unlock_key,
             show_window,
+            hide_to_tray,
             hide_window,
             toggle_window,
+            save_foreground_state,
+            restore_foreground_state,
+            get_foreground_lock_timeout,
+            set_foreground_lock_timeout,
             quit_app,
             handle_shutdown,
+            graceful_quit,
             capture_screen,
             capture_screen_region,
+            scroll_capture::capture_window_scrolling,
             get_monitors,
             refresh_monitors,
             get_windows,
@@ -1875,11 +4166,40 @@ pub fn run() {
             capture_monitor_stream,
             capture_monitor_stream_high,
             capture_monitor_stream_extreme,
+            capture_monitor_stream_timed,
+            capture_all_monitors_stream,
+            capture_all_monitors_preview,
+            set_capture_parallelism,
+            capture_active_window_stream,
+            get_capture_in_flight_count,
+            get_capture_stats,
+            protected_content::get_protected_content,
+            stream_adaptation::report_link_stats,
+            stream_adaptation::get_stream_recommendation,
+            stream_adaptation::set_stream_bandwidth_budget,
+            stream_adaptation::get_bandwidth_used,
+            set_capture_buffer_pooling,
+            reset_capture_buffers,
+            stream_server::start_stream_server,
+            stream_server::stop_stream_server,
+            capture_loop::start_capture_loop,
+            capture_loop::stop_capture_loop,
+            session_recording::start_session_recording,
+            session_recording::stop_session_recording,
+            elevation_broker::start_elevated_broker,
+            elevation_broker::broker_exec,
+            elevation_broker::stop_elevated_broker,
             show_system_notification,
+            notification_check::test_notification,
             is_window_focused,
             update_floating_icon_unread_count,
             trigger_floating_icon_flash,
             update_floating_icon_remote_state,
+            reset_floating_icon_position,
+            remote_consent::grant_remote_consent,
+            remote_consent::revoke_remote_consent,
+            remote_consent::get_remote_consent,
+            input_benchmark::benchmark_input_latency,
             remote_mouse_move,
             remote_mouse_down,
             remote_mouse_up,
@@ -1887,12 +4207,18 @@ pub fn run() {
             remote_mouse_wheel,
             remote_key_down,
             remote_key_up,
+            remote_type_text,
+            get_keyboard_layout,
+            remote_open::remote_open,
             start_uac_detection,
+            stop_uac_detection,
             is_uac_active,
             // Auto-start commands
             check_autostart_status,
             enable_autostart,
             disable_autostart,
+            get_install_location_status,
+            list_run_entries,
             mark_profile_setup_complete,
             is_profile_setup_complete,
             // Storage commands
@@ -1901,24 +4227,38 @@ pub fn run() {
             storage_delete,
             storage_has,
             storage_migrate_from_local,
+            storage_verify_and_repair,
+            export_settings_profile,
+            import_settings_profile,
             auth_storage_get,
             auth_storage_set,
             auth_storage_delete,
             // Phase 8: Silent upgrade commands
             download_installer,
             execute_installer_and_exit,
+            updater::perform_update,
+            updater::verify_installer_version,
             is_elevated,
             get_app_version,
+            get_security_info,
+            get_capabilities,
+            feature_policy::get_feature_policy,
+            // Proxy configuration commands
+            get_system_proxy,
+            set_proxy_override,
             // Session logging commands
             logging::log_write,
             logging::log_write_batch,
+            logging::log_flush,
             logging::log_get_directory,
             logging::log_list_files,
             logging::log_read_file,
             logging::log_get_total_size,
             logging::log_force_rotate,
+            logging::log_compact,
             logging::log_clear_all,
             logging::log_init,
+            logging::save_screenshot_to_logs,
             // Image storage commands
             image_storage::image_storage_write,
             image_storage::image_storage_read,
@@ -1927,7 +4267,48 @@ pub fn run() {
             image_storage::image_storage_clear_all,
             image_storage::image_storage_get_size,
             image_storage::image_storage_get_directory,
+            // Diagnostics commands
+            windows_update::get_pending_windows_updates,
+            event_log::query_event_log,
+            display_modes::get_display_modes,
+            display_modes::set_display_mode,
+            display_modes::revert_display_mode,
+            scheduled_tasks::list_scheduled_tasks,
+            scheduled_tasks::run_scheduled_task,
+            scheduled_tasks::stop_scheduled_task,
+            startup_programs::list_startup_programs,
+            startup_programs::disable_startup_program,
+            services::list_services,
+            services::control_service,
+            print_queue::list_printers,
+            print_queue::list_print_jobs,
+            print_queue::clear_print_queue,
+            network_drives::list_mapped_drives,
+            network_drives::list_network_shares,
+            security_products::get_security_products,
+            network_fixes::flush_dns,
+            network_fixes::reset_winsock,
+            network_fixes::renew_dhcp_lease,
+            network_config::get_network_config,
+            resource_usage::get_resource_usage,
+            network_overrides::set_interface_dns,
+            network_overrides::add_hosts_entry,
+            network_overrides::remove_hosts_entry,
+            network_overrides::revert_network_changes,
+            // Power commands
+            power::prevent_sleep,
+            power::get_power_status,
+            // Launch reason
+            get_launch_reason,
+            // Navigation history
+            navigation_history::record_navigation,
+            navigation_history::get_navigation_history,
+            // Scheduled captures
+            scheduled_capture::schedule_capture,
+            scheduled_capture::list_scheduled_captures,
+            scheduled_capture::cancel_scheduled_capture,
             // Watchdog commands (auto-restart)
+            instance_health::check_instance_health,
             is_watchdog_process,
             is_watchdog_enabled,
             trigger_restart
@@ -1939,6 +4320,20 @@ pub fn run() {
                     api.prevent_close();
                     let _ = window.hide();
                 }
+                tauri::WindowEvent::Resized(_) => {
+                    // Minimize sends the main window to the tray. Hide it while still
+                    // minimized (no unminimize here - restoring before hiding flashes the
+                    // window); the show paths unminimize right before showing.
+                    if window.label() == "main" && window.is_minimized().unwrap_or(false) {
+                        let _ = window.hide();
+                    }
+                }
+                tauri::WindowEvent::Moved(position) => {
+                    // Persist user-dragged floating icon position
+                    if window.label() == "floating-icon" {
+                        on_floating_icon_moved(window.app_handle(), *position);
+                    }
+                }
                 tauri::WindowEvent::Focused(is_focused) => {
                     // Handle taskbar click restoration for main window
                     if *is_focused && window.label() == "main" {
@@ -1985,6 +4380,17 @@ pub fn run() {
                 }
             }
 
+            // Repair a corrupt store file before anything opens the store
+            match storage::verify_and_repair(&app.handle()) {
+                Ok(report) if report.repaired => {
+                    debug_eprintln!("[App] Warning: Store was corrupt and has been reset ({} keys lost)", report.lost_keys.len());
+                }
+                Ok(_) => {}
+                Err(_e) => {
+                    debug_eprintln!("[App] Warning: Store verification failed: {}", _e);
+                }
+            }
+
             // Initialize persistent storage with defaults from .env
             debug_println!("[App] Initializing persistent storage...");
             if let Err(_e) = storage::init_store_with_defaults(&app.handle()) {
@@ -1994,6 +4400,12 @@ pub fn run() {
                 debug_println!("[App] Storage initialized successfully");
             }
 
+            // Read the admin feature policy once, before any gated command runs
+            feature_policy::policy();
+
+            // Re-arm captures scheduled before the last restart
+            scheduled_capture::restore_scheduled_captures(&app.handle());
+
             // Setup floating icon click listener
             setup_floating_icon(&app.handle());
 
@@ -2005,14 +4417,9 @@ pub fn run() {
                 // Get scale factor for DPI scaling
                 let scale_factor = floating_icon.scale_factor().unwrap_or(1.0);
 
-                // Get primary monitor dimensions from cache
-                let (_monitor_x, _monitor_y, screen_width, screen_height) = get_primary_monitor_dims();
-
                 // Set icon size (48 logical pixels)
                 let icon_size_logical = 48.0;
                 let icon_size_physical = (icon_size_logical * scale_factor) as u32;
-                let margin = 20;
-                let taskbar_offset = 50; // Extra offset to avoid Windows taskbar
 
                 // Force size by setting min and max to the same value
                 let size = tauri::Size::Logical(tauri::LogicalSize {
@@ -2023,9 +4430,16 @@ pub fn run() {
                 let _ = floating_icon.set_max_size(Some(size.clone()));
                 let _ = floating_icon.set_size(size);
 
-                // Position at bottom-right corner (above taskbar)
-                let x_pos = screen_width - icon_size_physical as i32 - margin;
-                let y_pos = screen_height - icon_size_physical as i32 - margin - taskbar_offset;
+                // Restore the saved position if it is still on-screen,
+                // otherwise position at bottom-right corner (above taskbar)
+                let saved_position = load_floating_icon_position(
+                    &app.handle(),
+                    icon_size_physical as i32,
+                    icon_size_physical as i32,
+                );
+                let (x_pos, y_pos) = saved_position.unwrap_or_else(|| {
+                    default_floating_icon_position(icon_size_physical as i32, icon_size_physical as i32)
+                });
 
                 let _ = floating_icon.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
                     x: x_pos,
@@ -2042,24 +4456,32 @@ pub fn run() {
                         let hwnd = HWND(hwnd_raw.0 as *mut std::ffi::c_void);
                         let _ = set_window_as_tool_window(hwnd);
                     }
+
+                    // The floating icon lives as long as the app: watch it for display changes
+                    install_display_change_listener(&app.handle(), &floating_icon);
                 }
 
                 // Reposition after window initializes (handles actual size on Linux)
+                // Only needed for the default corner - a restored position is used as-is.
+                // Move tracking starts afterwards so our own positioning isn't persisted.
                 let floating_icon_clone = floating_icon.clone();
-                let screen_width_clone = screen_width;
-                let screen_height_clone = screen_height;
+                let use_default = saved_position.is_none();
                 std::thread::spawn(move || {
                     std::thread::sleep(std::time::Duration::from_millis(300));
-                    if let Ok(size) = floating_icon_clone.outer_size() {
-                        let actual_width = size.width as i32;
-                        let actual_height = size.height as i32;
-                        let new_x = screen_width_clone - actual_width - margin;
-                        let new_y = screen_height_clone - actual_height - margin - taskbar_offset;
-                        let _ = floating_icon_clone.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
-                            x: new_x,
-                            y: new_y
-                        }));
+                    if use_default {
+                        if let Ok(size) = floating_icon_clone.outer_size() {
+                            let (new_x, new_y) = default_floating_icon_position(
+                                size.width as i32,
+                                size.height as i32,
+                            );
+                            let _ = floating_icon_clone.set_position(tauri::Position::Physical(tauri::PhysicalPosition {
+                                x: new_x,
+                                y: new_y
+                            }));
+                        }
                     }
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    FLOATING_ICON_TRACKING.store(true, std::sync::atomic::Ordering::SeqCst);
                 });
             }
 
@@ -2070,13 +4492,23 @@ pub fn run() {
                 }
                 // Show and focus the window on startup
                 let _ = main_window.show();
-                let _ = main_window.set_focus();
+                force_focus(&main_window);
             }
 
             Ok(())
         });
 
     builder
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Exits not started by graceful_quit (e.g. plugin/tray exit calls) run the
+            // shutdown sequence first; it exits again once state is saved
+            if let tauri::RunEvent::ExitRequested { api, .. } = event {
+                if !SHUTDOWN_COMPLETE.load(std::sync::atomic::Ordering::SeqCst) {
+                    api.prevent_exit();
+                    tauri::async_runtime::spawn(run_graceful_quit(app.clone(), None));
+                }
+            }
+        });
 }
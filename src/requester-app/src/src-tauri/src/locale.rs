@@ -0,0 +1,93 @@
+//! Locale Module
+//!
+//! Detects the user's language so support can be routed to someone who
+//! speaks it and notifications can be localized. Also useful when remote
+//! typing misbehaves: a UI language that doesn't match the active keyboard
+//! layout (see `get_keyboard_layout`) explains most wrong-character reports.
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Locale reported when none can be determined
+const FALLBACK_LOCALE: &str = "en-US";
+
+/// Convert a null-terminated UTF-16 locale name buffer (`len` includes the terminator)
+#[cfg(target_os = "windows")]
+fn locale_name(buffer: &[u16], len: i32) -> Option<String> {
+    let len = (len as usize).checked_sub(1)?;
+    let name = String::from_utf16_lossy(buffer.get(..len)?);
+    (!name.is_empty()).then_some(name)
+}
+
+/// Display language of the user's UI, e.g. "ar-EG"
+#[cfg(target_os = "windows")]
+fn ui_language() -> Option<String> {
+    use windows::Win32::Globalization::{GetUserDefaultUILanguage, LCIDToLocaleName, LOCALE_NAME_MAX_LENGTH};
+
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH as usize];
+    let len = unsafe { LCIDToLocaleName(GetUserDefaultUILanguage() as u32, Some(&mut buffer), 0) };
+    locale_name(&buffer, len)
+}
+
+/// Regional format locale of the user, e.g. "fr-FR"
+#[cfg(target_os = "windows")]
+fn format_locale() -> Option<String> {
+    use windows::Win32::Globalization::{GetUserDefaultLocaleName, LOCALE_NAME_MAX_LENGTH};
+
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH as usize];
+    let len = unsafe { GetUserDefaultLocaleName(&mut buffer) };
+    locale_name(&buffer, len)
+}
+
+/// `LANG`-style value ("en_US.UTF-8", "de_DE@euro") as a locale name ("en-US")
+#[cfg(not(target_os = "windows"))]
+fn locale_from_env(value: &str) -> Option<String> {
+    let name = value.split(['.', '@']).next()?.trim();
+    if name.is_empty() || name == "C" || name == "POSIX" {
+        return None;
+    }
+    Some(name.replace('_', "-"))
+}
+
+/// UI language and format locale: `{ uiLanguage, locale }` (null when unknown)
+pub fn locale_info() -> serde_json::Value {
+    #[cfg(target_os = "windows")]
+    {
+        serde_json::json!({
+            "uiLanguage": ui_language(),
+            "locale": format_locale(),
+        })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let locale = std::env::var("LANG").ok().as_deref().and_then(locale_from_env);
+        serde_json::json!({
+            "uiLanguage": locale.clone(),
+            "locale": locale,
+        })
+    }
+}
+
+/// Get the user's primary language and region as a locale name, e.g. "ar-EG"
+/// The UI display language, falling back to the regional format locale and
+/// then "en-US". Sent with the device info at login.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn get_user_locale() -> String {
+    let locale = ui_language()
+        .or_else(format_locale)
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string());
+    debug_eprintln!("[locale] User locale: {}", locale);
+    locale
+}
+
+/// Non-Windows: the locale from `LANG`
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub fn get_user_locale() -> String {
+    std::env::var("LANG")
+        .ok()
+        .as_deref()
+        .and_then(locale_from_env)
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+}
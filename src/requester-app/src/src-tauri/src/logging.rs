@@ -5,6 +5,7 @@
 //! - Supports automatic rotation (5MB max per file, 10 files max)
 //! - Is fail-safe and non-blocking for the main thread
 //! - Never exposes sensitive data
+//! - Can store support screenshots alongside the logs (capped count)
 
 use std::fs::{self, OpenOptions};
 use std::io::{BufWriter, Write};
@@ -13,6 +14,7 @@ use tauri::AppHandle;
 use tauri::Manager;
 use serde::{Deserialize, Serialize};
 use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
 
 // ============================================================================
 // CONSTANTS
@@ -30,6 +32,25 @@ const LOG_DIR_NAME: &str = "logs";
 /// Current log file name
 const CURRENT_LOG_FILE: &str = "session-current.log";
 
+/// Filename prefix of the gzipped archives `log_compact` merges archived
+/// session files into (each takes one slot of MAX_FILE_COUNT like any other archive)
+const COMPACTED_LOG_PREFIX: &str = "session-compacted";
+
+/// Filename extension of the compacted archives
+const COMPACTED_LOG_EXTENSION: &str = ".log.gz";
+
+/// Maximum number of compacted archives to keep (oldest deleted first)
+const MAX_COMPACTED_COUNT: usize = 3;
+
+/// Filename prefix for screenshots saved alongside the logs
+const SCREENSHOT_PREFIX: &str = "screenshot-";
+
+/// Maximum number of screenshots to keep in the logs directory
+const MAX_SCREENSHOT_COUNT: usize = 20;
+
+/// Maximum length of the sanitized screenshot label
+const MAX_SCREENSHOT_LABEL_LEN: usize = 40;
+
 // ============================================================================
 // TYPES
 // ============================================================================
@@ -98,7 +119,7 @@ fn generate_archive_filename() -> String {
 }
 
 /// Get current timestamp in Cairo timezone (Africa/Cairo, UTC+2)
-fn chrono_lite_timestamp() -> String {
+pub(crate) fn chrono_lite_timestamp() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     let now = SystemTime::now()
@@ -248,6 +269,60 @@ fn enforce_max_files(logs_dir: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
+/// Sanitize a screenshot label to a safe filename component
+/// Keeps ASCII letters, digits, '-' and '_'; everything else becomes '-'
+fn sanitize_screenshot_label(label: &str) -> String {
+    let sanitized: String = label
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .take(MAX_SCREENSHOT_LABEL_LEN)
+        .collect();
+
+    let sanitized = sanitized.trim_matches('-').to_string();
+    if sanitized.is_empty() {
+        "capture".to_string()
+    } else {
+        sanitized
+    }
+}
+
+/// Delete the oldest screenshots so at most `keep` remain
+fn enforce_max_screenshots(logs_dir: &PathBuf, keep: usize) {
+    let mut screenshots: Vec<(PathBuf, std::time::SystemTime)> = fs::read_dir(logs_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().starts_with(SCREENSHOT_PREFIX))
+                            .unwrap_or(false)
+                })
+                .filter_map(|path| {
+                    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                    Some((path, modified))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if screenshots.len() <= keep {
+        return;
+    }
+
+    // Oldest first
+    screenshots.sort_by_key(|(_, modified)| *modified);
+    let to_delete = screenshots.len() - keep;
+    for (path, _) in screenshots.iter().take(to_delete) {
+        if let Err(_e) = fs::remove_file(path) {
+            debug_eprintln!("[logging] Failed to delete old screenshot {:?}: {}", path, _e);
+        }
+    }
+}
+
 /// Write a log entry to the current log file
 fn write_log_entry(app: &AppHandle, entry: &LogEntry) -> Result<(), String> {
     // First, check if rotation is needed
@@ -302,6 +377,23 @@ pub fn log_write(
     write_log_entry(&app, &entry)
 }
 
+/// Flush the current log file to disk
+/// Entries are flushed as they are written; this also syncs the file so
+/// nothing is lost if the process exits right after (e.g. during shutdown)
+#[tauri::command]
+pub fn log_flush(app: AppHandle) -> Result<(), String> {
+    let current_path = get_current_log_path(&app)?;
+    if !current_path.exists() {
+        return Ok(());
+    }
+
+    OpenOptions::new()
+        .append(true)
+        .open(&current_path)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Failed to sync log file: {}", e))
+}
+
 /// Write multiple log entries at once (batch logging)
 #[tauri::command]
 pub fn log_write_batch(app: AppHandle, entries: Vec<LogEntry>) -> Result<(), String> {
@@ -363,10 +455,145 @@ pub fn log_read_file(app: AppHandle, filename: String) -> Result<String, String>
 
     let file_path = logs_dir.join(&filename);
 
+    if is_compacted_log(&filename) {
+        return read_compacted(&file_path);
+    }
+
     fs::read_to_string(&file_path)
         .map_err(|e| format!("Failed to read log file: {}", e))
 }
 
+/// Whether a log filename is a compacted archive
+fn is_compacted_log(name: &str) -> bool {
+    name.starts_with(COMPACTED_LOG_PREFIX) && name.ends_with(COMPACTED_LOG_EXTENSION)
+}
+
+/// Decompress a compacted archive
+/// An archive holds at most MAX_FILE_COUNT files of MAX_FILE_SIZE; anything
+/// beyond that is not read.
+fn read_compacted(path: &PathBuf) -> Result<String, String> {
+    use std::io::Read;
+
+    let file = fs::File::open(path).map_err(|e| format!("Failed to open compacted log: {}", e))?;
+    let mut content = String::new();
+    flate2::read::MultiGzDecoder::new(file)
+        .take(MAX_FILE_COUNT as u64 * MAX_FILE_SIZE)
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Failed to decompress compacted log: {}", e))?;
+    Ok(content)
+}
+
+/// Delete the oldest compacted archives so at most MAX_COMPACTED_COUNT remain
+fn enforce_max_compacted(logs_dir: &PathBuf) {
+    let compacted: Vec<LogFileInfo> = list_log_files(logs_dir)
+        .into_iter()
+        .filter(|f| is_compacted_log(&f.name))
+        .collect();
+
+    let excess = compacted.len().saturating_sub(MAX_COMPACTED_COUNT);
+    for file_info in compacted.iter().take(excess) {
+        if let Err(_e) = fs::remove_file(logs_dir.join(&file_info.name)) {
+            debug_eprintln!("[logging] Failed to delete old compacted log {}: {}", file_info.name, _e);
+        } else {
+            debug_eprintln!("[logging] Deleted old compacted log: {}", file_info.name);
+        }
+    }
+}
+
+/// Timestamp of the first entry in a log file's content, for ordering
+fn first_entry_ts(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        serde_json::from_str::<serde_json::Value>(line)
+            .ok()?
+            .get("ts")?
+            .as_str()
+            .map(str::to_string)
+    })
+}
+
+/// Merge all archived session files into a new gzipped archive
+/// Files are concatenated in chronological order (by first entry, entries are
+/// in order within a file) into `session-compacted-<timestamp>.log.gz`.
+/// Earlier compacted archives are left as they are (never re-merged), and
+/// only the newest MAX_COMPACTED_COUNT are kept. The current session file is
+/// left alone. Returns `{ archive, mergedFiles, sizeBytes }` (`mergedFiles` 0
+/// and no archive when there was nothing to merge).
+#[tauri::command]
+pub fn log_compact(app: AppHandle) -> Result<serde_json::Value, String> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let logs_dir = ensure_logs_dir(&app)?;
+    let archive_name = format!(
+        "{}-{}{}",
+        COMPACTED_LOG_PREFIX,
+        chrono_lite_timestamp().replace([':', '.', ' '], "-"),
+        COMPACTED_LOG_EXTENSION
+    );
+    let compacted_path = logs_dir.join(&archive_name);
+
+    let archives: Vec<LogFileInfo> = list_log_files(&logs_dir)
+        .into_iter()
+        .filter(|f| !is_compacted_log(&f.name))
+        .collect();
+    if archives.is_empty() {
+        return Ok(serde_json::json!({ "archive": null, "mergedFiles": 0, "sizeBytes": 0 }));
+    }
+
+    let mut sources: Vec<(Option<String>, u64, String)> = Vec::with_capacity(archives.len());
+    for archive in &archives {
+        match fs::read_to_string(logs_dir.join(&archive.name)) {
+            Ok(content) => sources.push((first_entry_ts(&content), archive.modified, content)),
+            Err(_e) => {
+                debug_eprintln!("[logging] Skipping unreadable log {} during compaction: {}", archive.name, _e);
+            }
+        }
+    }
+    // Entry timestamps share one fixed-offset format, so they sort as strings
+    sources.sort_by(|a, b| (a.0.as_deref(), a.1).cmp(&(b.0.as_deref(), b.1)));
+
+    let temp_path = logs_dir.join("compacting.tmp");
+    let write_result = (|| {
+        let file = fs::File::create(&temp_path).map_err(|e| format!("Failed to create compacted log: {}", e))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+
+        for (_, _, content) in &sources {
+            encoder
+                .write_all(content.as_bytes())
+                .and_then(|_| if content.ends_with('\n') { Ok(()) } else { encoder.write_all(b"\n") })
+                .map_err(|e| format!("Failed to write compacted log: {}", e))?;
+        }
+
+        encoder
+            .finish()
+            .and_then(|mut writer| writer.flush())
+            .map_err(|e| format!("Failed to finish compacted log: {}", e))
+    })();
+    if let Err(e) = write_result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    fs::rename(&temp_path, &compacted_path).map_err(|e| format!("Failed to save compacted log: {}", e))?;
+
+    // Only delete the originals once the merged copy is in place
+    for archive in &archives {
+        if let Err(_e) = fs::remove_file(logs_dir.join(&archive.name)) {
+            debug_eprintln!("[logging] Failed to delete compacted log {}: {}", archive.name, _e);
+        }
+    }
+
+    enforce_max_compacted(&logs_dir);
+
+    let size = get_file_size(&compacted_path);
+    debug_eprintln!("[logging] Compacted {} log files into {} ({} bytes)", sources.len(), archive_name, size);
+    Ok(serde_json::json!({
+        "archive": archive_name,
+        "mergedFiles": sources.len(),
+        "sizeBytes": size,
+    }))
+}
+
 /// Get total size of all log files in bytes
 #[tauri::command]
 pub fn log_get_total_size(app: AppHandle) -> Result<u64, String> {
@@ -443,6 +670,7 @@ pub fn log_init(
         context: Some(serde_json::json!({
             "version": app_version,
             "os": os_info,
+            "locale": crate::locale::locale_info(),
             "sessionStart": true
         })),
     };
@@ -452,3 +680,48 @@ pub fn log_init(
     debug_eprintln!("[logging] Session logging initialized");
     Ok(())
 }
+
+/// Capture a monitor and save it as a PNG in the logs directory
+/// Named `screenshot-<ts>-<label>.png`; only the newest 20 screenshots are kept.
+/// Returns the full path of the saved file.
+#[tauri::command]
+pub async fn save_screenshot_to_logs(
+    app: AppHandle,
+    monitor_id: usize,
+    label: String,
+) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let logs_dir = ensure_logs_dir(&app)?;
+    let filename = format!(
+        "{}{}-{}.png",
+        SCREENSHOT_PREFIX,
+        chrono_lite_timestamp().replace([':', '.', ' ', '+'], "-"),
+        sanitize_screenshot_label(&label)
+    );
+    let path = logs_dir.join(&filename);
+
+    tokio::task::spawn_blocking(move || {
+        let monitors = xcap::Monitor::all()
+            .map_err(|e| format!("Failed to get monitors: {}", e))?;
+
+        let monitor = monitors
+            .get(monitor_id)
+            .ok_or_else(|| format!("Monitor {} not found", monitor_id))?;
+
+        let image = monitor
+            .capture_image()
+            .map_err(|e| format!("Failed to capture monitor: {}", e))?;
+
+        // Make room before writing so the new screenshot is never the one removed
+        enforce_max_screenshots(&logs_dir, MAX_SCREENSHOT_COUNT - 1);
+
+        image
+            .save_with_format(&path, image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to save screenshot: {}", e))?;
+
+        debug_eprintln!("[logging] Saved screenshot: {}", filename);
+        Ok(path.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| format!("Screenshot task failed: {}", e))?
+}
@@ -0,0 +1,171 @@
+//! Mock Capture Module (`mock-capture` feature, debug builds only)
+//!
+//! Lets the remote-session UI be developed and integration-tested on
+//! macOS/Linux without Win32 or real screen content:
+//! - stream/preview captures return a synthetic gradient frame with a moving
+//!   bar (so consecutive frames differ) after a realistic capture delay
+//! - `get_monitors` / `get_windows` return canned data
+//!
+//! Mirrors the deployment worker's `mock-mode` feature. lib.rs refuses to
+//! compile this feature into release builds.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose, Engine as _};
+
+use crate::{debug_eprintln, CaptureTimings};
+
+/// Canned monitors: (name, x, y, width, height)
+const MOCK_MONITORS: &[(&str, i32, i32, u32, u32)] = &[
+    ("Mock Display 1", 0, 0, 1920, 1080),
+    ("Mock Display 2", 1920, 0, 1280, 720),
+];
+
+/// Canned windows: (title, class name)
+const MOCK_WINDOWS: &[(&str, &str)] = &[
+    ("Untitled - Notepad", "Notepad"),
+    ("Inbox - Outlook", "rctrl_renwnd32"),
+    ("Support Center - Google Chrome", "Chrome_WidgetWin_1"),
+];
+
+/// Frame counter driving the moving bar
+static FRAME_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Output size and simulated capture delay for a stream profile
+fn profile_params(profile: &str) -> (u32, u32, Duration) {
+    match profile {
+        "high" => (1280, 720, Duration::from_millis(35)),
+        "extreme" => (1920, 1080, Duration::from_millis(50)),
+        _ => (960, 540, Duration::from_millis(25)),
+    }
+}
+
+/// Render an RGB gradient (tinted per monitor) with a vertical bar at `frame`
+fn render_frame(monitor_id: usize, width: u32, height: u32, frame: u64) -> Vec<u8> {
+    let bar_x = ((frame * 8) % width as u64) as u32;
+    let tint = (monitor_id as u32 * 80) % 256;
+
+    let mut rgb = Vec::with_capacity((width * height * 3) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            if x.abs_diff(bar_x) < 6 {
+                rgb.extend_from_slice(&[255, 255, 255]);
+            } else {
+                rgb.push((x * 255 / width) as u8);
+                rgb.push((y * 255 / height) as u8);
+                rgb.push(tint as u8);
+            }
+        }
+    }
+    rgb
+}
+
+fn encode_jpeg(rgb: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+    let mut jpeg_buffer = Vec::with_capacity(200_000);
+    let encoder = jpeg_encoder::Encoder::new(&mut jpeg_buffer, 90);
+    encoder
+        .encode(rgb, width as u16, height as u16, jpeg_encoder::ColorType::Rgb)
+        .map_err(|e| format!("Failed to encode JPEG: {}", e))?;
+    Ok(jpeg_buffer)
+}
+
+fn check_monitor(monitor_id: usize) -> Result<(), String> {
+    if monitor_id < MOCK_MONITORS.len() {
+        Ok(())
+    } else {
+        Err(format!("Monitor {} not found", monitor_id))
+    }
+}
+
+/// Produce a synthetic stream frame for a profile as base64
+pub fn stream_frame(monitor_id: usize, profile: &'static str) -> Result<String, String> {
+    stream_frame_jpeg(monitor_id, profile).map(|jpeg| general_purpose::STANDARD.encode(&jpeg))
+}
+
+/// Produce a synthetic raw JPEG stream frame (blocking; sleeps to mimic capture)
+/// Records capture timings like the real pipeline so the stats overlay works.
+pub fn stream_frame_jpeg(monitor_id: usize, profile: &'static str) -> Result<Vec<u8>, String> {
+    check_monitor(monitor_id)?;
+
+    let (width, height, capture_delay) = profile_params(profile);
+    let frame = FRAME_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let t0 = Instant::now();
+    std::thread::sleep(capture_delay);
+    let t2 = Instant::now();
+    let rgb = render_frame(monitor_id, width, height, frame);
+    let t3 = Instant::now();
+    let jpeg = encode_jpeg(&rgb, width, height)?;
+    let t4 = Instant::now();
+
+    crate::record_capture_timings(
+        monitor_id,
+        profile,
+        "444",
+        CaptureTimings::from_instants([t0, t0, t2, t3, t4]),
+        jpeg.len(),
+    );
+
+    Ok(jpeg)
+}
+
+/// Produce a synthetic monitor preview (640px wide, like the real preview)
+/// Returns the JPEG and the monitor's full width and height
+pub fn preview_jpeg(monitor_id: usize) -> Result<(Vec<u8>, u32, u32), String> {
+    check_monitor(monitor_id)?;
+
+    let (_, _, _, width, height) = MOCK_MONITORS[monitor_id];
+    let preview_height = height * 640 / width;
+    let rgb = render_frame(monitor_id, 640, preview_height, 0);
+    let jpeg = encode_jpeg(&rgb, 640, preview_height)?;
+
+    Ok((jpeg, width, height))
+}
+
+/// Number of canned monitors
+pub fn monitor_count() -> usize {
+    MOCK_MONITORS.len()
+}
+
+/// Canned monitor list in the `get_monitors` JSON shape
+pub fn monitors_json() -> Result<String, String> {
+    debug_eprintln!("[mock_capture] Returning {} mock monitors", MOCK_MONITORS.len());
+
+    let monitors: Vec<serde_json::Value> = MOCK_MONITORS
+        .iter()
+        .enumerate()
+        .map(|(idx, (name, x, y, width, height))| {
+            serde_json::json!({
+                "id": idx,
+                "name": name,
+                "x": x,
+                "y": y,
+                "width": width,
+                "height": height,
+                "isPrimary": idx == 0,
+            })
+        })
+        .collect();
+
+    serde_json::to_string(&monitors).map_err(|e| format!("Failed to serialize monitors: {}", e))
+}
+
+/// Canned window list in the `get_windows` JSON shape
+pub fn windows_json() -> Result<String, String> {
+    let windows: Vec<serde_json::Value> = MOCK_WINDOWS
+        .iter()
+        .enumerate()
+        .map(|(idx, (title, class_name))| {
+            serde_json::json!({
+                "id": idx,
+                "hwnd": 0x1000 + idx as i64,
+                "title": title,
+                "appName": class_name,
+                "thumbnail": null
+            })
+        })
+        .collect();
+
+    serde_json::to_string(&windows).map_err(|e| format!("Failed to serialize: {}", e))
+}
@@ -0,0 +1,108 @@
+//! Navigation History Module
+//!
+//! Keeps a trail of ticket navigations (notification clicks, deep links) to
+//! diagnose "clicking the notification took me to the wrong ticket" reports.
+//! Entries live in an in-memory ring buffer; the most recent few are also
+//! persisted in the store so they survive a quick restart.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::debug_eprintln;
+use crate::storage;
+
+/// Entries kept in memory
+const MAX_HISTORY: usize = 50;
+
+/// Entries persisted across restarts
+const PERSISTED_HISTORY: usize = 10;
+
+/// One navigation to a ticket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NavigationEntry {
+    /// Unix ms
+    pub ts: f64,
+    pub ticket_id: String,
+    /// What triggered it: "notification", "deepLink", ...
+    pub source: String,
+    /// Recorded by a previous run of the app (restored from the store)
+    #[serde(default)]
+    pub restored: bool,
+}
+
+/// Newest last; None until loaded from the store
+static HISTORY: Mutex<Option<VecDeque<NavigationEntry>>> = Mutex::new(None);
+
+/// Run `f` on the history, loading the persisted entries on first use
+fn with_history<T>(app: &AppHandle, f: impl FnOnce(&mut VecDeque<NavigationEntry>) -> T) -> Result<T, String> {
+    let mut history = HISTORY
+        .lock()
+        .map_err(|e| format!("Failed to lock navigation history: {}", e))?;
+
+    let history = history.get_or_insert_with(|| {
+        let persisted: Vec<NavigationEntry> = storage::get_value(app, storage::KEY_NAVIGATION_HISTORY)
+            .ok()
+            .flatten()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default();
+        persisted
+            .into_iter()
+            .map(|entry| NavigationEntry { restored: true, ..entry })
+            .collect()
+    });
+    Ok(f(history))
+}
+
+/// Record a navigation to a ticket and persist the most recent entries
+pub(crate) fn record(app: &AppHandle, ticket_id: &str, source: &str) {
+    let entry = NavigationEntry {
+        ts: crate::unix_time_ms(),
+        ticket_id: ticket_id.to_string(),
+        source: source.to_string(),
+        restored: false,
+    };
+    debug_eprintln!("[navigation] {} -> ticket {}", source, ticket_id);
+
+    let recent = with_history(app, |history| {
+        history.push_back(entry);
+        while history.len() > MAX_HISTORY {
+            history.pop_front();
+        }
+        history
+            .iter()
+            .skip(history.len().saturating_sub(PERSISTED_HISTORY))
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    let persisted = recent.and_then(|recent| {
+        let value = serde_json::to_value(recent).map_err(|e| format!("Failed to serialize navigation history: {}", e))?;
+        storage::set_value(app, storage::KEY_NAVIGATION_HISTORY, value)
+    });
+    if let Err(_e) = persisted {
+        debug_eprintln!("[navigation] Failed to persist history: {}", _e);
+    }
+}
+
+/// Record a navigation handled by the frontend (e.g. a deep link from a
+/// second instance)
+#[tauri::command]
+pub fn record_navigation(app: AppHandle, ticket_id: String, source: String) -> Result<(), String> {
+    if ticket_id.trim().is_empty() {
+        return Err("Ticket ID is required".to_string());
+    }
+    record(&app, ticket_id.trim(), source.trim());
+    Ok(())
+}
+
+/// Get recent ticket navigations, newest first
+/// Returns `[{ ts, ticketId, source, restored }]`; `restored` entries were
+/// recorded before the last restart.
+#[tauri::command]
+pub fn get_navigation_history(app: AppHandle) -> Result<Vec<NavigationEntry>, String> {
+    with_history(&app, |history| history.iter().rev().cloned().collect())
+}
@@ -0,0 +1,219 @@
+//! Network Configuration Module
+//!
+//! The full per-adapter network picture (addresses, gateways, DNS servers,
+//! MAC, DHCP, link state) from `GetAdaptersAddresses`, instead of parsing
+//! `ipconfig` text, so a technician sees everything `ipconfig /all` would
+//! show in one structured call.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// One network adapter
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkAdapter {
+    /// Friendly name (e.g. "Ethernet", "Wi-Fi")
+    pub name: String,
+    /// Driver description (e.g. "Intel(R) Ethernet Connection I219-LM")
+    pub description: String,
+    pub ipv4: Vec<String>,
+    pub ipv6: Vec<String>,
+    pub gateway: Vec<String>,
+    pub dns: Vec<String>,
+    /// "AA-BB-CC-DD-EE-FF" (absent for adapters without a hardware address)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+    pub dhcp_enabled: bool,
+    /// "up", "down", "testing", "dormant", "notPresent", "lowerLayerDown" or "unknown"
+    pub connection_status: String,
+}
+
+/// IP_ADAPTER_DHCP_ENABLED bit of IP_ADAPTER_ADDRESSES_LH.Flags
+#[cfg(target_os = "windows")]
+const ADAPTER_DHCP_ENABLED: u32 = 0x4;
+
+/// IF_TYPE_SOFTWARE_LOOPBACK
+#[cfg(target_os = "windows")]
+const IF_TYPE_LOOPBACK: u32 = 24;
+
+#[cfg(target_os = "windows")]
+fn oper_status_name(status: i32) -> &'static str {
+    match status {
+        1 => "up",
+        2 => "down",
+        3 => "testing",
+        5 => "dormant",
+        6 => "notPresent",
+        7 => "lowerLayerDown",
+        _ => "unknown",
+    }
+}
+
+/// Format a socket address as an IP address string
+#[cfg(target_os = "windows")]
+unsafe fn socket_address_ip(address: &windows::Win32::Networking::WinSock::SOCKET_ADDRESS) -> Option<std::net::IpAddr> {
+    use windows::Win32::Networking::WinSock::{AF_INET, AF_INET6, SOCKADDR_IN, SOCKADDR_IN6};
+
+    let sockaddr = address.lpSockaddr;
+    if sockaddr.is_null() {
+        return None;
+    }
+    match (*sockaddr).sa_family {
+        AF_INET => {
+            let v4 = &*(sockaddr as *const SOCKADDR_IN);
+            // S_addr is in network byte order in memory
+            Some(std::net::Ipv4Addr::from(v4.sin_addr.S_un.S_addr.to_ne_bytes()).into())
+        }
+        AF_INET6 => {
+            let v6 = &*(sockaddr as *const SOCKADDR_IN6);
+            Some(std::net::Ipv6Addr::from(v6.sin6_addr.u.Byte).into())
+        }
+        _ => None,
+    }
+}
+
+/// Read a NUL-terminated wide string
+#[cfg(target_os = "windows")]
+unsafe fn wide_string(value: windows::core::PWSTR) -> String {
+    if value.is_null() {
+        String::new()
+    } else {
+        value.to_string().unwrap_or_default()
+    }
+}
+
+/// Query every adapter except loopback (blocking)
+#[cfg(target_os = "windows")]
+fn query_adapters() -> Result<Vec<NetworkAdapter>, String> {
+    use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, NO_ERROR};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetAdaptersAddresses, GAA_FLAG_INCLUDE_GATEWAYS, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_MULTICAST,
+        IP_ADAPTER_ADDRESSES_LH,
+    };
+
+    // AF_UNSPEC: IPv4 and IPv6
+    const FAMILY_UNSPEC: u32 = 0;
+    let flags = GAA_FLAG_INCLUDE_GATEWAYS | GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST;
+
+    unsafe {
+        // 15 KB is the documented starting size; retry if adapters were added meanwhile
+        let mut size: u32 = 15 * 1024;
+        let mut buffer: Vec<u64>;
+        let mut attempts = 0;
+        loop {
+            // u64 buffer keeps IP_ADAPTER_ADDRESSES_LH properly aligned
+            buffer = vec![0u64; (size as usize).div_ceil(8)];
+            let status = GetAdaptersAddresses(
+                FAMILY_UNSPEC,
+                flags,
+                None,
+                Some(buffer.as_mut_ptr() as *mut IP_ADAPTER_ADDRESSES_LH),
+                &mut size,
+            );
+            attempts += 1;
+            if status == NO_ERROR.0 {
+                break;
+            }
+            if status != ERROR_BUFFER_OVERFLOW.0 || attempts >= 3 {
+                return Err(format!("GetAdaptersAddresses failed: error {}", status));
+            }
+        }
+
+        let mut adapters = Vec::new();
+        let mut current = buffer.as_ptr() as *const IP_ADAPTER_ADDRESSES_LH;
+        while !current.is_null() {
+            let adapter = &*current;
+            current = adapter.Next;
+            if adapter.IfType == IF_TYPE_LOOPBACK {
+                continue;
+            }
+
+            let (mut ipv4, mut ipv6) = (Vec::new(), Vec::new());
+            let mut unicast = adapter.FirstUnicastAddress;
+            while !unicast.is_null() {
+                match socket_address_ip(&(*unicast).Address) {
+                    Some(std::net::IpAddr::V4(ip)) => ipv4.push(ip.to_string()),
+                    Some(std::net::IpAddr::V6(ip)) => ipv6.push(ip.to_string()),
+                    None => {}
+                }
+                unicast = (*unicast).Next;
+            }
+
+            let mut gateway = Vec::new();
+            let mut gateway_address = adapter.FirstGatewayAddress;
+            while !gateway_address.is_null() {
+                if let Some(ip) = socket_address_ip(&(*gateway_address).Address) {
+                    gateway.push(ip.to_string());
+                }
+                gateway_address = (*gateway_address).Next;
+            }
+
+            let mut dns = Vec::new();
+            let mut dns_address = adapter.FirstDnsServerAddress;
+            while !dns_address.is_null() {
+                if let Some(ip) = socket_address_ip(&(*dns_address).Address) {
+                    dns.push(ip.to_string());
+                }
+                dns_address = (*dns_address).Next;
+            }
+
+            let mac_len = (adapter.PhysicalAddressLength as usize).min(adapter.PhysicalAddress.len());
+            let mac = (mac_len > 0).then(|| {
+                adapter.PhysicalAddress[..mac_len]
+                    .iter()
+                    .map(|b| format!("{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join("-")
+            });
+
+            adapters.push(NetworkAdapter {
+                name: wide_string(adapter.FriendlyName),
+                description: wide_string(adapter.Description),
+                ipv4,
+                ipv6,
+                gateway,
+                dns,
+                mac,
+                dhcp_enabled: adapter.Anonymous2.Flags & ADAPTER_DHCP_ENABLED != 0,
+                connection_status: oper_status_name(adapter.OperStatus.0).to_string(),
+            });
+        }
+
+        debug_eprintln!("[network_config] Found {} adapters", adapters.len());
+        Ok(adapters)
+    }
+}
+
+/// Get the configuration of every network adapter
+/// Returns `[{ name, description, ipv4, ipv6, gateway, dns, mac?, dhcpEnabled, connectionStatus }]`
+/// (loopback excluded)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn get_network_config() -> Result<serde_json::Value, String> {
+    let adapters = tokio::task::spawn_blocking(query_adapters)
+        .await
+        .map_err(|e| format!("Network config task failed: {}", e))??;
+
+    serde_json::to_value(adapters).map_err(|e| format!("Failed to serialize network config: {}", e))
+}
+
+/// Non-Windows: a single entry with the local IP only
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn get_network_config() -> Result<serde_json::Value, String> {
+    let ipv4: Vec<String> = crate::get_local_ip().into_iter().collect();
+    Ok(serde_json::json!([{
+        "name": "default",
+        "description": "",
+        "ipv4": ipv4,
+        "ipv6": [],
+        "gateway": [],
+        "dns": [],
+        "dhcpEnabled": false,
+        "connectionStatus": "unknown",
+    }]))
+}
@@ -0,0 +1,265 @@
+//! Network Drives Module
+//!
+//! Read-only diagnostics for "my network drive is gone" calls:
+//! - mapped drives via WNetOpenEnum/WNetEnumResource (current connections
+//!   merged with remembered/persistent mappings, so broken mappings show up)
+//! - shares offered by a server via NetShareEnum
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// A mapped network drive
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappedDrive {
+    /// Drive letter (e.g. "Z:")
+    pub drive: String,
+    /// UNC path (e.g. "\\server\share")
+    pub remote_path: String,
+    /// "connected" or "disconnected" (remembered but not currently connected)
+    pub state: String,
+    /// Restored at logon
+    pub persistent: bool,
+    /// Network provider (e.g. "Microsoft Windows Network")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+/// A share offered by a server
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkShare {
+    pub name: String,
+    /// UNC path of the share
+    pub path: String,
+    /// "disk", "printer", "device", "ipc" or "unknown"
+    pub share_type: String,
+    /// Administrative/hidden share (C$, ADMIN$, IPC$)
+    pub is_special: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+}
+
+/// Read a nul-terminated wide string (None for null or empty)
+#[cfg(target_os = "windows")]
+unsafe fn pwstr_to_option(value: windows::core::PWSTR) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    value.to_string().ok().filter(|s| !s.is_empty())
+}
+
+/// Enumerate disk connections in one scope (connected or remembered)
+/// Returns (local name, remote name, provider) triples.
+#[cfg(target_os = "windows")]
+fn enum_disk_resources(
+    scope: windows::Win32::NetworkManagement::WNet::NET_RESOURCE_SCOPE,
+) -> Result<Vec<(String, String, Option<String>)>, String> {
+    use windows::Win32::Foundation::{ERROR_MORE_DATA, ERROR_NO_MORE_ITEMS, HANDLE, NO_ERROR};
+    use windows::Win32::NetworkManagement::WNet::{
+        WNetCloseEnum, WNetEnumResourceW, WNetOpenEnumW, NETRESOURCEW, RESOURCETYPE_DISK,
+        WNET_OPEN_ENUM_USAGE,
+    };
+
+    let mut resources = Vec::new();
+
+    unsafe {
+        let mut handle = HANDLE::default();
+        let status = WNetOpenEnumW(scope, RESOURCETYPE_DISK, WNET_OPEN_ENUM_USAGE(0), None, &mut handle);
+        if status != NO_ERROR {
+            return Err(format!("WNetOpenEnum failed: {:?}", status));
+        }
+
+        // 16 KB is the buffer size recommended by the WNetEnumResource docs
+        let mut buffer = vec![0u8; 16 * 1024];
+        let result = loop {
+            let mut count = u32::MAX;
+            let mut size = buffer.len() as u32;
+            let status = WNetEnumResourceW(handle, &mut count, buffer.as_mut_ptr() as *mut _, &mut size);
+
+            if status == ERROR_NO_MORE_ITEMS {
+                break Ok(());
+            }
+            if status == ERROR_MORE_DATA {
+                // A single entry didn't fit; grow to the size requested
+                buffer.resize(size as usize, 0);
+                continue;
+            }
+            if status != NO_ERROR {
+                break Err(format!("WNetEnumResource failed: {:?}", status));
+            }
+
+            let entries = std::slice::from_raw_parts(buffer.as_ptr() as *const NETRESOURCEW, count as usize);
+            for entry in entries {
+                if let (Some(local), Some(remote)) = (
+                    pwstr_to_option(entry.lpLocalName),
+                    pwstr_to_option(entry.lpRemoteName),
+                ) {
+                    resources.push((local.to_uppercase(), remote, pwstr_to_option(entry.lpProvider)));
+                }
+            }
+        };
+
+        let _ = WNetCloseEnum(handle);
+        result.map(|_| resources)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn enum_mapped_drives() -> Result<Vec<MappedDrive>, String> {
+    use windows::Win32::NetworkManagement::WNet::{RESOURCE_CONNECTED, RESOURCE_REMEMBERED};
+
+    let connected = enum_disk_resources(RESOURCE_CONNECTED)?;
+    // Remembered mappings are best effort; connected drives are the primary result
+    let remembered = enum_disk_resources(RESOURCE_REMEMBERED).unwrap_or_else(|_e| {
+        debug_eprintln!("[network_drives] Failed to read remembered drives: {}", _e);
+        Vec::new()
+    });
+
+    let mut drives: Vec<MappedDrive> = connected
+        .into_iter()
+        .map(|(drive, remote_path, provider)| MappedDrive {
+            persistent: remembered.iter().any(|(local, _, _)| *local == drive),
+            drive,
+            remote_path,
+            state: "connected".to_string(),
+            provider,
+        })
+        .collect();
+
+    for (drive, remote_path, provider) in remembered {
+        if !drives.iter().any(|d| d.drive == drive) {
+            drives.push(MappedDrive {
+                drive,
+                remote_path,
+                state: "disconnected".to_string(),
+                persistent: true,
+                provider,
+            });
+        }
+    }
+
+    drives.sort_by(|a, b| a.drive.cmp(&b.drive));
+    Ok(drives)
+}
+
+/// Normalize a server name ("\\server", "server" or "server.domain")
+#[cfg(target_os = "windows")]
+fn normalize_server(server: &str) -> Result<String, String> {
+    let name = server.trim().trim_start_matches('\\').trim_end_matches('\\');
+    if name.is_empty()
+        || name.len() > 255
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err(format!("Invalid server name: {}", server));
+    }
+    Ok(name.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn enum_shares(server: Option<&str>) -> Result<Vec<NetworkShare>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::NetworkManagement::NetManagement::{NetApiBufferFree, MAX_PREFERRED_LENGTH};
+    use windows::Win32::Storage::FileSystem::{NetShareEnum, SHARE_INFO_1};
+
+    const STYPE_MASK: u32 = 0x0000_00FF;
+    const STYPE_SPECIAL: u32 = 0x8000_0000;
+
+    let host = match server {
+        Some(server) => normalize_server(server)?,
+        None => std::env::var("COMPUTERNAME").unwrap_or_else(|_| "localhost".to_string()),
+    };
+    let server_wide: Option<Vec<u16>> = server.map(|_| {
+        format!("\\\\{}", host).encode_utf16().chain(std::iter::once(0)).collect()
+    });
+
+    unsafe {
+        let mut buffer: *mut u8 = std::ptr::null_mut();
+        let mut entries_read = 0u32;
+        let mut total_entries = 0u32;
+
+        let status = NetShareEnum(
+            server_wide.as_ref().map_or(PCWSTR::null(), |s| PCWSTR(s.as_ptr())),
+            1,
+            &mut buffer,
+            MAX_PREFERRED_LENGTH,
+            &mut entries_read,
+            &mut total_entries,
+            None,
+        );
+        if status != 0 {
+            if !buffer.is_null() {
+                NetApiBufferFree(Some(buffer as *const _));
+            }
+            return Err(format!("NetShareEnum failed for {}: error {}", host, status));
+        }
+
+        let infos = std::slice::from_raw_parts(buffer as *const SHARE_INFO_1, entries_read as usize);
+        let shares = infos
+            .iter()
+            .filter_map(|info| {
+                let name = pwstr_to_option(info.shi1_netname)?;
+                let share_type = match info.shi1_type.0 & STYPE_MASK {
+                    0 => "disk",
+                    1 => "printer",
+                    2 => "device",
+                    3 => "ipc",
+                    _ => "unknown",
+                };
+                Some(NetworkShare {
+                    path: format!("\\\\{}\\{}", host, name),
+                    name,
+                    share_type: share_type.to_string(),
+                    is_special: info.shi1_type.0 & STYPE_SPECIAL != 0,
+                    remark: pwstr_to_option(info.shi1_remark),
+                })
+            })
+            .collect();
+
+        NetApiBufferFree(Some(buffer as *const _));
+        Ok(shares)
+    }
+}
+
+/// List mapped network drives (current connections plus remembered mappings)
+/// Returns JSON array: `[{ drive, remotePath, state, persistent, provider? }]`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_mapped_drives() -> Result<String, String> {
+    let drives = tokio::task::spawn_blocking(enum_mapped_drives)
+        .await
+        .map_err(|e| format!("Mapped drive query task failed: {}", e))??;
+
+    serde_json::to_string(&drives).map_err(|e| format!("Failed to serialize mapped drives: {}", e))
+}
+
+/// List shares offered by a server (the local machine when `server` is None)
+/// Returns JSON array: `[{ name, path, shareType, isSpecial, remark? }]`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_network_shares(server: Option<String>) -> Result<String, String> {
+    let shares = tokio::task::spawn_blocking(move || enum_shares(server.as_deref()))
+        .await
+        .map_err(|e| format!("Share query task failed: {}", e))??;
+
+    serde_json::to_string(&shares).map_err(|e| format!("Failed to serialize shares: {}", e))
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_mapped_drives() -> Result<String, String> {
+    Err("Network drive diagnostics are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_network_shares(_server: Option<String>) -> Result<String, String> {
+    Err("Network drive diagnostics are only supported on Windows".to_string())
+}
@@ -0,0 +1,179 @@
+//! Network Fixes Module
+//!
+//! One-click versions of the usual first-line network fixes, so a technician
+//! doesn't have to walk the user through an elevated command prompt:
+//! - `flush_dns`: clears the DNS resolver cache (DnsFlushResolverCache)
+//! - `reset_winsock`: `netsh winsock reset`, takes effect after a reboot
+//! - `renew_dhcp_lease`: renews the DHCP lease of every adapter (IP Helper)
+//!
+//! Resetting Winsock and renewing leases require the app to run elevated;
+//! flushing the DNS cache does not.
+
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Result of a Winsock reset
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WinsockResetResult {
+    /// The reset only takes effect after a reboot
+    pub reboot_required: bool,
+    /// netsh output
+    pub output: String,
+}
+
+/// Result of renewing one adapter's DHCP lease
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DhcpRenewResult {
+    /// Adapter name (e.g. "\DEVICE\TCPIP_{GUID}")
+    pub adapter: String,
+    pub renewed: bool,
+    /// Why the renewal failed (e.g. static address, media disconnected)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn require_elevation(action: &str) -> Result<(), String> {
+    if crate::is_elevated() {
+        Ok(())
+    } else {
+        Err(format!("{} requires running the app as administrator", action))
+    }
+}
+
+/// Clear the DNS resolver cache
+/// DnsFlushResolverCache is exported by dnsapi.dll but not declared in the SDK
+/// headers, so it is resolved at runtime.
+#[cfg(target_os = "windows")]
+fn flush_dns_cache() -> Result<(), String> {
+    use windows::core::{s, w};
+    use windows::Win32::Foundation::FreeLibrary;
+    use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+    type DnsFlushResolverCacheFn = unsafe extern "system" fn() -> i32;
+
+    unsafe {
+        let module = LoadLibraryW(w!("dnsapi.dll")).map_err(|e| format!("Failed to load dnsapi.dll: {}", e))?;
+
+        let result = match GetProcAddress(module, s!("DnsFlushResolverCache")) {
+            Some(proc) => {
+                let flush: DnsFlushResolverCacheFn = std::mem::transmute(proc);
+                if flush() != 0 {
+                    Ok(())
+                } else {
+                    Err("DnsFlushResolverCache failed".to_string())
+                }
+            }
+            None => Err("DnsFlushResolverCache is not available".to_string()),
+        };
+
+        let _ = FreeLibrary(module);
+        result
+    }
+}
+
+/// Renew the DHCP lease of every adapter
+#[cfg(target_os = "windows")]
+fn renew_all_dhcp_leases() -> Result<Vec<DhcpRenewResult>, String> {
+    use windows::Win32::Foundation::{ERROR_INSUFFICIENT_BUFFER, NO_ERROR};
+    use windows::Win32::NetworkManagement::IpHelper::{
+        GetInterfaceInfo, IpRenewAddress, IP_ADAPTER_INDEX_MAP, IP_INTERFACE_INFO,
+    };
+
+    unsafe {
+        let mut size = 0u32;
+        let status = GetInterfaceInfo(None, &mut size);
+        if status != ERROR_INSUFFICIENT_BUFFER.0 && status != NO_ERROR.0 {
+            return Err(format!("GetInterfaceInfo failed: error {}", status));
+        }
+
+        // u64 buffer keeps IP_INTERFACE_INFO properly aligned
+        let mut buffer = vec![0u64; (size as usize).div_ceil(8).max(1)];
+        let info = buffer.as_mut_ptr() as *mut IP_INTERFACE_INFO;
+        let status = GetInterfaceInfo(Some(info), &mut size);
+        if status != NO_ERROR.0 {
+            return Err(format!("GetInterfaceInfo failed: error {}", status));
+        }
+
+        let count = (*info).NumAdapters.max(0) as usize;
+        let adapters = std::slice::from_raw_parts((*info).Adapter.as_ptr(), count);
+
+        Ok(adapters
+            .iter()
+            .map(|adapter: &IP_ADAPTER_INDEX_MAP| {
+                let name_len = adapter.Name.iter().position(|&c| c == 0).unwrap_or(adapter.Name.len());
+                let name = String::from_utf16_lossy(&adapter.Name[..name_len]);
+
+                let status = IpRenewAddress(adapter);
+                debug_eprintln!("[network_fixes] Renew {}: status {}", name, status);
+                DhcpRenewResult {
+                    adapter: name,
+                    renewed: status == NO_ERROR.0,
+                    error: (status != NO_ERROR.0)
+                        .then(|| windows::core::Error::from(windows::core::HRESULT::from_win32(status)).message()),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Clear the DNS resolver cache (same as `ipconfig /flushdns`)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn flush_dns() -> Result<(), String> {
+    tokio::task::spawn_blocking(flush_dns_cache)
+        .await
+        .map_err(|e| format!("DNS flush task failed: {}", e))?
+}
+
+/// Reset the Winsock catalog (`netsh winsock reset`), requires elevation
+/// The reset only takes effect after a reboot, reported as `rebootRequired`.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn reset_winsock() -> Result<WinsockResetResult, String> {
+    tokio::task::spawn_blocking(|| {
+        require_elevation("Resetting Winsock")?;
+        let output = crate::elevation_broker::run_system_tool("netsh.exe", &["winsock", "reset"])?;
+        Ok(WinsockResetResult { reboot_required: true, output })
+    })
+    .await
+    .map_err(|e| format!("Winsock reset task failed: {}", e))?
+}
+
+/// Renew the DHCP lease of every adapter (same as `ipconfig /renew`), requires elevation
+/// Adapters with a static address or no link report their error individually.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn renew_dhcp_lease() -> Result<Vec<DhcpRenewResult>, String> {
+    tokio::task::spawn_blocking(|| {
+        require_elevation("Renewing the DHCP lease")?;
+        renew_all_dhcp_leases()
+    })
+    .await
+    .map_err(|e| format!("DHCP renew task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn flush_dns() -> Result<(), String> {
+    Err("Network fixes are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn reset_winsock() -> Result<WinsockResetResult, String> {
+    Err("Network fixes are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn renew_dhcp_lease() -> Result<Vec<DhcpRenewResult>, String> {
+    Err("Network fixes are only supported on Windows".to_string())
+}
@@ -0,0 +1,473 @@
+//! Network Overrides Module
+//!
+//! Temporary DNS changes for diagnosing name resolution problems:
+//! - `set_interface_dns`: point an interface at other DNS servers
+//!   (SetInterfaceDnsSettings; an empty list returns to DHCP-provided servers)
+//! - `add_hosts_entry` / `remove_hosts_entry`: edit the hosts file
+//!
+//! Every change is tracked (the original DNS servers per interface, a backup
+//! of the original hosts file) so `revert_network_changes` can undo all of
+//! them at session end; graceful quit reverts as well. All commands require
+//! the app to run elevated.
+
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+#[cfg(target_os = "windows")]
+use crate::network_fixes::require_elevation;
+
+/// Marker appended to hosts lines added by this app
+#[cfg(target_os = "windows")]
+const HOSTS_MARKER: &str = "# added by Support Center";
+
+/// What `revert_network_changes` undid
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevertReport {
+    /// Interfaces whose DNS servers were restored
+    pub dns_interfaces: Vec<String>,
+    /// Whether the hosts file was restored from its backup
+    pub hosts_restored: bool,
+    /// Changes that could not be reverted (still tracked for another attempt)
+    pub errors: Vec<String>,
+}
+
+/// Original DNS servers of an interface before our first change
+#[cfg(target_os = "windows")]
+struct DnsChange {
+    interface: String,
+    ipv6: bool,
+    /// Comma-separated servers; empty = DHCP-provided
+    original: String,
+}
+
+/// Changes made this session
+#[cfg(target_os = "windows")]
+struct TrackedChanges {
+    dns: Vec<DnsChange>,
+    /// Copy of the hosts file before our first edit
+    hosts_backup: Option<PathBuf>,
+}
+
+#[cfg(target_os = "windows")]
+static CHANGES: Mutex<TrackedChanges> = Mutex::new(TrackedChanges {
+    dns: Vec::new(),
+    hosts_backup: None,
+});
+
+// ============================================================================
+// DNS servers
+// ============================================================================
+
+/// Resolve an interface alias (e.g. "Ethernet", "Wi-Fi") to its GUID
+#[cfg(target_os = "windows")]
+fn interface_guid(interface: &str) -> Result<windows::core::GUID, String> {
+    use windows::core::{GUID, PCWSTR};
+    use windows::Win32::Foundation::NO_ERROR;
+    use windows::Win32::NetworkManagement::IpHelper::{
+        ConvertInterfaceAliasToLuid, ConvertInterfaceLuidToGuid,
+    };
+    use windows::Win32::NetworkManagement::Ndis::NET_LUID_LH;
+
+    let alias: Vec<u16> = interface.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut luid = NET_LUID_LH::default();
+    let mut guid = GUID::zeroed();
+
+    unsafe {
+        if ConvertInterfaceAliasToLuid(PCWSTR(alias.as_ptr()), &mut luid) != NO_ERROR {
+            return Err(format!("Network interface not found: {}", interface));
+        }
+        let status = ConvertInterfaceLuidToGuid(&luid, &mut guid);
+        if status != NO_ERROR {
+            return Err(format!("Failed to resolve interface {}: {:?}", interface, status));
+        }
+    }
+    Ok(guid)
+}
+
+/// Read the configured (static) DNS servers of an interface; empty = DHCP-provided
+#[cfg(target_os = "windows")]
+fn get_dns_servers(guid: windows::core::GUID, ipv6: bool) -> Result<String, String> {
+    use windows::Win32::Foundation::NO_ERROR;
+    use windows::Win32::NetworkManagement::IpHelper::{
+        FreeInterfaceDnsSettings, GetInterfaceDnsSettings, DNS_INTERFACE_SETTINGS,
+        DNS_INTERFACE_SETTINGS_VERSION1, DNS_SETTING_IPV6,
+    };
+
+    let mut settings = DNS_INTERFACE_SETTINGS {
+        Version: DNS_INTERFACE_SETTINGS_VERSION1,
+        Flags: if ipv6 { DNS_SETTING_IPV6 as u64 } else { 0 },
+        ..Default::default()
+    };
+
+    unsafe {
+        let status = GetInterfaceDnsSettings(guid, &mut settings);
+        if status != NO_ERROR {
+            return Err(format!("Failed to read DNS settings: {:?}", status));
+        }
+        let servers = if settings.NameServer.is_null() {
+            String::new()
+        } else {
+            settings.NameServer.to_string().unwrap_or_default()
+        };
+        FreeInterfaceDnsSettings(&mut settings);
+        Ok(servers)
+    }
+}
+
+/// Set the DNS servers of an interface (comma-separated; empty = DHCP-provided)
+#[cfg(target_os = "windows")]
+fn set_dns_servers(guid: windows::core::GUID, ipv6: bool, servers: &str) -> Result<(), String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::NO_ERROR;
+    use windows::Win32::NetworkManagement::IpHelper::{
+        SetInterfaceDnsSettings, DNS_INTERFACE_SETTINGS, DNS_INTERFACE_SETTINGS_VERSION1,
+        DNS_SETTING_IPV6, DNS_SETTING_NAMESERVER,
+    };
+
+    let mut servers_wide: Vec<u16> = servers.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut flags = DNS_SETTING_NAMESERVER as u64;
+    if ipv6 {
+        flags |= DNS_SETTING_IPV6 as u64;
+    }
+    let settings = DNS_INTERFACE_SETTINGS {
+        Version: DNS_INTERFACE_SETTINGS_VERSION1,
+        Flags: flags,
+        NameServer: PWSTR(servers_wide.as_mut_ptr()),
+        ..Default::default()
+    };
+
+    unsafe {
+        let status = SetInterfaceDnsSettings(guid, &settings);
+        if status != NO_ERROR {
+            return Err(format!("Failed to set DNS servers: {:?}", status));
+        }
+    }
+    Ok(())
+}
+
+/// Point an interface at the given DNS servers, remembering the original ones
+#[cfg(target_os = "windows")]
+fn apply_interface_dns(interface: &str, servers: &[String]) -> Result<(), String> {
+    use std::net::IpAddr;
+
+    let mut ipv4 = Vec::new();
+    let mut ipv6 = Vec::new();
+    for server in servers {
+        match server.trim().parse::<IpAddr>() {
+            Ok(IpAddr::V4(addr)) => ipv4.push(addr.to_string()),
+            Ok(IpAddr::V6(addr)) => ipv6.push(addr.to_string()),
+            Err(_) => return Err(format!("Invalid DNS server address: {}", server)),
+        }
+    }
+
+    let guid = interface_guid(interface)?;
+    let mut changes = CHANGES.lock().map_err(|e| format!("Failed to lock network changes: {}", e))?;
+
+    // An empty list resets IPv4 (and IPv6) to DHCP; otherwise only the families given change
+    let families = [(false, ipv4), (true, ipv6)]
+        .into_iter()
+        .filter(|(_, list)| servers.is_empty() || !list.is_empty());
+    for (is_ipv6, list) in families {
+        let tracked = changes.dns.iter().any(|c| c.interface == interface && c.ipv6 == is_ipv6);
+        if !tracked {
+            let original = get_dns_servers(guid, is_ipv6)?;
+            changes.dns.push(DnsChange { interface: interface.to_string(), ipv6: is_ipv6, original });
+        }
+        set_dns_servers(guid, is_ipv6, &list.join(","))?;
+        debug_eprintln!("[network_overrides] {} DNS ({}) set to [{}]", interface, if is_ipv6 { "IPv6" } else { "IPv4" }, list.join(","));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Hosts file
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+fn hosts_path() -> PathBuf {
+    let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    PathBuf::from(system_root).join("System32").join("drivers").join("etc").join("hosts")
+}
+
+/// Validate a hostname for the hosts file (letters, digits, '.', '-', '_')
+#[cfg(target_os = "windows")]
+fn validate_hostname(hostname: &str) -> Result<String, String> {
+    let name = hostname.trim().trim_end_matches('.').to_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    if valid {
+        Ok(name)
+    } else {
+        Err(format!("Invalid hostname: {}", hostname))
+    }
+}
+
+/// Backup of the original hosts file, next to it
+#[cfg(target_os = "windows")]
+fn hosts_backup_path() -> PathBuf {
+    hosts_path().with_file_name("hosts.supportcenter.bak")
+}
+
+/// Replace the hosts file with `content`
+/// The content is written to a temporary file in the same directory and
+/// renamed over the original, so a failure never leaves a half-written hosts file.
+#[cfg(target_os = "windows")]
+fn replace_hosts_file(content: &[u8]) -> Result<(), String> {
+    let path = hosts_path();
+    let temp = path.with_file_name("hosts.supportcenter.tmp");
+    std::fs::write(&temp, content).map_err(|e| format!("Failed to write hosts file: {}", e))?;
+    std::fs::rename(&temp, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        format!("Failed to replace hosts file: {}", e)
+    })
+}
+
+/// Rewrite the hosts file, backing up the original before the first edit
+/// The file is read and rewritten while holding the changes lock. A backup
+/// left behind by an earlier session that never reverted is the real
+/// original, so it is restored first and kept rather than overwritten.
+#[cfg(target_os = "windows")]
+fn edit_hosts_file(edit: impl FnOnce(&str) -> String) -> Result<(), String> {
+    let mut changes = CHANGES.lock().map_err(|e| format!("Failed to lock network changes: {}", e))?;
+    if changes.hosts_backup.is_none() {
+        let backup = hosts_backup_path();
+        if backup.exists() {
+            let original = std::fs::read(&backup).map_err(|e| format!("Failed to read hosts backup: {}", e))?;
+            replace_hosts_file(&original)?;
+            debug_eprintln!("[network_overrides] Restored hosts file from a previous session's backup");
+        } else {
+            std::fs::copy(hosts_path(), &backup).map_err(|e| format!("Failed to back up hosts file: {}", e))?;
+        }
+        changes.hosts_backup = Some(backup);
+    }
+
+    let content = std::fs::read_to_string(hosts_path()).map_err(|e| format!("Failed to read hosts file: {}", e))?;
+    replace_hosts_file(edit(&content).as_bytes())
+}
+
+/// Remove `hostname` from every active hosts line (lines left without names are dropped)
+#[cfg(target_os = "windows")]
+fn without_hostname(content: &str, hostname: &str) -> String {
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let (entry, comment) = match line.find('#') {
+            Some(pos) => line.split_at(pos),
+            None => (line, ""),
+        };
+        let mut fields = entry.split_whitespace();
+        let Some(ip) = fields.next() else {
+            lines.push(line.to_string());
+            continue;
+        };
+        let names: Vec<&str> = fields.collect();
+        if !names.iter().any(|n| n.eq_ignore_ascii_case(hostname)) {
+            lines.push(line.to_string());
+            continue;
+        }
+        let remaining: Vec<&str> = names.into_iter().filter(|n| !n.eq_ignore_ascii_case(hostname)).collect();
+        if !remaining.is_empty() {
+            let comment = if comment.is_empty() { String::new() } else { format!(" {}", comment) };
+            lines.push(format!("{}\t{}{}", ip, remaining.join(" "), comment));
+        }
+    }
+    let mut result = lines.join("\r\n");
+    result.push_str("\r\n");
+    result
+}
+
+// ============================================================================
+// Revert
+// ============================================================================
+
+/// Undo every tracked change; failed reverts stay tracked
+#[cfg(target_os = "windows")]
+pub(crate) fn revert_all() -> Result<RevertReport, String> {
+    let mut changes = CHANGES.lock().map_err(|e| format!("Failed to lock network changes: {}", e))?;
+    let mut report = RevertReport::default();
+
+    let mut failed = Vec::new();
+    for change in changes.dns.drain(..) {
+        match interface_guid(&change.interface).and_then(|guid| set_dns_servers(guid, change.ipv6, &change.original)) {
+            Ok(()) => {
+                if !report.dns_interfaces.contains(&change.interface) {
+                    report.dns_interfaces.push(change.interface.clone());
+                }
+            }
+            Err(e) => {
+                report.errors.push(format!("{}: {}", change.interface, e));
+                failed.push(change);
+            }
+        }
+    }
+    changes.dns = failed;
+
+    if let Some(backup) = changes.hosts_backup.take() {
+        let restored = std::fs::read(&backup)
+            .map_err(|e| format!("Failed to read hosts backup: {}", e))
+            .and_then(|original| replace_hosts_file(&original));
+        match restored {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&backup);
+                report.hosts_restored = true;
+            }
+            Err(e) => {
+                report.errors.push(format!("hosts: {}", e));
+                changes.hosts_backup = Some(backup);
+            }
+        }
+    }
+
+    debug_eprintln!(
+        "[network_overrides] Reverted DNS on {} interfaces, hosts restored: {}, errors: {}",
+        report.dns_interfaces.len(),
+        report.hosts_restored,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+// ============================================================================
+// Commands
+// ============================================================================
+
+/// Set the DNS servers of an interface (by alias, e.g. "Ethernet"), requires elevation
+/// An empty `servers` list returns the interface to DHCP-provided servers.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn set_interface_dns(interface: String, servers: Vec<String>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        require_elevation("Changing DNS servers")?;
+        apply_interface_dns(&interface, &servers)
+    })
+    .await
+    .map_err(|e| format!("DNS change task failed: {}", e))?
+}
+
+/// Map `hostname` to `ip` in the hosts file (replaces existing entries), requires elevation
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn add_hosts_entry(hostname: String, ip: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        require_elevation("Editing the hosts file")?;
+        let hostname = validate_hostname(&hostname)?;
+        let ip: std::net::IpAddr = ip.trim().parse().map_err(|_| format!("Invalid IP address: {}", ip))?;
+
+        edit_hosts_file(|content| {
+            let mut updated = without_hostname(content, &hostname);
+            updated.push_str(&format!("{}\t{}\t{}\r\n", ip, hostname, HOSTS_MARKER));
+            updated
+        })
+    })
+    .await
+    .map_err(|e| format!("Hosts file task failed: {}", e))?
+}
+
+/// Remove every hosts file entry for `hostname`, requires elevation
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn remove_hosts_entry(hostname: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        require_elevation("Editing the hosts file")?;
+        let hostname = validate_hostname(&hostname)?;
+        edit_hosts_file(|content| without_hostname(content, &hostname))
+    })
+    .await
+    .map_err(|e| format!("Hosts file task failed: {}", e))?
+}
+
+/// Undo all DNS server and hosts file changes made this session
+/// Returns `{ dnsInterfaces, hostsRestored, errors }`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn revert_network_changes() -> Result<RevertReport, String> {
+    tokio::task::spawn_blocking(revert_all)
+        .await
+        .map_err(|e| format!("Revert task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn set_interface_dns(_interface: String, _servers: Vec<String>) -> Result<(), String> {
+    Err("Network overrides are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn add_hosts_entry(_hostname: String, _ip: String) -> Result<(), String> {
+    Err("Network overrides are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn remove_hosts_entry(_hostname: String) -> Result<(), String> {
+    Err("Network overrides are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn revert_network_changes() -> Result<RevertReport, String> {
+    Err("Network overrides are only supported on Windows".to_string())
+}
+
+#[cfg(all(test, target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_hostname() {
+        assert_eq!(validate_hostname("Example.COM").unwrap(), "example.com");
+        assert_eq!(validate_hostname("  intranet.local.  ").unwrap(), "intranet.local");
+        assert_eq!(validate_hostname("my_host-01").unwrap(), "my_host-01");
+
+        assert!(validate_hostname("").is_err());
+        assert!(validate_hostname("   ").is_err());
+        assert!(validate_hostname("bad..name").is_err());
+        assert!(validate_hostname("host name").is_err());
+        assert!(validate_hostname("host\tevil.com").is_err());
+        assert!(validate_hostname("evil.com\r\n1.2.3.4 bank.com").is_err());
+        assert!(validate_hostname("x#comment").is_err());
+        assert!(validate_hostname(&"a".repeat(64)).is_err());
+        assert!(validate_hostname(&["a"; 128].join(".")).is_err());
+    }
+
+    #[test]
+    fn test_without_hostname() {
+        let content = "# comment\r\n127.0.0.1\tlocalhost\r\n10.0.0.5\tintranet.local\t# added by Support Center\r\n";
+        assert_eq!(
+            without_hostname(content, "intranet.local"),
+            "# comment\r\n127.0.0.1\tlocalhost\r\n"
+        );
+
+        // Other names on the same line and its comment are kept, matching ignores case
+        assert_eq!(
+            without_hostname("10.0.0.5 a.local INTRANET.local b.local # office\n", "intranet.local"),
+            "10.0.0.5\ta.local b.local # office\r\n"
+        );
+
+        // Commented-out entries and unrelated lines are untouched
+        let commented = "# 10.0.0.5 intranet.local\r\n\r\n127.0.0.1\tlocalhost\r\n";
+        assert_eq!(without_hostname(commented, "intranet.local"), commented);
+
+        // Substrings of other names don't match
+        assert_eq!(
+            without_hostname("10.0.0.5\tmy-intranet.local\r\n", "intranet.local"),
+            "10.0.0.5\tmy-intranet.local\r\n"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Notification Check Module
+//!
+//! Self-check for "I don't get ticket notifications": shows a sample toast and
+//! reports the Windows settings that silently suppress toasts:
+//! - notifications turned off for all apps, or for this app
+//! - notifications disabled by group policy
+//! - Focus Assist ("priority only" / "alarms only")
+//! - the shell's notification state (presentation mode, full-screen app, ...)
+
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// App user model ID the toasts are shown under
+#[cfg(target_os = "windows")]
+const APP_USER_MODEL_ID: &str = "supportcenter.requester";
+
+/// Result of a notification self-check
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationCheck {
+    /// The sample toast was handed to the OS (it may still be suppressed)
+    pub toast_shown: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toast_error: Option<String>,
+    /// Notifications from apps are on (Settings > System > Notifications)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications_enabled: Option<bool>,
+    /// Notifications from this app are on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_notifications_enabled: Option<bool>,
+    /// Toasts are turned off by group policy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_by_policy: Option<bool>,
+    /// Focus Assist: "off", "priorityOnly" or "alarmsOnly"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus_assist: Option<String>,
+    /// Shell notification state: "acceptsNotifications", "busy", "fullScreen",
+    /// "presentationMode", "quietTime", "app" or "notPresent"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_notification_state: Option<String>,
+    /// Human-readable reasons notifications may not appear (empty if none found)
+    pub suppressed_reasons: Vec<String>,
+}
+
+/// Read a REG_DWORD value, None if the key or value is absent
+#[cfg(target_os = "windows")]
+pub(crate) fn registry_dword(root: windows::Win32::System::Registry::HKEY, key_path: &str, name: &str) -> Option<u32> {
+    use windows::Win32::System::Registry::REG_DWORD;
+
+    crate::autostart::read_registry_values(root, key_path)
+        .ok()?
+        .into_iter()
+        .find(|(value_name, _, value_type)| value_name.eq_ignore_ascii_case(name) && *value_type == REG_DWORD.0)
+        .and_then(|(_, data, _)| data.get(..4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+}
+
+/// Focus Assist profile from the shell's WNF state
+/// There is no public API for it; `NtQueryWnfStateData` on
+/// WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED is what the shell itself reads.
+#[cfg(target_os = "windows")]
+fn focus_assist_state() -> Option<&'static str> {
+    use windows::core::{s, w};
+    use windows::Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress};
+
+    type NtQueryWnfStateDataFn = unsafe extern "system" fn(
+        state_name: *const u64,
+        type_id: *const std::ffi::c_void,
+        explicit_scope: *const std::ffi::c_void,
+        change_stamp: *mut u32,
+        buffer: *mut std::ffi::c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+
+    const WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED: u64 = 0x0D83_063E_A3BF_1C75;
+
+    unsafe {
+        // ntdll is loaded in every process
+        let ntdll = GetModuleHandleW(w!("ntdll.dll")).ok()?;
+        let query: NtQueryWnfStateDataFn = std::mem::transmute(GetProcAddress(ntdll, s!("NtQueryWnfStateData"))?);
+
+        let state_name = WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED;
+        let mut change_stamp = 0u32;
+        let mut profile = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = query(
+            &state_name,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile as *mut u32 as *mut _,
+            &mut size,
+        );
+        if status < 0 {
+            debug_eprintln!("[notification_check] NtQueryWnfStateData failed: 0x{:08X}", status);
+            return None;
+        }
+
+        Some(match profile {
+            1 => "priorityOnly",
+            2 => "alarmsOnly",
+            _ => "off",
+        })
+    }
+}
+
+/// Shell notification state (SHQueryUserNotificationState)
+#[cfg(target_os = "windows")]
+fn user_notification_state() -> Option<&'static str> {
+    use windows::Win32::UI::Shell::SHQueryUserNotificationState;
+
+    let state = unsafe { SHQueryUserNotificationState() }.ok()?;
+    Some(match state.0 {
+        1 => "notPresent",
+        2 => "busy",
+        3 => "fullScreen",
+        4 => "presentationMode",
+        5 => "acceptsNotifications",
+        6 => "quietTime",
+        7 => "app",
+        _ => return None,
+    })
+}
+
+/// Read the notification settings and list what may suppress toasts (blocking)
+#[cfg(target_os = "windows")]
+fn read_notification_settings(check: &mut NotificationCheck) {
+    use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    // Absent values mean the default: enabled
+    check.notifications_enabled = Some(
+        registry_dword(
+            HKEY_CURRENT_USER,
+            r"Software\Microsoft\Windows\CurrentVersion\PushNotifications",
+            "ToastEnabled",
+        ) != Some(0),
+    );
+    check.app_notifications_enabled = Some(
+        registry_dword(
+            HKEY_CURRENT_USER,
+            &format!(
+                r"Software\Microsoft\Windows\CurrentVersion\Notifications\Settings\{}",
+                APP_USER_MODEL_ID
+            ),
+            "Enabled",
+        ) != Some(0),
+    );
+    let policy_key = r"Software\Policies\Microsoft\Windows\CurrentVersion\PushNotifications";
+    check.blocked_by_policy = Some(
+        [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE]
+            .into_iter()
+            .any(|root| registry_dword(root, policy_key, "NoToastApplicationNotification") == Some(1)),
+    );
+    check.focus_assist = focus_assist_state().map(str::to_string);
+    check.user_notification_state = user_notification_state().map(str::to_string);
+
+    let mut reasons = Vec::new();
+    if check.notifications_enabled == Some(false) {
+        reasons.push("Notifications are turned off for all apps in Windows settings".to_string());
+    }
+    if check.app_notifications_enabled == Some(false) {
+        reasons.push("Notifications are turned off for Support Center in Windows settings".to_string());
+    }
+    if check.blocked_by_policy == Some(true) {
+        reasons.push("Notifications are disabled by group policy".to_string());
+    }
+    match check.focus_assist.as_deref() {
+        Some("priorityOnly") => reasons.push("Focus Assist is on (priority only)".to_string()),
+        Some("alarmsOnly") => reasons.push("Focus Assist is on (alarms only)".to_string()),
+        _ => {}
+    }
+    match check.user_notification_state.as_deref() {
+        Some("busy") | Some("fullScreen") => {
+            reasons.push("A full-screen app is running; Windows holds notifications until it closes".to_string())
+        }
+        Some("presentationMode") => reasons.push("Presentation mode is on".to_string()),
+        Some("quietTime") => reasons.push("Windows is in quiet time (shortly after setup or sign-in)".to_string()),
+        _ => {}
+    }
+    check.suppressed_reasons = reasons;
+}
+
+/// Show a sample notification and report why notifications may be suppressed
+/// Returns `{ toastShown, toastError?, notificationsEnabled?, appNotificationsEnabled?,
+/// blockedByPolicy?, focusAssist?, userNotificationState?, suppressedReasons }`.
+/// Settings are only reported on Windows.
+#[tauri::command]
+pub async fn test_notification(app: AppHandle) -> Result<NotificationCheck, String> {
+    let mut check = NotificationCheck::default();
+
+    match crate::show_system_notification(
+        app,
+        "Test notification".to_string(),
+        "If you can see this, notifications are working.".to_string(),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    {
+        Ok(()) => check.toast_shown = true,
+        Err(e) => check.toast_error = Some(e),
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        check = tokio::task::spawn_blocking(move || {
+            read_notification_settings(&mut check);
+            check
+        })
+        .await
+        .map_err(|e| format!("Notification check failed: {}", e))?;
+        debug_eprintln!("[notification_check] {:?}", check);
+    }
+
+    Ok(check)
+}
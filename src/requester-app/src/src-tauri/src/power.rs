@@ -0,0 +1,148 @@
+//! Power Management Module
+//!
+//! Keeps the machine and display awake during remote sessions and reports
+//! AC/battery state so the technician knows if a laptop may die mid-session.
+//!
+//! `SetThreadExecutionState` is per-thread and is released when the calling
+//! thread exits, so the request is held by a dedicated keep-awake thread
+//! rather than by the (pooled) command thread.
+
+#[cfg(target_os = "windows")]
+use std::sync::mpsc::Sender;
+#[cfg(target_os = "windows")]
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Signals the keep-awake thread to release (dropping the sender also releases)
+#[cfg(target_os = "windows")]
+static KEEP_AWAKE: Mutex<Option<Sender<()>>> = Mutex::new(None);
+
+/// AC/battery status
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    /// Running on AC power (None = unknown)
+    pub on_ac_power: Option<bool>,
+    /// Whether a system battery is present
+    pub has_battery: bool,
+    /// Remaining battery charge in percent (None = unknown / no battery)
+    pub battery_percent: Option<u8>,
+    /// Estimated seconds of battery life left (None = unknown / on AC)
+    pub battery_seconds_remaining: Option<u32>,
+    /// Whether sleep prevention is currently active
+    pub sleep_prevented: bool,
+}
+
+/// Enable or disable sleep prevention
+///
+/// While enabled, the system and display are kept awake
+/// (ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED).
+/// Returns whether sleep prevention is active after the call.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn prevent_sleep(enabled: bool) -> Result<bool, String> {
+    use windows::Win32::System::Power::{
+        SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+    };
+
+    let mut keep_awake = KEEP_AWAKE
+        .lock()
+        .map_err(|e| format!("Failed to lock keep-awake state: {}", e))?;
+
+    if !enabled {
+        if keep_awake.take().is_some() {
+            debug_eprintln!("[power] Sleep prevention released");
+        }
+        return Ok(false);
+    }
+
+    if keep_awake.is_some() {
+        return Ok(true);
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel::<()>();
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel::<bool>();
+
+    std::thread::Builder::new()
+        .name("keep-awake".to_string())
+        .spawn(move || unsafe {
+            let previous =
+                SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED);
+            let _ = ready_tx.send(previous.0 != 0);
+            if previous.0 == 0 {
+                return;
+            }
+
+            // Block until released (explicit signal or sender dropped)
+            let _ = rx.recv();
+            SetThreadExecutionState(ES_CONTINUOUS);
+        })
+        .map_err(|e| format!("Failed to start keep-awake thread: {}", e))?;
+
+    match ready_rx.recv() {
+        Ok(true) => {
+            *keep_awake = Some(tx);
+            debug_eprintln!("[power] Sleep prevention enabled");
+            Ok(true)
+        }
+        _ => Err("SetThreadExecutionState failed".to_string()),
+    }
+}
+
+/// Get AC/battery status
+/// Returns JSON: `{ onAcPower, hasBattery, batteryPercent, batterySecondsRemaining, sleepPrevented }`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn get_power_status() -> Result<String, String> {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    /// BatteryFlag bit meaning "no system battery"
+    const BATTERY_FLAG_NO_BATTERY: u8 = 128;
+    /// BatteryFlag / ACLineStatus / BatteryLifePercent value meaning "unknown"
+    const UNKNOWN: u8 = 255;
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    unsafe {
+        GetSystemPowerStatus(&mut status)
+            .map_err(|e| format!("Failed to get power status: {}", e))?;
+    }
+
+    let has_battery =
+        status.BatteryFlag != UNKNOWN && status.BatteryFlag & BATTERY_FLAG_NO_BATTERY == 0;
+
+    let power = PowerStatus {
+        on_ac_power: match status.ACLineStatus {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        },
+        has_battery,
+        battery_percent: (has_battery && status.BatteryLifePercent != UNKNOWN)
+            .then_some(status.BatteryLifePercent),
+        battery_seconds_remaining: (has_battery && status.BatteryLifeTime != u32::MAX)
+            .then_some(status.BatteryLifeTime),
+        sleep_prevented: KEEP_AWAKE.lock().map(|k| k.is_some()).unwrap_or(false),
+    };
+
+    serde_json::to_string(&power).map_err(|e| format!("Failed to serialize power status: {}", e))
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub fn prevent_sleep(_enabled: bool) -> Result<bool, String> {
+    Err("Sleep prevention is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub fn get_power_status() -> Result<String, String> {
+    Err("Power status is only supported on Windows".to_string())
+}
@@ -0,0 +1,303 @@
+//! Print Queue Module
+//!
+//! Lists installed printers and their queued jobs, and clears a stuck queue,
+//! via the Winspool API (EnumPrinters / EnumJobs / SetJob). Saves walking the
+//! user through services.msc for the classic "my printer is stuck" call.
+//!
+//! Deleting other users' jobs needs administer rights on the printer; jobs
+//! that can't be deleted are counted rather than failing the whole clear.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// An installed printer
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrinterInfo {
+    pub name: String,
+    /// Raw PRINTER_STATUS_* flags (0 = ready)
+    pub status: u32,
+    /// Human-readable status flags (e.g. ["paused", "paperJam"]), empty when ready
+    pub status_flags: Vec<String>,
+    pub job_count: u32,
+    pub is_default: bool,
+}
+
+/// A queued print job
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintJob {
+    pub printer: String,
+    pub job_id: u32,
+    pub document: String,
+    pub user: String,
+    /// Status text reported by the driver, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_text: Option<String>,
+    /// Raw JOB_STATUS_* flags
+    pub status: u32,
+    pub total_pages: u32,
+    pub pages_printed: u32,
+}
+
+/// Result of clearing a print queue
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearQueueResult {
+    pub printer: String,
+    pub deleted: u32,
+    /// Jobs that could not be deleted (typically other users' jobs without admin rights)
+    pub failed: u32,
+}
+
+/// Printer status flags worth surfacing to a technician
+#[cfg(target_os = "windows")]
+const PRINTER_STATUS_NAMES: &[(u32, &str)] = &[
+    (0x0000_0001, "paused"),
+    (0x0000_0002, "error"),
+    (0x0000_0004, "pendingDeletion"),
+    (0x0000_0008, "paperJam"),
+    (0x0000_0010, "paperOut"),
+    (0x0000_0020, "manualFeed"),
+    (0x0000_0040, "paperProblem"),
+    (0x0000_0080, "offline"),
+    (0x0000_0400, "printing"),
+    (0x0000_1000, "notAvailable"),
+    (0x0004_0000, "tonerLow"),
+    (0x0008_0000, "noToner"),
+    (0x0040_0000, "userIntervention"),
+    (0x0080_0000, "outOfMemory"),
+    (0x0100_0000, "doorOpen"),
+];
+
+#[cfg(target_os = "windows")]
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Read a nul-terminated wide string (empty for null)
+#[cfg(target_os = "windows")]
+unsafe fn pwstr_to_string(value: windows::core::PWSTR) -> String {
+    if value.is_null() {
+        String::new()
+    } else {
+        value.to_string().unwrap_or_default()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn default_printer_name() -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Graphics::Printing::GetDefaultPrinterW;
+
+    unsafe {
+        let mut len = 0u32;
+        let _ = GetDefaultPrinterW(PWSTR::null(), &mut len);
+        if len == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; len as usize];
+        if !GetDefaultPrinterW(PWSTR(buffer.as_mut_ptr()), &mut len).as_bool() {
+            return None;
+        }
+        let end = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..end]))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn enum_printers() -> Result<Vec<PrinterInfo>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Printing::{
+        EnumPrintersW, PRINTER_ENUM_CONNECTIONS, PRINTER_ENUM_LOCAL, PRINTER_INFO_2W,
+    };
+
+    let flags = PRINTER_ENUM_LOCAL | PRINTER_ENUM_CONNECTIONS;
+    let default_printer = default_printer_name();
+
+    unsafe {
+        let mut needed = 0u32;
+        let mut returned = 0u32;
+        // First call sizes the buffer
+        let _ = EnumPrintersW(flags, PCWSTR::null(), 2, None, &mut needed, &mut returned);
+        if needed == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut buffer = vec![0u8; needed as usize];
+        EnumPrintersW(flags, PCWSTR::null(), 2, Some(&mut buffer), &mut needed, &mut returned)
+            .map_err(|e| format!("Failed to enumerate printers: {}", e))?;
+
+        let infos = std::slice::from_raw_parts(buffer.as_ptr() as *const PRINTER_INFO_2W, returned as usize);
+        Ok(infos
+            .iter()
+            .map(|info| {
+                let name = pwstr_to_string(info.pPrinterName);
+                PrinterInfo {
+                    is_default: default_printer.as_deref() == Some(name.as_str()),
+                    status: info.Status,
+                    status_flags: PRINTER_STATUS_NAMES
+                        .iter()
+                        .filter(|(flag, _)| info.Status & flag != 0)
+                        .map(|(_, label)| label.to_string())
+                        .collect(),
+                    job_count: info.cJobs,
+                    name,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Open a printer, run `f` with the handle, and close it
+#[cfg(target_os = "windows")]
+fn with_printer<T>(
+    printer: &str,
+    f: impl FnOnce(windows::Win32::Graphics::Printing::PRINTER_HANDLE) -> Result<T, String>,
+) -> Result<T, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Printing::{ClosePrinter, OpenPrinterW, PRINTER_HANDLE};
+
+    let name = to_wide(printer);
+    unsafe {
+        let mut handle = PRINTER_HANDLE::default();
+        OpenPrinterW(PCWSTR(name.as_ptr()), &mut handle, None)
+            .map_err(|e| format!("Failed to open printer '{}': {}", printer, e))?;
+        let result = f(handle);
+        let _ = ClosePrinter(handle);
+        result
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn enum_jobs(printer: &str) -> Result<Vec<PrintJob>, String> {
+    use windows::Win32::Graphics::Printing::{EnumJobsW, JOB_INFO_1W};
+
+    with_printer(printer, |handle| unsafe {
+        let mut needed = 0u32;
+        let mut returned = 0u32;
+        let _ = EnumJobsW(handle, 0, u32::MAX, 1, None, &mut needed, &mut returned);
+        if needed == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut buffer = vec![0u8; needed as usize];
+        EnumJobsW(handle, 0, u32::MAX, 1, Some(&mut buffer), &mut needed, &mut returned)
+            .map_err(|e| format!("Failed to enumerate jobs for '{}': {}", printer, e))?;
+
+        let jobs = std::slice::from_raw_parts(buffer.as_ptr() as *const JOB_INFO_1W, returned as usize);
+        Ok(jobs
+            .iter()
+            .map(|job| PrintJob {
+                printer: printer.to_string(),
+                job_id: job.JobId,
+                document: pwstr_to_string(job.pDocument),
+                user: pwstr_to_string(job.pUserName),
+                status_text: Some(pwstr_to_string(job.pStatus)).filter(|s| !s.is_empty()),
+                status: job.Status,
+                total_pages: job.TotalPages,
+                pages_printed: job.PagesPrinted,
+            })
+            .collect())
+    })
+}
+
+/// List installed printers (local and network connections)
+/// Returns JSON array: `[{ name, status, statusFlags, jobCount, isDefault }]`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_printers() -> Result<String, String> {
+    let printers = tokio::task::spawn_blocking(enum_printers)
+        .await
+        .map_err(|e| format!("Printer query task failed: {}", e))??;
+
+    serde_json::to_string(&printers).map_err(|e| format!("Failed to serialize printers: {}", e))
+}
+
+/// List queued print jobs for one printer, or for all printers when `printer` is None
+/// Returns JSON array: `[{ printer, jobId, document, user, statusText?, status, totalPages, pagesPrinted }]`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_print_jobs(printer: Option<String>) -> Result<String, String> {
+    let jobs = tokio::task::spawn_blocking(move || {
+        let printers = match printer {
+            Some(name) => vec![name],
+            None => enum_printers()?.into_iter().map(|p| p.name).collect(),
+        };
+
+        let mut jobs = Vec::new();
+        for name in printers {
+            match enum_jobs(&name) {
+                Ok(mut printer_jobs) => jobs.append(&mut printer_jobs),
+                // A single unreachable network printer shouldn't hide the others
+                Err(_e) => debug_eprintln!("[print_queue] Skipping printer: {}", _e),
+            }
+        }
+        Ok::<_, String>(jobs)
+    })
+    .await
+    .map_err(|e| format!("Print job query task failed: {}", e))??;
+
+    serde_json::to_string(&jobs).map_err(|e| format!("Failed to serialize print jobs: {}", e))
+}
+
+/// Delete every job in a printer's queue
+/// Returns JSON: `{ printer, deleted, failed }`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn clear_print_queue(printer: String) -> Result<String, String> {
+    use windows::Win32::Graphics::Printing::{SetJobW, JOB_CONTROL_DELETE};
+
+    let result = tokio::task::spawn_blocking(move || {
+        let jobs = enum_jobs(&printer)?;
+
+        with_printer(&printer, |handle| unsafe {
+            let mut deleted = 0u32;
+            let mut failed = 0u32;
+            for job in &jobs {
+                if SetJobW(handle, job.job_id, 0, None, JOB_CONTROL_DELETE).as_bool() {
+                    deleted += 1;
+                } else {
+                    failed += 1;
+                }
+            }
+            debug_eprintln!("[print_queue] Cleared '{}': {} deleted, {} failed", printer, deleted, failed);
+            Ok(ClearQueueResult {
+                printer: printer.clone(),
+                deleted,
+                failed,
+            })
+        })
+    })
+    .await
+    .map_err(|e| format!("Clear queue task failed: {}", e))??;
+
+    serde_json::to_string(&result).map_err(|e| format!("Failed to serialize result: {}", e))
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_printers() -> Result<String, String> {
+    Err("Printer management is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_print_jobs(_printer: Option<String>) -> Result<String, String> {
+    Err("Printer management is only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn clear_print_queue(_printer: String) -> Result<String, String> {
+    Err("Printer management is only supported on Windows".to_string())
+}
@@ -0,0 +1,181 @@
+//! Protected Content Module
+//!
+//! Screen capture returns black pixels for DRM-protected content (Netflix,
+//! some banking apps) and for windows that opted out of capture with
+//! `SetWindowDisplayAffinity`, so the technician sees an unexplained black
+//! rectangle. After each stream capture the visible windows on the monitor
+//! are checked:
+//! - a display affinity set (WDA_MONITOR shows black, WDA_EXCLUDEFROMCAPTURE
+//!   is left out of the capture entirely): protected for certain
+//! - a large window whose area in the frame is (almost) pure black: most
+//!   likely DRM video, which doesn't set an affinity
+//!
+//! The result is kept per monitor and surfaced as `protectedContentDetected`
+//! plus the window title in the stream metadata.
+
+use serde::Serialize;
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// A window whose content can't be captured
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedWindow {
+    pub title: String,
+    /// "displayAffinity" or "blackRegion"
+    pub reason: &'static str,
+}
+
+/// Last detection per monitor (None = nothing protected in the last frame)
+static LAST_DETECTION: Mutex<Vec<(usize, Option<ProtectedWindow>)>> = Mutex::new(Vec::new());
+
+/// Windows smaller than this (either side, in pixels) are not checked for black regions
+#[cfg(target_os = "windows")]
+const MIN_CHECKED_SIZE: i32 = 200;
+
+/// Grid of samples taken inside a window for the black-region check
+#[cfg(target_os = "windows")]
+const SAMPLE_GRID: u32 = 16;
+
+/// Share of samples that must be pure black for a window to count as blacked out
+#[cfg(target_os = "windows")]
+const BLACK_SAMPLE_RATIO: f64 = 0.98;
+
+/// Whether the part of an RGBA frame covered by `rect` (frame coordinates,
+/// already clipped) is pure black at almost every sample point
+#[cfg(target_os = "windows")]
+fn region_is_black(rgba: &[u8], frame_width: u32, rect: (u32, u32, u32, u32)) -> bool {
+    let (left, top, right, bottom) = rect;
+    let (width, height) = (right - left, bottom - top);
+
+    let mut black = 0u32;
+    let mut total = 0u32;
+    for row in 0..SAMPLE_GRID {
+        for col in 0..SAMPLE_GRID {
+            // Sample cell centers so window borders aren't hit
+            let x = left + (width * (2 * col + 1)) / (2 * SAMPLE_GRID);
+            let y = top + (height * (2 * row + 1)) / (2 * SAMPLE_GRID);
+            let offset = ((y * frame_width + x) * 4) as usize;
+            if let Some(pixel) = rgba.get(offset..offset + 3) {
+                total += 1;
+                if pixel == [0, 0, 0] {
+                    black += 1;
+                }
+            }
+        }
+    }
+    total > 0 && black as f64 / total as f64 >= BLACK_SAMPLE_RATIO
+}
+
+/// Visible, titled top-level windows in z-order: (title, rect, display affinity)
+#[cfg(target_os = "windows")]
+fn visible_windows() -> Vec<(String, windows::Win32::Foundation::RECT, u32)> {
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowDisplayAffinity, GetWindowRect, GetWindowTextW, IsIconic, IsWindowVisible,
+    };
+
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam.0 as *mut Vec<(String, RECT, u32)>);
+        if !IsWindowVisible(hwnd).as_bool() || IsIconic(hwnd).as_bool() {
+            return BOOL(1);
+        }
+        let mut title_buf = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut title_buf);
+        if len <= 0 {
+            return BOOL(1);
+        }
+        let mut rect = RECT::default();
+        if GetWindowRect(hwnd, &mut rect).is_err() {
+            return BOOL(1);
+        }
+        let mut affinity = 0u32;
+        let _ = GetWindowDisplayAffinity(hwnd, &mut affinity);
+        windows.push((String::from_utf16_lossy(&title_buf[..len as usize]), rect, affinity));
+        BOOL(1)
+    }
+
+    let mut windows: Vec<(String, RECT, u32)> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(Some(collect), LPARAM(&mut windows as *mut _ as isize));
+    }
+    windows
+}
+
+/// Check a freshly captured monitor frame for protected content and remember
+/// the result for the monitor
+/// `origin` is the monitor's top-left in virtual screen coordinates.
+#[cfg(target_os = "windows")]
+pub(crate) fn inspect_frame(monitor_id: usize, origin: (i32, i32), rgba: &[u8], width: u32, height: u32) {
+    let mut covered: Vec<windows::Win32::Foundation::RECT> = Vec::new();
+    let mut detected = None;
+
+    for (title, rect, affinity) in visible_windows() {
+        // Clip to the frame
+        let left = (rect.left - origin.0).clamp(0, width as i32);
+        let top = (rect.top - origin.1).clamp(0, height as i32);
+        let right = (rect.right - origin.0).clamp(0, width as i32);
+        let bottom = (rect.bottom - origin.1).clamp(0, height as i32);
+        if right <= left || bottom <= top {
+            continue;
+        }
+
+        if affinity != 0 {
+            detected = Some(ProtectedWindow { title, reason: "displayAffinity" });
+            break;
+        }
+
+        // Only check windows not mostly hidden behind ones above them (z-order)
+        let center = ((rect.left + rect.right) / 2, (rect.top + rect.bottom) / 2);
+        let occluded = covered
+            .iter()
+            .any(|r| center.0 >= r.left && center.0 < r.right && center.1 >= r.top && center.1 < r.bottom);
+        covered.push(rect);
+        if occluded || right - left < MIN_CHECKED_SIZE || bottom - top < MIN_CHECKED_SIZE {
+            continue;
+        }
+
+        if region_is_black(rgba, width, (left as u32, top as u32, right as u32, bottom as u32)) {
+            detected = Some(ProtectedWindow { title, reason: "blackRegion" });
+            break;
+        }
+    }
+
+    if let Some(_window) = &detected {
+        debug_eprintln!(
+            "[protected_content] Monitor {}: \"{}\" ({})",
+            monitor_id, _window.title, _window.reason
+        );
+    }
+    if let Ok(mut last) = LAST_DETECTION.lock() {
+        match last.iter_mut().find(|(id, _)| *id == monitor_id) {
+            Some((_, entry)) => *entry = detected,
+            None => last.push((monitor_id, detected)),
+        }
+    }
+}
+
+/// Protected window found in the monitor's last captured frame, if any
+pub(crate) fn last_detection(monitor_id: usize) -> Option<ProtectedWindow> {
+    LAST_DETECTION
+        .lock()
+        .ok()?
+        .iter()
+        .find(|(id, _)| *id == monitor_id)
+        .and_then(|(_, detected)| detected.clone())
+}
+
+/// Protected content in a monitor's last captured stream frame
+/// Returns `{ protectedContentDetected, window? { title, reason } }` for stream
+/// paths without per-frame metadata (e.g. the binary WebSocket stream).
+#[tauri::command]
+pub fn get_protected_content(monitor_id: usize) -> serde_json::Value {
+    let window = last_detection(monitor_id);
+    serde_json::json!({
+        "protectedContentDetected": window.is_some(),
+        "window": window,
+    })
+}
@@ -0,0 +1,143 @@
+//! Remote Consent Module
+//!
+//! The controlled user must consent before a technician can inject input.
+//! The consent dialog lives in the frontend, but the gate is enforced here:
+//! every `remote_*` command checks for an active, unexpired consent and fails
+//! with a `ConsentRequired` error otherwise, so a frontend bug can't bypass it.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::debug_eprintln;
+
+/// Error prefix returned by gated commands without an active consent
+pub const CONSENT_REQUIRED_ERROR: &str = "ConsentRequired";
+
+/// Longest consent that can be granted at once
+const MAX_CONSENT_SECS: u64 = 8 * 3600;
+
+/// An active consent
+struct RemoteConsent {
+    session_id: String,
+    granted_at_ms: f64,
+    expires: Instant,
+}
+
+static CONSENT: Mutex<Option<RemoteConsent>> = Mutex::new(None);
+
+/// Current consent state
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentStatus {
+    pub active: bool,
+    /// Remote session the consent was given for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Grant time (Unix ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub granted_at_ms: Option<f64>,
+    /// Seconds until the consent expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_secs: Option<u64>,
+}
+
+fn status(consent: Option<&RemoteConsent>) -> ConsentStatus {
+    match consent {
+        Some(consent) => ConsentStatus {
+            active: true,
+            session_id: Some(consent.session_id.clone()),
+            granted_at_ms: Some(consent.granted_at_ms),
+            remaining_secs: Some(consent.expires.saturating_duration_since(Instant::now()).as_secs()),
+        },
+        None => ConsentStatus {
+            active: false,
+            session_id: None,
+            granted_at_ms: None,
+            remaining_secs: None,
+        },
+    }
+}
+
+/// Release held modifiers when control ends, so no injected key stays down
+fn end_control(_reason: &str) {
+    debug_eprintln!("[remote_consent] Consent ended ({})", _reason);
+    if let Err(_e) = crate::remote_input::release_modifiers() {
+        debug_eprintln!("[remote_consent] Failed to release modifiers: {}", _e);
+    }
+}
+
+/// Gate for input commands: Ok only while the user's consent is active
+pub(crate) fn require_consent() -> Result<(), String> {
+    let mut consent = CONSENT
+        .lock()
+        .map_err(|e| format!("Failed to lock remote consent: {}", e))?;
+
+    match consent.as_ref() {
+        Some(active) if active.expires > Instant::now() => Ok(()),
+        Some(_) => {
+            *consent = None;
+            end_control("expired");
+            Err(format!("{}: remote control consent has expired", CONSENT_REQUIRED_ERROR))
+        }
+        None => Err(format!("{}: the user has not consented to remote control", CONSENT_REQUIRED_ERROR)),
+    }
+}
+
+/// Record the user's consent to remote control for a session
+/// `duration_secs`: how long the consent lasts (1 s to 8 h). Replaces any
+/// earlier consent.
+#[tauri::command]
+pub fn grant_remote_consent(session_id: String, duration_secs: u64) -> Result<ConsentStatus, String> {
+    let session_id = session_id.trim().to_string();
+    if session_id.is_empty() {
+        return Err("Session ID is required".to_string());
+    }
+    if duration_secs == 0 || duration_secs > MAX_CONSENT_SECS {
+        return Err(format!("Consent duration must be 1-{} seconds", MAX_CONSENT_SECS));
+    }
+
+    let mut consent = CONSENT
+        .lock()
+        .map_err(|e| format!("Failed to lock remote consent: {}", e))?;
+    *consent = Some(RemoteConsent {
+        session_id,
+        granted_at_ms: crate::unix_time_ms(),
+        expires: Instant::now() + Duration::from_secs(duration_secs),
+    });
+
+    debug_eprintln!("[remote_consent] Consent granted for {}s", duration_secs);
+    Ok(status(consent.as_ref()))
+}
+
+/// Withdraw consent: input commands fail from now on
+/// `session_id`: only withdraw the consent given for this session, so a
+/// session that is shutting down can't revoke the one replacing it.
+#[tauri::command]
+pub fn revoke_remote_consent(session_id: Option<String>) -> Result<(), String> {
+    let mut consent = CONSENT
+        .lock()
+        .map_err(|e| format!("Failed to lock remote consent: {}", e))?;
+    if let (Some(session_id), Some(active)) = (session_id.as_deref(), consent.as_ref()) {
+        if active.session_id != session_id.trim() {
+            return Ok(());
+        }
+    }
+    let revoked = consent.take();
+    drop(consent);
+    if revoked.is_some() {
+        end_control("revoked");
+    }
+    Ok(())
+}
+
+/// Get the current consent state
+/// Returns `{ active, sessionId?, grantedAtMs?, remainingSecs? }`.
+#[tauri::command]
+pub fn get_remote_consent() -> Result<ConsentStatus, String> {
+    let consent = CONSENT
+        .lock()
+        .map_err(|e| format!("Failed to lock remote consent: {}", e))?;
+    Ok(status(consent.as_ref().filter(|c| c.expires > Instant::now())))
+}
@@ -6,12 +6,13 @@
 #[cfg(target_os = "windows")]
 use windows::{
     Win32::UI::Input::KeyboardAndMouse::{
-        keybd_event, SendInput, INPUT, INPUT_0, INPUT_MOUSE,
-        KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        keybd_event, GetKeyboardLayout, GetKeyboardLayoutList, SendInput, VkKeyScanExW, HKL, INPUT,
+        INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+        KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_RETURN,
         MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP,
         MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN,
-        MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_WHEEL,
-        MOUSEINPUT, VK_CONTROL, VK_MENU, VK_SHIFT,
+        MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK, MOUSEEVENTF_WHEEL,
+        MOUSEINPUT, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
     },
 };
 
@@ -48,6 +49,76 @@ pub fn inject_mouse_move_with_dims(x: i32, y: i32, screen_width: i32, screen_hei
 }
 
 
+#[cfg(target_os = "windows")]
+/// Inject mouse move to absolute coordinates over the whole virtual desktop
+/// `abs_x`/`abs_y` are already normalized to the 0-65535 range spanning all monitors
+/// (MOUSEEVENTF_VIRTUALDESK), so secondary displays are reachable.
+pub fn inject_mouse_move_virtual(abs_x: i32, abs_y: i32) -> Result<(), String> {
+    unsafe {
+        let input = INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx: abs_x,
+                    dy: abs_y,
+                    mouseData: 0,
+                    dwFlags: MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        let result = SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+        if result == 0 {
+            return Err("Failed to inject mouse move".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+/// Inject mouse click at absolute coordinates over the whole virtual desktop
+/// Batches move + down + up in a single SendInput call like `inject_mouse_click_with_dims`
+pub fn inject_mouse_click_virtual(abs_x: i32, abs_y: i32, button: u32) -> Result<(), String> {
+    unsafe {
+        let (down_flag, up_flag) = match button {
+            0 => (MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP),
+            1 => (MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP),
+            2 => (MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP),
+            _ => return Err("Invalid button".to_string()),
+        };
+
+        let mouse_input = |dx: i32, dy: i32, flags| INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx,
+                    dy,
+                    mouseData: 0,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        let inputs = [
+            mouse_input(abs_x, abs_y, MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE | MOUSEEVENTF_VIRTUALDESK),
+            mouse_input(0, 0, down_flag),
+            mouse_input(0, 0, up_flag),
+        ];
+
+        let result = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+        if result == 0 {
+            return Err("Failed to inject mouse click".to_string());
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(target_os = "windows")]
 /// Inject mouse button down event
 pub fn inject_mouse_down(button: u32) -> Result<(), String> {
@@ -332,11 +403,69 @@ fn key_code_to_vk(code: &str) -> Option<u16> {
     }
 }
 
+#[cfg(target_os = "windows")]
+/// Keyboard layout of the foreground window's thread, which receives injected keys
+fn target_layout() -> HKL {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let foreground = GetForegroundWindow();
+        let thread_id = if foreground.0.is_null() {
+            0
+        } else {
+            GetWindowThreadProcessId(foreground, None)
+        };
+        GetKeyboardLayout(thread_id)
+    }
+}
+
+#[cfg(target_os = "windows")]
+/// Virtual key and (shift, ctrl, alt) state that type `ch` on the target layout
+/// None if the layout has no key for it.
+fn char_to_vk(ch: char) -> Option<(u16, bool, bool, bool)> {
+    // VkKeyScanExW takes one UTF-16 unit
+    let unit = u16::try_from(ch as u32).ok()?;
+    let scan = unsafe { VkKeyScanExW(unit, target_layout()) };
+    if scan == -1 {
+        return None;
+    }
+    let state = (scan as u16) >> 8;
+    Some(((scan as u16) & 0xFF, state & 1 != 0, state & 2 != 0, state & 4 != 0))
+}
+
+#[cfg(target_os = "windows")]
+/// Resolve a key event to (vk, ctrl, shift, alt)
+/// With `key` (the character the controller typed), the key is mapped through
+/// the target's layout so AZERTY/QWERTZ/Arabic machines get the right character;
+/// otherwise the physical `code` is mapped as on a US layout.
+fn resolve_key(code: &str, key: Option<&str>, ctrl: bool, shift: bool, alt: bool) -> Result<(u16, bool, bool, bool), String> {
+    let mut chars = key.unwrap_or_default().chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        if let Some((vk, needs_shift, needs_ctrl, needs_alt)) = char_to_vk(ch) {
+            // The character already reflects the controller's Shift
+            return Ok((vk, ctrl || needs_ctrl, needs_shift, alt || needs_alt));
+        }
+    }
+    let vk = key_code_to_vk(code).ok_or("Unknown key code")?;
+    Ok((vk, ctrl, shift, alt))
+}
+
+#[cfg(target_os = "windows")]
+/// Keys resolved at key down, by physical code, so key up releases the same
+/// keys even if the typed character changed in between (Shift released first)
+static PRESSED_KEYS: std::sync::Mutex<Option<std::collections::HashMap<String, (u16, bool, bool, bool)>>> =
+    std::sync::Mutex::new(None);
+
 #[cfg(target_os = "windows")]
 /// Inject keyboard key down event
-pub fn inject_key_down(code: &str, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
+/// `key`: optional typed character, mapped through the target's keyboard layout
+pub fn inject_key_down(code: &str, key: Option<&str>, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
     unsafe {
-        let vk = key_code_to_vk(code).ok_or("Unknown key code")?;
+        let resolved = resolve_key(code, key, ctrl, shift, alt)?;
+        if let Ok(mut pressed) = PRESSED_KEYS.lock() {
+            pressed.get_or_insert_with(Default::default).insert(code.to_string(), resolved);
+        }
+        let (vk, ctrl, shift, alt) = resolved;
 
         // Press modifier keys first
         if ctrl {
@@ -358,9 +487,17 @@ pub fn inject_key_down(code: &str, ctrl: bool, shift: bool, alt: bool) -> Result
 
 #[cfg(target_os = "windows")]
 /// Inject keyboard key up event
-pub fn inject_key_up(code: &str, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
+/// Releases the keys resolved by the matching `inject_key_down`, if any
+pub fn inject_key_up(code: &str, key: Option<&str>, ctrl: bool, shift: bool, alt: bool) -> Result<(), String> {
     unsafe {
-        let vk = key_code_to_vk(code).ok_or("Unknown key code")?;
+        let pressed = PRESSED_KEYS
+            .lock()
+            .ok()
+            .and_then(|mut pressed| pressed.as_mut().and_then(|pressed| pressed.remove(code)));
+        let (vk, ctrl, shift, alt) = match pressed {
+            Some(resolved) => resolved,
+            None => resolve_key(code, key, ctrl, shift, alt)?,
+        };
 
         // Release the main key
         keybd_event(vk as u8, 0, KEYEVENTF_KEYUP, 0);
@@ -380,12 +517,117 @@ pub fn inject_key_up(code: &str, ctrl: bool, shift: bool, alt: bool) -> Result<(
     }
 }
 
+#[cfg(target_os = "windows")]
+fn key_input(vk: u16, unicode: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(vk),
+                wScan: unicode,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+#[cfg(target_os = "windows")]
+/// Type text on the target's keyboard layout
+/// Each character is typed with the key (and Shift/AltGr state) that produces
+/// it on the active layout; characters the layout can't type are sent as
+/// Unicode input instead.
+pub fn inject_text(text: &str) -> Result<(), String> {
+    for ch in text.chars() {
+        let mut inputs = Vec::new();
+        match (ch, char_to_vk(ch)) {
+            ('\n', _) => {
+                inputs.push(key_input(VK_RETURN.0, 0, KEYBD_EVENT_FLAGS(0)));
+                inputs.push(key_input(VK_RETURN.0, 0, KEYEVENTF_KEYUP));
+            }
+            ('\r', _) => continue,
+            (_, Some((vk, shift, ctrl, alt))) => {
+                let modifiers: Vec<u16> = [(ctrl, VK_CONTROL), (alt, VK_MENU), (shift, VK_SHIFT)]
+                    .into_iter()
+                    .filter(|(held, _)| *held)
+                    .map(|(_, modifier)| modifier.0)
+                    .collect();
+                inputs.extend(modifiers.iter().map(|&m| key_input(m, 0, KEYBD_EVENT_FLAGS(0))));
+                inputs.push(key_input(vk, 0, KEYBD_EVENT_FLAGS(0)));
+                inputs.push(key_input(vk, 0, KEYEVENTF_KEYUP));
+                inputs.extend(modifiers.iter().rev().map(|&m| key_input(m, 0, KEYEVENTF_KEYUP)));
+            }
+            (_, None) => {
+                let mut units = [0u16; 2];
+                for &unit in ch.encode_utf16(&mut units).iter() {
+                    inputs.push(key_input(0, unit, KEYEVENTF_UNICODE));
+                    inputs.push(key_input(0, unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP));
+                }
+            }
+        }
+
+        let sent = unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32) };
+        if sent as usize != inputs.len() {
+            return Err("Failed to inject text".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+/// Layout handle as the usual 8-digit hex (low word = language ID)
+fn format_layout(hkl: HKL) -> String {
+    format!("{:08X}", hkl.0 as usize as u32)
+}
+
+#[cfg(target_os = "windows")]
+/// Active keyboard layout of the foreground window and the installed layouts
+/// Returns JSON: `{ active, activeLangId, installed: [..] }`.
+pub fn keyboard_layout_info() -> Result<String, String> {
+    let active = target_layout();
+
+    let count = unsafe { GetKeyboardLayoutList(None) };
+    let mut layouts = vec![HKL::default(); count.max(0) as usize];
+    let filled = unsafe { GetKeyboardLayoutList(Some(&mut layouts)) };
+    layouts.truncate(filled.max(0) as usize);
+
+    Ok(serde_json::json!({
+        "active": format_layout(active),
+        "activeLangId": format!("{:04X}", active.0 as usize & 0xFFFF),
+        "installed": layouts.into_iter().map(format_layout).collect::<Vec<_>>(),
+    })
+    .to_string())
+}
+
+#[cfg(target_os = "windows")]
+/// Release every modifier key (Ctrl, Shift, Alt, Win)
+/// Used when a remote session ends so no injected modifier is left held down
+pub fn release_modifiers() -> Result<(), String> {
+    unsafe {
+        for vk in [VK_CONTROL, VK_SHIFT, VK_MENU, VK_LWIN, VK_RWIN] {
+            keybd_event(vk.0 as u8, 0, KEYEVENTF_KEYUP, 0);
+        }
+        Ok(())
+    }
+}
+
 // Stub implementations for non-Windows platforms
 #[cfg(not(target_os = "windows"))]
 pub fn inject_mouse_move_with_dims(_x: i32, _y: i32, _screen_width: i32, _screen_height: i32) -> Result<(), String> {
     Err("Input injection is only supported on Windows".to_string())
 }
 
+#[cfg(not(target_os = "windows"))]
+pub fn inject_mouse_move_virtual(_abs_x: i32, _abs_y: i32) -> Result<(), String> {
+    Err("Input injection is only supported on Windows".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn inject_mouse_click_virtual(_abs_x: i32, _abs_y: i32, _button: u32) -> Result<(), String> {
+    Err("Input injection is only supported on Windows".to_string())
+}
+
 #[cfg(not(target_os = "windows"))]
 pub fn inject_mouse_move(_x: i32, _y: i32) -> Result<(), String> {
     Err("Input injection is only supported on Windows".to_string())
@@ -417,11 +659,26 @@ pub fn inject_mouse_wheel(_delta: i32) -> Result<(), String> {
 }
 
 #[cfg(not(target_os = "windows"))]
-pub fn inject_key_down(_code: &str, _ctrl: bool, _shift: bool, _alt: bool) -> Result<(), String> {
+pub fn inject_key_down(_code: &str, _key: Option<&str>, _ctrl: bool, _shift: bool, _alt: bool) -> Result<(), String> {
+    Err("Input injection is only supported on Windows".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn inject_key_up(_code: &str, _key: Option<&str>, _ctrl: bool, _shift: bool, _alt: bool) -> Result<(), String> {
     Err("Input injection is only supported on Windows".to_string())
 }
 
 #[cfg(not(target_os = "windows"))]
-pub fn inject_key_up(_code: &str, _ctrl: bool, _shift: bool, _alt: bool) -> Result<(), String> {
+pub fn inject_text(_text: &str) -> Result<(), String> {
     Err("Input injection is only supported on Windows".to_string())
 }
+
+#[cfg(not(target_os = "windows"))]
+pub fn keyboard_layout_info() -> Result<String, String> {
+    Err("Keyboard layouts are only supported on Windows".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn release_modifiers() -> Result<(), String> {
+    Ok(())
+}
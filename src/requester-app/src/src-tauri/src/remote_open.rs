@@ -0,0 +1,178 @@
+//! Remote Open Module
+//!
+//! Lets a technician open a help page or a document (e.g. a config file) on
+//! the controlled machine during a remote session.
+//!
+//! SECURITY: this must never become a way to run programs on the user's
+//! machine:
+//! - URLs must be http or https with a host
+//! - files must exist, resolve (after following links) inside the user
+//!   profile, Public or ProgramData, and have a document extension from
+//!   `OPENABLE_EXTENSIONS` - executables, scripts, installers and shortcuts
+//!   are refused
+
+#[cfg(target_os = "windows")]
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
+
+/// Document types that may be opened (with their associated application)
+#[cfg(target_os = "windows")]
+const OPENABLE_EXTENSIONS: &[&str] = &[
+    "txt", "log", "ini", "cfg", "conf", "config", "json", "xml", "yaml", "yml", "csv", "md",
+    "pdf", "rtf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods",
+    "png", "jpg", "jpeg", "gif", "bmp",
+];
+
+/// Environment variables naming the directories files may be opened from
+#[cfg(target_os = "windows")]
+const ALLOWED_ROOT_VARS: &[&str] = &["USERPROFILE", "PUBLIC", "ProgramData"];
+
+/// What `remote_open` was asked to open
+#[cfg(target_os = "windows")]
+#[derive(Debug)]
+enum OpenTarget {
+    Url(String),
+    File(PathBuf),
+}
+
+/// Strip the `\\?\` prefix `canonicalize` adds (the shell doesn't expect it)
+#[cfg(target_os = "windows")]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|p| p.strip_prefix(r"\\?\")) {
+        Some(stripped) if !stripped.starts_with("UNC\\") => PathBuf::from(stripped),
+        _ => path,
+    }
+}
+
+/// Validate an http(s) URL
+#[cfg(target_os = "windows")]
+fn validate_url(input: &str) -> Result<String, String> {
+    let parsed = url::Url::parse(input).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("URL scheme not allowed: {}", parsed.scheme()));
+    }
+    if parsed.host_str().map_or(true, str::is_empty) {
+        return Err("URL has no host".to_string());
+    }
+    Ok(parsed.to_string())
+}
+
+/// Validate a document path: exists, is a file, is inside an allowed
+/// directory and has a document extension
+#[cfg(target_os = "windows")]
+fn validate_file(input: &str) -> Result<PathBuf, String> {
+    let path = Path::new(input);
+    // Alternate data streams ("file.txt:payload") - only the drive colon is allowed
+    if input.char_indices().any(|(i, c)| c == ':' && i != 1) {
+        return Err(format!("Invalid path: {}", input));
+    }
+
+    let canonical = path
+        .canonicalize()
+        .map(strip_verbatim)
+        .map_err(|_| format!("File not found: {}", input))?;
+    if !canonical.is_file() {
+        return Err(format!("Not a file: {}", input));
+    }
+
+    let extension = canonical
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !OPENABLE_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(format!("File type not allowed: .{}", extension));
+    }
+
+    let allowed = ALLOWED_ROOT_VARS
+        .iter()
+        .filter_map(|var| std::env::var_os(var))
+        .filter_map(|root| PathBuf::from(root).canonicalize().ok().map(strip_verbatim))
+        .any(|root| canonical.starts_with(&root));
+    if !allowed {
+        return Err(format!("Path is outside the allowed directories: {}", canonical.display()));
+    }
+
+    Ok(canonical)
+}
+
+/// Classify and validate the input
+#[cfg(target_os = "windows")]
+fn parse_target(input: &str) -> Result<OpenTarget, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Nothing to open".to_string());
+    }
+
+    let lower = input.to_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        return validate_url(input).map(OpenTarget::Url);
+    }
+    // Any other scheme (file:, ms-settings:, search-ms:, ...) is refused
+    if let Some((scheme, _)) = input.split_once(':') {
+        if scheme.len() > 1 {
+            return Err(format!("URL scheme not allowed: {}", scheme));
+        }
+    }
+
+    validate_file(input).map(OpenTarget::File)
+}
+
+/// Open a URL in the default browser or a document in its associated application
+#[cfg(target_os = "windows")]
+fn shell_open(target: &str) -> Result<(), String> {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let target_wide: Vec<u16> = target.encode_utf16().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        let result = ShellExecuteW(
+            HWND::default(),
+            w!("open"),
+            PCWSTR(target_wide.as_ptr()),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        );
+        // Values <= 32 are error codes
+        if result.0 as isize <= 32 {
+            return Err(format!("Failed to open {}: error {}", target, result.0 as isize));
+        }
+    }
+    Ok(())
+}
+
+/// Open a URL (http/https) or a document on this machine for a technician
+/// Files must exist inside the user profile, Public or ProgramData and be a
+/// document type; executables and scripts are refused. Requires the user's
+/// remote-control consent.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn remote_open(path_or_url: String) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    crate::remote_consent::require_consent()?;
+
+    let target = match parse_target(&path_or_url)? {
+        OpenTarget::Url(url) => url,
+        OpenTarget::File(path) => path.to_string_lossy().to_string(),
+    };
+
+    debug_eprintln!("[remote_open] Opening {}", target);
+    tokio::task::spawn_blocking(move || shell_open(&target))
+        .await
+        .map_err(|e| format!("Open task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn remote_open(_path_or_url: String) -> Result<(), String> {
+    feature_policy::require(Feature::RemoteInput)?;
+    Err("Remote open is only supported on Windows".to_string())
+}
@@ -0,0 +1,67 @@
+//! Resource Usage Module
+//!
+//! Reports the app's own memory and handle usage so growth over a long remote
+//! session (e.g. frame buffers that are never freed) can be charted and
+//! confirmed:
+//! - working set and private bytes via GetProcessMemoryInfo
+//! - kernel handles via GetProcessHandleCount
+//! - GDI and USER objects via GetGuiResources
+
+/// Query the current process's memory and handle counts
+#[cfg(target_os = "windows")]
+fn query_resource_usage() -> Result<serde_json::Value, String> {
+    use windows::Win32::System::ProcessStatus::{
+        GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS, PROCESS_MEMORY_COUNTERS_EX,
+    };
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, GetGuiResources, GetProcessHandleCount, GR_GDIOBJECTS,
+        GR_GDIOBJECTS_PEAK, GR_USEROBJECTS,
+    };
+
+    unsafe {
+        // Pseudo handle, no need to close
+        let process = GetCurrentProcess();
+
+        let mut counters = PROCESS_MEMORY_COUNTERS_EX {
+            cb: std::mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+            ..Default::default()
+        };
+        GetProcessMemoryInfo(
+            process,
+            &mut counters as *mut PROCESS_MEMORY_COUNTERS_EX as *mut PROCESS_MEMORY_COUNTERS,
+            counters.cb,
+        )
+        .map_err(|e| format!("Failed to query process memory: {}", e))?;
+
+        let mut handle_count = 0u32;
+        GetProcessHandleCount(process, &mut handle_count)
+            .map_err(|e| format!("Failed to query handle count: {}", e))?;
+
+        Ok(serde_json::json!({
+            "timestampMs": crate::unix_time_ms(),
+            "workingSetBytes": counters.WorkingSetSize,
+            "peakWorkingSetBytes": counters.PeakWorkingSetSize,
+            "privateBytes": counters.PrivateUsage,
+            "handleCount": handle_count,
+            "gdiObjects": GetGuiResources(process, GR_GDIOBJECTS),
+            "peakGdiObjects": GetGuiResources(process, GR_GDIOBJECTS_PEAK),
+            "userObjects": GetGuiResources(process, GR_USEROBJECTS),
+        }))
+    }
+}
+
+/// Get the app's current memory and handle usage
+/// Returns `{ timestampMs, workingSetBytes, peakWorkingSetBytes, privateBytes,
+/// handleCount, gdiObjects, peakGdiObjects, userObjects }`.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn get_resource_usage() -> Result<serde_json::Value, String> {
+    query_resource_usage()
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub fn get_resource_usage() -> Result<serde_json::Value, String> {
+    Err("Resource usage reporting is only supported on Windows".to_string())
+}
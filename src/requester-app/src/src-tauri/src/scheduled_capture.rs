@@ -0,0 +1,203 @@
+//! Scheduled Capture Module
+//!
+//! For issues that recur on a schedule ("it glitches every hour on the hour"),
+//! a technician can schedule screenshots ahead of time and collect them later
+//! with the log bundle. Each capture is saved to the logs directory exactly
+//! like `save_screenshot_to_logs`.
+//!
+//! Schedules are persisted in the store so they survive a restart; on startup
+//! pending ones are re-armed and those whose time passed while the app was
+//! down are dropped.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
+use crate::storage;
+
+/// Pending schedules allowed at once
+const MAX_SCHEDULED: usize = 20;
+
+/// How far ahead a capture can be scheduled
+const MAX_SCHEDULE_AHEAD_MS: f64 = 7.0 * 24.0 * 3600.0 * 1000.0;
+
+/// A capture missed by less than this (e.g. during a restart) still runs
+const MISSED_GRACE_MS: f64 = 60_000.0;
+
+/// Longest single sleep; the wall clock is re-checked after each one so
+/// system sleep or clock changes don't delay a capture
+const MAX_SLEEP: Duration = Duration::from_secs(30);
+
+/// A pending capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledCapture {
+    pub id: String,
+    pub monitor_id: usize,
+    /// Unix ms
+    pub at_epoch_ms: f64,
+    pub label: String,
+}
+
+/// Pending schedules, ordered by time
+static SCHEDULES: Mutex<Vec<ScheduledCapture>> = Mutex::new(Vec::new());
+
+fn lock_schedules() -> Result<std::sync::MutexGuard<'static, Vec<ScheduledCapture>>, String> {
+    SCHEDULES
+        .lock()
+        .map_err(|e| format!("Failed to lock scheduled captures: {}", e))
+}
+
+fn persist(app: &AppHandle, schedules: &[ScheduledCapture]) {
+    let persisted = serde_json::to_value(schedules)
+        .map_err(|e| format!("Failed to serialize scheduled captures: {}", e))
+        .and_then(|value| storage::set_value(app, storage::KEY_SCHEDULED_CAPTURES, value));
+    if let Err(_e) = persisted {
+        debug_eprintln!("[scheduled_capture] Failed to persist schedules: {}", _e);
+    }
+}
+
+/// Remove a schedule; false if it was already cancelled
+fn take_schedule(app: &AppHandle, id: &str) -> Result<bool, String> {
+    let mut schedules = lock_schedules()?;
+    let before = schedules.len();
+    schedules.retain(|s| s.id != id);
+    let removed = schedules.len() != before;
+    if removed {
+        persist(app, &schedules);
+    }
+    Ok(removed)
+}
+
+/// Wait for a schedule's time, then capture unless it was cancelled meanwhile
+async fn run_schedule(app: AppHandle, schedule: ScheduledCapture) {
+    loop {
+        let remaining_ms = schedule.at_epoch_ms - crate::unix_time_ms();
+        if remaining_ms <= 0.0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(remaining_ms as u64).min(MAX_SLEEP)).await;
+
+        match lock_schedules() {
+            Ok(schedules) if schedules.iter().any(|s| s.id == schedule.id) => {}
+            _ => return,
+        }
+    }
+
+    match take_schedule(&app, &schedule.id) {
+        Ok(true) => {}
+        _ => return,
+    }
+
+    let result =
+        crate::logging::save_screenshot_to_logs(app.clone(), schedule.monitor_id, schedule.label.clone()).await;
+    let payload = match &result {
+        Ok(path) => serde_json::json!({ "id": schedule.id, "label": schedule.label, "path": path }),
+        Err(e) => {
+            debug_eprintln!("[scheduled_capture] Capture {} failed: {}", schedule.id, e);
+            serde_json::json!({ "id": schedule.id, "label": schedule.label, "error": e })
+        }
+    };
+    let _ = app.emit("scheduled-capture-complete", payload);
+}
+
+/// Re-arm persisted schedules at startup
+pub(crate) fn restore_scheduled_captures(app: &AppHandle) {
+    let persisted: Vec<ScheduledCapture> = storage::get_value(app, storage::KEY_SCHEDULED_CAPTURES)
+        .ok()
+        .flatten()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    if persisted.is_empty() {
+        return;
+    }
+
+    let now = crate::unix_time_ms();
+    let (pending, _missed): (Vec<_>, Vec<_>) = persisted
+        .into_iter()
+        .partition(|s| s.at_epoch_ms + MISSED_GRACE_MS > now);
+    debug_eprintln!(
+        "[scheduled_capture] Restored {} schedule(s), dropped {} missed",
+        pending.len(),
+        _missed.len()
+    );
+
+    let Ok(mut schedules) = lock_schedules() else {
+        return;
+    };
+    *schedules = pending.clone();
+    persist(app, &schedules);
+    drop(schedules);
+
+    for schedule in pending {
+        tauri::async_runtime::spawn(run_schedule(app.clone(), schedule));
+    }
+}
+
+/// Schedule a screenshot of a monitor at a future time
+/// `at_epoch_ms`: Unix ms, at most 7 days ahead. The file is saved to the
+/// logs directory as by `save_screenshot_to_logs` and
+/// `scheduled-capture-complete` is emitted with `{ id, label, path?, error? }`.
+/// Returns the new schedule.
+#[tauri::command]
+pub fn schedule_capture(
+    app: AppHandle,
+    monitor_id: usize,
+    at_epoch_ms: f64,
+    label: String,
+) -> Result<ScheduledCapture, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let now = crate::unix_time_ms();
+    if !at_epoch_ms.is_finite() || at_epoch_ms <= now {
+        return Err("Scheduled time must be in the future".to_string());
+    }
+    if at_epoch_ms - now > MAX_SCHEDULE_AHEAD_MS {
+        return Err("Captures can be scheduled at most 7 days ahead".to_string());
+    }
+
+    let schedule = ScheduledCapture {
+        id: uuid::Uuid::new_v4().simple().to_string(),
+        monitor_id,
+        at_epoch_ms,
+        label: label.trim().to_string(),
+    };
+
+    {
+        let mut schedules = lock_schedules()?;
+        if schedules.len() >= MAX_SCHEDULED {
+            return Err(format!("At most {} captures can be scheduled at once", MAX_SCHEDULED));
+        }
+        schedules.push(schedule.clone());
+        schedules.sort_by(|a, b| a.at_epoch_ms.total_cmp(&b.at_epoch_ms));
+        persist(&app, &schedules);
+    }
+
+    debug_eprintln!(
+        "[scheduled_capture] Capture {} of monitor {} scheduled in {:.0}s",
+        schedule.id,
+        monitor_id,
+        (at_epoch_ms - now) / 1000.0
+    );
+    tauri::async_runtime::spawn(run_schedule(app, schedule.clone()));
+    Ok(schedule)
+}
+
+/// List pending captures, soonest first
+#[tauri::command]
+pub fn list_scheduled_captures() -> Result<Vec<ScheduledCapture>, String> {
+    Ok(lock_schedules()?.clone())
+}
+
+/// Cancel a pending capture
+#[tauri::command]
+pub fn cancel_scheduled_capture(app: AppHandle, id: String) -> Result<(), String> {
+    if take_schedule(&app, id.trim())? {
+        Ok(())
+    } else {
+        Err(format!("No scheduled capture with ID {}", id))
+    }
+}
@@ -0,0 +1,416 @@
+//! Scheduled Tasks Module
+//!
+//! Lists and controls Task Scheduler tasks on this machine via the Task
+//! Scheduler COM API (ITaskService), so a technician can trigger a maintenance
+//! task or stop a misbehaving job during a remote session.
+//!
+//! Unless the app runs elevated, only tasks that run as the current user are
+//! listed or controlled.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Maximum number of tasks returned by a listing
+#[cfg(target_os = "windows")]
+const MAX_TASKS: usize = 500;
+
+/// A registered scheduled task
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    /// Full task path (e.g. "\Microsoft\Windows\Defrag\ScheduledDefrag")
+    pub path: String,
+    pub name: String,
+    pub enabled: bool,
+    /// "unknown", "disabled", "queued", "ready" or "running"
+    pub state: String,
+    /// Next run time (local, "YYYY-MM-DD HH:MM:SS"), None if not scheduled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run_time: Option<String>,
+    /// Account the task runs as
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_as: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+fn state_name(state: i32) -> &'static str {
+    match state {
+        1 => "disabled",
+        2 => "queued",
+        3 => "ready",
+        4 => "running",
+        _ => "unknown",
+    }
+}
+
+/// Format an OLE automation date (days since 1899-12-30, local time)
+#[cfg(target_os = "windows")]
+fn format_ole_date(date: f64) -> Option<String> {
+    // Zero means "never"
+    if date <= 0.0 {
+        return None;
+    }
+
+    let total_secs = (date * 86_400.0).round() as i64;
+    // Shift the epoch from 1899-12-30 to 1970-01-01 (25569 days)
+    let days = total_secs.div_euclid(86_400) - 25_569;
+    let secs_of_day = total_secs.rem_euclid(86_400);
+
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    Some(format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        (secs_of_day % 3_600) / 60,
+        secs_of_day % 60
+    ))
+}
+
+/// Whether a task principal refers to the current user
+#[cfg(target_os = "windows")]
+fn is_current_user(run_as: Option<&str>) -> bool {
+    let Some(run_as) = run_as.filter(|r| !r.is_empty()) else {
+        return false;
+    };
+    let Ok(username) = std::env::var("USERNAME") else {
+        return false;
+    };
+    let account = run_as.rsplit('\\').next().unwrap_or(run_as);
+    account.eq_ignore_ascii_case(&username)
+}
+
+/// Run `f` with a connected ITaskService on a COM-initialized thread
+#[cfg(target_os = "windows")]
+fn with_task_service<T>(
+    f: impl FnOnce(&windows::Win32::System::TaskScheduler::ITaskService) -> Result<T, String>,
+) -> Result<T, String> {
+    use windows::core::VARIANT;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    };
+    use windows::Win32::System::TaskScheduler::{ITaskService, TaskScheduler};
+
+    unsafe {
+        // S_FALSE = already initialized, still needs uninit
+        let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+        let result = (|| {
+            let service: ITaskService = CoCreateInstance(&TaskScheduler, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("Failed to create task service: {}", e))?;
+            let empty = VARIANT::default();
+            service
+                .Connect(&empty, &empty, &empty, &empty)
+                .map_err(|e| format!("Failed to connect to Task Scheduler: {}", e))?;
+            f(&service)
+        })();
+
+        if com_initialized {
+            CoUninitialize();
+        }
+
+        result
+    }
+}
+
+/// Read the account a registered task runs as
+#[cfg(target_os = "windows")]
+unsafe fn task_run_as(task: &windows::Win32::System::TaskScheduler::IRegisteredTask) -> Option<String> {
+    task.Definition()
+        .and_then(|definition| definition.Principal())
+        .and_then(|principal| principal.UserId())
+        .map(|user| user.to_string())
+        .ok()
+        .filter(|user| !user.is_empty())
+}
+
+/// Recursively collect tasks from a folder and its subfolders
+#[cfg(target_os = "windows")]
+unsafe fn collect_tasks(
+    folder: &windows::Win32::System::TaskScheduler::ITaskFolder,
+    own_only: bool,
+    out: &mut Vec<ScheduledTask>,
+) {
+    use windows::core::VARIANT;
+    use windows::Win32::System::TaskScheduler::TASK_ENUM_HIDDEN;
+
+    if let Ok(tasks) = folder.GetTasks(TASK_ENUM_HIDDEN.0) {
+        let count = tasks.Count().unwrap_or(0);
+        // Collections are 1-based
+        for index in 1..=count {
+            if out.len() >= MAX_TASKS {
+                return;
+            }
+            let Ok(task) = tasks.get_Item(&VARIANT::from(index)) else {
+                continue;
+            };
+
+            let run_as = task_run_as(&task);
+            if own_only && !is_current_user(run_as.as_deref()) {
+                continue;
+            }
+
+            out.push(ScheduledTask {
+                path: task.Path().map(|p| p.to_string()).unwrap_or_default(),
+                name: task.Name().map(|n| n.to_string()).unwrap_or_default(),
+                enabled: task.Enabled().map(|e| e.as_bool()).unwrap_or(false),
+                state: state_name(task.State().map(|s| s.0).unwrap_or(0)).to_string(),
+                next_run_time: task.NextRunTime().ok().and_then(format_ole_date),
+                run_as,
+            });
+        }
+    }
+
+    if let Ok(folders) = folder.GetFolders(0) {
+        let count = folders.Count().unwrap_or(0);
+        for index in 1..=count {
+            if out.len() >= MAX_TASKS {
+                return;
+            }
+            if let Ok(subfolder) = folders.get_Item(&VARIANT::from(index)) {
+                collect_tasks(&subfolder, own_only, out);
+            }
+        }
+    }
+}
+
+/// Look up a task by path, enforcing the own-tasks restriction when not elevated
+#[cfg(target_os = "windows")]
+unsafe fn get_task(
+    service: &windows::Win32::System::TaskScheduler::ITaskService,
+    path: &str,
+) -> Result<windows::Win32::System::TaskScheduler::IRegisteredTask, String> {
+    use windows::core::BSTR;
+
+    let root = service
+        .GetFolder(&BSTR::from("\\"))
+        .map_err(|e| format!("Failed to open task root folder: {}", e))?;
+    let task = root
+        .GetTask(&BSTR::from(path))
+        .map_err(|e| format!("Scheduled task not found: {} ({})", path, e))?;
+
+    if !crate::is_elevated() && !is_current_user(task_run_as(&task).as_deref()) {
+        return Err(format!("Scheduled task {} does not run as the current user", path));
+    }
+
+    Ok(task)
+}
+
+/// A task that starts at user logon (startup program triage)
+#[cfg(target_os = "windows")]
+pub(crate) struct LogonTask {
+    pub path: String,
+    pub name: String,
+    pub enabled: bool,
+    /// Program and arguments of the first exec action
+    pub command: Option<String>,
+}
+
+/// Whether a task has a logon trigger
+#[cfg(target_os = "windows")]
+unsafe fn has_logon_trigger(definition: &windows::Win32::System::TaskScheduler::ITaskDefinition) -> bool {
+    use windows::Win32::System::TaskScheduler::TASK_TRIGGER_LOGON;
+
+    let Ok(triggers) = definition.Triggers() else {
+        return false;
+    };
+    let count = triggers.Count().unwrap_or(0);
+    // Collections are 1-based
+    (1..=count).any(|index| {
+        triggers
+            .get_Item(index)
+            .and_then(|trigger| trigger.Type())
+            .map(|kind| kind == TASK_TRIGGER_LOGON)
+            .unwrap_or(false)
+    })
+}
+
+/// Command line of a task's first exec action
+#[cfg(target_os = "windows")]
+unsafe fn task_command(definition: &windows::Win32::System::TaskScheduler::ITaskDefinition) -> Option<String> {
+    use windows::core::Interface;
+    use windows::Win32::System::TaskScheduler::IExecAction;
+
+    let action = definition.Actions().ok()?.get_Item(1).ok()?;
+    let exec: IExecAction = action.cast().ok()?;
+    let program = exec.Path().ok()?.to_string();
+    let arguments = exec.Arguments().map(|a| a.to_string()).unwrap_or_default();
+    Some(if arguments.is_empty() { program } else { format!("{} {}", program, arguments) })
+}
+
+/// Recursively collect tasks with a logon trigger
+#[cfg(target_os = "windows")]
+unsafe fn collect_logon_tasks(
+    folder: &windows::Win32::System::TaskScheduler::ITaskFolder,
+    own_only: bool,
+    out: &mut Vec<LogonTask>,
+) {
+    use windows::core::VARIANT;
+    use windows::Win32::System::TaskScheduler::TASK_ENUM_HIDDEN;
+
+    if let Ok(tasks) = folder.GetTasks(TASK_ENUM_HIDDEN.0) {
+        let count = tasks.Count().unwrap_or(0);
+        for index in 1..=count {
+            if out.len() >= MAX_TASKS {
+                return;
+            }
+            let Ok(task) = tasks.get_Item(&VARIANT::from(index)) else {
+                continue;
+            };
+            let Ok(definition) = task.Definition() else {
+                continue;
+            };
+            if !has_logon_trigger(&definition) {
+                continue;
+            }
+            if own_only && !is_current_user(task_run_as(&task).as_deref()) {
+                continue;
+            }
+
+            out.push(LogonTask {
+                path: task.Path().map(|p| p.to_string()).unwrap_or_default(),
+                name: task.Name().map(|n| n.to_string()).unwrap_or_default(),
+                enabled: task.Enabled().map(|e| e.as_bool()).unwrap_or(false),
+                command: task_command(&definition),
+            });
+        }
+    }
+
+    if let Ok(folders) = folder.GetFolders(0) {
+        let count = folders.Count().unwrap_or(0);
+        for index in 1..=count {
+            if let Ok(subfolder) = folders.get_Item(&VARIANT::from(index)) {
+                collect_logon_tasks(&subfolder, own_only, out);
+            }
+        }
+    }
+}
+
+/// List tasks that start at logon (only the current user's unless elevated)
+#[cfg(target_os = "windows")]
+pub(crate) fn list_logon_tasks() -> Result<Vec<LogonTask>, String> {
+    let own_only = !crate::is_elevated();
+    with_task_service(|service| unsafe {
+        use windows::core::BSTR;
+
+        let root = service
+            .GetFolder(&BSTR::from("\\"))
+            .map_err(|e| format!("Failed to open task root folder: {}", e))?;
+        let mut tasks = Vec::new();
+        collect_logon_tasks(&root, own_only, &mut tasks);
+        Ok(tasks)
+    })
+}
+
+/// Enable or disable a task (same own-tasks restriction as run/stop)
+#[cfg(target_os = "windows")]
+pub(crate) fn set_task_enabled(path: &str, enabled: bool) -> Result<(), String> {
+    with_task_service(|service| unsafe {
+        use windows::Win32::Foundation::{VARIANT_FALSE, VARIANT_TRUE};
+
+        let task = get_task(service, path)?;
+        task.SetEnabled(if enabled { VARIANT_TRUE } else { VARIANT_FALSE })
+            .map_err(|e| format!("Failed to update task {}: {}", path, e))?;
+        debug_eprintln!("[scheduled_tasks] {} {}", if enabled { "Enabled" } else { "Disabled" }, path);
+        Ok(())
+    })
+}
+
+/// List scheduled tasks
+/// Returns JSON array: `[{ path, name, enabled, state, nextRunTime?, runAs? }]`
+/// (only the current user's tasks unless elevated, at most 500)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_scheduled_tasks() -> Result<String, String> {
+    let tasks = tokio::task::spawn_blocking(|| {
+        let own_only = !crate::is_elevated();
+        with_task_service(|service| unsafe {
+            use windows::core::BSTR;
+
+            let root = service
+                .GetFolder(&BSTR::from("\\"))
+                .map_err(|e| format!("Failed to open task root folder: {}", e))?;
+            let mut tasks = Vec::new();
+            collect_tasks(&root, own_only, &mut tasks);
+            debug_eprintln!("[scheduled_tasks] Listed {} tasks (own_only={})", tasks.len(), own_only);
+            Ok(tasks)
+        })
+    })
+    .await
+    .map_err(|e| format!("Task listing failed: {}", e))??;
+
+    serde_json::to_string(&tasks).map_err(|e| format!("Failed to serialize tasks: {}", e))
+}
+
+/// Start a scheduled task now
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn run_scheduled_task(path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        with_task_service(|service| unsafe {
+            use windows::core::VARIANT;
+
+            let task = get_task(service, &path)?;
+            task.Run(&VARIANT::default())
+                .map_err(|e| format!("Failed to run task {}: {}", path, e))?;
+            debug_eprintln!("[scheduled_tasks] Started {}", path);
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| format!("Task run failed: {}", e))?
+}
+
+/// Stop all running instances of a scheduled task
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn stop_scheduled_task(path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        with_task_service(|service| unsafe {
+            let task = get_task(service, &path)?;
+            task.Stop(0)
+                .map_err(|e| format!("Failed to stop task {}: {}", path, e))?;
+            debug_eprintln!("[scheduled_tasks] Stopped {}", path);
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| format!("Task stop failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_scheduled_tasks() -> Result<String, String> {
+    Err("Scheduled tasks are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn run_scheduled_task(_path: String) -> Result<(), String> {
+    Err("Scheduled tasks are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn stop_scheduled_task(_path: String) -> Result<(), String> {
+    Err("Scheduled tasks are only supported on Windows".to_string())
+}
@@ -0,0 +1,316 @@
+//! Scroll Capture Module
+//!
+//! Captures a window whose content is taller than its viewport (a long log,
+//! web page or chat transcript) as one tall image:
+//! 1. scroll the window to the top (WM_VSCROLL SB_TOP)
+//! 2. capture the client area with PrintWindow
+//! 3. scroll one page (WM_VSCROLL SB_PAGEDOWN, or the mouse wheel for windows
+//!    that ignore scroll messages, e.g. browsers), capture again
+//! 4. find how far the content moved by matching rows between the frames,
+//!    append only the new rows, and stop when the content no longer moves
+//!
+//! Sticky headers and footers (rows identical at the same position in both
+//! frames) are kept once. Windows that don't scroll return a single view. The
+//! window is left scrolled to wherever the capture ended.
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
+
+/// Stop after this many viewports
+#[cfg(target_os = "windows")]
+const MAX_SCROLL_FRAMES: usize = 40;
+
+/// Stop once the stitched image is this tall (pixels)
+#[cfg(target_os = "windows")]
+const MAX_STITCHED_HEIGHT: u32 = 30_000;
+
+/// Time for the window to repaint after scrolling
+#[cfg(target_os = "windows")]
+const SCROLL_SETTLE_MS: u64 = 200;
+
+/// Mouse wheel notches per step for windows that only scroll by wheel
+#[cfg(target_os = "windows")]
+const WHEEL_NOTCHES_PER_STEP: i32 = 5;
+
+/// How a window is scrolled
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScrollMethod {
+    ScrollMessage,
+    MouseWheel,
+}
+
+/// One captured viewport (RGBA rows) with a hash per row for matching
+#[cfg(target_os = "windows")]
+struct Frame {
+    image: image::RgbaImage,
+    row_hashes: Vec<u64>,
+}
+
+#[cfg(target_os = "windows")]
+impl Frame {
+    fn new(image: image::RgbaImage) -> Self {
+        use std::hash::{Hash, Hasher};
+
+        let row_bytes = image.width() as usize * 4;
+        let row_hashes = image
+            .as_raw()
+            .chunks(row_bytes.max(1))
+            .map(|row| {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                row.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+        Self { image, row_hashes }
+    }
+
+    fn height(&self) -> usize {
+        self.row_hashes.len()
+    }
+
+    /// Raw RGBA bytes of rows `start..end`
+    fn rows(&self, start: usize, end: usize) -> &[u8] {
+        let row_bytes = self.image.width() as usize * 4;
+        &self.image.as_raw()[start * row_bytes..end * row_bytes]
+    }
+}
+
+/// How the content moved between two frames of the same size
+#[cfg(target_os = "windows")]
+#[derive(Debug, PartialEq)]
+struct ScrollOffset {
+    /// Rows the content moved up (0 = nothing moved)
+    scrolled: usize,
+    /// Rows at the bottom that stayed in place (sticky footer)
+    footer: usize,
+}
+
+/// Find how far the content scrolled from `prev` to `next` (row hashes)
+///
+/// Leading and trailing rows that are identical in place are treated as a
+/// fixed header/footer. Within the rest, the smallest shift where the bottom
+/// of `prev` matches the top of `next` is the scroll distance; if nothing
+/// matches, the whole body is new.
+#[cfg(target_os = "windows")]
+fn find_scroll_offset(prev: &[u64], next: &[u64]) -> ScrollOffset {
+    if prev == next {
+        return ScrollOffset { scrolled: 0, footer: 0 };
+    }
+
+    let height = prev.len().min(next.len());
+    let header = prev.iter().zip(next).take_while(|(a, b)| a == b).count();
+    let footer = prev[..height]
+        .iter()
+        .rev()
+        .zip(next[..height].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(height - header);
+
+    let body_prev = &prev[header..height - footer];
+    let body_next = &next[header..height - footer];
+    let body = body_prev.len();
+
+    let scrolled = (1..body)
+        .find(|&shift| body_prev[shift..] == body_next[..body - shift])
+        .unwrap_or(body);
+    ScrollOffset { scrolled, footer }
+}
+
+/// Capture the client area of a window with PrintWindow
+#[cfg(target_os = "windows")]
+fn print_window(hwnd: windows::Win32::Foundation::HWND) -> Result<image::RgbaImage, String> {
+    use windows::Win32::Foundation::RECT;
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+        ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS, PW_CLIENTONLY};
+    use windows::Win32::UI::WindowsAndMessaging::GetClientRect;
+
+    /// Capture DirectComposition/GPU-rendered content too (not in the SDK bindings)
+    const PW_RENDERFULLCONTENT: u32 = 0x2;
+
+    unsafe {
+        let mut rect = RECT::default();
+        GetClientRect(hwnd, &mut rect).map_err(|e| format!("Failed to get window size: {}", e))?;
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width <= 0 || height <= 0 {
+            return Err("Window has no visible client area".to_string());
+        }
+
+        let window_dc = GetDC(hwnd);
+        let memory_dc = CreateCompatibleDC(window_dc);
+        let bitmap = CreateCompatibleBitmap(window_dc, width, height);
+        let previous = SelectObject(memory_dc, bitmap);
+
+        let printed = PrintWindow(hwnd, memory_dc, PRINT_WINDOW_FLAGS(PW_CLIENTONLY.0 | PW_RENDERFULLCONTENT));
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                biHeight: -height, // top-down rows
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let lines = GetDIBits(
+            memory_dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut std::ffi::c_void),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        SelectObject(memory_dc, previous);
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory_dc);
+        ReleaseDC(hwnd, window_dc);
+
+        if !printed.as_bool() {
+            return Err("PrintWindow failed".to_string());
+        }
+        if lines == 0 {
+            return Err("Failed to read window pixels".to_string());
+        }
+
+        // BGRA -> RGBA, opaque
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            pixel[3] = 255;
+        }
+        image::RgbaImage::from_raw(width as u32, height as u32, pixels)
+            .ok_or_else(|| "Captured buffer has the wrong size".to_string())
+    }
+}
+
+/// Scroll a window one step down (or to the top)
+#[cfg(target_os = "windows")]
+fn scroll_window(hwnd: windows::Win32::Foundation::HWND, method: ScrollMethod, to_top: bool) {
+    use windows::Win32::Foundation::{LPARAM, POINT, RECT, WPARAM};
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetClientRect, PostMessageW, SendMessageW, SB_PAGEDOWN, SB_TOP, WHEEL_DELTA,
+        WM_MOUSEWHEEL, WM_VSCROLL,
+    };
+
+    unsafe {
+        match method {
+            ScrollMethod::ScrollMessage => {
+                let command = if to_top { SB_TOP } else { SB_PAGEDOWN };
+                SendMessageW(hwnd, WM_VSCROLL, WPARAM(command.0 as usize), LPARAM(0));
+            }
+            ScrollMethod::MouseWheel => {
+                // Wheel messages carry the cursor position in screen coordinates
+                let mut rect = RECT::default();
+                let _ = GetClientRect(hwnd, &mut rect);
+                let mut center = POINT { x: rect.right / 2, y: rect.bottom / 2 };
+                let _ = ClientToScreen(hwnd, &mut center);
+
+                let notches = if to_top { WHEEL_NOTCHES_PER_STEP * MAX_SCROLL_FRAMES as i32 } else { -WHEEL_NOTCHES_PER_STEP };
+                let delta = (notches * WHEEL_DELTA as i32) as i16 as u16 as usize;
+                let position = ((center.y as u16 as isize) << 16) | (center.x as u16 as isize);
+                let _ = PostMessageW(hwnd, WM_MOUSEWHEEL, WPARAM(delta << 16), LPARAM(position));
+            }
+        }
+    }
+    std::thread::sleep(std::time::Duration::from_millis(SCROLL_SETTLE_MS));
+}
+
+/// Scroll through a window and stitch its viewports into one image
+#[cfg(target_os = "windows")]
+fn capture_scrolling(hwnd: windows::Win32::Foundation::HWND) -> Result<image::RgbaImage, String> {
+    let mut method = ScrollMethod::ScrollMessage;
+    scroll_window(hwnd, method, true);
+
+    let first = Frame::new(print_window(hwnd)?);
+    let (width, height) = first.image.dimensions();
+    let mut stitched: Vec<u8> = first.image.as_raw().clone();
+    let mut stitched_height = height;
+    let mut prev = first;
+    let mut frames = 1;
+
+    while frames < MAX_SCROLL_FRAMES && stitched_height < MAX_STITCHED_HEIGHT {
+        scroll_window(hwnd, method, false);
+        let next = Frame::new(print_window(hwnd)?);
+        if next.image.dimensions() != (width, height) {
+            debug_eprintln!("[scroll_capture] Window was resized during capture, stopping");
+            break;
+        }
+
+        let offset = find_scroll_offset(&prev.row_hashes, &next.row_hashes);
+        if offset.scrolled == 0 {
+            // Some windows (browsers, modern apps) ignore WM_VSCROLL: try the wheel once
+            if frames == 1 && method == ScrollMethod::ScrollMessage {
+                method = ScrollMethod::MouseWheel;
+                continue;
+            }
+            break;
+        }
+
+        // Replace the previous footer with the new rows and the (same) footer
+        let row_bytes = width as usize * 4;
+        let keep_rows = stitched_height as usize - offset.footer;
+        stitched.truncate(keep_rows * row_bytes);
+        let new_start = next.height() - offset.footer - offset.scrolled;
+        stitched.extend_from_slice(next.rows(new_start, next.height()));
+        stitched_height = (keep_rows + offset.scrolled + offset.footer) as u32;
+
+        prev = next;
+        frames += 1;
+    }
+
+    debug_eprintln!(
+        "[scroll_capture] Stitched {} frames into {}x{} ({:?})",
+        frames, width, stitched_height, method
+    );
+    image::RgbaImage::from_raw(width, stitched_height, stitched)
+        .ok_or_else(|| "Stitched buffer has the wrong size".to_string())
+}
+
+/// Capture a scrollable window (by HWND) as one tall image
+/// Scrolls the window from the top through its content and stitches the
+/// viewports; a window that doesn't scroll returns its single view.
+/// Returns base64-encoded PNG image.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn capture_window_scrolling(hwnd: i64) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    use base64::{engine::general_purpose, Engine as _};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+
+    tokio::task::spawn_blocking(move || {
+        let hwnd = HWND(hwnd as isize as *mut std::ffi::c_void);
+        if !unsafe { IsWindow(hwnd) }.as_bool() {
+            return Err(format!("Window not found: {}", hwnd.0 as isize));
+        }
+
+        let image = capture_scrolling(hwnd)?;
+        let mut png_buffer = Vec::new();
+        image
+            .write_to(&mut std::io::Cursor::new(&mut png_buffer), image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        Ok(general_purpose::STANDARD.encode(&png_buffer))
+    })
+    .await
+    .map_err(|e| format!("Capture task failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn capture_window_scrolling(_hwnd: i64) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    Err("Scrolling window capture is only supported on Windows".to_string())
+}
@@ -0,0 +1,151 @@
+//! Security Products Module
+//!
+//! Lists the antivirus/EDR products registered with Windows Security Center
+//! (WMI `root\SecurityCenter2`, class `AntiVirusProduct`), so a technician can
+//! tell which security tooling might be blocking an install or remote session.
+//!
+//! Security Center only exists on client editions of Windows; on servers the
+//! namespace is missing and the query fails.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// A registered security product
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityProduct {
+    pub name: String,
+    /// Real-time protection is on
+    pub enabled: bool,
+    /// Signature definitions are current
+    pub up_to_date: bool,
+    /// Raw `productState` bit field
+    pub product_state: u32,
+    /// Executable of the product's UI/agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
+}
+
+/// Decode Security Center's `productState`: (enabled, definitions up to date)
+/// The second byte holds the scanner state (0x10 = on), the low byte the
+/// signature state (0x00 = up to date, 0x10 = out of date).
+#[cfg(target_os = "windows")]
+fn decode_product_state(state: u32) -> (bool, bool) {
+    (state & 0x1000 != 0, state & 0x10 == 0)
+}
+
+/// Read a string property of a WMI object (None if missing or null)
+#[cfg(target_os = "windows")]
+unsafe fn wmi_string(
+    object: &windows::Win32::System::Wmi::IWbemClassObject,
+    name: windows::core::PCWSTR,
+) -> Option<String> {
+    use windows::core::{BSTR, VARIANT};
+
+    let mut value = VARIANT::default();
+    object.Get(name, 0, &mut value, None, None).ok()?;
+    BSTR::try_from(&value).ok().map(|s| s.to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "windows")]
+fn query_security_products() -> Result<Vec<SecurityProduct>, String> {
+    use windows::core::{w, BSTR, VARIANT};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    };
+    use windows::Win32::System::Wmi::{
+        IWbemClassObject, IWbemLocator, WbemLocator, WBEM_FLAG_FORWARD_ONLY,
+        WBEM_FLAG_RETURN_IMMEDIATELY, WBEM_INFINITE,
+    };
+
+    unsafe {
+        // S_FALSE = already initialized, still needs uninit
+        let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+        let result = (|| {
+            let locator: IWbemLocator = CoCreateInstance(&WbemLocator, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("Failed to create WMI locator: {}", e))?;
+            let services = locator
+                .ConnectServer(
+                    &BSTR::from("ROOT\\SecurityCenter2"),
+                    &BSTR::new(),
+                    &BSTR::new(),
+                    &BSTR::new(),
+                    0,
+                    &BSTR::new(),
+                    None,
+                )
+                .map_err(|e| format!("Security Center is not available: {}", e))?;
+            let results = services
+                .ExecQuery(
+                    &BSTR::from("WQL"),
+                    &BSTR::from("SELECT displayName, productState, pathToSignedProductExe FROM AntiVirusProduct"),
+                    WBEM_FLAG_FORWARD_ONLY | WBEM_FLAG_RETURN_IMMEDIATELY,
+                    None,
+                )
+                .map_err(|e| format!("Failed to query security products: {}", e))?;
+
+            let mut products = Vec::new();
+            loop {
+                let mut objects: [Option<IWbemClassObject>; 1] = [None];
+                let mut returned = 0u32;
+                let _ = results.Next(WBEM_INFINITE, &mut objects, &mut returned);
+                let Some(object) = objects[0].take().filter(|_| returned > 0) else {
+                    break;
+                };
+
+                let Some(name) = wmi_string(&object, w!("displayName")) else {
+                    continue;
+                };
+                let mut state = VARIANT::default();
+                let product_state = object
+                    .Get(w!("productState"), 0, &mut state, None, None)
+                    .ok()
+                    .and_then(|_| i32::try_from(&state).ok())
+                    .unwrap_or(0) as u32;
+                let (enabled, up_to_date) = decode_product_state(product_state);
+
+                products.push(SecurityProduct {
+                    name,
+                    enabled,
+                    up_to_date,
+                    product_state,
+                    executable: wmi_string(&object, w!("pathToSignedProductExe")),
+                });
+            }
+
+            debug_eprintln!("[security_products] Found {} security products", products.len());
+            Ok(products)
+        })();
+
+        if com_initialized {
+            CoUninitialize();
+        }
+
+        result
+    }
+}
+
+/// List antivirus/EDR products registered with Windows Security Center
+/// Returns JSON array: `[{ name, enabled, upToDate, productState, executable? }]`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn get_security_products() -> Result<String, String> {
+    let products = tokio::task::spawn_blocking(query_security_products)
+        .await
+        .map_err(|e| format!("Security product query task failed: {}", e))??;
+
+    serde_json::to_string(&products).map_err(|e| format!("Failed to serialize security products: {}", e))
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn get_security_products() -> Result<String, String> {
+    Err("Security product detection is only supported on Windows".to_string())
+}
@@ -0,0 +1,371 @@
+//! Services Module
+//!
+//! Lists and controls Windows services on this machine through the Service
+//! Control Manager, so a technician can restart a hung service (Print
+//! Spooler, a line-of-business service) during a remote session.
+//!
+//! Listing works for any user; starting and stopping services requires the
+//! app to run elevated.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// How long a start or stop may take before it is reported as timed out
+#[cfg(target_os = "windows")]
+const STATE_CHANGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Interval between status polls while waiting for a state change
+#[cfg(target_os = "windows")]
+const STATE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// An installed Win32 service
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceInfo {
+    /// Service (key) name, e.g. "Spooler"
+    pub name: String,
+    /// Display name, e.g. "Print Spooler"
+    pub display_name: String,
+    /// "stopped", "startPending", "stopPending", "running", "continuePending",
+    /// "pausePending", "paused" or "unknown"
+    pub state: String,
+    /// "boot", "system", "auto", "manual", "disabled" or "unknown"
+    /// (absent if the configuration couldn't be read)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_type: Option<String>,
+}
+
+/// Action requested by `control_service`
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+#[cfg(target_os = "windows")]
+impl ServiceAction {
+    fn parse(action: &str) -> Result<Self, String> {
+        match action {
+            "start" => Ok(ServiceAction::Start),
+            "stop" => Ok(ServiceAction::Stop),
+            "restart" => Ok(ServiceAction::Restart),
+            _ => Err(format!("Unknown service action: {} (expected start, stop or restart)", action)),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn state_name(state: u32) -> &'static str {
+    match state {
+        1 => "stopped",
+        2 => "startPending",
+        3 => "stopPending",
+        4 => "running",
+        5 => "continuePending",
+        6 => "pausePending",
+        7 => "paused",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn start_type_name(start_type: u32) -> &'static str {
+    match start_type {
+        0 => "boot",
+        1 => "system",
+        2 => "auto",
+        3 => "manual",
+        4 => "disabled",
+        _ => "unknown",
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// SCM or service handle, closed on drop
+#[cfg(target_os = "windows")]
+struct ScHandle(windows::Win32::System::Services::SC_HANDLE);
+
+#[cfg(target_os = "windows")]
+impl Drop for ScHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::System::Services::CloseServiceHandle(self.0);
+        }
+    }
+}
+
+/// Connect to the local Service Control Manager
+#[cfg(target_os = "windows")]
+fn open_scm(access: u32) -> Result<ScHandle, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Services::OpenSCManagerW;
+
+    unsafe { OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), access) }
+        .map(ScHandle)
+        .map_err(|e| format!("Failed to open the Service Control Manager: {}", e))
+}
+
+/// Open a service by (key) name
+#[cfg(target_os = "windows")]
+fn open_service(scm: &ScHandle, name: &str, access: u32) -> Result<ScHandle, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_SERVICE_DOES_NOT_EXIST};
+    use windows::Win32::System::Services::OpenServiceW;
+
+    let name_wide = to_wide(name);
+    unsafe { OpenServiceW(scm.0, PCWSTR::from_raw(name_wide.as_ptr()), access) }
+        .map(ScHandle)
+        .map_err(|e| {
+            if e.code() == ERROR_SERVICE_DOES_NOT_EXIST.to_hresult() {
+                format!("Service not found: {}", name)
+            } else if e.code() == ERROR_ACCESS_DENIED.to_hresult() {
+                format!("Access denied to service {}", name)
+            } else {
+                format!("Failed to open service {}: {}", name, e)
+            }
+        })
+}
+
+/// Start type of a service, None if its configuration can't be read
+#[cfg(target_os = "windows")]
+fn query_start_type(scm: &ScHandle, name: &str) -> Option<u32> {
+    use windows::Win32::System::Services::{QueryServiceConfigW, QUERY_SERVICE_CONFIGW, SERVICE_QUERY_CONFIG};
+
+    let service = open_service(scm, name, SERVICE_QUERY_CONFIG).ok()?;
+    unsafe {
+        // First call reports the size needed for the config and its strings
+        let mut needed = 0u32;
+        let _ = QueryServiceConfigW(service.0, None, 0, &mut needed);
+        if needed == 0 {
+            return None;
+        }
+        // u64 buffer keeps QUERY_SERVICE_CONFIGW properly aligned
+        let mut buffer = vec![0u64; (needed as usize).div_ceil(8)];
+        let config = buffer.as_mut_ptr() as *mut QUERY_SERVICE_CONFIGW;
+        QueryServiceConfigW(service.0, Some(config), needed, &mut needed).ok()?;
+        Some((*config).dwStartType.0)
+    }
+}
+
+/// Enumerate all Win32 services (blocking)
+#[cfg(target_os = "windows")]
+fn enumerate_services(filter: Option<&str>) -> Result<Vec<ServiceInfo>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{GetLastError, ERROR_MORE_DATA};
+    use windows::Win32::System::Services::{
+        EnumServicesStatusExW, ENUM_SERVICE_STATUS_PROCESSW, SC_ENUM_PROCESS_INFO, SC_MANAGER_CONNECT,
+        SC_MANAGER_ENUMERATE_SERVICE, SERVICE_STATE_ALL, SERVICE_WIN32,
+    };
+
+    let scm = open_scm(SC_MANAGER_CONNECT | SC_MANAGER_ENUMERATE_SERVICE)?;
+    let filter = filter.map(str::to_lowercase).filter(|f| !f.is_empty());
+
+    // u64 buffer keeps ENUM_SERVICE_STATUS_PROCESSW properly aligned
+    let mut buffer: Vec<u64> = vec![0; 8 * 1024];
+    let mut services = Vec::new();
+    let mut resume = 0u32;
+
+    loop {
+        let mut needed = 0u32;
+        let mut returned = 0u32;
+        let bytes = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8) };
+
+        let result = unsafe {
+            EnumServicesStatusExW(
+                scm.0,
+                SC_ENUM_PROCESS_INFO,
+                SERVICE_WIN32,
+                SERVICE_STATE_ALL,
+                Some(bytes),
+                &mut needed,
+                &mut returned,
+                Some(&mut resume),
+                PCWSTR::null(),
+            )
+        };
+        let more_data = match result {
+            Ok(()) => false,
+            Err(_) if unsafe { GetLastError() } == ERROR_MORE_DATA => true,
+            Err(e) => return Err(format!("Failed to enumerate services: {}", e)),
+        };
+
+        let entries = unsafe {
+            std::slice::from_raw_parts(buffer.as_ptr() as *const ENUM_SERVICE_STATUS_PROCESSW, returned as usize)
+        };
+        for entry in entries {
+            let name = unsafe { entry.lpServiceName.to_string() }.unwrap_or_default();
+            let display_name = unsafe { entry.lpDisplayName.to_string() }.unwrap_or_default();
+            if let Some(filter) = &filter {
+                if !name.to_lowercase().contains(filter) && !display_name.to_lowercase().contains(filter) {
+                    continue;
+                }
+            }
+            services.push(ServiceInfo {
+                state: state_name(entry.ServiceStatusProcess.dwCurrentState.0).to_string(),
+                start_type: None,
+                name,
+                display_name,
+            });
+        }
+
+        if !more_data {
+            break;
+        }
+        if returned == 0 {
+            // A single entry didn't fit; grow to the size requested
+            buffer.resize((needed as usize).div_ceil(8), 0);
+        }
+    }
+
+    // Start types need a handle per service, so only read them for the matches
+    for service in &mut services {
+        service.start_type = query_start_type(&scm, &service.name).map(|t| start_type_name(t).to_string());
+    }
+    services.sort_by_key(|s| s.display_name.to_lowercase());
+
+    Ok(services)
+}
+
+/// Current state of an open service
+#[cfg(target_os = "windows")]
+fn query_state(service: &ScHandle, name: &str) -> Result<u32, String> {
+    use windows::Win32::System::Services::{QueryServiceStatus, SERVICE_STATUS};
+
+    let mut status = SERVICE_STATUS::default();
+    unsafe { QueryServiceStatus(service.0, &mut status) }
+        .map_err(|e| format!("Failed to query service {}: {}", name, e))?;
+    Ok(status.dwCurrentState.0)
+}
+
+/// Poll until the service reaches `target` (a SERVICE_* state)
+#[cfg(target_os = "windows")]
+fn wait_for_state(service: &ScHandle, name: &str, target: u32) -> Result<(), String> {
+    let started = std::time::Instant::now();
+    loop {
+        let state = query_state(service, name)?;
+        if state == target {
+            return Ok(());
+        }
+        if started.elapsed() > STATE_CHANGE_TIMEOUT {
+            return Err(format!(
+                "Service {} did not become {} within {}s (currently {})",
+                name,
+                state_name(target),
+                STATE_CHANGE_TIMEOUT.as_secs(),
+                state_name(state)
+            ));
+        }
+        std::thread::sleep(STATE_POLL_INTERVAL);
+    }
+}
+
+/// Stop a service and wait until it is stopped
+#[cfg(target_os = "windows")]
+fn stop_service(service: &ScHandle, name: &str) -> Result<(), String> {
+    use windows::Win32::Foundation::ERROR_DEPENDENT_SERVICES_RUNNING;
+    use windows::Win32::System::Services::{ControlService, SERVICE_CONTROL_STOP, SERVICE_STATUS, SERVICE_STOPPED};
+
+    if query_state(service, name)? == SERVICE_STOPPED.0 {
+        return Ok(());
+    }
+
+    let mut status = SERVICE_STATUS::default();
+    unsafe { ControlService(service.0, SERVICE_CONTROL_STOP, &mut status) }.map_err(|e| {
+        if e.code() == ERROR_DEPENDENT_SERVICES_RUNNING.to_hresult() {
+            format!("Cannot stop {}: other running services depend on it", name)
+        } else {
+            format!("Failed to stop service {}: {}", name, e)
+        }
+    })?;
+    wait_for_state(service, name, SERVICE_STOPPED.0)
+}
+
+/// Start a service and wait until it is running
+#[cfg(target_os = "windows")]
+fn start_service(service: &ScHandle, name: &str) -> Result<(), String> {
+    use windows::Win32::Foundation::{ERROR_SERVICE_ALREADY_RUNNING, ERROR_SERVICE_DISABLED};
+    use windows::Win32::System::Services::{StartServiceW, SERVICE_RUNNING};
+
+    match unsafe { StartServiceW(service.0, None) } {
+        Ok(()) => {}
+        Err(e) if e.code() == ERROR_SERVICE_ALREADY_RUNNING.to_hresult() => {}
+        Err(e) if e.code() == ERROR_SERVICE_DISABLED.to_hresult() => {
+            return Err(format!("Cannot start {}: the service is disabled", name));
+        }
+        Err(e) => return Err(format!("Failed to start service {}: {}", name, e)),
+    }
+    wait_for_state(service, name, SERVICE_RUNNING.0)
+}
+
+/// List Win32 services
+/// `filter` matches (case-insensitively) anywhere in the name or display name.
+/// Returns JSON array: `[{ name, displayName, state, startType? }]` sorted by display name
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_services(filter: Option<String>) -> Result<String, String> {
+    let services = tokio::task::spawn_blocking(move || {
+        let services = enumerate_services(filter.as_deref())?;
+        debug_eprintln!("[services] Listed {} services (filter={:?})", services.len(), filter);
+        Ok::<_, String>(services)
+    })
+    .await
+    .map_err(|e| format!("Service listing failed: {}", e))??;
+
+    serde_json::to_string(&services).map_err(|e| format!("Failed to serialize services: {}", e))
+}
+
+/// Start, stop or restart a service (requires elevation)
+/// `action` is "start", "stop" or "restart". Waits for the state change to
+/// complete and returns the service's resulting state (e.g. "running").
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn control_service(name: String, action: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        use windows::Win32::System::Services::{
+            SC_MANAGER_CONNECT, SERVICE_QUERY_STATUS, SERVICE_START, SERVICE_STOP,
+        };
+
+        let action = ServiceAction::parse(&action)?;
+        crate::network_fixes::require_elevation("Controlling a service")?;
+
+        let scm = open_scm(SC_MANAGER_CONNECT)?;
+        let service = open_service(&scm, &name, SERVICE_QUERY_STATUS | SERVICE_START | SERVICE_STOP)?;
+
+        if matches!(action, ServiceAction::Stop | ServiceAction::Restart) {
+            stop_service(&service, &name)?;
+        }
+        if matches!(action, ServiceAction::Start | ServiceAction::Restart) {
+            start_service(&service, &name)?;
+        }
+
+        let state = state_name(query_state(&service, &name)?);
+        debug_eprintln!("[services] {:?} {} -> {}", action, name, state);
+        Ok(state.to_string())
+    })
+    .await
+    .map_err(|e| format!("Service control failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_services(_filter: Option<String>) -> Result<String, String> {
+    Err("Services are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn control_service(_name: String, _action: String) -> Result<String, String> {
+    Err("Services are only supported on Windows".to_string())
+}
@@ -0,0 +1,494 @@
+//! Session Recording Module
+//!
+//! Optionally records a remote session (training, dispute resolution) to a
+//! local MJPEG AVI file. Frames of the server-driven capture loop
+//! (`capture_loop`) are handed over without blocking: the loop only clones the
+//! JPEG into a bounded queue, and a dedicated thread stamps the time on it,
+//! re-encodes it and appends it to the file. When the queue is full the frame
+//! is dropped from the recording, never delayed in the live stream.
+//!
+//! The recording runs at a capped frame rate to keep files small. AVI has a
+//! constant frame rate, so gaps (skipped ticks, a paused loop) are filled with
+//! empty "repeat previous frame" chunks to keep playback in real time.
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
+
+/// Default and maximum recording frame rate
+const DEFAULT_RECORDING_FPS: u32 = 5;
+const MAX_RECORDING_FPS: u32 = 15;
+
+/// Frames waiting to be written; further frames are dropped from the recording
+const RECORDING_QUEUE_SIZE: usize = 8;
+
+/// JPEG quality of recorded frames
+const RECORDING_JPEG_QUALITY: u8 = 70;
+
+/// Recording stops growing at this size (AVI 1.0 offsets are 32-bit)
+const MAX_RECORDING_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Longest gap filled with repeat chunks; longer pauses are shortened
+const MAX_GAP_FRAMES: u64 = 60 * MAX_RECORDING_FPS as u64;
+
+/// Timestamp overlay: pixels per glyph dot
+const OVERLAY_SCALE: u32 = 3;
+
+/// A frame handed from the capture loop to the recording thread
+struct RecordedFrame {
+    jpeg: Vec<u8>,
+    captured_at: Instant,
+    /// Wall-clock time stamped on the frame ("YYYY-MM-DD HH:MM:SS")
+    timestamp: String,
+}
+
+/// The recording in progress
+struct ActiveRecording {
+    /// Monitor being recorded (None = the first monitor that delivers a frame)
+    monitor_id: Option<usize>,
+    min_interval: Duration,
+    last_accepted: Option<Instant>,
+    /// Frames not queued because the writer fell behind
+    dropped_frames: u64,
+    sender: SyncSender<RecordedFrame>,
+    worker: std::thread::JoinHandle<Result<RecordingSummary, String>>,
+}
+
+static RECORDING: Mutex<Option<ActiveRecording>> = Mutex::new(None);
+
+/// Result of a finished recording
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSummary {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Frames written (excluding repeat chunks)
+    pub frames: u64,
+    /// Frames left out because the writer fell behind or couldn't decode them
+    pub dropped_frames: u64,
+    pub duration_secs: f64,
+}
+
+// ============================================================================
+// AVI (RIFF) writer
+// ============================================================================
+
+/// Bytes before the first 'movi' chunk: RIFF header, hdrl list, movi list header
+const AVI_HEADER_LEN: u64 = 12 + 8 + 192 + 12;
+
+/// Position of the 'movi' fourcc (idx1 offsets are relative to it)
+const AVI_MOVI_OFFSET: u64 = AVI_HEADER_LEN - 4;
+
+/// AVIF_HASINDEX / AVIIF_KEYFRAME
+const AVIF_HASINDEX: u32 = 0x10;
+const AVIIF_KEYFRAME: u32 = 0x10;
+
+/// Minimal MJPEG AVI writer (one video stream, idx1 index)
+struct AviWriter {
+    out: BufWriter<File>,
+    width: u32,
+    height: u32,
+    fps: u32,
+    /// (offset from 'movi', size) of every chunk, empty ones included
+    index: Vec<(u32, u32)>,
+    position: u64,
+    max_chunk: u32,
+}
+
+impl AviWriter {
+    fn new(file: File, width: u32, height: u32, fps: u32) -> std::io::Result<Self> {
+        let mut writer = AviWriter {
+            out: BufWriter::new(file),
+            width,
+            height,
+            fps,
+            index: Vec::new(),
+            position: AVI_HEADER_LEN,
+            max_chunk: 0,
+        };
+        // Placeholder header, rewritten with the final counts by `finish`
+        writer.write_header(0)?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self, riff_size: u32) -> std::io::Result<()> {
+        let frames = self.index.len() as u32;
+        let movi_size = (self.position - AVI_MOVI_OFFSET) as u32;
+        let mut h: Vec<u8> = Vec::with_capacity(AVI_HEADER_LEN as usize);
+        let fourcc = |h: &mut Vec<u8>, tag: &[u8; 4]| h.extend_from_slice(tag);
+        let dword = |h: &mut Vec<u8>, value: u32| h.extend_from_slice(&value.to_le_bytes());
+
+        fourcc(&mut h, b"RIFF");
+        dword(&mut h, riff_size);
+        fourcc(&mut h, b"AVI ");
+
+        fourcc(&mut h, b"LIST");
+        dword(&mut h, 192);
+        fourcc(&mut h, b"hdrl");
+
+        // Main AVI header
+        fourcc(&mut h, b"avih");
+        dword(&mut h, 56);
+        dword(&mut h, 1_000_000 / self.fps);
+        dword(&mut h, self.max_chunk.saturating_mul(self.fps));
+        dword(&mut h, 0);
+        dword(&mut h, AVIF_HASINDEX);
+        dword(&mut h, frames);
+        dword(&mut h, 0);
+        dword(&mut h, 1);
+        dword(&mut h, self.max_chunk);
+        dword(&mut h, self.width);
+        dword(&mut h, self.height);
+        h.extend_from_slice(&[0u8; 16]);
+
+        fourcc(&mut h, b"LIST");
+        dword(&mut h, 116);
+        fourcc(&mut h, b"strl");
+
+        // Stream header
+        fourcc(&mut h, b"strh");
+        dword(&mut h, 56);
+        fourcc(&mut h, b"vids");
+        fourcc(&mut h, b"MJPG");
+        dword(&mut h, 0);
+        dword(&mut h, 0); // priority + language
+        dword(&mut h, 0);
+        dword(&mut h, 1);
+        dword(&mut h, self.fps);
+        dword(&mut h, 0);
+        dword(&mut h, frames);
+        dword(&mut h, self.max_chunk);
+        dword(&mut h, u32::MAX);
+        dword(&mut h, 0);
+        h.extend_from_slice(&0u16.to_le_bytes());
+        h.extend_from_slice(&0u16.to_le_bytes());
+        h.extend_from_slice(&(self.width as u16).to_le_bytes());
+        h.extend_from_slice(&(self.height as u16).to_le_bytes());
+
+        // Stream format (BITMAPINFOHEADER)
+        fourcc(&mut h, b"strf");
+        dword(&mut h, 40);
+        dword(&mut h, 40);
+        dword(&mut h, self.width);
+        dword(&mut h, self.height);
+        h.extend_from_slice(&1u16.to_le_bytes());
+        h.extend_from_slice(&24u16.to_le_bytes());
+        fourcc(&mut h, b"MJPG");
+        dword(&mut h, self.width * self.height * 3);
+        h.extend_from_slice(&[0u8; 16]);
+
+        fourcc(&mut h, b"LIST");
+        dword(&mut h, movi_size);
+        fourcc(&mut h, b"movi");
+
+        debug_assert_eq!(h.len() as u64, AVI_HEADER_LEN);
+        self.out.write_all(&h)
+    }
+
+    /// Append a '00dc' chunk; empty data repeats the previous frame
+    fn write_chunk(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let size = data.len() as u32;
+        self.out.write_all(b"00dc")?;
+        self.out.write_all(&size.to_le_bytes())?;
+        self.out.write_all(data)?;
+        // Chunks are word-aligned
+        let padding = data.len() % 2;
+        if padding == 1 {
+            self.out.write_all(&[0])?;
+        }
+        self.index.push(((self.position - AVI_MOVI_OFFSET) as u32, size));
+        self.position += 8 + data.len() as u64 + padding as u64;
+        self.max_chunk = self.max_chunk.max(size);
+        Ok(())
+    }
+
+    /// Write the index and the final header; returns the file size
+    fn finish(mut self) -> std::io::Result<u64> {
+        self.out.write_all(b"idx1")?;
+        self.out.write_all(&((self.index.len() * 16) as u32).to_le_bytes())?;
+        for (offset, size) in &self.index {
+            self.out.write_all(b"00dc")?;
+            self.out.write_all(&AVIIF_KEYFRAME.to_le_bytes())?;
+            self.out.write_all(&offset.to_le_bytes())?;
+            self.out.write_all(&size.to_le_bytes())?;
+        }
+        let file_size = self.position + 8 + self.index.len() as u64 * 16;
+
+        self.out.seek(SeekFrom::Start(0))?;
+        self.write_header((file_size - 8) as u32)?;
+        self.out.flush()?;
+        Ok(file_size)
+    }
+}
+
+// ============================================================================
+// Timestamp overlay
+// ============================================================================
+
+/// 3x5 glyphs (one row per byte, bit 2 = left column) for the timestamp
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        _ => [0; 5],
+    }
+}
+
+/// Draw `text` in white on a black box at the top-left of the frame
+fn draw_overlay(frame: &mut image::RgbImage, text: &str) {
+    let cell = 4 * OVERLAY_SCALE;
+    let box_width = (text.chars().count() as u32 * cell + 2 * OVERLAY_SCALE).min(frame.width());
+    let box_height = (7 * OVERLAY_SCALE).min(frame.height());
+
+    for y in 0..box_height {
+        for x in 0..box_width {
+            frame.put_pixel(x, y, image::Rgb([0, 0, 0]));
+        }
+    }
+    for (i, c) in text.chars().enumerate() {
+        let left = OVERLAY_SCALE + i as u32 * cell;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for col in 0..3u32 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..OVERLAY_SCALE {
+                    for dx in 0..OVERLAY_SCALE {
+                        let x = left + col * OVERLAY_SCALE + dx;
+                        let y = OVERLAY_SCALE + row as u32 * OVERLAY_SCALE + dy;
+                        if x < frame.width() && y < frame.height() {
+                            frame.put_pixel(x, y, image::Rgb([255, 255, 255]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Decode a captured frame, fit it to the recording size, stamp the time and re-encode
+fn prepare_frame(jpeg: &[u8], timestamp: &str, size: Option<(u32, u32)>) -> Result<(Vec<u8>, u32, u32), String> {
+    let mut frame = image::load_from_memory_with_format(jpeg, image::ImageFormat::Jpeg)
+        .map_err(|e| format!("Failed to decode frame: {}", e))?
+        .to_rgb8();
+
+    // AVI has one frame size; later frames (profile changes) are scaled to it
+    if let Some((width, height)) = size {
+        if frame.dimensions() != (width, height) {
+            frame = image::imageops::resize(&frame, width, height, image::imageops::FilterType::Triangle);
+        }
+    }
+
+    draw_overlay(&mut frame, timestamp);
+
+    let (width, height) = frame.dimensions();
+    let mut encoded = Vec::new();
+    jpeg_encoder::Encoder::new(&mut encoded, RECORDING_JPEG_QUALITY)
+        .encode(frame.as_raw(), width as u16, height as u16, jpeg_encoder::ColorType::Rgb)
+        .map_err(|e| format!("Failed to encode frame: {}", e))?;
+    Ok((encoded, width, height))
+}
+
+/// Recording thread: write queued frames until the sender is dropped
+fn run_writer(file: File, path: PathBuf, fps: u32, frames: Receiver<RecordedFrame>) -> Result<RecordingSummary, String> {
+    let frame_period = Duration::from_secs_f64(1.0 / fps as f64);
+    let mut file = Some(file);
+    let mut writer: Option<AviWriter> = None;
+    let mut started: Option<Instant> = None;
+    let mut next_slot: u64 = 0;
+    let mut written: u64 = 0;
+    let mut failed: u64 = 0;
+
+    for frame in frames {
+        let size = writer.as_ref().map(|w| (w.width, w.height));
+        let (jpeg, width, height) = match prepare_frame(&frame.jpeg, &frame.timestamp, size) {
+            Ok(prepared) => prepared,
+            Err(_e) => {
+                debug_eprintln!("[session_recording] Skipping frame: {}", _e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        // The first frame decides the frame size
+        if writer.is_none() {
+            let Some(file) = file.take() else { break };
+            started = Some(frame.captured_at);
+            writer = Some(
+                AviWriter::new(file, width, height, fps).map_err(|e| format!("Failed to write recording: {}", e))?,
+            );
+        }
+        let Some(avi) = writer.as_mut() else { break };
+        if avi.position + jpeg.len() as u64 >= MAX_RECORDING_BYTES {
+            debug_eprintln!("[session_recording] Size limit reached, ignoring further frames");
+            break;
+        }
+
+        // Fill the time since the previous frame with repeats to stay in real time
+        let elapsed = started.map(|s| frame.captured_at.saturating_duration_since(s)).unwrap_or_default();
+        let slot = (elapsed.as_secs_f64() / frame_period.as_secs_f64()).round() as u64;
+        let gap = slot.saturating_sub(next_slot).min(MAX_GAP_FRAMES);
+        for _ in 0..gap {
+            avi.write_chunk(&[]).map_err(|e| format!("Failed to write recording: {}", e))?;
+        }
+        avi.write_chunk(&jpeg).map_err(|e| format!("Failed to write recording: {}", e))?;
+        next_slot = next_slot.max(slot) + 1;
+        written += 1;
+    }
+
+    let (size_bytes, duration_secs) = match writer {
+        Some(avi) => {
+            let duration = avi.index.len() as f64 / fps as f64;
+            let size = avi.finish().map_err(|e| format!("Failed to finalize recording: {}", e))?;
+            (size, duration)
+        }
+        // No frame arrived: leave an empty file rather than a broken AVI
+        None => (0, 0.0),
+    };
+
+    debug_eprintln!(
+        "[session_recording] Finished {} ({} frames, {} bytes)",
+        path.display(),
+        written,
+        size_bytes
+    );
+    Ok(RecordingSummary {
+        path: path.to_string_lossy().to_string(),
+        size_bytes,
+        frames: written,
+        dropped_frames: failed,
+        duration_secs,
+    })
+}
+
+/// Hand a captured frame to the recording, if one is active (never blocks)
+pub(crate) fn submit_frame(monitor_id: usize, jpeg: &[u8]) {
+    let Ok(mut recording) = RECORDING.lock() else {
+        return;
+    };
+    let Some(active) = recording.as_mut() else {
+        return;
+    };
+    // Stick to one monitor: the frame size of an AVI is fixed
+    if *active.monitor_id.get_or_insert(monitor_id) != monitor_id {
+        return;
+    }
+    let now = Instant::now();
+    if active.last_accepted.is_some_and(|last| now.duration_since(last) < active.min_interval) {
+        return;
+    }
+
+    // Local time as in the session logs ("YYYY-MM-DDTHH:MM:SS.mmm+02:00")
+    let log_time = crate::logging::chrono_lite_timestamp();
+    let frame = RecordedFrame {
+        jpeg: jpeg.to_vec(),
+        captured_at: now,
+        timestamp: format!("{} {}", &log_time[..10], &log_time[11..19]),
+    };
+    match active.sender.try_send(frame) {
+        Ok(()) => active.last_accepted = Some(now),
+        Err(TrySendError::Full(_)) => {
+            active.dropped_frames += 1;
+            debug_eprintln!("[session_recording] Writer behind, frame not recorded");
+        }
+        Err(TrySendError::Disconnected(_)) => {}
+    }
+}
+
+/// Start recording the capture loop's frames to an MJPEG AVI file
+/// `output_path`: absolute path of the file to create (extension set to .avi);
+/// `monitor_id`: monitor to record (default: the first one streaming);
+/// `fps`: recording frame rate, 1-15 (default 5). Frames are only recorded
+/// while a capture loop (`start_capture_loop`) runs.
+/// Returns the path of the file being written.
+#[tauri::command]
+pub fn start_session_recording(
+    output_path: String,
+    monitor_id: Option<usize>,
+    fps: Option<u32>,
+) -> Result<String, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    let mut path = PathBuf::from(&output_path);
+    if !path.is_absolute() {
+        return Err(format!("Recording path must be absolute: {}", output_path));
+    }
+    path.set_extension("avi");
+
+    let mut recording = RECORDING
+        .lock()
+        .map_err(|e| format!("Failed to lock recording state: {}", e))?;
+    if recording.is_some() {
+        return Err("A session recording is already running".to_string());
+    }
+
+    // create_new: never overwrite an earlier recording
+    let file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+    let fps = fps.unwrap_or(DEFAULT_RECORDING_FPS).clamp(1, MAX_RECORDING_FPS);
+    let (sender, receiver) = sync_channel(RECORDING_QUEUE_SIZE);
+    let writer_path = path.clone();
+    let worker = std::thread::Builder::new()
+        .name("session-recording".to_string())
+        .spawn(move || run_writer(file, writer_path, fps, receiver))
+        .map_err(|e| format!("Failed to start recording thread: {}", e))?;
+
+    *recording = Some(ActiveRecording {
+        monitor_id,
+        // Slightly under the period so capture jitter doesn't halve the rate
+        min_interval: Duration::from_secs_f64(0.9 / fps as f64),
+        last_accepted: None,
+        dropped_frames: 0,
+        sender,
+        worker,
+    });
+
+    debug_eprintln!("[session_recording] Recording to {} at {} fps", path.display(), fps);
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Stop the session recording and finalize the file
+/// Returns `{ path, sizeBytes, frames, droppedFrames, durationSecs }`.
+#[tauri::command]
+pub async fn stop_session_recording() -> Result<RecordingSummary, String> {
+    let active = RECORDING
+        .lock()
+        .map_err(|e| format!("Failed to lock recording state: {}", e))?
+        .take()
+        .ok_or_else(|| "No session recording is running".to_string())?;
+
+    // Dropping the sender ends the writer loop once the queue is drained
+    let ActiveRecording { sender, worker, dropped_frames, .. } = active;
+    drop(sender);
+
+    let mut summary = tokio::task::spawn_blocking(move || {
+        worker
+            .join()
+            .map_err(|_| "Recording thread panicked".to_string())?
+    })
+    .await
+    .map_err(|e| format!("Recording stop failed: {}", e))??;
+    summary.dropped_frames += dropped_frames;
+    Ok(summary)
+}
@@ -0,0 +1,333 @@
+//! Startup Programs Module
+//!
+//! One-stop list of everything that starts at logon, for slow-boot triage:
+//! - Run keys: HKCU, HKLM and HKLM WOW6432Node (32-bit programs)
+//! - Startup folders: the user's and the all-users folder
+//! - Scheduled tasks with a logon trigger
+//!
+//! Disabling works like Task Manager's Startup tab: Run and Startup folder
+//! entries get a "disabled" flag under `Explorer\StartupApproved` (the entry
+//! itself is left in place), tasks are disabled in Task Scheduler. Machine-wide
+//! entries (HKLM, all-users folder) require the app to run elevated.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// StartupApproved subkeys (same path under HKCU and HKLM)
+#[cfg(target_os = "windows")]
+const STARTUP_APPROVED_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\StartupApproved";
+
+/// Where a startup entry is registered
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StartupSource {
+    UserRun,
+    MachineRun,
+    MachineRun32,
+    UserStartupFolder,
+    CommonStartupFolder,
+    ScheduledTask,
+}
+
+#[cfg(target_os = "windows")]
+impl StartupSource {
+    const ALL: [StartupSource; 6] = [
+        StartupSource::UserRun,
+        StartupSource::MachineRun,
+        StartupSource::MachineRun32,
+        StartupSource::UserStartupFolder,
+        StartupSource::CommonStartupFolder,
+        StartupSource::ScheduledTask,
+    ];
+
+    /// Name used by the frontend
+    fn as_str(self) -> &'static str {
+        match self {
+            StartupSource::UserRun => "hkcu_run",
+            StartupSource::MachineRun => "hklm_run",
+            StartupSource::MachineRun32 => "hklm_run32",
+            StartupSource::UserStartupFolder => "user_startup_folder",
+            StartupSource::CommonStartupFolder => "common_startup_folder",
+            StartupSource::ScheduledTask => "scheduled_task",
+        }
+    }
+
+    fn parse(source: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.as_str() == source)
+            .ok_or_else(|| format!("Unknown startup source: {}", source))
+    }
+
+    /// Registry root of the source and its StartupApproved flags
+    fn root(self) -> windows::Win32::System::Registry::HKEY {
+        use windows::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+        match self {
+            StartupSource::UserRun | StartupSource::UserStartupFolder => HKEY_CURRENT_USER,
+            _ => HKEY_LOCAL_MACHINE,
+        }
+    }
+
+    /// Run key holding the entries (Run sources only)
+    fn run_key(self) -> Option<&'static str> {
+        match self {
+            StartupSource::UserRun | StartupSource::MachineRun => {
+                Some(r"Software\Microsoft\Windows\CurrentVersion\Run")
+            }
+            StartupSource::MachineRun32 => Some(r"Software\WOW6432Node\Microsoft\Windows\CurrentVersion\Run"),
+            _ => None,
+        }
+    }
+
+    /// StartupApproved subkey for the source's flags
+    fn approved_subkey(self) -> Option<&'static str> {
+        match self {
+            StartupSource::UserRun | StartupSource::MachineRun => Some("Run"),
+            StartupSource::MachineRun32 => Some("Run32"),
+            StartupSource::UserStartupFolder | StartupSource::CommonStartupFolder => Some("StartupFolder"),
+            StartupSource::ScheduledTask => None,
+        }
+    }
+
+    /// Startup folder (folder sources only)
+    fn folder(self) -> Option<std::path::PathBuf> {
+        let (var, relative) = match self {
+            StartupSource::UserStartupFolder => ("APPDATA", r"Microsoft\Windows\Start Menu\Programs\Startup"),
+            StartupSource::CommonStartupFolder => ("ProgramData", r"Microsoft\Windows\Start Menu\Programs\StartUp"),
+            _ => return None,
+        };
+        std::env::var_os(var).map(|base| std::path::PathBuf::from(base).join(relative))
+    }
+
+    /// Whether changing the entry needs elevation
+    fn machine_wide(self) -> bool {
+        matches!(
+            self,
+            StartupSource::MachineRun | StartupSource::MachineRun32 | StartupSource::CommonStartupFolder
+        )
+    }
+}
+
+/// Something that runs at logon
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupProgram {
+    /// "hkcu_run", "hklm_run", "hklm_run32", "user_startup_folder",
+    /// "common_startup_folder" or "scheduled_task"
+    pub source: String,
+    /// Value name, file name, or task path (pass to `disable_startup_program`)
+    pub name: String,
+    /// Command line, file path, or task action
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    pub enabled: bool,
+}
+
+/// StartupApproved flags of a source
+#[cfg(target_os = "windows")]
+fn approved_values(source: StartupSource) -> Result<Vec<(String, Vec<u8>, u32)>, String> {
+    match source.approved_subkey() {
+        Some(subkey) => crate::autostart::read_registry_values(
+            source.root(),
+            &format!(r"{}\{}", STARTUP_APPROVED_KEY, subkey),
+        ),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Entries of one Run key
+#[cfg(target_os = "windows")]
+fn list_run_key(source: StartupSource, out: &mut Vec<StartupProgram>) -> Result<(), String> {
+    use windows::Win32::System::Registry::{REG_EXPAND_SZ, REG_SZ};
+
+    let Some(run_key) = source.run_key() else {
+        return Ok(());
+    };
+    let approved = approved_values(source)?;
+    for (name, data, value_type) in crate::autostart::read_registry_values(source.root(), run_key)? {
+        if value_type != REG_SZ.0 && value_type != REG_EXPAND_SZ.0 {
+            continue;
+        }
+        out.push(StartupProgram {
+            source: source.as_str().to_string(),
+            enabled: crate::autostart::is_startup_approved(&approved, &name),
+            command: Some(crate::autostart::registry_string(&data)),
+            name,
+        });
+    }
+    Ok(())
+}
+
+/// Files in one Startup folder
+#[cfg(target_os = "windows")]
+fn list_startup_folder(source: StartupSource, out: &mut Vec<StartupProgram>) -> Result<(), String> {
+    let Some(folder) = source.folder() else {
+        return Ok(());
+    };
+    let Ok(entries) = std::fs::read_dir(&folder) else {
+        return Ok(());
+    };
+    let approved = approved_values(source)?;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.eq_ignore_ascii_case("desktop.ini") || !entry.path().is_file() {
+            continue;
+        }
+        out.push(StartupProgram {
+            source: source.as_str().to_string(),
+            enabled: crate::autostart::is_startup_approved(&approved, &name),
+            command: Some(entry.path().to_string_lossy().to_string()),
+            name,
+        });
+    }
+    Ok(())
+}
+
+/// Set the StartupApproved "disabled" flag of a Run or Startup folder entry
+/// The value is 12 bytes: 0x03 (disabled) padded to 4 bytes, then the FILETIME
+/// of the change, exactly as Task Manager writes it.
+#[cfg(target_os = "windows")]
+fn write_disabled_flag(source: StartupSource, name: &str) -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY, KEY_SET_VALUE, REG_BINARY,
+        REG_OPTION_NON_VOLATILE,
+    };
+
+    let subkey = source
+        .approved_subkey()
+        .ok_or_else(|| format!("{} entries have no StartupApproved flag", source.as_str()))?;
+    let key_wide: Vec<u16> = format!(r"{}\{}", STARTUP_APPROVED_KEY, subkey)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let name_wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+
+    // FILETIME: 100ns intervals since 1601-01-01
+    let unix_100ns = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() / 100)
+        .unwrap_or(0) as u64;
+    let mut data = [0u8; 12];
+    data[0] = 0x03;
+    data[4..12].copy_from_slice(&(unix_100ns + 116_444_736_000_000_000).to_le_bytes());
+
+    unsafe {
+        let mut h_key = HKEY::default();
+        let status = RegCreateKeyExW(
+            source.root(),
+            PCWSTR(key_wide.as_ptr()),
+            0,
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_SET_VALUE,
+            None,
+            &mut h_key,
+            None,
+        );
+        if status.is_err() {
+            return Err(format!("Failed to open StartupApproved key: {:?}", status));
+        }
+        let status = RegSetValueExW(h_key, PCWSTR(name_wide.as_ptr()), 0, REG_BINARY, Some(&data));
+        let _ = RegCloseKey(h_key);
+        if status.is_err() {
+            return Err(format!("Failed to write StartupApproved flag: {:?}", status));
+        }
+    }
+    Ok(())
+}
+
+/// List startup programs from Run keys, Startup folders and logon tasks
+/// Returns JSON array: `[{ source, name, command?, enabled }]`
+/// (logon tasks are limited to the current user's unless elevated)
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn list_startup_programs() -> Result<String, String> {
+    let programs = tokio::task::spawn_blocking(|| {
+        let mut programs = Vec::new();
+        for source in StartupSource::ALL {
+            let result = match source {
+                StartupSource::UserRun | StartupSource::MachineRun | StartupSource::MachineRun32 => {
+                    list_run_key(source, &mut programs)
+                }
+                StartupSource::UserStartupFolder | StartupSource::CommonStartupFolder => {
+                    list_startup_folder(source, &mut programs)
+                }
+                StartupSource::ScheduledTask => crate::scheduled_tasks::list_logon_tasks().map(|tasks| {
+                    programs.extend(tasks.into_iter().map(|task| StartupProgram {
+                        source: source.as_str().to_string(),
+                        name: task.path,
+                        command: task.command,
+                        enabled: task.enabled,
+                    }));
+                }),
+            };
+            // One unreadable source shouldn't hide the others
+            if let Err(_e) = result {
+                debug_eprintln!("[startup_programs] Failed to list {}: {}", source.as_str(), _e);
+            }
+        }
+        debug_eprintln!("[startup_programs] Listed {} startup programs", programs.len());
+        programs
+    })
+    .await
+    .map_err(|e| format!("Startup program listing failed: {}", e))?;
+
+    serde_json::to_string(&programs).map_err(|e| format!("Failed to serialize startup programs: {}", e))
+}
+
+/// Disable a startup program the way Task Manager does
+/// `source` and `name` are as returned by `list_startup_programs`. Run and
+/// Startup folder entries are flagged as disabled (not deleted); tasks are
+/// disabled. Machine-wide entries require elevation.
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn disable_startup_program(source: String, name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let source = StartupSource::parse(&source)?;
+        if source.machine_wide() {
+            crate::network_fixes::require_elevation("Disabling a machine-wide startup program")?;
+        }
+
+        if source == StartupSource::ScheduledTask {
+            return crate::scheduled_tasks::set_task_enabled(&name, false);
+        }
+
+        // Only flag entries that exist, so typos don't leave stray values
+        let mut existing = Vec::new();
+        match source {
+            StartupSource::UserStartupFolder | StartupSource::CommonStartupFolder => {
+                list_startup_folder(source, &mut existing)?
+            }
+            _ => list_run_key(source, &mut existing)?,
+        }
+        let entry = existing
+            .into_iter()
+            .find(|p| p.name.eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("Startup program not found: {} ({})", name, source.as_str()))?;
+
+        write_disabled_flag(source, &entry.name)?;
+        debug_eprintln!("[startup_programs] Disabled {} ({})", entry.name, source.as_str());
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Startup program disable failed: {}", e))?
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn list_startup_programs() -> Result<String, String> {
+    Err("Startup programs are only supported on Windows".to_string())
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn disable_startup_program(_source: String, _name: String) -> Result<(), String> {
+    Err("Startup programs are only supported on Windows".to_string())
+}
@@ -9,8 +9,11 @@
 //! Storage keys are defined as constants to prevent typos and ensure consistency.
 
 use tauri::AppHandle;
+use tauri::Manager;
 use tauri_plugin_store::StoreExt;
+use serde::Serialize;
 use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
 use crate::debug_println;
 
@@ -43,6 +46,22 @@ pub const KEY_THEME_PREFERENCE: &str = "theme_preference";
 pub const KEY_FEATURE_FLAGS: &str = "feature_flags";
 pub const KEY_AUTOSTART_CONFIGURED: &str = "autostart_configured";
 pub const KEY_PROFILE_SETUP_COMPLETED: &str = "profile_setup_completed";
+pub const KEY_PROXY_OVERRIDE: &str = "proxy_override";
+pub const KEY_FLOATING_ICON_POSITION: &str = "floating_icon_position";
+pub const KEY_NAVIGATION_HISTORY: &str = "navigation_history";
+pub const KEY_SCHEDULED_CAPTURES: &str = "scheduled_captures";
+
+/// Per-machine state left out of exported settings profiles
+/// (screen position, first-run/setup markers and the proxy override, which
+/// depends on the machine's network, don't carry over to another machine)
+pub const MACHINE_LOCAL_KEYS: &[&str] = &[
+    KEY_AUTOSTART_CONFIGURED,
+    KEY_PROFILE_SETUP_COMPLETED,
+    KEY_PROXY_OVERRIDE,
+    KEY_FLOATING_ICON_POSITION,
+    KEY_NAVIGATION_HISTORY,
+    KEY_SCHEDULED_CAPTURES,
+];
 
 // ============================================================================
 // STORE SINGLETON
@@ -205,6 +224,230 @@ pub fn migrate_from_local_storage(app: &AppHandle, local_storage_data: Value) ->
     }
 }
 
+// ============================================================================
+// SETTINGS PROFILES (export/import)
+// ============================================================================
+
+/// Version of the settings profile format
+const SETTINGS_PROFILE_VERSION: u64 = 1;
+
+/// Result of `import_settings_profile`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileImportReport {
+    /// Keys written to the store
+    pub imported: Vec<String>,
+    /// Keys left unchanged (already set and `overwrite` was false)
+    pub kept: Vec<String>,
+    /// Keys refused (auth keys, per-machine state)
+    pub rejected: Vec<String>,
+}
+
+/// Whether a key belongs in a portable settings profile
+fn is_profile_key(key: &str) -> bool {
+    is_allowed_key(key) && !MACHINE_LOCAL_KEYS.contains(&key)
+}
+
+/// Export the non-secret settings as a portable JSON profile
+/// Format: `{ "version": 1, "settings": { key: value } }`
+/// SECURITY (Finding #42): auth keys are never exported.
+pub fn export_settings_profile(app: &AppHandle) -> Result<String, String> {
+    let store = get_store(app)?;
+
+    let settings: serde_json::Map<String, Value> = store
+        .entries()
+        .into_iter()
+        .filter(|(key, _)| is_profile_key(key))
+        .collect();
+
+    debug_println!("[Storage] Exported settings profile ({} keys)", settings.len());
+    serde_json::to_string_pretty(&serde_json::json!({
+        "version": SETTINGS_PROFILE_VERSION,
+        "settings": settings,
+    }))
+    .map_err(|e| format!("Failed to serialize settings profile: {}", e))
+}
+
+/// Apply a settings profile produced by `export_settings_profile`
+/// Keys are validated like `storage_set`; auth keys and per-machine state are
+/// rejected. Existing values are only replaced when `overwrite` is true.
+pub fn import_settings_profile(app: &AppHandle, json: &str, overwrite: bool) -> Result<ProfileImportReport, String> {
+    let profile: Value = serde_json::from_str(json).map_err(|e| format!("Invalid settings profile: {}", e))?;
+
+    match profile.get("version").and_then(Value::as_u64) {
+        Some(SETTINGS_PROFILE_VERSION) => {}
+        Some(version) => return Err(format!("Unsupported settings profile version: {}", version)),
+        None => return Err("Invalid settings profile: missing version".to_string()),
+    }
+    let settings = profile
+        .get("settings")
+        .and_then(Value::as_object)
+        .ok_or_else(|| "Invalid settings profile: missing settings".to_string())?;
+
+    let store = get_store(app)?;
+    let mut report = ProfileImportReport {
+        imported: Vec::new(),
+        kept: Vec::new(),
+        rejected: Vec::new(),
+    };
+
+    for (key, value) in settings {
+        if validate_key(key).is_err() || !is_profile_key(key) {
+            report.rejected.push(key.clone());
+        } else if !overwrite && store.has(key) {
+            report.kept.push(key.clone());
+        } else {
+            store.set(key.clone(), value.clone());
+            report.imported.push(key.clone());
+        }
+    }
+
+    if !report.imported.is_empty() {
+        store.save().map_err(|e| format!("Failed to save imported settings: {}", e))?;
+    }
+
+    debug_println!(
+        "[Storage] Imported settings profile: {} imported, {} kept, {} rejected",
+        report.imported.len(),
+        report.kept.len(),
+        report.rejected.len()
+    );
+    Ok(report)
+}
+
+// ============================================================================
+// INTEGRITY CHECK / REPAIR
+// ============================================================================
+
+/// Result of `verify_and_repair`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreRepairReport {
+    /// Whether the store file was readable (or absent)
+    pub healthy: bool,
+    /// Whether a corrupt store was backed up and reinitialized
+    pub repaired: bool,
+    /// Where the corrupt file was moved to
+    pub backup_path: Option<String>,
+    /// Top-level keys found in the corrupt file (their values were not recovered)
+    pub lost_keys: Vec<String>,
+}
+
+/// Get the on-disk path of the store file (resolved like the store plugin: app data dir)
+fn get_store_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(STORE_FILENAME))
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+/// Best-effort scan of (possibly truncated) JSON for top-level object keys
+fn scan_top_level_keys(content: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut current = String::new();
+    let mut last_string: Option<String> = None;
+
+    for c in content.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+                current.push(c);
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+                last_string = Some(std::mem::take(&mut current));
+            } else {
+                current.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => {
+                depth += 1;
+                last_string = None;
+            }
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                last_string = None;
+            }
+            ':' => {
+                if depth == 1 {
+                    if let Some(key) = last_string.take() {
+                        if !keys.contains(&key) {
+                            keys.push(key);
+                        }
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => last_string = None,
+        }
+    }
+
+    keys
+}
+
+/// Verify the store file parses; if not, back it up and reinitialize defaults
+///
+/// Must run before anything else opens the store: the store plugin fails to
+/// load a corrupt file, which would otherwise break every storage call.
+pub fn verify_and_repair(app: &AppHandle) -> Result<StoreRepairReport, String> {
+    let path = get_store_path(app)?;
+
+    let content = match std::fs::read(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(StoreRepairReport {
+                healthy: true,
+                repaired: false,
+                backup_path: None,
+                lost_keys: Vec::new(),
+            });
+        }
+        Err(e) => return Err(format!("Failed to read store file: {}", e)),
+    };
+
+    if serde_json::from_slice::<serde_json::Map<String, Value>>(&content).is_ok() {
+        return Ok(StoreRepairReport {
+            healthy: true,
+            repaired: false,
+            backup_path: None,
+            lost_keys: Vec::new(),
+        });
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let backup_path = path.with_file_name(format!("{}.corrupt-{}", STORE_FILENAME, timestamp));
+
+    std::fs::rename(&path, &backup_path)
+        .map_err(|e| format!("Failed to back up corrupt store: {}", e))?;
+
+    let lost_keys = scan_top_level_keys(&String::from_utf8_lossy(&content));
+    debug_println!(
+        "[Storage] Corrupt store backed up to {:?} ({} keys lost)",
+        backup_path,
+        lost_keys.len()
+    );
+
+    init_store_with_defaults(app)?;
+
+    Ok(StoreRepairReport {
+        healthy: false,
+        repaired: true,
+        backup_path: Some(backup_path.to_string_lossy().to_string()),
+        lost_keys,
+    })
+}
+
 // ============================================================================
 // VALIDATION HELPERS
 // ============================================================================
@@ -0,0 +1,393 @@
+//! Stream Adaptation Module
+//!
+//! Picks the stream profile and chroma from both ends of the pipeline:
+//! - encode time on this machine (from the capture stats), and
+//! - loss and round trip on the link, which only the frontend can observe
+//!   and reports via `report_link_stats`.
+//!
+//! A lossy WAN link benefits from smaller frames no matter how fast we
+//! encode, so link conditions cap the profile and encode time can only lower
+//! it further.
+//!
+//! Policy (link stats are averaged and ignored once stale):
+//! - loss >= 5% or RTT >= 300ms: "standard" with 4:2:0 chroma
+//! - loss >= 2% or RTT >= 150ms: at most "high"; 4:2:0 if loss >= 2%
+//! - otherwise: no link cap ("extreme", 4:4:4)
+//! - encode time over the frame budget (30 fps): one profile below the current
+//! - stream data near the bandwidth budget: "standard" with 4:2:0 chroma
+//!
+//! An optional bandwidth budget (metered links) caps the stream's data rate.
+//! Every frame handed out is counted; a token bucket refilled at the budget
+//! rate gates new captures, which lowers the effective frame rate and pauses
+//! the stream when the budget is used up. `bandwidth-budget-warning` is
+//! emitted as usage approaches the cap and when the stream pauses.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::debug_eprintln;
+
+/// Weight of the newest report in the link averages
+const LINK_STATS_EMA_ALPHA: f64 = 0.3;
+
+/// Link stats older than this no longer influence the recommendation
+const LINK_STATS_MAX_AGE: Duration = Duration::from_secs(10);
+
+/// Loss / RTT thresholds of the policy
+const HEAVY_LOSS_PERCENT: f64 = 5.0;
+const LOSS_PERCENT: f64 = 2.0;
+const HEAVY_RTT_MS: f64 = 300.0;
+const RTT_MS: f64 = 150.0;
+
+/// Encode budget per frame (30 fps)
+const FRAME_BUDGET_MS: f64 = 1000.0 / 30.0;
+
+/// Profiles from smallest to largest
+const PROFILES: [&str; 3] = ["standard", "high", "extreme"];
+
+/// Smallest bandwidth budget accepted (a single small frame is ~20 KB)
+const MIN_BUDGET_BYTES_PER_MINUTE: u64 = 256 * 1024;
+
+/// Share of the budget used in the last minute that triggers a warning and
+/// the smallest profile; the warning re-arms once usage drops below the reset
+const BUDGET_WARNING_RATIO: f64 = 0.8;
+const BUDGET_WARNING_RESET_RATIO: f64 = 0.6;
+
+/// Burst the token bucket allows, in seconds of budget
+const BUDGET_BURST_SECS: f64 = 10.0;
+
+/// Window of the "last minute" usage
+const USAGE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Averaged link conditions reported by the frontend
+#[derive(Debug, Clone, Copy)]
+struct LinkStats {
+    loss_percent: f64,
+    rtt_ms: f64,
+    updated: Instant,
+}
+
+static LINK_STATS: Mutex<Option<LinkStats>> = Mutex::new(None);
+
+/// Stream bytes handed out since the app started
+static SESSION_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Start of the session usage count (first stream frame)
+static SESSION_STARTED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Frame sizes of the last minute
+static RECENT_FRAMES: Mutex<VecDeque<(Instant, u64)>> = Mutex::new(VecDeque::new());
+
+/// Active bandwidth budget
+struct BandwidthBudget {
+    app: AppHandle,
+    bytes_per_minute: u64,
+    /// Bytes that may still be sent (negative after an oversized frame)
+    tokens: f64,
+    refilled: Instant,
+    warned: bool,
+    paused: bool,
+}
+
+impl BandwidthBudget {
+    fn refill(&mut self) {
+        let rate = self.bytes_per_minute as f64 / 60.0;
+        let elapsed = self.refilled.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate * BUDGET_BURST_SECS);
+        self.refilled = Instant::now();
+    }
+}
+
+static BANDWIDTH_BUDGET: Mutex<Option<BandwidthBudget>> = Mutex::new(None);
+
+/// Profile and chroma the stream should use, and why
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamRecommendation {
+    /// "standard", "high" or "extreme"
+    pub profile: &'static str,
+    /// "444" or "420"
+    pub chroma: &'static str,
+    pub reason: String,
+    /// Averaged link loss (absent if no recent report)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loss_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<f64>,
+    /// Average encode time of the current profile (absent before the first frame)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encode_ms: Option<f64>,
+}
+
+/// Stream bytes handed out in the last minute
+fn bytes_last_minute() -> u64 {
+    let Ok(mut frames) = RECENT_FRAMES.lock() else {
+        return 0;
+    };
+    while frames.front().is_some_and(|(at, _)| at.elapsed() > USAGE_WINDOW) {
+        frames.pop_front();
+    }
+    frames.iter().map(|(_, bytes)| bytes).sum()
+}
+
+/// Share of the budget used in the last minute (None without a budget)
+fn budget_usage() -> Option<(f64, u64)> {
+    let budget = BANDWIDTH_BUDGET.lock().ok()?.as_ref()?.bytes_per_minute;
+    Some((bytes_last_minute() as f64 / budget as f64, budget))
+}
+
+fn emit_budget_warning(budget: &BandwidthBudget, used: u64, paused: bool) {
+    let _ = budget.app.emit(
+        "bandwidth-budget-warning",
+        serde_json::json!({
+            "usedBytesLastMinute": used,
+            "budgetBytesPerMinute": budget.bytes_per_minute,
+            "percent": used as f64 * 100.0 / budget.bytes_per_minute as f64,
+            "paused": paused,
+        }),
+    );
+}
+
+/// Whether the bandwidth budget leaves room for another stream frame
+/// Stream paths check this before capturing; without a budget it is always true.
+pub(crate) fn budget_allows_frame() -> bool {
+    let Ok(mut budget) = BANDWIDTH_BUDGET.lock() else {
+        return true;
+    };
+    let Some(budget) = budget.as_mut() else {
+        return true;
+    };
+
+    budget.refill();
+    let allowed = budget.tokens > 0.0;
+    if allowed != !budget.paused {
+        budget.paused = !allowed;
+        debug_eprintln!("[stream_adaptation] Bandwidth budget {}", if allowed { "resumed" } else { "exhausted, pausing" });
+        if !allowed {
+            emit_budget_warning(budget, bytes_last_minute(), true);
+        }
+    }
+    allowed
+}
+
+/// Count a stream frame handed out (JPEG bytes)
+pub(crate) fn record_stream_bytes(bytes: u64) {
+    SESSION_BYTES.fetch_add(bytes, Ordering::Relaxed);
+    if let Ok(mut started) = SESSION_STARTED.lock() {
+        started.get_or_insert_with(Instant::now);
+    }
+    if let Ok(mut frames) = RECENT_FRAMES.lock() {
+        frames.push_back((Instant::now(), bytes));
+    }
+
+    let Ok(mut budget) = BANDWIDTH_BUDGET.lock() else {
+        return;
+    };
+    let Some(budget) = budget.as_mut() else {
+        return;
+    };
+    budget.refill();
+    budget.tokens -= bytes as f64;
+
+    let used = bytes_last_minute();
+    let ratio = used as f64 / budget.bytes_per_minute as f64;
+    if !budget.warned && ratio >= BUDGET_WARNING_RATIO {
+        budget.warned = true;
+        emit_budget_warning(budget, used, false);
+    } else if budget.warned && ratio < BUDGET_WARNING_RESET_RATIO {
+        budget.warned = false;
+    }
+}
+
+fn profile_rank(profile: &str) -> usize {
+    PROFILES.iter().position(|p| *p == profile).unwrap_or(0)
+}
+
+/// Apply the policy to the current link and capture stats
+fn recommend() -> StreamRecommendation {
+    let link = LINK_STATS
+        .lock()
+        .ok()
+        .and_then(|stats| *stats)
+        .filter(|stats| stats.updated.elapsed() < LINK_STATS_MAX_AGE);
+    let capture = crate::CAPTURE_STATS
+        .lock()
+        .ok()
+        .and_then(|stats| stats.as_ref().map(|s| (s.profile, s.average.total_ms)));
+
+    let mut rank = PROFILES.len() - 1;
+    let mut chroma = "444";
+    let mut reasons = Vec::new();
+
+    if let Some(link) = link {
+        if link.loss_percent >= HEAVY_LOSS_PERCENT || link.rtt_ms >= HEAVY_RTT_MS {
+            rank = 0;
+            chroma = "420";
+            reasons.push(format!("poor link ({:.1}% loss, {:.0}ms RTT)", link.loss_percent, link.rtt_ms));
+        } else if link.loss_percent >= LOSS_PERCENT || link.rtt_ms >= RTT_MS {
+            rank = 1;
+            if link.loss_percent >= LOSS_PERCENT {
+                chroma = "420";
+            }
+            reasons.push(format!("degraded link ({:.1}% loss, {:.0}ms RTT)", link.loss_percent, link.rtt_ms));
+        }
+    }
+
+    if let Some((usage, budget)) = budget_usage().filter(|(usage, _)| *usage >= BUDGET_WARNING_RATIO) {
+        rank = 0;
+        chroma = "420";
+        reasons.push(format!("{:.0}% of the {} KB/min bandwidth budget used", usage * 100.0, budget / 1024));
+    }
+
+    if let Some((profile, encode_ms)) = capture {
+        if encode_ms > FRAME_BUDGET_MS {
+            let encode_rank = profile_rank(profile).saturating_sub(1);
+            if encode_rank < rank {
+                rank = encode_rank;
+                reasons.push(format!(
+                    "{} encode takes {:.1}ms (budget {:.1}ms)",
+                    profile, encode_ms, FRAME_BUDGET_MS
+                ));
+            }
+        }
+    }
+
+    StreamRecommendation {
+        profile: PROFILES[rank],
+        chroma,
+        reason: if reasons.is_empty() {
+            "link and encoder have headroom".to_string()
+        } else {
+            reasons.join("; ")
+        },
+        loss_percent: link.map(|l| l.loss_percent),
+        rtt_ms: link.map(|l| l.rtt_ms),
+        encode_ms: capture.map(|(_, ms)| ms),
+    }
+}
+
+/// Report link conditions observed by the frontend
+/// `loss_percent`: frames lost or retransmitted (0-100); `rtt_ms`: round trip.
+/// Returns the updated `{ profile, chroma, reason, lossPercent?, rttMs?, encodeMs? }`.
+#[tauri::command]
+pub fn report_link_stats(loss_percent: f64, rtt_ms: f64) -> Result<StreamRecommendation, String> {
+    if !loss_percent.is_finite() || !(0.0..=100.0).contains(&loss_percent) {
+        return Err(format!("Invalid loss percentage: {}", loss_percent));
+    }
+    if !rtt_ms.is_finite() || rtt_ms < 0.0 {
+        return Err(format!("Invalid round trip: {}", rtt_ms));
+    }
+
+    {
+        let mut stats = LINK_STATS
+            .lock()
+            .map_err(|e| format!("Failed to lock link stats: {}", e))?;
+        let blend = |average: f64, sample: f64| average + LINK_STATS_EMA_ALPHA * (sample - average);
+        *stats = Some(match stats.filter(|s| s.updated.elapsed() < LINK_STATS_MAX_AGE) {
+            // Restart the average after a gap so old conditions don't linger
+            Some(current) => LinkStats {
+                loss_percent: blend(current.loss_percent, loss_percent),
+                rtt_ms: blend(current.rtt_ms, rtt_ms),
+                updated: Instant::now(),
+            },
+            None => LinkStats { loss_percent, rtt_ms, updated: Instant::now() },
+        });
+    }
+
+    let recommendation = recommend();
+    debug_eprintln!(
+        "[stream_adaptation] {:.1}% loss, {:.0}ms RTT -> {} {} ({})",
+        loss_percent, rtt_ms, recommendation.profile, recommendation.chroma, recommendation.reason
+    );
+    Ok(recommendation)
+}
+
+/// Current recommendation without reporting new link stats
+#[tauri::command]
+pub fn get_stream_recommendation() -> StreamRecommendation {
+    recommend()
+}
+
+/// Bandwidth used by the stream
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthUsage {
+    /// Stream bytes since the first frame of this app session
+    pub session_bytes: u64,
+    pub session_secs: f64,
+    pub last_minute_bytes: u64,
+    /// Active budget (absent if none)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget_bytes_per_minute: Option<u64>,
+    /// New frames are held back until the budget refills
+    pub paused: bool,
+}
+
+/// Cap the stream's data rate (metered connections)
+/// `bytes_per_minute`: budget, at least 256 KB; None or 0 removes the budget.
+/// Frames beyond the budget are not captured, lowering the frame rate or
+/// pausing the stream; `bandwidth-budget-warning` events
+/// (`{ usedBytesLastMinute, budgetBytesPerMinute, percent, paused }`) are
+/// emitted at 80% usage and when the stream pauses.
+#[tauri::command]
+pub fn set_stream_bandwidth_budget(app: AppHandle, bytes_per_minute: Option<u64>) -> Result<(), String> {
+    let mut budget = BANDWIDTH_BUDGET
+        .lock()
+        .map_err(|e| format!("Failed to lock bandwidth budget: {}", e))?;
+
+    match bytes_per_minute.filter(|b| *b > 0) {
+        None => {
+            *budget = None;
+            debug_eprintln!("[stream_adaptation] Bandwidth budget removed");
+        }
+        Some(bytes) if bytes < MIN_BUDGET_BYTES_PER_MINUTE => {
+            return Err(format!(
+                "Bandwidth budget must be at least {} bytes per minute",
+                MIN_BUDGET_BYTES_PER_MINUTE
+            ));
+        }
+        Some(bytes) => {
+            let rate = bytes as f64 / 60.0;
+            // Start from what the last minute already used so a new budget can't be gamed
+            let used = bytes_last_minute() as f64;
+            *budget = Some(BandwidthBudget {
+                app,
+                bytes_per_minute: bytes,
+                tokens: (rate * BUDGET_BURST_SECS).min(bytes as f64 - used),
+                refilled: Instant::now(),
+                warned: false,
+                paused: false,
+            });
+            debug_eprintln!("[stream_adaptation] Bandwidth budget set to {} bytes/min", bytes);
+        }
+    }
+    Ok(())
+}
+
+/// Get the stream's bandwidth usage for this session
+/// Returns `{ sessionBytes, sessionSecs, lastMinuteBytes, budgetBytesPerMinute?, paused }`.
+#[tauri::command]
+pub fn get_bandwidth_used() -> BandwidthUsage {
+    let (budget_bytes_per_minute, paused) = BANDWIDTH_BUDGET
+        .lock()
+        .ok()
+        .and_then(|budget| budget.as_ref().map(|b| (Some(b.bytes_per_minute), b.paused)))
+        .unwrap_or((None, false));
+
+    BandwidthUsage {
+        session_bytes: SESSION_BYTES.load(Ordering::Relaxed),
+        session_secs: SESSION_STARTED
+            .lock()
+            .ok()
+            .and_then(|started| started.map(|s| s.elapsed().as_secs_f64()))
+            .unwrap_or(0.0),
+        last_minute_bytes: bytes_last_minute(),
+        budget_bytes_per_minute,
+        paused,
+    }
+}
@@ -0,0 +1,346 @@
+//! Binary Stream Server Module
+//!
+//! Serves stream frames as raw JPEG bytes over a local WebSocket instead of
+//! base64 strings over Tauri IPC, which inflates every frame by ~33% and costs
+//! an encode/decode on both ends of the real-time streaming hot loop.
+//!
+//! `start_stream_server` binds 127.0.0.1 on a random port and returns
+//! `{ port, token }`. The frontend connects to
+//! `ws://localhost:{port}/?token=...&monitorId=0&profile=standard&chroma=444&maxFps=30`
+//! and receives one binary message per frame. Text messages with any of
+//! `{ monitorId, profile, chroma, maxFps }` change the settings mid-stream;
+//! errors are sent back as `{ "error": "..." }` text messages.
+//!
+//! SECURITY: any web page can open a WebSocket to localhost, so every
+//! connection must present the random token handed to our own frontend.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::debug_eprintln;
+use crate::feature_policy::{self, Feature};
+
+/// Default and maximum frame rate pushed to a client
+const DEFAULT_MAX_FPS: u32 = 30;
+const MAX_FPS_LIMIT: u32 = 60;
+
+/// Wait before retrying when the monitor already has a capture in flight
+const CAPTURE_BUSY_RETRY: Duration = Duration::from_millis(10);
+
+/// Wait before checking again while the bandwidth budget is used up
+const BUDGET_EXHAUSTED_RETRY: Duration = Duration::from_millis(100);
+
+/// Wait before retrying after a capture error (e.g. monitor unplugged)
+const CAPTURE_ERROR_RETRY: Duration = Duration::from_millis(500);
+
+/// Running server: its port, connection token and shutdown signal
+struct StreamServer {
+    port: u16,
+    token: String,
+    shutdown: watch::Sender<bool>,
+}
+
+static STREAM_SERVER: Mutex<Option<StreamServer>> = Mutex::new(None);
+
+/// Connection details returned to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamServerInfo {
+    pub port: u16,
+    pub token: String,
+}
+
+/// Settings change sent by the client (query string or text message)
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamSettingsUpdate {
+    monitor_id: Option<usize>,
+    profile: Option<String>,
+    chroma: Option<String>,
+    max_fps: Option<u32>,
+}
+
+impl StreamSettingsUpdate {
+    /// Read settings from the connection query parameters
+    fn from_query(params: &HashMap<String, String>) -> Result<Self, String> {
+        let parse_number = |key: &str| -> Result<Option<u32>, String> {
+            params
+                .get(key)
+                .map(|v| v.parse::<u32>().map_err(|_| format!("Invalid {}: {}", key, v)))
+                .transpose()
+        };
+
+        Ok(Self {
+            monitor_id: parse_number("monitorId")?.map(|id| id as usize),
+            profile: params.get("profile").cloned(),
+            chroma: params.get("chroma").cloned(),
+            max_fps: parse_number("maxFps")?,
+        })
+    }
+}
+
+/// Current settings of one streaming connection
+#[derive(Clone, Copy)]
+struct StreamSettings {
+    monitor_id: usize,
+    profile: &'static str,
+    sampling_factor: jpeg_encoder::SamplingFactor,
+    chroma: &'static str,
+    frame_interval: Duration,
+}
+
+impl Default for StreamSettings {
+    fn default() -> Self {
+        Self {
+            monitor_id: 0,
+            profile: "standard",
+            sampling_factor: jpeg_encoder::SamplingFactor::R_4_4_4,
+            chroma: "444",
+            frame_interval: Duration::from_millis(1000 / DEFAULT_MAX_FPS as u64),
+        }
+    }
+}
+
+impl StreamSettings {
+    /// Apply an update; settings are unchanged if any field is invalid
+    fn apply(&mut self, update: StreamSettingsUpdate) -> Result<(), String> {
+        let mut next = *self;
+
+        if let Some(monitor_id) = update.monitor_id {
+            next.monitor_id = monitor_id;
+        }
+        if let Some(profile) = update.profile {
+            next.profile = crate::stream_profile(&profile)
+                .ok_or_else(|| format!("Unknown stream profile: {}", profile))?;
+        }
+        if update.chroma.is_some() {
+            let (sampling_factor, chroma) = crate::jpeg_sampling_factor(update.chroma.as_deref())?;
+            next.sampling_factor = sampling_factor;
+            next.chroma = chroma;
+        }
+        if let Some(max_fps) = update.max_fps {
+            let max_fps = max_fps.clamp(1, MAX_FPS_LIMIT);
+            next.frame_interval = Duration::from_millis(1000 / max_fps as u64);
+        }
+
+        *self = next;
+        Ok(())
+    }
+}
+
+/// Parse the query string of a request URI
+fn query_params(uri: &tokio_tungstenite::tungstenite::http::Uri) -> HashMap<String, String> {
+    url::Url::parse(&format!("ws://localhost{}", uri))
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+fn error_message(message: &str) -> Message {
+    Message::Text(serde_json::json!({ "error": message }).to_string())
+}
+
+/// Capture one frame, or None if the monitor already has a capture in flight
+async fn capture_frame(settings: StreamSettings) -> Result<Option<Vec<u8>>, String> {
+    let Some(guard) = crate::InFlightCapture::try_acquire(settings.monitor_id) else {
+        return Ok(None);
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let _guard = guard;
+        crate::capture_stream_jpeg(
+            settings.monitor_id,
+            settings.profile,
+            settings.sampling_factor,
+            settings.chroma,
+        )
+    })
+    .await
+    .map_err(|e| format!("Capture task failed: {}", e))?
+    .map(Some)
+}
+
+/// Handshake (token check) and push frames until the client disconnects
+async fn serve_connection(stream: TcpStream, token: String, mut shutdown: watch::Receiver<bool>) {
+    let mut params = HashMap::new();
+    let handshake = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+        params = query_params(request.uri());
+        if params.get("token") != Some(&token) {
+            let mut rejection = ErrorResponse::new(Some("Invalid stream token".to_string()));
+            *rejection.status_mut() = StatusCode::UNAUTHORIZED;
+            return Err(rejection);
+        }
+        Ok(response)
+    })
+    .await;
+
+    let ws = match handshake {
+        Ok(ws) => ws,
+        Err(_e) => {
+            debug_eprintln!("[stream_server] Handshake rejected: {}", _e);
+            return;
+        }
+    };
+    let (mut sink, mut source) = ws.split();
+
+    let mut settings = StreamSettings::default();
+    if let Err(e) = StreamSettingsUpdate::from_query(&params).and_then(|update| settings.apply(update)) {
+        let _ = sink.send(error_message(&e)).await;
+        let _ = sink.close().await;
+        return;
+    }
+
+    debug_eprintln!(
+        "[stream_server] Client connected: monitor {}, {} {}",
+        settings.monitor_id, settings.profile, settings.chroma
+    );
+
+    // Settings updates arrive on a separate task so reads never stall the frame loop
+    let (updates_tx, mut updates_rx) = mpsc::channel::<String>(8);
+    tokio::spawn(async move {
+        while let Some(Ok(message)) = source.next().await {
+            match message {
+                Message::Text(text) => {
+                    if updates_tx.send(text).await.is_err() {
+                        break;
+                    }
+                }
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        // Dropping the sender tells the frame loop the client is gone
+    });
+
+    loop {
+        if *shutdown.borrow_and_update() {
+            break;
+        }
+
+        // Apply pending settings changes before the next frame
+        loop {
+            match updates_rx.try_recv() {
+                Ok(text) => {
+                    let result = serde_json::from_str::<StreamSettingsUpdate>(&text)
+                        .map_err(|e| format!("Invalid settings message: {}", e))
+                        .and_then(|update| settings.apply(update));
+                    if let Err(e) = result {
+                        if sink.send(error_message(&e)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    debug_eprintln!("[stream_server] Client disconnected");
+                    return;
+                }
+            }
+        }
+
+        if !crate::stream_adaptation::budget_allows_frame() {
+            tokio::time::sleep(BUDGET_EXHAUSTED_RETRY).await;
+            continue;
+        }
+
+        let started = Instant::now();
+        match capture_frame(settings).await {
+            Ok(Some(jpeg)) => {
+                let bytes = jpeg.len() as u64;
+                if sink.send(Message::Binary(jpeg)).await.is_err() {
+                    break;
+                }
+                crate::stream_adaptation::record_stream_bytes(bytes);
+                if let Some(remaining) = settings.frame_interval.checked_sub(started.elapsed()) {
+                    tokio::time::sleep(remaining).await;
+                }
+            }
+            Ok(None) => tokio::time::sleep(CAPTURE_BUSY_RETRY).await,
+            Err(e) => {
+                if sink.send(error_message(&e)).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(CAPTURE_ERROR_RETRY).await;
+            }
+        }
+    }
+
+    let _ = sink.close().await;
+}
+
+/// Accept connections until shutdown is signalled
+async fn accept_loop(listener: TcpListener, token: String, mut shutdown: watch::Receiver<bool>) {
+    loop {
+        tokio::select! {
+            _ = shutdown.changed() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let _ = stream.set_nodelay(true);
+                    tokio::spawn(serve_connection(stream, token.clone(), shutdown.clone()));
+                }
+                Err(_e) => debug_eprintln!("[stream_server] Accept failed: {}", _e),
+            },
+        }
+    }
+    debug_eprintln!("[stream_server] Stopped");
+}
+
+/// Start the binary stream server (no-op if already running)
+/// Returns `{ port, token }` for `ws://localhost:{port}/?token=...`
+#[tauri::command]
+pub async fn start_stream_server() -> Result<StreamServerInfo, String> {
+    feature_policy::require(Feature::ScreenCapture)?;
+    if let Some(server) = STREAM_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock stream server: {}", e))?
+        .as_ref()
+    {
+        return Ok(StreamServerInfo { port: server.port, token: server.token.clone() });
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to bind stream server: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read stream server address: {}", e))?
+        .port();
+    let token = uuid::Uuid::new_v4().simple().to_string();
+
+    let mut server = STREAM_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock stream server: {}", e))?;
+    // Another call may have started a server while we were binding
+    if let Some(existing) = server.as_ref() {
+        return Ok(StreamServerInfo { port: existing.port, token: existing.token.clone() });
+    }
+
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tauri::async_runtime::spawn(accept_loop(listener, token.clone(), shutdown_rx));
+    *server = Some(StreamServer { port, token: token.clone(), shutdown: shutdown_tx });
+
+    debug_eprintln!("[stream_server] Listening on 127.0.0.1:{}", port);
+    Ok(StreamServerInfo { port, token })
+}
+
+/// Stop the binary stream server and disconnect all clients
+#[tauri::command]
+pub fn stop_stream_server() -> Result<(), String> {
+    let server = STREAM_SERVER
+        .lock()
+        .map_err(|e| format!("Failed to lock stream server: {}", e))?
+        .take();
+
+    if let Some(server) = server {
+        let _ = server.shutdown.send(true);
+    }
+    Ok(())
+}
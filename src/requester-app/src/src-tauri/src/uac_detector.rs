@@ -4,8 +4,9 @@
 /// UAC prompts run on the secure desktop which cannot be captured or controlled remotely.
 
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
 #[cfg(target_os = "windows")]
@@ -81,10 +82,21 @@ fn is_uac_active() -> bool {
     false
 }
 
+/// Process-wide detector shared by the start/stop commands
+static GLOBAL_DETECTOR: OnceLock<UACDetector> = OnceLock::new();
+
+/// Get the process-wide UAC detector
+/// A single instance ensures starting twice never spawns duplicate watchers
+pub fn global() -> &'static UACDetector {
+    GLOBAL_DETECTOR.get_or_init(UACDetector::new)
+}
+
 /// UAC Detector that polls for UAC prompts
 pub struct UACDetector {
     running: Arc<AtomicBool>,
     uac_active: Arc<AtomicBool>,
+    /// Handle of the watcher task (None when stopped)
+    task: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl UACDetector {
@@ -92,11 +104,12 @@ impl UACDetector {
         Self {
             running: Arc::new(AtomicBool::new(false)),
             uac_active: Arc::new(AtomicBool::new(false)),
+            task: Mutex::new(None),
         }
     }
 
     /// Start detecting UAC prompts
-    /// Returns a callback that will be called when UAC state changes
+    /// `on_change` is called when UAC state changes. No-op if already running.
     pub async fn start<F>(&self, mut on_change: F)
     where
         F: FnMut(bool) + Send + 'static,
@@ -109,7 +122,7 @@ impl UACDetector {
         let running = self.running.clone();
         let uac_active = self.uac_active.clone();
 
-        tokio::spawn(async move {
+        let handle = tokio::spawn(async move {
             while running.load(Ordering::SeqCst) {
                 let current_state = is_uac_active();
                 let previous_state = uac_active.swap(current_state, Ordering::SeqCst);
@@ -123,11 +136,27 @@ impl UACDetector {
                 sleep(Duration::from_millis(500)).await;
             }
         });
+
+        if let Ok(mut task) = self.task.lock() {
+            *task = Some(handle);
+        }
     }
 
     /// Stop detecting UAC prompts
+    /// Cancels the watcher task immediately and resets the UAC state
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
+        if let Ok(mut task) = self.task.lock() {
+            if let Some(handle) = task.take() {
+                handle.abort();
+            }
+        }
+        self.uac_active.store(false, Ordering::SeqCst);
+    }
+
+    /// Check if the watcher is running
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
     }
 
     /// Check if UAC is currently active
@@ -0,0 +1,513 @@
+//! Update Flow Module
+//!
+//! Runs the complete silent-upgrade flow (download -> verify -> launch) as a
+//! single command so the frontend's happy path is one call. The granular
+//! commands (`download_installer`, `execute_installer_and_exit`) remain for
+//! advanced use and share the download/spawn helpers here and in lib.rs.
+//!
+//! Progress is reported with events:
+//! - `update-stage`: `{ stage, targetVersion }` where stage is
+//!   "downloading", "verifying" or "launching"
+//! - `update-download-progress`: `{ downloaded, total }` (total may be null)
+//!
+//! Verification checks the SHA-256, optionally the Authenticode signature, and
+//! that the installer's file version is the requested target version.
+//!
+//! Errors are prefixed with the failing stage, e.g. `[verifying] SHA-256 mismatch ...`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::debug_eprintln;
+
+/// Minimum interval between `update-download-progress` events
+const PROGRESS_EMIT_INTERVAL_MS: u128 = 250;
+
+/// Installers downloaded from trusted hosts by this process (canonical paths)
+/// SECURITY: `execute_installer_and_exit` only runs files listed here.
+static DOWNLOADED_INSTALLERS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Whether a path is an installer this process downloaded
+pub(crate) fn is_downloaded_installer(path: &Path) -> bool {
+    let Ok(canonical) = path.canonicalize() else {
+        return false;
+    };
+    DOWNLOADED_INSTALLERS
+        .lock()
+        .map(|paths| paths.contains(&canonical))
+        .unwrap_or(false)
+}
+
+/// Stage of the update flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateStage {
+    Downloading,
+    Verifying,
+    Launching,
+}
+
+impl UpdateStage {
+    fn as_str(self) -> &'static str {
+        match self {
+            UpdateStage::Downloading => "downloading",
+            UpdateStage::Verifying => "verifying",
+            UpdateStage::Launching => "launching",
+        }
+    }
+
+    /// Prefix an error message with this stage's marker
+    fn error(self, message: impl std::fmt::Display) -> String {
+        format!("[{}] {}", self.as_str(), message)
+    }
+}
+
+/// Result of a completed update flow
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateResult {
+    /// Last stage reached (always "launching" on success)
+    pub stage: UpdateStage,
+    pub target_version: String,
+    pub installer_path: String,
+    pub bytes_downloaded: u64,
+    /// Lowercase hex SHA-256 of the downloaded installer
+    pub sha256: String,
+    /// Authenticode result (None = signature check not requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_verified: Option<bool>,
+    /// File version of the installer, checked against the target version
+    /// (None where it can't be read)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installer_version: Option<String>,
+}
+
+fn emit_stage(app: &AppHandle, stage: UpdateStage, target_version: &str) {
+    debug_eprintln!("[update] Stage: {}", stage.as_str());
+    let _ = app.emit(
+        "update-stage",
+        serde_json::json!({ "stage": stage, "targetVersion": target_version }),
+    );
+}
+
+/// Download an installer from a trusted host into the temp directory
+/// Streams the body to disk; when `emit_progress` is set, emits throttled
+/// `update-download-progress` events. Returns the file path and byte count.
+pub(crate) async fn download_to_temp(
+    app: &AppHandle,
+    url: &str,
+    target_version: &str,
+    emit_progress: bool,
+) -> Result<(PathBuf, u64), String> {
+    use tokio::io::AsyncWriteExt;
+
+    // SECURITY: Validate URL is from a trusted host
+    if !crate::is_trusted_download_url(url) {
+        debug_eprintln!("[update] SECURITY: Rejected download from untrusted host: {}", url);
+        return Err(format!(
+            "Security error: Downloads only allowed from trusted hosts: {:?}",
+            crate::TRUSTED_DOWNLOAD_HOSTS
+        ));
+    }
+
+    let filename = format!("it-support-center-{}-setup.exe", target_version);
+    let download_path = std::env::temp_dir().join(&filename);
+
+    debug_eprintln!("[update] Download path: {:?}", download_path);
+
+    // Download using reqwest with the effective proxy setting
+    let client = crate::proxy::build_http_client(app)?;
+    let mut response = client.get(url).send().await
+        .map_err(|e| format!("Download failed: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Download failed with status: {}", response.status()));
+    }
+
+    let total = response.content_length();
+    let mut file = tokio::fs::File::create(&download_path).await
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+
+    let mut downloaded: u64 = 0;
+    let mut last_emit = std::time::Instant::now();
+
+    while let Some(chunk) = response.chunk().await
+        .map_err(|e| format!("Failed to read response body: {}", e))?
+    {
+        file.write_all(&chunk).await
+            .map_err(|e| format!("Failed to write temp file: {}", e))?;
+        downloaded += chunk.len() as u64;
+
+        if emit_progress && last_emit.elapsed().as_millis() >= PROGRESS_EMIT_INTERVAL_MS {
+            last_emit = std::time::Instant::now();
+            let _ = app.emit(
+                "update-download-progress",
+                serde_json::json!({ "downloaded": downloaded, "total": total }),
+            );
+        }
+    }
+
+    file.sync_all().await
+        .map_err(|e| format!("Failed to sync file: {}", e))?;
+
+    if downloaded == 0 {
+        return Err("Downloaded file is empty".to_string());
+    }
+
+    if emit_progress {
+        let _ = app.emit(
+            "update-download-progress",
+            serde_json::json!({ "downloaded": downloaded, "total": total }),
+        );
+    }
+
+    debug_eprintln!("[update] Successfully downloaded {} bytes to {:?}", downloaded, download_path);
+
+    if let Ok(canonical) = download_path.canonicalize() {
+        if let Ok(mut paths) = DOWNLOADED_INSTALLERS.lock() {
+            if !paths.contains(&canonical) {
+                paths.push(canonical);
+            }
+        }
+    }
+
+    Ok((download_path, downloaded))
+}
+
+/// Compute the lowercase hex SHA-256 of a file
+async fn sha256_file(path: &Path) -> Result<String, String> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(path).await
+        .map_err(|e| format!("Failed to open installer: {}", e))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await
+            .map_err(|e| format!("Failed to read installer: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// Verify the Authenticode signature of a file via WinVerifyTrust
+#[cfg(target_os = "windows")]
+fn verify_signature(path: &Path) -> Result<(), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{HANDLE, HWND};
+    use windows::Win32::Security::WinTrust::{
+        WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2, WINTRUST_DATA, WINTRUST_DATA_0,
+        WINTRUST_FILE_INFO, WTD_CHOICE_FILE, WTD_REVOKE_WHOLECHAIN, WTD_STATEACTION_CLOSE,
+        WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+    };
+
+    let path_wide: Vec<u16> = path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let mut file_info = WINTRUST_FILE_INFO {
+        cbStruct: std::mem::size_of::<WINTRUST_FILE_INFO>() as u32,
+        pcwszFilePath: PCWSTR::from_raw(path_wide.as_ptr()),
+        hFile: HANDLE::default(),
+        pgKnownSubject: std::ptr::null_mut(),
+    };
+
+    let mut data = WINTRUST_DATA {
+        cbStruct: std::mem::size_of::<WINTRUST_DATA>() as u32,
+        dwUIChoice: WTD_UI_NONE,
+        fdwRevocationChecks: WTD_REVOKE_WHOLECHAIN,
+        dwUnionChoice: WTD_CHOICE_FILE,
+        Anonymous: WINTRUST_DATA_0 { pFile: &mut file_info },
+        dwStateAction: WTD_STATEACTION_VERIFY,
+        ..Default::default()
+    };
+
+    let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+
+    unsafe {
+        let status = WinVerifyTrust(HWND::default(), &mut action, &mut data as *mut _ as *mut _);
+
+        // Release the state data allocated by the verify call
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        let _ = WinVerifyTrust(HWND::default(), &mut action, &mut data as *mut _ as *mut _);
+
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(format!("Installer signature is not trusted (0x{:08X})", status as u32))
+        }
+    }
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+fn verify_signature(_path: &Path) -> Result<(), String> {
+    Err("Signature verification is only supported on Windows".to_string())
+}
+
+/// Read the file version ("a.b.c.d") from a file's version resource
+#[cfg(target_os = "windows")]
+fn read_file_version(path: &Path) -> Result<String, String> {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Storage::FileSystem::{
+        GetFileVersionInfoSizeW, GetFileVersionInfoW, VerQueryValueW, VS_FIXEDFILEINFO,
+    };
+
+    let path_wide: Vec<u16> = path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe {
+        let size = GetFileVersionInfoSizeW(PCWSTR::from_raw(path_wide.as_ptr()), None);
+        if size == 0 {
+            return Err("Installer has no version resource".to_string());
+        }
+        let mut data = vec![0u8; size as usize];
+        GetFileVersionInfoW(PCWSTR::from_raw(path_wide.as_ptr()), 0, size, data.as_mut_ptr() as *mut _)
+            .map_err(|e| format!("Failed to read installer version: {}", e))?;
+
+        let mut info: *mut std::ffi::c_void = std::ptr::null_mut();
+        let mut info_len = 0u32;
+        if !VerQueryValueW(data.as_ptr() as *const _, w!("\\"), &mut info, &mut info_len).as_bool()
+            || info.is_null()
+            || (info_len as usize) < std::mem::size_of::<VS_FIXEDFILEINFO>()
+        {
+            return Err("Installer version resource has no fixed file info".to_string());
+        }
+        let fixed = &*(info as *const VS_FIXEDFILEINFO);
+        Ok(format!(
+            "{}.{}.{}.{}",
+            fixed.dwFileVersionMS >> 16,
+            fixed.dwFileVersionMS & 0xFFFF,
+            fixed.dwFileVersionLS >> 16,
+            fixed.dwFileVersionLS & 0xFFFF
+        ))
+    }
+}
+
+/// Numeric version components padded to four ("v1.2.3-beta" -> [1, 2, 3, 0])
+fn version_components(version: &str) -> Option<[u32; 4]> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    // Ignore pre-release / build suffixes; the version resource can't carry them
+    let version = version.split(['-', '+', ' ']).next().unwrap_or_default();
+
+    let mut components = [0u32; 4];
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.is_empty() || parts.len() > 4 {
+        return None;
+    }
+    for (component, part) in components.iter_mut().zip(parts) {
+        *component = part.parse().ok()?;
+    }
+    Some(components)
+}
+
+/// Check that a downloaded installer's file version matches `target_version`
+/// Returns the installer's version, or None where version resources can't be
+/// read (non-Windows). Trailing zero components are ignored ("1.2.3" matches
+/// "1.2.3.0").
+pub(crate) async fn check_installer_version(path: &Path, target_version: &str) -> Result<Option<String>, String> {
+    let expected = version_components(target_version)
+        .ok_or_else(|| format!("Target version is not a numeric version: {}", target_version))?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let file_path = path.to_path_buf();
+        let actual = tokio::task::spawn_blocking(move || read_file_version(&file_path))
+            .await
+            .map_err(|e| format!("Version check task failed: {}", e))??;
+
+        if version_components(&actual) != Some(expected) {
+            return Err(format!(
+                "Installer version mismatch (expected {}, got {})",
+                target_version, actual
+            ));
+        }
+        debug_eprintln!("[update] Installer version {} matches target", actual);
+        Ok(Some(actual))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, expected);
+        Ok(None)
+    }
+}
+
+/// Verify that a downloaded installer is the requested version
+/// Reads the file version resource and compares it with `target_version`.
+/// Returns the installer's version ("a.b.c.d"); errors on mismatch.
+#[tauri::command]
+pub async fn verify_installer_version(installer_path: String, target_version: String) -> Result<String, String> {
+    check_installer_version(Path::new(&installer_path), &target_version)
+        .await?
+        .ok_or_else(|| "Installer version checks are only supported on Windows".to_string())
+}
+
+/// Verification results of a launched installer
+struct VerifiedInstaller {
+    sha256: String,
+    signature_verified: Option<bool>,
+    installer_version: Option<String>,
+}
+
+/// Run the pre-execution checks: SHA-256, optionally the Authenticode
+/// signature, then the file version against `target_version`
+async fn verify_installer(
+    path: &Path,
+    expected_sha256: &str,
+    verify_signature: bool,
+    target_version: &str,
+) -> Result<VerifiedInstaller, String> {
+    let sha256 = sha256_file(path).await?;
+    if sha256 != expected_sha256 {
+        return Err(format!(
+            "SHA-256 mismatch (expected {}, got {})",
+            expected_sha256, sha256
+        ));
+    }
+
+    let signature_verified = if verify_signature {
+        let file_path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || self::verify_signature(&file_path))
+            .await
+            .map_err(|e| format!("Signature check task failed: {}", e))??;
+        Some(true)
+    } else {
+        None
+    };
+
+    let installer_version = check_installer_version(path, target_version).await?;
+
+    Ok(VerifiedInstaller {
+        sha256,
+        signature_verified,
+        installer_version,
+    })
+}
+
+/// Verify a downloaded installer and launch it
+/// The hash is checked again right before spawning, since the file sits in
+/// the shared temp directory and could be replaced after the first check.
+/// The file is deleted on any verification failure so it can't be launched
+/// later. Errors carry a `[stage]` prefix.
+async fn verify_then_launch(
+    app: &AppHandle,
+    path: &Path,
+    expected_sha256: &str,
+    verify_signature: bool,
+    target_version: &str,
+    args: &[String],
+) -> Result<VerifiedInstaller, String> {
+    emit_stage(app, UpdateStage::Verifying, target_version);
+    let verified = match verify_installer(path, expected_sha256, verify_signature, target_version).await {
+        Ok(verified) => verified,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(path).await;
+            return Err(UpdateStage::Verifying.error(e));
+        }
+    };
+
+    emit_stage(app, UpdateStage::Launching, target_version);
+    let unchanged = sha256_file(path).await.and_then(|current| {
+        if current == verified.sha256 {
+            Ok(())
+        } else {
+            Err(format!(
+                "Installer changed after verification (expected {}, got {})",
+                verified.sha256, current
+            ))
+        }
+    });
+    if let Err(e) = unchanged {
+        let _ = tokio::fs::remove_file(path).await;
+        return Err(UpdateStage::Launching.error(e));
+    }
+
+    crate::launch_installer(&path.to_string_lossy(), crate::InstallerKind::Nsis, args, None, None)
+        .await
+        .map_err(|e| UpdateStage::Launching.error(e))?;
+    Ok(verified)
+}
+
+/// Download, verify and launch an update installer in one call
+///
+/// Arguments:
+/// - `expected_sha256`: hex SHA-256 the download must match (case-insensitive)
+/// - `silent_args`: optional override of the NSIS silent-install preset (pre-split)
+/// - `verify_signature`: also require a trusted Authenticode signature
+///
+/// On success the installer is running and the app exits shortly after
+/// returning the `UpdateResult`. Errors carry a `[stage]` prefix.
+#[tauri::command]
+pub async fn perform_update(
+    app: AppHandle,
+    url: String,
+    target_version: String,
+    expected_sha256: String,
+    silent_args: Option<Vec<String>>,
+    verify_signature: Option<bool>,
+) -> Result<UpdateResult, String> {
+    let expected_sha256 = expected_sha256.trim().to_lowercase();
+    if expected_sha256.len() != 64 || !expected_sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(UpdateStage::Verifying.error("Expected SHA-256 must be 64 hex characters"));
+    }
+
+    // SECURITY: Validate args before downloading anything
+    let silent_args = match silent_args {
+        Some(args) if args.iter().any(|a| !a.trim().is_empty()) => args,
+        _ => crate::silent_args_for("nsis"),
+    };
+    let args = crate::validate_installer_args(&silent_args)
+        .map_err(|e| UpdateStage::Launching.error(e))?;
+
+    emit_stage(&app, UpdateStage::Downloading, &target_version);
+    let (installer_path, bytes_downloaded) = download_to_temp(&app, &url, &target_version, true)
+        .await
+        .map_err(|e| UpdateStage::Downloading.error(e))?;
+
+    let verified = verify_then_launch(
+        &app,
+        &installer_path,
+        &expected_sha256,
+        verify_signature.unwrap_or(false),
+        &target_version,
+        &args,
+    )
+    .await?;
+
+    // Give the frontend time to receive the result
+    let exit_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        debug_eprintln!("[update] Exiting app for update...");
+        exit_handle.exit(0);
+    });
+
+    Ok(UpdateResult {
+        stage: UpdateStage::Launching,
+        target_version,
+        installer_path: installer_path.to_string_lossy().to_string(),
+        bytes_downloaded,
+        sha256: verified.sha256,
+        signature_verified: verified.signature_verified,
+        installer_version: verified.installer_version,
+    })
+}
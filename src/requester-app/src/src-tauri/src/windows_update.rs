@@ -0,0 +1,144 @@
+//! Windows Update Diagnostics Module
+//!
+//! Read-only query of pending Windows Updates via the Windows Update Agent
+//! COM API (IUpdateSession / IUpdateSearcher). Used by support to check
+//! whether outstanding OS updates may be behind a reported issue.
+//!
+//! Nothing is downloaded or installed - this only searches for applicable,
+//! not-yet-installed updates.
+
+#[cfg(target_os = "windows")]
+use serde::Serialize;
+
+#[cfg(target_os = "windows")]
+use crate::debug_eprintln;
+
+/// Pending Windows Update summary
+#[cfg(target_os = "windows")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingUpdates {
+    /// Number of applicable, not-installed updates
+    pub count: usize,
+    /// Titles of the pending updates
+    pub titles: Vec<String>,
+    /// Whether a reboot is pending to finish previously installed updates
+    pub reboot_required: bool,
+    /// Whether the Windows Update service could be queried
+    pub service_available: bool,
+    /// Explanation when the service could not be queried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Search criteria: applicable software updates that are not installed or hidden
+#[cfg(target_os = "windows")]
+const SEARCH_CRITERIA: &str = "IsInstalled=0 and IsHidden=0 and Type='Software'";
+
+/// HRESULT returned when the Windows Update service is disabled (ERROR_SERVICE_DISABLED)
+#[cfg(target_os = "windows")]
+const HRESULT_SERVICE_DISABLED: i32 = 0x80070422u32 as i32;
+
+/// HRESULT returned when the Windows Update service is not running (WU_E_NO_SERVICE)
+#[cfg(target_os = "windows")]
+const HRESULT_WU_NO_SERVICE: i32 = 0x80240016u32 as i32;
+
+/// Query pending updates using the Windows Update Agent API
+///
+/// Must run on a blocking thread - the search can take from seconds to minutes.
+#[cfg(target_os = "windows")]
+fn query_pending_updates() -> Result<PendingUpdates, String> {
+    use windows::core::BSTR;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    };
+    use windows::Win32::System::UpdateAgent::{
+        ISystemInformation, IUpdateSession, SystemInformation, UpdateSession,
+    };
+
+    unsafe {
+        // Initialize COM for this thread (S_FALSE = already initialized, still needs uninit)
+        let com_initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+
+        let result = (|| {
+            // Reboot-pending state is cheap and works even if searching fails
+            let reboot_required = CoCreateInstance::<_, ISystemInformation>(&SystemInformation, None, CLSCTX_INPROC_SERVER)
+                .and_then(|info| info.RebootRequired())
+                .map(|v| v.as_bool())
+                .unwrap_or(false);
+
+            let session: IUpdateSession = CoCreateInstance(&UpdateSession, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| format!("Failed to create update session: {}", e))?;
+
+            let searcher = session
+                .CreateUpdateSearcher()
+                .map_err(|e| format!("Failed to create update searcher: {}", e))?;
+
+            let search_result = match searcher.Search(&BSTR::from(SEARCH_CRITERIA)) {
+                Ok(result) => result,
+                Err(e) if e.code().0 == HRESULT_SERVICE_DISABLED || e.code().0 == HRESULT_WU_NO_SERVICE => {
+                    debug_eprintln!("[windows_update] Windows Update service unavailable: {}", e);
+                    return Ok(PendingUpdates {
+                        count: 0,
+                        titles: Vec::new(),
+                        reboot_required,
+                        service_available: false,
+                        message: Some("Windows Update service is disabled or not running".to_string()),
+                    });
+                }
+                Err(e) => return Err(format!("Windows Update search failed: {}", e)),
+            };
+
+            let updates = search_result
+                .Updates()
+                .map_err(|e| format!("Failed to read update list: {}", e))?;
+            let count = updates
+                .Count()
+                .map_err(|e| format!("Failed to read update count: {}", e))?;
+
+            let mut titles = Vec::with_capacity(count.max(0) as usize);
+            for index in 0..count {
+                if let Ok(update) = updates.get_Item(index) {
+                    if let Ok(title) = update.Title() {
+                        titles.push(title.to_string());
+                    }
+                }
+            }
+
+            Ok(PendingUpdates {
+                count: count.max(0) as usize,
+                titles,
+                reboot_required,
+                service_available: true,
+                message: None,
+            })
+        })();
+
+        if com_initialized {
+            CoUninitialize();
+        }
+
+        result
+    }
+}
+
+/// Get pending Windows Updates on this machine
+/// Returns JSON: `{ count, titles, rebootRequired, serviceAvailable, message? }`
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn get_pending_windows_updates() -> Result<String, String> {
+    let updates = tokio::task::spawn_blocking(query_pending_updates)
+        .await
+        .map_err(|e| format!("Update query task failed: {}", e))??;
+
+    serde_json::to_string(&updates)
+        .map_err(|e| format!("Failed to serialize updates: {}", e))
+}
+
+/// Non-Windows stub
+#[cfg(not(target_os = "windows"))]
+#[tauri::command]
+pub async fn get_pending_windows_updates() -> Result<String, String> {
+    Err("Windows Update query is only supported on Windows".to_string())
+}
@@ -38,11 +38,13 @@ export interface ExtendedAuthResult extends AuthResult {
  * @param appVersion - Optional application version string
  * @param localIp - Optional local IP address
  * @param computerName - Optional computer/hostname
+ * @param locale - Optional user locale (e.g. "ar-EG")
  */
 export function getDeviceInfo(
   appVersion?: string,
   localIp?: string,
-  computerName?: string
+  computerName?: string,
+  locale?: string
 ): DeviceInfo {
   const ua = navigator.userAgent;
   let os = "Unknown";
@@ -66,6 +68,7 @@ export function getDeviceInfo(
     app_version: appVersion,
     ip_address: localIp, // Include local IP address if available
     computer_name: computerName, // Include computer hostname if available
+    locale: locale ?? navigator.language, // Route to right-language support
   };
 }
 
@@ -100,10 +103,11 @@ function extractEnforcementFromError(error: unknown): VersionEnforcementError |
  */
 export async function loginWithSSO(username: string): Promise<ExtendedAuthResult> {
   try {
-    // Get app version, local IP, and computer name from Tauri if available
+    // Get app version, local IP, computer name, and locale from Tauri if available
     let appVersion = "1.0.0"; // Fallback version
     let localIp: string | undefined;
     let computerName: string | undefined;
+    let locale: string | undefined;
 
     try {
       if (isTauri()) {
@@ -125,12 +129,19 @@ export async function loginWithSSO(username: string): Promise<ExtendedAuthResult
         } catch (err) {
           console.warn("Failed to get computer name:", err);
         }
+
+        // Get user locale
+        try {
+          locale = await invoke<string>("get_user_locale");
+        } catch (err) {
+          console.warn("Failed to get user locale:", err);
+        }
       }
     } catch (err) {
       console.warn("Failed to get app version:", err);
     }
 
-    const deviceInfo = getDeviceInfo(appVersion, localIp, computerName);
+    const deviceInfo = getDeviceInfo(appVersion, localIp, computerName, locale);
 
     const loginData: SSOLoginRequest = {
       username,
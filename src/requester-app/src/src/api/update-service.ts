@@ -124,16 +124,51 @@ export async function downloadInstaller(
   }
 }
 
+/**
+ * Split a server-provided argument string into individual arguments
+ * Honors double quotes so values with spaces stay one argument
+ * (e.g. `/S INSTALLDIR="C:\Program Files\X"` -> ["/S", "INSTALLDIR=C:\Program Files\X"]).
+ */
+export function splitInstallerArgs(args: string): string[] {
+  const tokens: string[] = [];
+  let current = "";
+  let inQuotes = false;
+  let hasToken = false;
+
+  for (const char of args) {
+    if (char === '"') {
+      inQuotes = !inQuotes;
+      hasToken = true;
+    } else if (/\s/.test(char) && !inQuotes) {
+      if (hasToken) {
+        tokens.push(current);
+        current = "";
+        hasToken = false;
+      }
+    } else {
+      current += char;
+      hasToken = true;
+    }
+  }
+  if (hasToken) {
+    tokens.push(current);
+  }
+  return tokens;
+}
+
 /**
  * Execute the installer silently and exit the app
  * The installer will continue running after the app closes
+ * Only installers downloaded via `downloadInstaller` in this session are accepted.
  *
  * @param installerPath - Path to the downloaded installer
- * @param silentArgs - Command-line arguments for silent installation
+ * @param silentArgs - Silent-install arguments, one entry per argument (overrides the preset)
+ * @param installerKind - Installer type ("nsis", "inno", "msi") used to pick default silent args
  */
 export async function executeInstallerAndExit(
   installerPath: string,
-  silentArgs: string = "/S"
+  silentArgs?: string[],
+  installerKind: string = "nsis"
 ): Promise<void> {
   if (!isTauri()) {
     throw new Error("Installer execution is only supported in desktop app");
@@ -147,7 +182,8 @@ export async function executeInstallerAndExit(
   try {
     await invoke("execute_installer_and_exit", {
       installerPath: installerPath,
-      silentArgs: silentArgs,
+      installerKind: installerKind,
+      silentArgs: silentArgs ?? null,
     });
 
     // This point should not be reached as app will exit
@@ -159,6 +195,59 @@ export async function executeInstallerAndExit(
   }
 }
 
+/**
+ * Structured result of the one-call update flow (`perform_update`)
+ */
+export interface UpdateResult {
+  stage: "downloading" | "verifying" | "launching";
+  targetVersion: string;
+  installerPath: string;
+  bytesDownloaded: number;
+  sha256: string;
+  signatureVerified?: boolean;
+}
+
+/**
+ * Download, verify and launch the installer in a single command
+ * Emits `update-stage` and `update-download-progress` events; errors are
+ * prefixed with the failing stage (e.g. "[verifying] SHA-256 mismatch ...").
+ * The app exits shortly after this resolves.
+ *
+ * @param installerUrl - URL to download the installer from
+ * @param targetVersion - Target version string for filename
+ * @param expectedSha256 - Hex SHA-256 the download must match
+ * @param silentArgs - Optional override of the NSIS silent-install preset, one entry per argument
+ * @param verifySignature - Also require a trusted Authenticode signature
+ */
+export async function performUpdate(
+  installerUrl: string,
+  targetVersion: string,
+  expectedSha256: string,
+  silentArgs?: string[],
+  verifySignature: boolean = false
+): Promise<UpdateResult> {
+  if (!isTauri()) {
+    throw new Error("Installer download is only supported in desktop app");
+  }
+
+  const { invoke } = await import("@tauri-apps/api/core");
+
+  try {
+    return await invoke<UpdateResult>("perform_update", {
+      url: installerUrl,
+      targetVersion,
+      expectedSha256,
+      silentArgs: silentArgs ?? null,
+      verifySignature,
+    });
+  } catch (error) {
+    console.error("[update] Update flow failed:", error);
+    throw new Error(
+      error instanceof Error ? error.message : String(error)
+    );
+  }
+}
+
 /**
  * Open the installer URL in the default browser for manual download
  * Fallback when silent upgrade is not available
@@ -215,7 +304,10 @@ export async function performSilentUpgrade(
     // Stage 2: Install
     onProgress?.({ isInstalling: true });
 
-    await executeInstallerAndExit(installerPath, silentInstallArgs || "/S");
+    await executeInstallerAndExit(
+      installerPath,
+      silentInstallArgs ? splitInstallerArgs(silentInstallArgs) : undefined
+    );
 
     // App should have exited by now
   } catch (error) {
@@ -7,6 +7,10 @@
  * TIMEOUT BEHAVIOR: Timeout (10s) results in ACCEPT, not reject.
  * Only explicit Reject click triggers termination.
  *
+ * CONSENT: only clicking Accept grants the technician remote control of this
+ * machine for the session (see `grant_remote_consent`); a session accepted
+ * by the timeout is view-only. The banner says so.
+ *
  * This component runs in the main window, so it has full Tauri permissions.
  */
 
@@ -30,7 +34,7 @@ export function IncomingRequestBanner() {
   const handleAccept = async () => {
     if (isClosing()) return;
 
-    console.log("[IncomingRequestBanner] User clicked Accept or timeout");
+    console.log("[IncomingRequestBanner] User clicked Accept");
     setIsClosing(true);
 
     // Clear countdown interval
@@ -39,7 +43,7 @@ export function IncomingRequestBanner() {
       intervalId = undefined;
     }
 
-    await remoteAccessStore.acceptPendingSession();
+    await remoteAccessStore.acceptPendingSession(true);
   };
 
   const handleReject = async () => {
@@ -218,6 +222,13 @@ export function IncomingRequestBanner() {
                       {pending().agentName}
                     </span>
                   </div>
+                  {/* Consent notice */}
+                  <div style={{
+                    'font-size': '11px',
+                    color: '#64748b'
+                  }}>
+                    Accept lets them view your screen and control your mouse and keyboard; if you don't respond they can only view
+                  </div>
                 </div>
 
                 {/* Buttons - Side by Side */}
@@ -200,6 +200,10 @@ export async function showNotification(options: {
   type?: string;
   /** Optional tag to replace previous notifications with same tag */
   tag?: string;
+  /** Optional toast sound ("default", "mail", "alarm", "call", "silent") */
+  sound?: string;
+  /** Optional priority ("high" keeps the toast on screen longer) */
+  priority?: string;
 }): Promise<void> {
   // Check master notification toggle FIRST
   const prefs = getNotificationPreferences();
@@ -239,6 +243,8 @@ export async function showNotification(options: {
         body: options.body,
         notificationType: options.type,
         ticketId: options.data.ticketId,
+        sound: options.sound,
+        priority: options.priority,
       });
 
       console.log(`[Notifications] ✅ Notification sent via Tauri command (Windows + click handler):`, options.title);
@@ -282,6 +288,8 @@ export async function showNotification(options: {
       body: options.body,
       notificationType: options.type,
       ticketId: options.data?.ticketId,
+      sound: options.sound,
+      priority: options.priority,
     });
 
     console.log(`[Notifications] ✅ Notification sent via Tauri command:`, options.title);
@@ -15,6 +15,25 @@ import { invoke } from "@tauri-apps/api/core";
  */
 export type ResolutionProfile = "standard" | "high" | "extreme";
 
+/**
+ * JPEG chroma subsampling
+ * - 444: full-resolution color, sharp colored text/UI lines (default, larger frames)
+ * - 420: half-resolution color, smaller frames with some color fringing on text
+ */
+export type ChromaSubsampling = "444" | "420";
+
+/**
+ * Frame transport
+ * - websocket: raw JPEG bytes pushed over a local WebSocket (no base64, default)
+ * - ipc: base64 JPEG strings polled via Tauri invoke (fallback)
+ */
+export type StreamTransport = "websocket" | "ipc";
+
+interface StreamServerInfo {
+  port: number;
+  token: string;
+}
+
 export interface ResolutionConfig {
   width: number;
   height: number;
@@ -49,6 +68,8 @@ interface TauriScreenStreamOptions {
   width?: number;
   height?: number;
   profile?: ResolutionProfile;
+  chroma?: ChromaSubsampling;
+  transport?: StreamTransport;
 }
 
 /**
@@ -59,7 +80,13 @@ interface TauriScreenStreamOptions {
 export async function createTauriScreenStream(
   options: TauriScreenStreamOptions
 ): Promise<MediaStream> {
-  const { monitorId, frameRate = 15, profile = "standard" } = options;
+  const {
+    monitorId,
+    frameRate = 15,
+    profile = "standard",
+    chroma = "444",
+    transport = "websocket",
+  } = options;
 
   // Get resolution config from profile (or use custom width/height if provided)
   const profileConfig = RESOLUTION_PROFILES[profile];
@@ -73,6 +100,8 @@ export async function createTauriScreenStream(
   console.log("[TauriScreenStream] Profile:", profile, `(${profileConfig.description})`);
   console.log("[TauriScreenStream] Target resolution:", width, "x", height);
   console.log("[TauriScreenStream] Target frame rate:", frameRate, "fps");
+  console.log("[TauriScreenStream] Chroma subsampling:", chroma);
+  console.log("[TauriScreenStream] Transport:", transport);
   console.log("[TauriScreenStream] Capture command:", captureCommand);
   console.log("[TauriScreenStream] ========================================");
 
@@ -119,7 +148,7 @@ export async function createTauriScreenStream(
     try {
       // Capture screen using Tauri (resolution based on profile)
       const captureStart = performance.now();
-      const base64 = await invoke<string>(captureCommand, { monitorId });
+      const base64 = await invoke<string>(captureCommand, { monitorId, chroma });
       const captureTime = performance.now() - captureStart;
       totalCaptureTime += captureTime;
 
@@ -179,9 +208,94 @@ export async function createTauriScreenStream(
     }
   }
 
-  // Start capture loop
-  console.log("[TauriScreenStream] Starting capture loop...");
-  captureLoop();
+  // Binary frames over the local WebSocket stream server
+  let socket: WebSocket | null = null;
+
+  async function startWebSocketStream(): Promise<void> {
+    const server = await invoke<StreamServerInfo>("start_stream_server");
+    const params = new URLSearchParams({
+      token: server.token,
+      monitorId: String(monitorId),
+      profile,
+      chroma,
+      maxFps: String(frameRate),
+    });
+
+    socket = new WebSocket(`ws://localhost:${server.port}/?${params}`);
+    socket.binaryType = "blob";
+
+    await new Promise<void>((resolve, reject) => {
+      socket!.onopen = () => resolve();
+      socket!.onerror = () => reject(new Error("Failed to connect to stream server"));
+    });
+
+    let isDrawing = false;
+    socket.onmessage = async (event: MessageEvent) => {
+      if (typeof event.data === "string") {
+        captureErrors++;
+        if (captureErrors <= 3) {
+          console.error("[TauriScreenStream] ❌ Stream error:", event.data);
+        }
+        return;
+      }
+
+      // Drop frames that arrive while the previous one is still decoding
+      if (isDrawing) return;
+      isDrawing = true;
+
+      try {
+        const bitmap = await createImageBitmap(event.data as Blob);
+        if (frameCount === 0) {
+          console.log("[TauriScreenStream] ✅ First frame received!");
+          console.log("[TauriScreenStream] Frame size:", (event.data as Blob).size, "bytes");
+          console.log("[TauriScreenStream] Actual image dimensions:", bitmap.width, "x", bitmap.height);
+        }
+        ctx!.drawImage(bitmap, 0, 0, width, height);
+        bitmap.close();
+        frameCount++;
+
+        const statsDelta = performance.now() - lastStatsTime;
+        if (statsDelta >= 5000) {
+          const fps = (frameCount / (statsDelta / 1000)).toFixed(1);
+          console.log(`[TauriScreenStream] Stats: ${fps} fps, ${frameCount} frames, errors: ${captureErrors}`);
+          frameCount = 0;
+          captureErrors = 0;
+          lastStatsTime = performance.now();
+        }
+      } catch (error) {
+        captureErrors++;
+        if (captureErrors <= 3) {
+          console.error("[TauriScreenStream] ❌ Frame decode error:", error);
+        }
+      } finally {
+        isDrawing = false;
+      }
+    };
+
+    socket.onclose = () => {
+      if (isCapturing) {
+        console.warn("[TauriScreenStream] Stream server connection closed");
+      }
+    };
+  }
+
+  let activeTransport: StreamTransport = transport;
+  if (transport === "websocket") {
+    try {
+      await startWebSocketStream();
+      console.log("[TauriScreenStream] Streaming binary frames over WebSocket");
+    } catch (error) {
+      console.warn("[TauriScreenStream] WebSocket stream unavailable, falling back to IPC:", error);
+      socket = null;
+      activeTransport = "ipc";
+    }
+  }
+
+  if (activeTransport === "ipc") {
+    // Start capture loop
+    console.log("[TauriScreenStream] Starting capture loop...");
+    captureLoop();
+  }
 
   // Override track.stop to clean up
   const originalStop = videoTrack.stop.bind(videoTrack);
@@ -191,6 +305,11 @@ export async function createTauriScreenStream(
     console.log("[TauriScreenStream] Total frames captured:", frameCount);
     console.log("[TauriScreenStream] ========================================");
     isCapturing = false;
+    socket?.close();
+    // Release the pooled frame buffers held for this session
+    invoke<number>("reset_capture_buffers").catch((error) => {
+      console.warn("[TauriScreenStream] Failed to release capture buffers:", error);
+    });
     originalStop();
   };
 
@@ -198,6 +317,7 @@ export async function createTauriScreenStream(
   videoTrack.onended = () => {
     console.log("[TauriScreenStream] Video track ended event");
     isCapturing = false;
+    socket?.close();
   };
 
   console.log("[TauriScreenStream] ✅ Stream created successfully");
@@ -242,3 +362,100 @@ export async function createScreenStream(
     audio: false,
   });
 }
+
+interface TimedStreamFrame {
+  image: string;
+  clientTimestampMs: number;
+  serverReceivedMs: number;
+  serverCompleteMs: number;
+  serverProcessingMs: number;
+}
+
+/**
+ * Latency split of one probe frame
+ * - roundTripMs: invoke call to frame received (what the viewer experiences)
+ * - serverProcessingMs: capture + resize + encode (+ backpressure) in the backend
+ * - transportMs: the rest (IPC serialization and delivery)
+ */
+export interface CaptureLatencySample {
+  roundTripMs: number;
+  serverProcessingMs: number;
+  transportMs: number;
+}
+
+/**
+ * Measure end-to-end capture latency with `capture_monitor_stream_timed`
+ * Each probe reports the previous round trip back so it lands in the
+ * backend's histogram (see `get_capture_stats`).
+ * @param samples - Number of frames to probe (sequential)
+ */
+export async function measureCaptureLatency(
+  monitorId: number,
+  profile: ResolutionProfile = "standard",
+  chroma: ChromaSubsampling = "444",
+  samples = 10
+): Promise<CaptureLatencySample[]> {
+  const results: CaptureLatencySample[] = [];
+  let previousRoundTripMs: number | undefined;
+
+  for (let i = 0; i < samples; i++) {
+    const frame = await invoke<TimedStreamFrame>("capture_monitor_stream_timed", {
+      monitorId,
+      clientTimestampMs: Date.now(),
+      profile,
+      chroma,
+      previousRoundTripMs,
+    });
+    const roundTripMs = Date.now() - frame.clientTimestampMs;
+    results.push({
+      roundTripMs,
+      serverProcessingMs: frame.serverProcessingMs,
+      transportMs: Math.max(0, roundTripMs - frame.serverProcessingMs),
+    });
+    previousRoundTripMs = roundTripMs;
+  }
+
+  return results;
+}
+
+/**
+ * Memory and handle usage of the app process (see `get_resource_usage`)
+ */
+export interface ResourceUsageSample {
+  timestampMs: number;
+  workingSetBytes: number;
+  peakWorkingSetBytes: number;
+  privateBytes: number;
+  handleCount: number;
+  gdiObjects: number;
+  peakGdiObjects: number;
+  userObjects: number;
+}
+
+/**
+ * Sample resource usage periodically over a session, e.g. to chart memory
+ * growth while streaming. Failed samples (non-Windows) are skipped.
+ * @param onSample - Called with every sample and the full series so far
+ * @param intervalMs - Sampling interval
+ * @returns Stops sampling
+ */
+export function sampleResourceUsage(
+  onSample: (sample: ResourceUsageSample, series: ResourceUsageSample[]) => void,
+  intervalMs = 5000
+): () => void {
+  const series: ResourceUsageSample[] = [];
+
+  const sample = async () => {
+    try {
+      const usage = await invoke<ResourceUsageSample>("get_resource_usage");
+      series.push(usage);
+      onSample(usage, series);
+    } catch (error) {
+      console.warn("[TauriScreenStream] Resource usage sample failed:", error);
+    }
+  };
+
+  void sample();
+  const intervalId = setInterval(sample, intervalMs);
+  return () => clearInterval(intervalId);
+}
@@ -22,6 +22,9 @@ import { logger } from '@/logging/logger';
 import { RuntimeConfig } from '../runtime-config';
 import fetchClient, { APIError } from '@/api/fetch-client';
 
+/** Error prefix of input commands refused without the user's consent (remote_consent.rs) */
+const CONSENT_REQUIRED_ERROR = "ConsentRequired";
+
 interface SignalingMessage {
   type: string;
   data: any;
@@ -42,6 +45,8 @@ interface WebRTCHostCallbacks {
   onControlEnabled?: () => void;
   /** Called when agent disables control */
   onControlDisabled?: () => void;
+  /** Called when input is refused because the user's consent is missing or expired */
+  onConsentRequired?: (message: string) => void;
 }
 
 interface WebRTCHostOptions {
@@ -1011,8 +1016,8 @@ export class WebRTCHost {
       this.pendingMouseMove = null;
 
       // Fire-and-forget: don't await, just send and continue
-      invoke("remote_mouse_move", { x: pos.x, y: pos.y }).catch((error) => {
-        console.error("[WebRTCHost] Error injecting mouse move:", error);
+      invoke("remote_mouse_move", { x: pos.x, y: pos.y, monitorId: this.getInputMonitorId() }).catch((error) => {
+        this.handleInputError("mouse move", error);
       });
 
       // Check for more moves after a micro-yield (allows new events to arrive)
@@ -1038,17 +1043,28 @@ export class WebRTCHost {
    * Handle mouse click - fire and forget
    */
   private handleMouseClick(x: number, y: number, button: number): void {
-    invoke("remote_mouse_click", { x, y, button }).catch((error) => {
-      console.error("[WebRTCHost] Error injecting mouse click:", error);
+    invoke("remote_mouse_click", { x, y, button, monitorId: this.getInputMonitorId() }).catch((error) => {
+      this.handleInputError("mouse click", error);
     });
   }
 
+  /**
+   * Monitor that input coordinates are relative to (the captured screen),
+   * or null to use the primary-screen mapping
+   */
+  private getInputMonitorId(): number | null {
+    if (this.selectedSource && this.selectedSource.type === "screen") {
+      return this.selectedSource.id;
+    }
+    return null;
+  }
+
   /**
    * Handle mouse down - fire and forget
    */
   private handleMouseDown(button: number): void {
     invoke("remote_mouse_down", { button }).catch((error) => {
-      console.error("[WebRTCHost] Error injecting mouse down:", error);
+      this.handleInputError("mouse down", error);
     });
   }
 
@@ -1057,7 +1073,7 @@ export class WebRTCHost {
    */
   private handleMouseUp(button: number): void {
     invoke("remote_mouse_up", { button }).catch((error) => {
-      console.error("[WebRTCHost] Error injecting mouse up:", error);
+      this.handleInputError("mouse up", error);
     });
   }
 
@@ -1068,7 +1084,7 @@ export class WebRTCHost {
     // Convert wheel delta to Windows format (120 units per notch)
     const delta = Math.round(-deltaY);
     invoke("remote_mouse_wheel", { delta }).catch((error) => {
-      console.error("[WebRTCHost] Error injecting mouse wheel:", error);
+      this.handleInputError("mouse wheel", error);
     });
   }
 
@@ -1078,11 +1094,13 @@ export class WebRTCHost {
   private handleKeyDown(event: any): void {
     invoke("remote_key_down", {
       code: event.code,
+      // Typed character, mapped through this machine's keyboard layout
+      key: event.key,
       ctrl: event.ctrlKey || false,
       shift: event.shiftKey || false,
       alt: event.altKey || false,
     }).catch((error) => {
-      console.error("[WebRTCHost] Error injecting key down:", error);
+      this.handleInputError("key down", error);
     });
   }
 
@@ -1092,12 +1110,42 @@ export class WebRTCHost {
   private handleKeyUp(event: any): void {
     invoke("remote_key_up", {
       code: event.code,
+      // Typed character, mapped through this machine's keyboard layout
+      key: event.key,
       ctrl: event.ctrlKey || false,
       shift: event.shiftKey || false,
       alt: event.altKey || false,
     }).catch((error) => {
-      console.error("[WebRTCHost] Error injecting key up:", error);
+      this.handleInputError("key up", error);
+    });
+  }
+
+  /**
+   * Handle a failed input injection
+   *
+   * Input commands are refused without an active consent from the user. In
+   * that case control is turned off, which tells the technician through
+   * ControlDisabled, instead of dropping every following event silently.
+   */
+  private handleInputError(action: string, error: unknown): void {
+    const message = error instanceof Error ? error.message : String(error);
+    if (!message.startsWith(CONSENT_REQUIRED_ERROR)) {
+      console.error(`[WebRTCHost] Error injecting ${action}:`, error);
+      return;
+    }
+
+    // Fire-and-forget events fail in bursts - only handle the first
+    if (!this.controlEnabled) {
+      return;
+    }
+    logger.warn('remote-support', 'Input refused without remote control consent', {
+      sessionId: this.sessionId,
+      error: message,
     });
+    console.warn("[WebRTCHost] Input refused without consent - disabling control:", message);
+    this.disableControl();
+    this.callbacks.onControlDisabled?.();
+    this.callbacks.onConsentRequired?.(message);
   }
 
   /**
@@ -1245,11 +1293,19 @@ export class WebRTCHost {
     // Stop heartbeat
     this.stopHeartbeat();
 
+    // Withdraw the user's consent so no further input can be injected
+    invoke("revoke_remote_consent", { sessionId: this.sessionId }).catch((error) => {
+      console.warn("[WebRTCHost] Failed to revoke remote control consent:", error);
+    });
+
     // Stop UAC detection
     if (this.uacUnlisten) {
       console.log("[WebRTCHost] Cleaning up UAC detection");
       this.uacUnlisten();
       this.uacUnlisten = null;
+      invoke("stop_uac_detection").catch((error) => {
+        console.warn("[WebRTCHost] Failed to stop UAC detection:", error);
+      });
     }
 
     // Stop screen stream - CRITICAL: This stops the capture loop
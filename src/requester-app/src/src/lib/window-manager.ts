@@ -1,5 +1,6 @@
 // Window management utilities for Tauri
 import { invoke } from '@tauri-apps/api/core';
+import { emit, listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 /**
  * Window management functions for the floating IT Support Center app
@@ -29,6 +30,42 @@ export class WindowManager {
     }
   }
 
+  /**
+   * Hide the main window to the tray, optionally fading it out first
+   */
+  static async hideToTray(animate = true): Promise<void> {
+    try {
+      await invoke('hide_to_tray', { animate });
+    } catch (error) {
+      console.error('Failed to hide window to tray:', error);
+      throw error;
+    }
+  }
+
+  /**
+   * Record the window the user was focused on before we bring ours up
+   */
+  static async saveForegroundState(): Promise<boolean> {
+    try {
+      return await invoke<boolean>('save_foreground_state');
+    } catch (error) {
+      console.error('Failed to save foreground state:', error);
+      return false;
+    }
+  }
+
+  /**
+   * Give focus back to the recorded window and reset our topmost flag
+   */
+  static async restoreForegroundState(): Promise<boolean> {
+    try {
+      return await invoke<boolean>('restore_foreground_state');
+    } catch (error) {
+      console.error('Failed to restore foreground state:', error);
+      return false;
+    }
+  }
+
   /**
    * Toggle window visibility (show if hidden, hide if shown)
    */
@@ -65,6 +102,48 @@ export class WindowManager {
     }
   }
 
+  /**
+   * Save state and exit: ends any remote session, emits `shutting-down`,
+   * waits (bounded by waitMs) for `shutdown-ready`, syncs storage and logs
+   */
+  static async gracefulQuit(waitMs?: number): Promise<void> {
+    try {
+      await invoke('graceful_quit', { waitMs });
+    } catch (error) {
+      console.error('Failed to quit gracefully:', error);
+      throw error;
+    }
+  }
+
+  /**
+   * Run a handler when the app is shutting down, then confirm with
+   * `shutdown-ready` so the backend doesn't wait for the full timeout
+   */
+  static async onShuttingDown(handler: () => void | Promise<void>): Promise<UnlistenFn> {
+    return listen('shutting-down', async () => {
+      try {
+        await handler();
+      } catch (error) {
+        console.error('Shutdown handler failed:', error);
+      } finally {
+        await emit('shutdown-ready');
+      }
+    });
+  }
+
+  /**
+   * Run a handler after the display configuration changed (docking, monitor
+   * hot-plug, resolution change). The backend has already dropped its cached
+   * screen dimensions; the payload is the new primary screen size.
+   */
+  static async onDisplayChanged(
+    handler: (size: { width: number; height: number }) => void
+  ): Promise<UnlistenFn> {
+    return listen<{ width: number; height: number }>('display-changed', (event) => {
+      handler(event.payload);
+    });
+  }
+
   /**
    * Handle clean application shutdown
    */
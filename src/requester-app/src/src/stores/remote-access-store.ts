@@ -78,6 +78,10 @@ let sessionStartInProgress: string | null = null;
 // Shared constant for acceptance timeout
 const ACCEPTANCE_TIMEOUT_SECONDS = 10;
 
+// How long an explicitly accepted session may inject input; revoked earlier
+// when the session stops
+const REMOTE_CONSENT_SECONDS = 60 * 60;
+
 // Track active countdown timers
 const countdownTimers: Map<string, ReturnType<typeof setTimeout>> = new Map();
 
@@ -130,6 +134,24 @@ function createRemoteAccessStore() {
     }
   }
 
+  /**
+   * Record the user's consent to remote control for a session
+   * Input commands are refused by the Tauri side until this succeeds.
+   * Only called for an explicit Accept click, never for the auto-accept timeout.
+   */
+  async function grantRemoteConsent(sessionId: string): Promise<void> {
+    try {
+      await invoke("grant_remote_consent", { sessionId, durationSecs: REMOTE_CONSENT_SECONDS });
+      logger.info('remote-support', 'Remote control consent granted', { sessionId });
+    } catch (error) {
+      // The session still starts view-only; input will be refused
+      logger.error('remote-support', 'Failed to grant remote control consent', {
+        sessionId,
+        error: error instanceof Error ? error.message : String(error),
+      });
+    }
+  }
+
   /**
    * Start acceptance timer for pending session
    * Auto-accepts after ACCEPTANCE_TIMEOUT_SECONDS
@@ -140,10 +162,10 @@ function createRemoteAccessStore() {
       clearTimeout(countdownTimers.get(sessionId)!);
     }
 
-    // Set 10-second timer - timeout results in ACCEPT, not reject
+    // Set 10-second timer - timeout results in ACCEPT (view-only), not reject
     const timerId = setTimeout(async () => {
       console.log("[RemoteAccess] Acceptance timeout - auto-accepting session:", sessionId);
-      await acceptPendingSession();
+      await acceptPendingSession(false);
     }, ACCEPTANCE_TIMEOUT_SECONDS * 1000);
 
     countdownTimers.set(sessionId, timerId);
@@ -228,9 +250,11 @@ function createRemoteAccessStore() {
 
   /**
    * Accept pending session - start WebRTC connection
-   * Called when user clicks Accept button
+   * Called when user clicks Accept button (userClicked) or the countdown runs
+   * out. Only a click grants remote control consent; an auto-accepted session
+   * is view-only.
    */
-  async function acceptPendingSession(): Promise<void> {
+  async function acceptPendingSession(userClicked: boolean = false): Promise<void> {
     const pending = state.pendingSession;
     if (!pending) {
       console.warn("[RemoteAccess] No pending session to accept");
@@ -259,6 +283,7 @@ function createRemoteAccessStore() {
     logger.info('remote-support', 'User accepted remote session', {
       sessionId: pending.sessionId,
       agentName: pending.agentName,
+      userClicked,
     });
     console.log("[RemoteAccess] ========================================");
     console.log("[RemoteAccess] ✅ USER ACCEPTED REMOTE SESSION");
@@ -306,6 +331,7 @@ function createRemoteAccessStore() {
 
       // Update floating icon
       await updateFloatingIconRemoteState(true, pending.agentName);
+      await setKeepAwake(true);
 
       // Get primary monitor (same as auto-start flow)
       console.log("[RemoteAccess] Getting primary monitor...");
@@ -320,6 +346,12 @@ function createRemoteAccessStore() {
 
       console.log("[RemoteAccess] ✅ Auto-selected primary monitor:", primaryMonitor.name);
 
+      // Only an explicit Accept click is consent to remote control; an
+      // unattended machine that times out gets a view-only session
+      if (userClicked) {
+        await grantRemoteConsent(pending.sessionId);
+      }
+
       // Start WebRTC session
       console.log("[RemoteAccess] Starting WebRTC session...");
       await startWebRTCSession(pending.sessionId, primaryMonitor);
@@ -480,6 +512,14 @@ function createRemoteAccessStore() {
           console.log("[RemoteAccess] Control disabled by agent");
           setState({ controlEnabled: false });
         },
+        onConsentRequired: (message) => {
+          // Control was turned off and the technician notified by the host
+          console.warn("[RemoteAccess] Remote control consent missing:", message);
+          setState({
+            controlEnabled: false,
+            error: "Remote control was blocked because you have not accepted it for this session",
+          });
+        },
       }, source, resolutionProfile); // Pass selected source and resolution profile
 
       console.log("[RemoteAccess] ✅ WebRTCHost instance created");
@@ -684,6 +724,7 @@ function createRemoteAccessStore() {
     // Reset floating icon if no more active sessions
     if (updatedSessions.length === 0) {
       await updateFloatingIconRemoteState(false);
+      await setKeepAwake(false);
     }
 
     logger.info('remote-support', 'Banner session removed', {
@@ -710,6 +751,7 @@ function createRemoteAccessStore() {
 
     // Reset floating icon
     await updateFloatingIconRemoteState(false);
+    await setKeepAwake(false);
   }
 
   /**
@@ -747,6 +789,20 @@ function createRemoteAccessStore() {
     }
   }
 
+  /**
+   * Keep the machine and display awake while a remote session is active
+   */
+  async function setKeepAwake(enabled: boolean): Promise<void> {
+    try {
+      await invoke('prevent_sleep', { enabled });
+    } catch (error) {
+      logger.warn('remote-support', 'Failed to update sleep prevention', {
+        enabled,
+        error: error instanceof Error ? error.message : String(error),
+      });
+    }
+  }
+
   /**
    * Handle termination request from user (via terminate button)
    * Stops WebRTC session, notifies agent, and hides panel
@@ -774,6 +830,7 @@ function createRemoteAccessStore() {
       // Update floating icon if no more sessions
       if (updatedSessions.length === 0) {
         await updateFloatingIconRemoteState(false);
+        await setKeepAwake(false);
       }
 
       // Notify agent via SignalR (optional - will be handled by SignalR disconnect)
@@ -53,6 +53,7 @@ export interface DeviceInfo {
   app_version?: string;
   ip_address?: string; // Local IP address for accurate tracking
   computer_name?: string; // Computer hostname for identification
+  locale?: string; // User's UI language and region (e.g. "ar-EG") for localized support
 }
 
 /**